@@ -0,0 +1,1008 @@
+#![cfg_attr(not(feature = "std"), no_std, no_main)]
+mod tick;
+mod tick_bitmap;
+/// Concentrated-liquidity pool implementation based on the UniswapV3 model.
+///
+/// Liquidity providers choose the price range in which their liquidity is active.
+/// Ranges are delimited by ticks which are multiples of the pool's tick spacing.
+/// Liquidity is tracked in positions - NFT-like records identified by an id,
+/// which can be transferred between accounts.
+///
+/// Trade fees accrue to the positions whose price range contains the current price
+/// and are collected together with the withdrawn liquidity.
+#[ink::contract]
+pub mod concentrated_pool {
+    use crate::{
+        tick::{self, TickInfo},
+        tick_bitmap::TickBitmap,
+    };
+    use amm_helpers::{
+        concentrated_math::{self as math, tick_math},
+        constants::concentrated_pool::{
+            MAX_FEE, MAX_SQRT_RATIO, MAX_TICK, MAX_TICK_SPACING, MIN_SQRT_RATIO, MIN_TICK,
+        },
+        ensure,
+        types::WrappedU256,
+    };
+    use ink::{
+        contract_ref,
+        prelude::{vec, vec::Vec},
+        storage::Mapping,
+    };
+    use primitive_types::U256;
+    use psp22::PSP22;
+    use traits::{ConcentratedPool, ConcentratedPoolError, MathError, Position};
+
+    #[ink(event)]
+    pub struct Mint {
+        #[ink(topic)]
+        pub sender: AccountId,
+        #[ink(topic)]
+        pub owner: AccountId,
+        pub position_id: u128,
+        pub tick_lower: i32,
+        pub tick_upper: i32,
+        pub liquidity: u128,
+        pub amount_0: u128,
+        pub amount_1: u128,
+    }
+
+    #[ink(event)]
+    pub struct Burn {
+        #[ink(topic)]
+        pub owner: AccountId,
+        pub position_id: u128,
+        pub tick_lower: i32,
+        pub tick_upper: i32,
+        pub liquidity: u128,
+        pub amount_0: u128,
+        pub amount_1: u128,
+    }
+
+    #[ink(event)]
+    pub struct Collect {
+        #[ink(topic)]
+        pub owner: AccountId,
+        pub position_id: u128,
+        #[ink(topic)]
+        pub to: AccountId,
+        pub amount_0: u128,
+        pub amount_1: u128,
+    }
+
+    #[ink(event)]
+    pub struct Swap {
+        #[ink(topic)]
+        pub sender: AccountId,
+        pub token_in: AccountId,
+        pub amount_in: u128,
+        pub token_out: AccountId,
+        pub amount_out: u128,
+        #[ink(topic)]
+        pub to: AccountId,
+        pub sqrt_price: WrappedU256,
+        pub tick: i32,
+    }
+
+    /// Event emitted when a position is created, transferred or burned.
+    #[ink(event)]
+    pub struct PositionTransfer {
+        /// Previous owner. `None` in case of creating the position.
+        #[ink(topic)]
+        pub from: Option<AccountId>,
+        /// New owner. `None` in case of burning the position.
+        #[ink(topic)]
+        pub to: Option<AccountId>,
+        pub position_id: u128,
+    }
+
+    /// Result of a swap computed against the current state of the pool.
+    struct SwapOutcome {
+        /// Amount of the input token, including the fee.
+        amount_in: u128,
+        amount_out: u128,
+        fee: u128,
+        sqrt_price: U256,
+        tick: i32,
+        liquidity: u128,
+        /// Fee growth of the input token after the swap.
+        fee_growth_global: U256,
+        /// Initialized ticks crossed by the swap, with the fee growth
+        /// of the input token at the moment of crossing.
+        crossed_ticks: Vec<(i32, U256)>,
+    }
+
+    #[ink(storage)]
+    pub struct ConcentratedPoolContract {
+        token_0: AccountId,
+        token_1: AccountId,
+        /// Trade fee, in hundredths of a basis point.
+        fee: u32,
+        tick_spacing: i32,
+        max_liquidity_per_tick: u128,
+        /// Current sqrt price (Q64.96).
+        sqrt_price: WrappedU256,
+        /// Current tick.
+        tick: i32,
+        /// Liquidity active at the current price.
+        liquidity: u128,
+        /// Fee growth of token 0 per unit of liquidity (Q128.128), allowed to overflow.
+        fee_growth_global_0: WrappedU256,
+        /// Fee growth of token 1 per unit of liquidity (Q128.128), allowed to overflow.
+        fee_growth_global_1: WrappedU256,
+        reserve_0: u128,
+        reserve_1: u128,
+        ticks: Mapping<i32, TickInfo>,
+        tick_bitmap: TickBitmap,
+        positions: Mapping<u128, Position>,
+        next_position_id: u128,
+    }
+
+    impl ConcentratedPoolContract {
+        /// Creates a pool of `token_0` and `token_1` with the initial `sqrt_price`
+        /// (of token 0 in terms of token 1, as a Q64.96 number).
+        ///
+        /// `fee` is given in hundredths of a basis point, e.g. 3000 for 0.3%.
+        #[ink(constructor)]
+        pub fn new(
+            token_0: AccountId,
+            token_1: AccountId,
+            fee: u32,
+            tick_spacing: i32,
+            sqrt_price: WrappedU256,
+        ) -> Result<Self, ConcentratedPoolError> {
+            ensure!(token_0 != token_1, ConcentratedPoolError::IdenticalTokenId);
+            ensure!(fee <= MAX_FEE, ConcentratedPoolError::InvalidFee);
+            ensure!(
+                tick_spacing > 0 && tick_spacing <= MAX_TICK_SPACING,
+                ConcentratedPoolError::InvalidTickSpacing
+            );
+            let tick = tick_math::get_tick_at_sqrt_ratio(sqrt_price.0)
+                .map_err(|_| ConcentratedPoolError::InvalidSqrtPrice)?;
+            Ok(Self {
+                token_0,
+                token_1,
+                fee,
+                tick_spacing,
+                max_liquidity_per_tick: tick::max_liquidity_per_tick(tick_spacing),
+                sqrt_price,
+                tick,
+                liquidity: 0,
+                fee_growth_global_0: WrappedU256::ZERO,
+                fee_growth_global_1: WrappedU256::ZERO,
+                reserve_0: 0,
+                reserve_1: 0,
+                ticks: Default::default(),
+                tick_bitmap: TickBitmap::new(),
+                positions: Default::default(),
+                next_position_id: 0,
+            })
+        }
+
+        #[inline]
+        fn token_by_address(&self, address: AccountId) -> contract_ref!(PSP22) {
+            address.into()
+        }
+
+        /// Checks if tokens are valid and returns whether token 0 is swapped for token 1.
+        fn zero_for_one(
+            &self,
+            token_in: AccountId,
+            token_out: AccountId,
+        ) -> Result<bool, ConcentratedPoolError> {
+            ensure!(
+                token_in != token_out,
+                ConcentratedPoolError::IdenticalTokenId
+            );
+            for token in [token_in, token_out] {
+                ensure!(
+                    token == self.token_0 || token == self.token_1,
+                    ConcentratedPoolError::InvalidTokenId(token)
+                );
+            }
+            Ok(token_in == self.token_0)
+        }
+
+        fn check_ticks(
+            &self,
+            tick_lower: i32,
+            tick_upper: i32,
+        ) -> Result<(), ConcentratedPoolError> {
+            ensure!(
+                tick_lower < tick_upper
+                    && tick_lower >= MIN_TICK
+                    && tick_upper <= MAX_TICK
+                    && tick_lower % self.tick_spacing == 0
+                    && tick_upper % self.tick_spacing == 0,
+                ConcentratedPoolError::InvalidTickRange
+            );
+            Ok(())
+        }
+
+        fn get_position(&self, position_id: u128) -> Result<Position, ConcentratedPoolError> {
+            self.positions
+                .get(position_id)
+                .ok_or(ConcentratedPoolError::PositionNotFound)
+        }
+
+        /// Returns the position, checking that the caller is its owner.
+        fn get_owned_position(&self, position_id: u128) -> Result<Position, ConcentratedPoolError> {
+            let position = self.get_position(position_id)?;
+            ensure!(
+                position.owner == self.env().caller(),
+                ConcentratedPoolError::CallerIsNotPositionOwner
+            );
+            Ok(position)
+        }
+
+        /// Computes token amounts corresponding to `liquidity` in the price range
+        /// [`tick_lower`, `tick_upper`) at the current price.
+        fn amounts_for_liquidity(
+            &self,
+            tick_lower: i32,
+            tick_upper: i32,
+            liquidity: u128,
+            round_up: bool,
+        ) -> Result<(u128, u128), ConcentratedPoolError> {
+            let sqrt_price_lower = tick_math::get_sqrt_ratio_at_tick(tick_lower)?;
+            let sqrt_price_upper = tick_math::get_sqrt_ratio_at_tick(tick_upper)?;
+            let sqrt_price = self.sqrt_price.0;
+            let (amount_0, amount_1) = if self.tick < tick_lower {
+                // the range is above the current price, only token 0 is provided
+                (
+                    math::get_amount0_delta(
+                        sqrt_price_lower,
+                        sqrt_price_upper,
+                        liquidity,
+                        round_up,
+                    )?,
+                    U256::zero(),
+                )
+            } else if self.tick < tick_upper {
+                (
+                    math::get_amount0_delta(sqrt_price, sqrt_price_upper, liquidity, round_up)?,
+                    math::get_amount1_delta(sqrt_price_lower, sqrt_price, liquidity, round_up)?,
+                )
+            } else {
+                // the range is below the current price, only token 1 is provided
+                (
+                    U256::zero(),
+                    math::get_amount1_delta(
+                        sqrt_price_lower,
+                        sqrt_price_upper,
+                        liquidity,
+                        round_up,
+                    )?,
+                )
+            };
+            Ok((
+                amount_0
+                    .try_into()
+                    .map_err(|_| MathError::CastOverflow(80))?,
+                amount_1
+                    .try_into()
+                    .map_err(|_| MathError::CastOverflow(81))?,
+            ))
+        }
+
+        /// Updates the `tick` when the liquidity of a position using it as a boundary changes.
+        ///
+        /// Returns whether the tick was flipped from initialized to uninitialized or vice versa.
+        fn update_tick(
+            &mut self,
+            tick: i32,
+            liquidity_delta: i128,
+            upper: bool,
+        ) -> Result<bool, ConcentratedPoolError> {
+            let mut info = self.ticks.get(tick).unwrap_or_default();
+            let liquidity_gross_before = info.liquidity_gross;
+            let liquidity_gross_after = math::add_delta(liquidity_gross_before, liquidity_delta)?;
+            ensure!(
+                liquidity_gross_after <= self.max_liquidity_per_tick,
+                ConcentratedPoolError::TickLiquidityOverflow
+            );
+            if liquidity_gross_before == 0 && tick <= self.tick {
+                // by convention, all the fee growth before initializing the tick happened below it
+                info.fee_growth_outside_0 = self.fee_growth_global_0;
+                info.fee_growth_outside_1 = self.fee_growth_global_1;
+            }
+            info.liquidity_gross = liquidity_gross_after;
+            info.liquidity_net = if upper {
+                info.liquidity_net
+                    .checked_sub(liquidity_delta)
+                    .ok_or(MathError::SubUnderflow(80))?
+            } else {
+                info.liquidity_net
+                    .checked_add(liquidity_delta)
+                    .ok_or(MathError::AddOverflow(80))?
+            };
+            self.ticks.insert(tick, &info);
+
+            let flipped = (liquidity_gross_after == 0) != (liquidity_gross_before == 0);
+            if flipped {
+                self.tick_bitmap.flip_tick(tick, self.tick_spacing);
+            }
+            Ok(flipped)
+        }
+
+        /// Changes the liquidity of the `position` by `liquidity_delta`
+        /// and credits it with the fees accrued since its last update.
+        ///
+        /// NOTE: The updated position has to be stored by the caller.
+        fn modify_position(
+            &mut self,
+            position: &mut Position,
+            liquidity_delta: i128,
+        ) -> Result<(), ConcentratedPoolError> {
+            let (tick_lower, tick_upper) = (position.tick_lower, position.tick_upper);
+            let (mut flipped_lower, mut flipped_upper) = (false, false);
+            if liquidity_delta != 0 {
+                flipped_lower = self.update_tick(tick_lower, liquidity_delta, false)?;
+                flipped_upper = self.update_tick(tick_upper, liquidity_delta, true)?;
+            }
+
+            let (fee_growth_inside_0, fee_growth_inside_1) = tick::fee_growth_inside(
+                (tick_lower, &self.ticks.get(tick_lower).unwrap_or_default()),
+                (tick_upper, &self.ticks.get(tick_upper).unwrap_or_default()),
+                self.tick,
+                self.fee_growth_global_0.0,
+                self.fee_growth_global_1.0,
+            );
+            let fees_0: u128 = math::mul_div(
+                tick::wrapping_sub(fee_growth_inside_0, position.fee_growth_inside_0_last.0),
+                position.liquidity.into(),
+                math::q128(),
+            )?
+            .try_into()
+            .map_err(|_| MathError::CastOverflow(82))?;
+            let fees_1: u128 = math::mul_div(
+                tick::wrapping_sub(fee_growth_inside_1, position.fee_growth_inside_1_last.0),
+                position.liquidity.into(),
+                math::q128(),
+            )?
+            .try_into()
+            .map_err(|_| MathError::CastOverflow(83))?;
+            position.tokens_owed_0 = position
+                .tokens_owed_0
+                .checked_add(fees_0)
+                .ok_or(MathError::AddOverflow(82))?;
+            position.tokens_owed_1 = position
+                .tokens_owed_1
+                .checked_add(fees_1)
+                .ok_or(MathError::AddOverflow(83))?;
+            position.fee_growth_inside_0_last = fee_growth_inside_0.into();
+            position.fee_growth_inside_1_last = fee_growth_inside_1.into();
+            position.liquidity = math::add_delta(position.liquidity, liquidity_delta)?;
+
+            // ticks which are no longer used by any position are cleared
+            if liquidity_delta < 0 {
+                if flipped_lower {
+                    self.ticks.remove(tick_lower);
+                }
+                if flipped_upper {
+                    self.ticks.remove(tick_upper);
+                }
+            }
+            if self.tick >= tick_lower && self.tick < tick_upper {
+                self.liquidity = math::add_delta(self.liquidity, liquidity_delta)?;
+            }
+            Ok(())
+        }
+
+        /// Adds `liquidity` to the position, transferring the required amounts from the caller.
+        fn add_liquidity(
+            &mut self,
+            position_id: u128,
+            mut position: Position,
+            liquidity: u128,
+            amount_0_max: u128,
+            amount_1_max: u128,
+        ) -> Result<(u128, u128), ConcentratedPoolError> {
+            ensure!(liquidity > 0, ConcentratedPoolError::ZeroLiquidity);
+            let liquidity_delta =
+                i128::try_from(liquidity).map_err(|_| MathError::CastOverflow(84))?;
+            let (amount_0, amount_1) = self.amounts_for_liquidity(
+                position.tick_lower,
+                position.tick_upper,
+                liquidity,
+                true,
+            )?;
+            ensure!(
+                amount_0 <= amount_0_max && amount_1 <= amount_1_max,
+                ConcentratedPoolError::TooLargeInputAmount
+            );
+            self.modify_position(&mut position, liquidity_delta)?;
+            self.positions.insert(position_id, &position);
+
+            let caller = self.env().caller();
+            let account_id = self.env().account_id();
+            for (token, amount) in [(self.token_0, amount_0), (self.token_1, amount_1)] {
+                if amount > 0 {
+                    self.token_by_address(token).transfer_from(
+                        caller,
+                        account_id,
+                        amount,
+                        vec![],
+                    )?;
+                }
+            }
+            self.increase_reserves(amount_0, amount_1)?;
+
+            self.env().emit_event(Mint {
+                sender: caller,
+                owner: position.owner,
+                position_id,
+                tick_lower: position.tick_lower,
+                tick_upper: position.tick_upper,
+                liquidity,
+                amount_0,
+                amount_1,
+            });
+            Ok((amount_0, amount_1))
+        }
+
+        fn increase_reserves(
+            &mut self,
+            amount_0: u128,
+            amount_1: u128,
+        ) -> Result<(), ConcentratedPoolError> {
+            self.reserve_0 = self
+                .reserve_0
+                .checked_add(amount_0)
+                .ok_or(MathError::AddOverflow(85))?;
+            self.reserve_1 = self
+                .reserve_1
+                .checked_add(amount_1)
+                .ok_or(MathError::AddOverflow(86))?;
+            Ok(())
+        }
+
+        fn decrease_reserves(
+            &mut self,
+            amount_0: u128,
+            amount_1: u128,
+        ) -> Result<(), ConcentratedPoolError> {
+            self.reserve_0 = self
+                .reserve_0
+                .checked_sub(amount_0)
+                .ok_or(MathError::SubUnderflow(85))?;
+            self.reserve_1 = self
+                .reserve_1
+                .checked_sub(amount_1)
+                .ok_or(MathError::SubUnderflow(86))?;
+            Ok(())
+        }
+
+        /// Computes the outcome of swapping `amount` (exact input if `exact_in`,
+        /// otherwise exact output) without modifying the pool.
+        ///
+        /// Fails if there is not enough liquidity to swap the whole `amount`.
+        fn compute_swap(
+            &self,
+            zero_for_one: bool,
+            amount: u128,
+            exact_in: bool,
+        ) -> Result<SwapOutcome, ConcentratedPoolError> {
+            ensure!(
+                amount > 0,
+                if exact_in {
+                    ConcentratedPoolError::InsufficientInputAmount
+                } else {
+                    ConcentratedPoolError::InsufficientOutputAmount
+                }
+            );
+            let sqrt_price_limit = if zero_for_one {
+                MIN_SQRT_RATIO + 1
+            } else {
+                MAX_SQRT_RATIO - 1
+            };
+            let mut amount_remaining = U256::from(amount);
+            let mut amount_calculated = U256::zero();
+            let mut fee = U256::zero();
+            let mut sqrt_price = self.sqrt_price.0;
+            let mut tick = self.tick;
+            let mut liquidity = self.liquidity;
+            let mut fee_growth_global = if zero_for_one {
+                self.fee_growth_global_0.0
+            } else {
+                self.fee_growth_global_1.0
+            };
+            let mut crossed_ticks = Vec::new();
+
+            while !amount_remaining.is_zero() && sqrt_price != sqrt_price_limit {
+                let sqrt_price_start = sqrt_price;
+                let (tick_next, initialized) = self
+                    .tick_bitmap
+                    .next_initialized_tick_within_one_word(tick, self.tick_spacing, zero_for_one);
+                let tick_next = tick_next.clamp(MIN_TICK, MAX_TICK);
+                let sqrt_price_next = tick_math::get_sqrt_ratio_at_tick(tick_next)?;
+                let sqrt_price_target = if zero_for_one {
+                    sqrt_price_next.max(sqrt_price_limit)
+                } else {
+                    sqrt_price_next.min(sqrt_price_limit)
+                };
+
+                let step = math::compute_swap_step(
+                    sqrt_price,
+                    sqrt_price_target,
+                    liquidity,
+                    amount_remaining,
+                    exact_in,
+                    self.fee,
+                )?;
+                sqrt_price = step.sqrt_price_next;
+                let step_amount_in = step
+                    .amount_in
+                    .checked_add(step.fee_amount)
+                    .ok_or(MathError::AddOverflow(87))?;
+                let (step_amount_specified, step_amount_calculated) = if exact_in {
+                    (step_amount_in, step.amount_out)
+                } else {
+                    (step.amount_out, step_amount_in)
+                };
+                amount_remaining = amount_remaining
+                    .checked_sub(step_amount_specified)
+                    .ok_or(MathError::SubUnderflow(87))?;
+                amount_calculated = amount_calculated
+                    .checked_add(step_amount_calculated)
+                    .ok_or(MathError::AddOverflow(88))?;
+                fee = fee
+                    .checked_add(step.fee_amount)
+                    .ok_or(MathError::AddOverflow(89))?;
+                if liquidity > 0 {
+                    let fee_growth =
+                        math::mul_div(step.fee_amount, math::q128(), liquidity.into())?;
+                    fee_growth_global = fee_growth_global.overflowing_add(fee_growth).0;
+                }
+
+                if sqrt_price == sqrt_price_next {
+                    // the price reached the next tick
+                    if initialized {
+                        crossed_ticks.push((tick_next, fee_growth_global));
+                        let liquidity_net =
+                            self.ticks.get(tick_next).unwrap_or_default().liquidity_net;
+                        // moving to the left, the liquidity net is subtracted
+                        let liquidity_delta = if zero_for_one {
+                            liquidity_net
+                                .checked_neg()
+                                .ok_or(MathError::SubUnderflow(88))?
+                        } else {
+                            liquidity_net
+                        };
+                        liquidity = math::add_delta(liquidity, liquidity_delta)?;
+                    }
+                    tick = if zero_for_one {
+                        tick_next - 1
+                    } else {
+                        tick_next
+                    };
+                } else if sqrt_price != sqrt_price_start {
+                    tick = tick_math::get_tick_at_sqrt_ratio(sqrt_price)?;
+                }
+            }
+            ensure!(
+                amount_remaining.is_zero(),
+                ConcentratedPoolError::InsufficientLiquidity
+            );
+
+            let amount_calculated: u128 = amount_calculated
+                .try_into()
+                .map_err(|_| MathError::CastOverflow(85))?;
+            let (amount_in, amount_out) = if exact_in {
+                (amount, amount_calculated)
+            } else {
+                (amount_calculated, amount)
+            };
+            Ok(SwapOutcome {
+                amount_in,
+                amount_out,
+                fee: fee.try_into().map_err(|_| MathError::CastOverflow(86))?,
+                sqrt_price,
+                tick,
+                liquidity,
+                fee_growth_global,
+                crossed_ticks,
+            })
+        }
+
+        /// Updates the pool with the swap `outcome` and transfers the output amount to `to`.
+        ///
+        /// NOTE: The input amount should be transferred to the pool prior to calling this function.
+        fn apply_swap(
+            &mut self,
+            token_in: AccountId,
+            token_out: AccountId,
+            outcome: SwapOutcome,
+            to: AccountId,
+        ) -> Result<(), ConcentratedPoolError> {
+            let zero_for_one = token_in == self.token_0;
+            for (tick, fee_growth_global) in outcome.crossed_ticks {
+                let mut info = self.ticks.get(tick).unwrap_or_default();
+                if zero_for_one {
+                    info.cross(fee_growth_global, self.fee_growth_global_1.0);
+                } else {
+                    info.cross(self.fee_growth_global_0.0, fee_growth_global);
+                }
+                self.ticks.insert(tick, &info);
+            }
+            self.sqrt_price = outcome.sqrt_price.into();
+            self.tick = outcome.tick;
+            self.liquidity = outcome.liquidity;
+            if zero_for_one {
+                self.fee_growth_global_0 = outcome.fee_growth_global.into();
+                self.increase_reserves(outcome.amount_in, 0)?;
+                self.decrease_reserves(0, outcome.amount_out)?;
+            } else {
+                self.fee_growth_global_1 = outcome.fee_growth_global.into();
+                self.increase_reserves(0, outcome.amount_in)?;
+                self.decrease_reserves(outcome.amount_out, 0)?;
+            }
+
+            self.token_by_address(token_out)
+                .transfer(to, outcome.amount_out, vec![])?;
+
+            self.env().emit_event(Swap {
+                sender: self.env().caller(),
+                token_in,
+                amount_in: outcome.amount_in,
+                token_out,
+                amount_out: outcome.amount_out,
+                to,
+                sqrt_price: self.sqrt_price,
+                tick: self.tick,
+            });
+            Ok(())
+        }
+
+        fn _swap_exact_in(
+            &mut self,
+            token_in: AccountId,
+            token_out: AccountId,
+            token_in_amount: Option<u128>,
+            min_token_out_amount: u128,
+            to: AccountId,
+        ) -> Result<(u128, u128), ConcentratedPoolError> {
+            let zero_for_one = self.zero_for_one(token_in, token_out)?;
+            let token_in_amount = self._transfer_in(token_in, zero_for_one, token_in_amount)?;
+            let outcome = self.compute_swap(zero_for_one, token_in_amount, true)?;
+            ensure!(
+                outcome.amount_out >= min_token_out_amount,
+                ConcentratedPoolError::InsufficientOutputAmount
+            );
+            let result = (outcome.amount_out, outcome.fee);
+            self.apply_swap(token_in, token_out, outcome, to)?;
+            Ok(result)
+        }
+
+        /// Handles PSP22 token transfer,
+        ///
+        /// If `amount` is `Some(amount)`, transfer this amount of `token`
+        /// from the caller to this contract.
+        ///
+        /// If `amount` of `None`, calculate the difference between
+        /// this contract balance and recorded reserve of `token`.
+        fn _transfer_in(
+            &self,
+            token: AccountId,
+            is_token_0: bool,
+            amount: Option<u128>,
+        ) -> Result<u128, ConcentratedPoolError> {
+            let mut token = self.token_by_address(token);
+            let amount = if let Some(token_amount) = amount {
+                token.transfer_from(
+                    self.env().caller(),
+                    self.env().account_id(),
+                    token_amount,
+                    vec![],
+                )?;
+                token_amount
+            } else {
+                let reserve = if is_token_0 {
+                    self.reserve_0
+                } else {
+                    self.reserve_1
+                };
+                token
+                    .balance_of(self.env().account_id())
+                    .checked_sub(reserve)
+                    .ok_or(MathError::SubUnderflow(89))?
+            };
+            ensure!(amount > 0, ConcentratedPoolError::InsufficientInputAmount);
+            Ok(amount)
+        }
+    }
+
+    impl ConcentratedPool for ConcentratedPoolContract {
+        #[ink(message)]
+        fn tokens(&self) -> (AccountId, AccountId) {
+            (self.token_0, self.token_1)
+        }
+
+        #[ink(message)]
+        fn fee(&self) -> u32 {
+            self.fee
+        }
+
+        #[ink(message)]
+        fn tick_spacing(&self) -> i32 {
+            self.tick_spacing
+        }
+
+        #[ink(message)]
+        fn sqrt_price(&self) -> WrappedU256 {
+            self.sqrt_price
+        }
+
+        #[ink(message)]
+        fn current_tick(&self) -> i32 {
+            self.tick
+        }
+
+        #[ink(message)]
+        fn liquidity(&self) -> u128 {
+            self.liquidity
+        }
+
+        #[ink(message)]
+        fn reserves(&self) -> (u128, u128) {
+            (self.reserve_0, self.reserve_1)
+        }
+
+        #[ink(message)]
+        fn position(&self, position_id: u128) -> Option<Position> {
+            self.positions.get(position_id)
+        }
+
+        #[ink(message)]
+        fn get_amounts_for_liquidity(
+            &self,
+            tick_lower: i32,
+            tick_upper: i32,
+            liquidity: u128,
+        ) -> Result<(u128, u128), ConcentratedPoolError> {
+            self.check_ticks(tick_lower, tick_upper)?;
+            self.amounts_for_liquidity(tick_lower, tick_upper, liquidity, true)
+        }
+
+        #[ink(message)]
+        fn mint_position(
+            &mut self,
+            tick_lower: i32,
+            tick_upper: i32,
+            liquidity: u128,
+            amount_0_max: u128,
+            amount_1_max: u128,
+            to: AccountId,
+        ) -> Result<(u128, u128, u128), ConcentratedPoolError> {
+            self.check_ticks(tick_lower, tick_upper)?;
+            let position_id = self.next_position_id;
+            self.next_position_id = position_id
+                .checked_add(1)
+                .ok_or(MathError::AddOverflow(81))?;
+            let position = Position {
+                owner: to,
+                tick_lower,
+                tick_upper,
+                liquidity: 0,
+                fee_growth_inside_0_last: WrappedU256::ZERO,
+                fee_growth_inside_1_last: WrappedU256::ZERO,
+                tokens_owed_0: 0,
+                tokens_owed_1: 0,
+            };
+            let (amount_0, amount_1) =
+                self.add_liquidity(position_id, position, liquidity, amount_0_max, amount_1_max)?;
+            self.env().emit_event(PositionTransfer {
+                from: None,
+                to: Some(to),
+                position_id,
+            });
+            Ok((position_id, amount_0, amount_1))
+        }
+
+        #[ink(message)]
+        fn increase_liquidity(
+            &mut self,
+            position_id: u128,
+            liquidity: u128,
+            amount_0_max: u128,
+            amount_1_max: u128,
+        ) -> Result<(u128, u128), ConcentratedPoolError> {
+            let position = self.get_position(position_id)?;
+            self.add_liquidity(position_id, position, liquidity, amount_0_max, amount_1_max)
+        }
+
+        #[ink(message)]
+        fn decrease_liquidity(
+            &mut self,
+            position_id: u128,
+            liquidity: u128,
+            amount_0_min: u128,
+            amount_1_min: u128,
+        ) -> Result<(u128, u128), ConcentratedPoolError> {
+            let mut position = self.get_owned_position(position_id)?;
+            ensure!(liquidity > 0, ConcentratedPoolError::ZeroLiquidity);
+            ensure!(
+                liquidity <= position.liquidity,
+                ConcentratedPoolError::InsufficientLiquidity
+            );
+            let liquidity_delta = i128::try_from(liquidity)
+                .map_err(|_| MathError::CastOverflow(84))?
+                .checked_neg()
+                .ok_or(MathError::SubUnderflow(81))?;
+            let (amount_0, amount_1) = self.amounts_for_liquidity(
+                position.tick_lower,
+                position.tick_upper,
+                liquidity,
+                false,
+            )?;
+            ensure!(
+                amount_0 >= amount_0_min && amount_1 >= amount_1_min,
+                ConcentratedPoolError::InsufficientOutputAmount
+            );
+            self.modify_position(&mut position, liquidity_delta)?;
+            // withdrawn amounts are collected together with the fees
+            position.tokens_owed_0 = position
+                .tokens_owed_0
+                .checked_add(amount_0)
+                .ok_or(MathError::AddOverflow(84))?;
+            position.tokens_owed_1 = position
+                .tokens_owed_1
+                .checked_add(amount_1)
+                .ok_or(MathError::AddOverflow(90))?;
+            self.positions.insert(position_id, &position);
+
+            self.env().emit_event(Burn {
+                owner: position.owner,
+                position_id,
+                tick_lower: position.tick_lower,
+                tick_upper: position.tick_upper,
+                liquidity,
+                amount_0,
+                amount_1,
+            });
+            Ok((amount_0, amount_1))
+        }
+
+        #[ink(message)]
+        fn collect(
+            &mut self,
+            position_id: u128,
+            to: AccountId,
+        ) -> Result<(u128, u128), ConcentratedPoolError> {
+            let mut position = self.get_owned_position(position_id)?;
+            if position.liquidity > 0 {
+                // accrue the fees earned so far
+                self.modify_position(&mut position, 0)?;
+            }
+            let (amount_0, amount_1) = (position.tokens_owed_0, position.tokens_owed_1);
+            position.tokens_owed_0 = 0;
+            position.tokens_owed_1 = 0;
+            self.positions.insert(position_id, &position);
+
+            self.decrease_reserves(amount_0, amount_1)?;
+            for (token, amount) in [(self.token_0, amount_0), (self.token_1, amount_1)] {
+                if amount > 0 {
+                    self.token_by_address(token).transfer(to, amount, vec![])?;
+                }
+            }
+
+            self.env().emit_event(Collect {
+                owner: position.owner,
+                position_id,
+                to,
+                amount_0,
+                amount_1,
+            });
+            Ok((amount_0, amount_1))
+        }
+
+        #[ink(message)]
+        fn burn_position(&mut self, position_id: u128) -> Result<(), ConcentratedPoolError> {
+            let position = self.get_owned_position(position_id)?;
+            ensure!(
+                position.liquidity == 0
+                    && position.tokens_owed_0 == 0
+                    && position.tokens_owed_1 == 0,
+                ConcentratedPoolError::PositionNotEmpty
+            );
+            self.positions.remove(position_id);
+            self.env().emit_event(PositionTransfer {
+                from: Some(position.owner),
+                to: None,
+                position_id,
+            });
+            Ok(())
+        }
+
+        #[ink(message)]
+        fn transfer_position(
+            &mut self,
+            position_id: u128,
+            to: AccountId,
+        ) -> Result<(), ConcentratedPoolError> {
+            let mut position = self.get_owned_position(position_id)?;
+            position.owner = to;
+            self.positions.insert(position_id, &position);
+            self.env().emit_event(PositionTransfer {
+                from: Some(self.env().caller()),
+                to: Some(to),
+                position_id,
+            });
+            Ok(())
+        }
+
+        #[ink(message)]
+        fn swap_exact_in(
+            &mut self,
+            token_in: AccountId,
+            token_out: AccountId,
+            token_in_amount: u128,
+            min_token_out_amount: u128,
+            to: AccountId,
+        ) -> Result<(u128, u128), ConcentratedPoolError> {
+            self._swap_exact_in(
+                token_in,
+                token_out,
+                Some(token_in_amount),
+                min_token_out_amount,
+                to,
+            )
+        }
+
+        #[ink(message)]
+        fn swap_exact_out(
+            &mut self,
+            token_in: AccountId,
+            token_out: AccountId,
+            token_out_amount: u128,
+            max_token_in_amount: u128,
+            to: AccountId,
+        ) -> Result<(u128, u128), ConcentratedPoolError> {
+            let zero_for_one = self.zero_for_one(token_in, token_out)?;
+            let outcome = self.compute_swap(zero_for_one, token_out_amount, false)?;
+            ensure!(
+                outcome.amount_in <= max_token_in_amount,
+                ConcentratedPoolError::TooLargeInputAmount
+            );
+            _ = self._transfer_in(token_in, zero_for_one, Some(outcome.amount_in))?;
+            let result = (outcome.amount_in, outcome.fee);
+            self.apply_swap(token_in, token_out, outcome, to)?;
+            Ok(result)
+        }
+
+        #[ink(message)]
+        fn swap_received(
+            &mut self,
+            token_in: AccountId,
+            token_out: AccountId,
+            min_token_out_amount: u128,
+            to: AccountId,
+        ) -> Result<(u128, u128), ConcentratedPoolError> {
+            self._swap_exact_in(token_in, token_out, None, min_token_out_amount, to)
+        }
+
+        #[ink(message)]
+        fn get_swap_amount_out(
+            &self,
+            token_in: AccountId,
+            token_out: AccountId,
+            token_in_amount: u128,
+        ) -> Result<(u128, u128), ConcentratedPoolError> {
+            let zero_for_one = self.zero_for_one(token_in, token_out)?;
+            let outcome = self.compute_swap(zero_for_one, token_in_amount, true)?;
+            Ok((outcome.amount_out, outcome.fee))
+        }
+
+        #[ink(message)]
+        fn get_swap_amount_in(
+            &self,
+            token_in: AccountId,
+            token_out: AccountId,
+            token_out_amount: u128,
+        ) -> Result<(u128, u128), ConcentratedPoolError> {
+            let zero_for_one = self.zero_for_one(token_in, token_out)?;
+            let outcome = self.compute_swap(zero_for_one, token_out_amount, false)?;
+            Ok((outcome.amount_in, outcome.fee))
+        }
+    }
+}
@@ -0,0 +1,77 @@
+use amm_helpers::{
+    constants::concentrated_pool::{MAX_TICK, MIN_TICK},
+    types::WrappedU256,
+};
+use primitive_types::U256;
+
+/// State of an initialized tick, i.e. a tick which is a boundary of some position.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, scale::Encode, scale::Decode)]
+#[cfg_attr(
+    feature = "std",
+    derive(scale_info::TypeInfo, ink::storage::traits::StorageLayout)
+)]
+pub struct TickInfo {
+    /// Total liquidity of the positions using the tick as a boundary.
+    pub liquidity_gross: u128,
+    /// Liquidity added when the tick is crossed left to right (removed right to left).
+    pub liquidity_net: i128,
+    /// Fee growth of token 0 on the other side of the tick (relative to the current tick).
+    pub fee_growth_outside_0: WrappedU256,
+    /// Fee growth of token 1 on the other side of the tick (relative to the current tick).
+    pub fee_growth_outside_1: WrappedU256,
+}
+
+impl TickInfo {
+    /// Flips the fee growth outside of the tick when the price crosses it.
+    pub fn cross(&mut self, fee_growth_global_0: U256, fee_growth_global_1: U256) {
+        self.fee_growth_outside_0 =
+            wrapping_sub(fee_growth_global_0, self.fee_growth_outside_0.0).into();
+        self.fee_growth_outside_1 =
+            wrapping_sub(fee_growth_global_1, self.fee_growth_outside_1.0).into();
+    }
+}
+
+/// Fee growth accumulators are allowed to overflow, their differences are computed
+/// with wrapping subtraction.
+pub fn wrapping_sub(a: U256, b: U256) -> U256 {
+    a.overflowing_sub(b).0
+}
+
+/// Returns the max liquidity of a single tick such that the liquidity of all
+/// initializable ticks sums up to at most `u128::MAX`.
+pub fn max_liquidity_per_tick(tick_spacing: i32) -> u128 {
+    let min_tick = MIN_TICK / tick_spacing * tick_spacing;
+    let max_tick = MAX_TICK / tick_spacing * tick_spacing;
+    let num_ticks = ((max_tick - min_tick) / tick_spacing) as u128 + 1;
+    u128::MAX / num_ticks
+}
+
+/// Computes the fee growth of both tokens inside the price range [`tick_lower`, `tick_upper`).
+pub fn fee_growth_inside(
+    (tick_lower, lower): (i32, &TickInfo),
+    (tick_upper, upper): (i32, &TickInfo),
+    tick_current: i32,
+    fee_growth_global_0: U256,
+    fee_growth_global_1: U256,
+) -> (U256, U256) {
+    let (below_0, below_1) = if tick_current >= tick_lower {
+        (lower.fee_growth_outside_0.0, lower.fee_growth_outside_1.0)
+    } else {
+        (
+            wrapping_sub(fee_growth_global_0, lower.fee_growth_outside_0.0),
+            wrapping_sub(fee_growth_global_1, lower.fee_growth_outside_1.0),
+        )
+    };
+    let (above_0, above_1) = if tick_current < tick_upper {
+        (upper.fee_growth_outside_0.0, upper.fee_growth_outside_1.0)
+    } else {
+        (
+            wrapping_sub(fee_growth_global_0, upper.fee_growth_outside_0.0),
+            wrapping_sub(fee_growth_global_1, upper.fee_growth_outside_1.0),
+        )
+    };
+    (
+        wrapping_sub(wrapping_sub(fee_growth_global_0, below_0), above_0),
+        wrapping_sub(wrapping_sub(fee_growth_global_1, below_1), above_1),
+    )
+}
@@ -0,0 +1,92 @@
+use amm_helpers::types::WrappedU256;
+use ink::storage::Mapping;
+use primitive_types::U256;
+
+/// Bitmap of the initialized ticks, packed into 256-bit words.
+///
+/// Only ticks which are multiples of the tick spacing are tracked, so the bit position
+/// of a tick is its index compressed by the tick spacing.
+#[ink::storage_item]
+#[derive(Debug)]
+pub struct TickBitmap {
+    words: Mapping<i16, WrappedU256>,
+}
+
+/// Returns the word index and the bit position of the `compressed` tick.
+fn position(compressed: i32) -> (i16, u8) {
+    ((compressed >> 8) as i16, (compressed & 0xff) as u8)
+}
+
+impl TickBitmap {
+    #[allow(clippy::new_without_default)]
+    pub fn new() -> Self {
+        Self {
+            words: Default::default(),
+        }
+    }
+
+    fn word(&self, index: i16) -> U256 {
+        self.words.get(index).unwrap_or_default().0
+    }
+
+    /// Flips the initialized state of the `tick`.
+    ///
+    /// NOTE: `tick` must be a multiple of the `tick_spacing`.
+    pub fn flip_tick(&mut self, tick: i32, tick_spacing: i32) {
+        let (index, bit) = position(tick / tick_spacing);
+        let word = self.word(index) ^ (U256::one() << bit);
+        if word.is_zero() {
+            self.words.remove(index);
+        } else {
+            self.words.insert(index, &WrappedU256::from(word));
+        }
+    }
+
+    /// Returns the next initialized tick in the same word as the `tick`, either to the
+    /// left (less than or equal to the `tick`) if `lte` is set, or to the right (greater
+    /// than the `tick`) otherwise.
+    ///
+    /// If there is no initialized tick in the word, returns the tick at the word boundary
+    /// together with `false`.
+    pub fn next_initialized_tick_within_one_word(
+        &self,
+        tick: i32,
+        tick_spacing: i32,
+        lte: bool,
+    ) -> (i32, bool) {
+        let compressed = tick.div_euclid(tick_spacing);
+        if lte {
+            let (index, bit) = position(compressed);
+            // all the bits at or to the right of the current bit
+            let mask = (U256::one() << bit) - 1 + (U256::one() << bit);
+            let masked = self.word(index) & mask;
+            if masked.is_zero() {
+                ((compressed - bit as i32) * tick_spacing, false)
+            } else {
+                let most_significant = masked.bits() as i32 - 1;
+                (
+                    (compressed - (bit as i32 - most_significant)) * tick_spacing,
+                    true,
+                )
+            }
+        } else {
+            // start from the word of the next tick, since the current tick state doesn't matter
+            let (index, bit) = position(compressed + 1);
+            // all the bits at or to the left of the bit
+            let mask = !((U256::one() << bit) - 1);
+            let masked = self.word(index) & mask;
+            if masked.is_zero() {
+                (
+                    (compressed + 1 + (u8::MAX - bit) as i32) * tick_spacing,
+                    false,
+                )
+            } else {
+                let least_significant = masked.trailing_zeros() as i32;
+                (
+                    (compressed + 1 + (least_significant - bit as i32)) * tick_spacing,
+                    true,
+                )
+            }
+        }
+    }
+}
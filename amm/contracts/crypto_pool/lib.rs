@@ -0,0 +1,1365 @@
+#![cfg_attr(not(feature = "std"), no_std, no_main)]
+mod oracle;
+/// Crypto-swap implementation based on the CurveFi V2 (cryptoswap) model.
+///
+/// This pool contract supports 2 PSP22 tokens of volatile prices, e.g. AZERO x USDC.
+///
+/// The liquidity is concentrated around the price scale. The balances of the second token
+/// are scaled with it, so that the pool works as a stableswap pool around the price scale
+/// and as a constant product pool far from it.
+///
+/// The pool tracks the EMA of its spot price. When the pool has earned enough in fees,
+/// the price scale is moved (repegged) towards the EMA price, so that the liquidity follows
+/// the market price. Half of the pool's profit is reserved for such repegging.
+#[ink::contract]
+pub mod crypto_pool {
+    use crate::oracle::EmaOracle;
+    use amm_helpers::{
+        constants::{
+            crypto_pool::{
+                MAX_A, MAX_ADJUSTMENT_STEP, MAX_ALLOWED_EXTRA_PROFIT, MAX_GAMMA, MAX_MA_HALF_TIME,
+                MAX_PROTOCOL_FEE, MIN_A, MIN_GAMMA, MIN_MA_HALF_TIME, N_COINS, PRECISION,
+            },
+            stable_pool::{FEE_DENOM, TOKEN_TARGET_DECIMALS},
+        },
+        crypto_swap_math::{self as math, fees::DynamicFees},
+        ensure,
+        math::casted_mul,
+        stable_swap_math::compute_amounts_given_lp,
+    };
+    use ink::contract_ref;
+    use ink::prelude::{
+        string::{String, ToString},
+        {vec, vec::Vec},
+    };
+    use psp22::{PSP22Data, PSP22Error, PSP22Event, PSP22Metadata, PSP22};
+    use traits::{
+        CryptoPool, CryptoPoolError, MathError, Ownable2Step, Ownable2StepData, Ownable2StepResult,
+    };
+
+    #[ink(event)]
+    pub struct AddLiquidity {
+        #[ink(topic)]
+        pub provider: AccountId,
+        pub token_amounts: Vec<u128>,
+        pub shares: u128,
+        #[ink(topic)]
+        pub to: AccountId,
+    }
+
+    #[ink(event)]
+    pub struct RemoveLiquidity {
+        #[ink(topic)]
+        pub provider: AccountId,
+        pub token_amounts: Vec<u128>,
+        pub shares: u128,
+        #[ink(topic)]
+        pub to: AccountId,
+    }
+
+    #[ink(event)]
+    pub struct Swap {
+        #[ink(topic)]
+        pub sender: AccountId,
+        pub token_in: AccountId,
+        pub amount_in: u128,
+        pub token_out: AccountId,
+        pub amount_out: u128,
+        #[ink(topic)]
+        pub to: AccountId,
+    }
+
+    #[ink(event)]
+    pub struct Sync {
+        pub reserves: Vec<u128>,
+    }
+
+    #[ink(event)]
+    pub struct Repeg {
+        pub price_scale: u128,
+        pub virtual_price: u128,
+    }
+
+    #[ink(event)]
+    pub struct Approval {
+        /// Account providing allowance.
+        #[ink(topic)]
+        pub owner: AccountId,
+        /// Allowance beneficiary.
+        #[ink(topic)]
+        pub spender: AccountId,
+        /// New allowance amount.
+        pub amount: u128,
+    }
+
+    /// Event emitted when transfer of tokens occurs.
+    #[ink(event)]
+    pub struct Transfer {
+        /// Transfer sender. `None` in case of minting new tokens.
+        #[ink(topic)]
+        pub from: Option<AccountId>,
+        /// Transfer recipient. `None` in case of burning tokens.
+        #[ink(topic)]
+        pub to: Option<AccountId>,
+        /// Amount of tokens transferred (or minted/burned).
+        pub value: u128,
+    }
+
+    #[ink(event)]
+    pub struct TransferOwnershipInitiated {
+        #[ink(topic)]
+        pub new_owner: AccountId,
+    }
+
+    #[ink(event)]
+    pub struct TransferOwnershipAccepted {
+        #[ink(topic)]
+        pub new_owner: AccountId,
+    }
+
+    #[ink(event)]
+    pub struct OwnershipRenounced {}
+
+    #[ink(event)]
+    pub struct FeeReceiverChanged {
+        #[ink(topic)]
+        pub new_fee_receiver: Option<AccountId>,
+    }
+
+    #[ink(event)]
+    pub struct FeeChanged {
+        pub mid_fee: u32,
+        pub out_fee: u32,
+        pub fee_gamma: u128,
+        pub protocol_fee: u32,
+    }
+
+    #[ink(event)]
+    pub struct RebalancingParamsChanged {
+        pub allowed_extra_profit: u128,
+        pub adjustment_step: u128,
+        pub ma_half_time: u64,
+    }
+
+    #[ink::storage_item]
+    #[derive(Debug)]
+    pub struct CryptoPoolData {
+        /// List of tokens.
+        tokens: Vec<AccountId>,
+        /// Tokens precision factors used for normalization.
+        precisions: Vec<u128>,
+        /// Reserves of tokens
+        reserves: Vec<u128>,
+        /// Amplification coefficient, multiplied by N^N * A_MULTIPLIER.
+        amp: u128,
+        /// Distance from the constant product curve, with 1e18 precision.
+        gamma: u128,
+        /// Dynamic trade fees
+        fees: DynamicFees,
+        /// Part of the pool's profit which goes to the protocol, with 1e9 precision.
+        protocol_fee: u32,
+        /// Who receives protocol fees (if any).
+        fee_receiver: Option<AccountId>,
+        /// Profit (in terms of virtual price) above the half of the total profit
+        /// which must be left after repegging.
+        allowed_extra_profit: u128,
+        /// Min relative step of the price scale adjustment.
+        adjustment_step: u128,
+        /// Price of the second token in terms of the first one around which
+        /// the liquidity is concentrated.
+        price_scale: u128,
+        /// EMA price oracle.
+        oracle: EmaOracle,
+        /// Invariant of the current balances.
+        d: u128,
+        /// Virtual price of the LP token.
+        virtual_price: u128,
+        /// Growth of the virtual price caused by fees, not decreased by repegging.
+        xcp_profit: u128,
+        /// `xcp_profit` as of the last protocol fee claim.
+        xcp_profit_a: u128,
+        /// Whether the pool has enough profit to repeg, but the price scale was not adjusted yet.
+        not_adjusted: bool,
+    }
+
+    #[ink(storage)]
+    pub struct CryptoPoolContract {
+        ownable: Ownable2StepData,
+        pool: CryptoPoolData,
+        psp22: PSP22Data,
+    }
+
+    impl CryptoPoolContract {
+        #[allow(clippy::too_many_arguments)]
+        #[ink(constructor)]
+        pub fn new(
+            tokens: Vec<AccountId>,
+            tokens_decimals: Vec<u8>,
+            amp: u128,
+            gamma: u128,
+            mid_fee: u32,
+            out_fee: u32,
+            fee_gamma: u128,
+            allowed_extra_profit: u128,
+            adjustment_step: u128,
+            ma_half_time: u64,
+            initial_price: u128,
+            owner: AccountId,
+            protocol_fee: u32,
+            fee_receiver: Option<AccountId>,
+        ) -> Result<Self, CryptoPoolError> {
+            ensure!(
+                tokens.len() == N_COINS && tokens_decimals.len() == N_COINS,
+                CryptoPoolError::IncorrectTokenCount
+            );
+            ensure!(tokens[0] != tokens[1], CryptoPoolError::IdenticalTokenId);
+            ensure!(
+                tokens_decimals.iter().all(|&d| d <= TOKEN_TARGET_DECIMALS),
+                CryptoPoolError::TooLargeTokenDecimal
+            );
+            ensure!(
+                (MIN_A..=MAX_A).contains(&amp) && (MIN_GAMMA..=MAX_GAMMA).contains(&gamma),
+                CryptoPoolError::InvalidAmpGamma
+            );
+            ensure!(
+                protocol_fee <= MAX_PROTOCOL_FEE,
+                CryptoPoolError::InvalidFee
+            );
+            Self::check_rebalancing_params(allowed_extra_profit, adjustment_step, ma_half_time)?;
+            ensure!(
+                initial_price > 0 && initial_price <= PRECISION * PRECISION,
+                CryptoPoolError::InvalidPrice
+            );
+
+            let precisions = tokens_decimals
+                .iter()
+                .map(|&decimal| {
+                    10u128.pow(TOKEN_TARGET_DECIMALS.checked_sub(decimal).unwrap() as u32)
+                })
+                .collect();
+            Ok(Self {
+                ownable: Ownable2StepData::new(owner),
+                pool: CryptoPoolData {
+                    tokens,
+                    precisions,
+                    reserves: vec![0; N_COINS],
+                    amp,
+                    gamma,
+                    fees: DynamicFees::new(mid_fee, out_fee, fee_gamma)
+                        .ok_or(CryptoPoolError::InvalidFee)?,
+                    protocol_fee,
+                    fee_receiver,
+                    allowed_extra_profit,
+                    adjustment_step,
+                    price_scale: initial_price,
+                    oracle: EmaOracle::new(
+                        initial_price,
+                        Self::env().block_timestamp(),
+                        ma_half_time,
+                    ),
+                    d: 0,
+                    virtual_price: 0,
+                    xcp_profit: 0,
+                    xcp_profit_a: 0,
+                    not_adjusted: false,
+                },
+                psp22: PSP22Data::default(),
+            })
+        }
+
+        fn check_rebalancing_params(
+            allowed_extra_profit: u128,
+            adjustment_step: u128,
+            ma_half_time: u64,
+        ) -> Result<(), CryptoPoolError> {
+            ensure!(
+                allowed_extra_profit <= MAX_ALLOWED_EXTRA_PROFIT
+                    && adjustment_step <= MAX_ADJUSTMENT_STEP
+                    && (MIN_MA_HALF_TIME..=MAX_MA_HALF_TIME).contains(&ma_half_time),
+                CryptoPoolError::InvalidRebalancingParams
+            );
+            Ok(())
+        }
+
+        /// A helper function emitting events contained in a vector of PSP22Events.
+        fn emit_events(&self, events: Vec<PSP22Event>) {
+            for event in events {
+                match event {
+                    PSP22Event::Transfer { from, to, value } => {
+                        self.env().emit_event(Transfer { from, to, value })
+                    }
+                    PSP22Event::Approval {
+                        owner,
+                        spender,
+                        amount,
+                    } => self.env().emit_event(Approval {
+                        owner,
+                        spender,
+                        amount,
+                    }),
+                }
+            }
+        }
+
+        #[inline]
+        fn token_by_address(&self, address: AccountId) -> contract_ref!(PSP22) {
+            address.into()
+        }
+
+        #[inline]
+        fn token_by_id(&self, token_id: usize) -> contract_ref!(PSP22) {
+            self.pool.tokens[token_id].into()
+        }
+
+        fn token_id(&self, token: AccountId) -> Result<usize, CryptoPoolError> {
+            self.pool
+                .tokens
+                .iter()
+                .position(|&id| id == token)
+                .ok_or(CryptoPoolError::InvalidTokenId(token))
+        }
+
+        /// Checks if tokens are valid and returns the tokens ids
+        fn check_tokens(
+            &self,
+            token_in: AccountId,
+            token_out: AccountId,
+        ) -> Result<(usize, usize), CryptoPoolError> {
+            ensure!(token_in != token_out, CryptoPoolError::IdenticalTokenId);
+            //check token ids
+            let token_in_id = self.token_id(token_in)?;
+            let token_out_id = self.token_id(token_out)?;
+            Ok((token_in_id, token_out_id))
+        }
+
+        /// Scales `amount` of `token_id` to 18 decimal places and,
+        /// for the second token, to the value in terms of the first token.
+        fn amount_to_xp(
+            &self,
+            token_id: usize,
+            amount: u128,
+            round_up: bool,
+        ) -> Result<u128, CryptoPoolError> {
+            let amount = amount
+                .checked_mul(self.pool.precisions[token_id])
+                .ok_or(MathError::MulOverflow(181))?;
+            if token_id == 0 {
+                return Ok(amount);
+            }
+            let (quotient, remainder) =
+                casted_mul(amount, self.pool.price_scale).div_mod(PRECISION.into());
+            let quotient = if round_up && !remainder.is_zero() {
+                quotient + 1
+            } else {
+                quotient
+            };
+            Ok(quotient
+                .try_into()
+                .map_err(|_| MathError::CastOverflow(181))?)
+        }
+
+        /// Inverse of `amount_to_xp`.
+        fn amount_from_xp(
+            &self,
+            token_id: usize,
+            amount: u128,
+            round_up: bool,
+        ) -> Result<u128, CryptoPoolError> {
+            let divisor = if token_id == 0 {
+                self.pool.precisions[0].into()
+            } else {
+                casted_mul(self.pool.precisions[1], self.pool.price_scale)
+            };
+            let multiplier = if token_id == 0 { 1 } else { PRECISION };
+            let (quotient, remainder) = casted_mul(amount, multiplier).div_mod(divisor);
+            let quotient = if round_up && !remainder.is_zero() {
+                quotient + 1
+            } else {
+                quotient
+            };
+            Ok(quotient
+                .try_into()
+                .map_err(|_| MathError::CastOverflow(182))?)
+        }
+
+        /// Returns the balances scaled with the price scale.
+        fn xp(&self, reserves: &[u128]) -> Result<[u128; 2], CryptoPoolError> {
+            Ok([
+                self.amount_to_xp(0, reserves[0], false)?,
+                self.amount_to_xp(1, reserves[1], false)?,
+            ])
+        }
+
+        fn ensure_liquidity(&self) -> Result<(), CryptoPoolError> {
+            ensure!(
+                self.psp22.total_supply() > 0,
+                CryptoPoolError::InsufficientLiquidity
+            );
+            Ok(())
+        }
+
+        /// Returns a tuple of (amount out, fee) for swapping `token_in_amount`.
+        fn compute_swap_to(
+            &self,
+            token_in_id: usize,
+            token_in_amount: u128,
+        ) -> Result<(u128, u128), CryptoPoolError> {
+            self.ensure_liquidity()?;
+            let token_out_id = 1 - token_in_id;
+            let (amount_out, fee) = math::swap_to(
+                self.pool.amp,
+                self.pool.gamma,
+                &self.pool.fees,
+                self.xp(&self.pool.reserves)?,
+                token_in_id,
+                self.amount_to_xp(token_in_id, token_in_amount, false)?,
+            )?;
+            Ok((
+                self.amount_from_xp(token_out_id, amount_out, false)?,
+                self.amount_from_xp(token_out_id, fee, false)?,
+            ))
+        }
+
+        /// Returns a tuple of (amount in, fee) for swapping to `token_out_amount`.
+        fn compute_swap_from(
+            &self,
+            token_out_id: usize,
+            token_out_amount: u128,
+        ) -> Result<(u128, u128), CryptoPoolError> {
+            self.ensure_liquidity()?;
+            ensure!(
+                token_out_amount < self.pool.reserves[token_out_id],
+                CryptoPoolError::InsufficientLiquidity
+            );
+            let token_in_id = 1 - token_out_id;
+            let (amount_in, fee) = math::swap_from(
+                self.pool.amp,
+                self.pool.gamma,
+                &self.pool.fees,
+                self.xp(&self.pool.reserves)?,
+                token_out_id,
+                self.amount_to_xp(token_out_id, token_out_amount, true)?,
+            )?;
+            Ok((
+                self.amount_from_xp(token_in_id, amount_in, true)?,
+                self.amount_from_xp(token_out_id, fee, false)?,
+            ))
+        }
+
+        /// Returns a tuple of (lpt amount, new invariant) for depositing `amounts`.
+        fn compute_deposit(&self, amounts: &[u128]) -> Result<(u128, u128), CryptoPoolError> {
+            ensure!(
+                amounts.len() == N_COINS,
+                CryptoPoolError::IncorrectAmountsCount
+            );
+            ensure!(
+                amounts.iter().any(|&amount| amount > 0),
+                CryptoPoolError::ZeroAmounts
+            );
+            let new_reserves = self
+                .pool
+                .reserves
+                .iter()
+                .zip(amounts.iter())
+                .map(|(&reserve, &amount)| {
+                    reserve
+                        .checked_add(amount)
+                        .ok_or(MathError::AddOverflow(183))
+                })
+                .collect::<Result<Vec<u128>, MathError>>()?;
+            let new_xp = self.xp(&new_reserves)?;
+            let total_supply = self.psp22.total_supply();
+            if total_supply == 0 {
+                // The initial deposit determines the invariant, so both of the tokens are required.
+                ensure!(
+                    amounts.iter().all(|&amount| amount > 0),
+                    CryptoPoolError::ZeroAmounts
+                );
+                let d = math::compute_d(self.pool.amp, self.pool.gamma, new_xp)?;
+                // initial virtual price is 1
+                Ok((math::compute_xcp(d, self.pool.price_scale)?, d))
+            } else {
+                Ok(math::compute_lp_amount_for_deposit(
+                    self.pool.amp,
+                    self.pool.gamma,
+                    &self.pool.fees,
+                    self.xp(&self.pool.reserves)?,
+                    new_xp,
+                    self.pool.d,
+                    total_supply,
+                )?)
+            }
+        }
+
+        /// Returns a tuple of (withdraw amount, new invariant) for burning `shares` in `token_id`.
+        fn compute_one_coin_withdraw(
+            &self,
+            token_id: usize,
+            shares: u128,
+        ) -> Result<(u128, u128), CryptoPoolError> {
+            ensure!(
+                shares < self.psp22.total_supply(),
+                CryptoPoolError::InsufficientLiquidity
+            );
+            let (amount, d) = math::compute_amount_for_one_coin_withdraw(
+                self.pool.amp,
+                self.pool.gamma,
+                &self.pool.fees,
+                self.xp(&self.pool.reserves)?,
+                self.pool.d,
+                shares,
+                self.psp22.total_supply(),
+                token_id,
+            )?;
+            Ok((self.amount_from_xp(token_id, amount, false)?, d))
+        }
+
+        /// Computes the virtual price of the LP token for invariant `d` at `price_scale`.
+        fn compute_virtual_price(
+            &self,
+            d: u128,
+            price_scale: u128,
+        ) -> Result<u128, CryptoPoolError> {
+            let xcp = math::compute_xcp(d, price_scale)?;
+            Ok(casted_mul(PRECISION, xcp)
+                .checked_div(self.psp22.total_supply().into())
+                .ok_or(MathError::DivByZero(184))?
+                .try_into()
+                .map_err(|_| MathError::CastOverflow(184))?)
+        }
+
+        /// Updates the price oracle and the pool's profit after an operation which changed
+        /// the reserves and repegs the pool if it is profitable enough.
+        ///
+        /// `last_price` is the price of the operation, if it is not given, the spot price
+        /// of the pool is used. `new_d` is the invariant after the operation, if it is not given,
+        /// it is computed from the reserves.
+        fn tweak_price(
+            &mut self,
+            last_price: Option<u128>,
+            new_d: Option<u128>,
+        ) -> Result<(), CryptoPoolError> {
+            let (amp, gamma, price_scale) = (self.pool.amp, self.pool.gamma, self.pool.price_scale);
+            let xp = self.xp(&self.pool.reserves)?;
+            let d_unadjusted = match new_d {
+                Some(d) => d,
+                None => math::compute_d(amp, gamma, xp)?,
+            };
+            let last_price = match last_price {
+                Some(price) => price,
+                None => math::compute_spot_price(amp, gamma, xp, d_unadjusted, price_scale)?,
+            };
+            let price_oracle = self
+                .pool
+                .oracle
+                .update(self.env().block_timestamp(), last_price)?;
+
+            // update profit without the price scale adjustment first
+            let old_virtual_price = self.pool.virtual_price;
+            let virtual_price = self.compute_virtual_price(d_unadjusted, price_scale)?;
+            ensure!(
+                virtual_price >= old_virtual_price,
+                CryptoPoolError::VirtualPriceDecreased
+            );
+            let xcp_profit: u128 = casted_mul(self.pool.xcp_profit, virtual_price)
+                .checked_div(old_virtual_price.into())
+                .ok_or(MathError::DivByZero(185))?
+                .try_into()
+                .map_err(|_| MathError::CastOverflow(185))?;
+            self.pool.xcp_profit = xcp_profit;
+
+            // relative distance between the oracle price and the price scale
+            let ratio: u128 = (casted_mul(price_oracle, PRECISION) / price_scale)
+                .try_into()
+                .unwrap_or(u128::MAX);
+            let norm = ratio.abs_diff(PRECISION);
+            let adjustment_step = self.pool.adjustment_step.max(norm / 5);
+
+            // repeg only if the half of the profit (the other half goes to LPs)
+            // is enough to cover the loss of the adjustment
+            if !self.pool.not_adjusted
+                && norm > adjustment_step
+                && (virtual_price * 2).saturating_sub(PRECISION)
+                    > xcp_profit.saturating_add(2 * self.pool.allowed_extra_profit)
+            {
+                self.pool.not_adjusted = true;
+            }
+
+            if self.pool.not_adjusted {
+                if norm > adjustment_step {
+                    // move the price scale towards the oracle price by `adjustment_step`
+                    let new_price_scale: u128 = ((casted_mul(price_scale, norm - adjustment_step)
+                        + casted_mul(adjustment_step, price_oracle))
+                        / norm)
+                        .try_into()
+                        .map_err(|_| MathError::CastOverflow(186))?;
+                    let new_xp = [
+                        xp[0],
+                        (casted_mul(xp[1], new_price_scale) / price_scale)
+                            .try_into()
+                            .map_err(|_| MathError::CastOverflow(186))?,
+                    ];
+                    let new_d = math::compute_d(amp, gamma, new_xp)?;
+                    let new_virtual_price = self.compute_virtual_price(new_d, new_price_scale)?;
+                    if new_virtual_price > PRECISION
+                        && (2 * new_virtual_price).saturating_sub(PRECISION) > xcp_profit
+                    {
+                        self.pool.price_scale = new_price_scale;
+                        self.pool.d = new_d;
+                        self.pool.virtual_price = new_virtual_price;
+                        self.env().emit_event(Repeg {
+                            price_scale: new_price_scale,
+                            virtual_price: new_virtual_price,
+                        });
+                        return Ok(());
+                    }
+                }
+                // not enough profit or the price scale is close enough to the oracle price
+                self.pool.not_adjusted = false;
+                self.pool.d = d_unadjusted;
+                self.pool.virtual_price = virtual_price;
+                return self.claim_protocol_fee();
+            }
+
+            self.pool.d = d_unadjusted;
+            self.pool.virtual_price = virtual_price;
+            Ok(())
+        }
+
+        /// Mints the protocol's part of the profit since the last claim to the `fee_receiver`
+        /// (if one is set) as LP tokens.
+        fn claim_protocol_fee(&mut self) -> Result<(), CryptoPoolError> {
+            if self.pool.xcp_profit <= self.pool.xcp_profit_a {
+                return Ok(());
+            }
+            if let Some(fee_to) = self.pool.fee_receiver {
+                // virtual price growth of the protocol, the profit is half of the xcp_profit growth
+                let fees: u128 = (casted_mul(
+                    self.pool.xcp_profit - self.pool.xcp_profit_a,
+                    self.pool.protocol_fee.into(),
+                ) / (2 * FEE_DENOM as u128))
+                    .try_into()
+                    .map_err(|_| MathError::CastOverflow(187))?;
+                if fees > 0 {
+                    let virtual_price = self.pool.virtual_price;
+                    // mint LP tokens worth `fees / virtual_price` of the pool
+                    let frac = (casted_mul(virtual_price, PRECISION)
+                        / virtual_price
+                            .checked_sub(fees)
+                            .filter(|&x| x > 0)
+                            .ok_or(MathError::SubUnderflow(187))?)
+                    .saturating_sub(PRECISION.into());
+                    let protocol_fee_lp: u128 = (frac * self.psp22.total_supply() / PRECISION)
+                        .try_into()
+                        .map_err(|_| MathError::CastOverflow(187))?;
+                    let events = self.psp22.mint(fee_to, protocol_fee_lp)?;
+                    self.emit_events(events);
+                    self.pool.xcp_profit -= 2 * fees;
+                }
+            }
+            self.pool.virtual_price =
+                self.compute_virtual_price(self.pool.d, self.pool.price_scale)?;
+            self.pool.xcp_profit_a = self.pool.xcp_profit;
+            Ok(())
+        }
+
+        fn decrease_reserve(
+            &mut self,
+            token_id: usize,
+            amount: u128,
+        ) -> Result<(), CryptoPoolError> {
+            self.pool.reserves[token_id] = self.pool.reserves[token_id]
+                .checked_sub(amount)
+                .ok_or(MathError::SubUnderflow(188))?;
+            Ok(())
+        }
+
+        fn increase_reserve(
+            &mut self,
+            token_id: usize,
+            amount: u128,
+        ) -> Result<(), CryptoPoolError> {
+            self.pool.reserves[token_id] = self.pool.reserves[token_id]
+                .checked_add(amount)
+                .ok_or(MathError::AddOverflow(188))?;
+            Ok(())
+        }
+
+        /// Returns the price of the second token in terms of the first one
+        /// given by a swap of `amount_in` of `token_in_id` to `amount_out`.
+        ///
+        /// Returns `None` if the swap is too small to give a reliable price.
+        fn swap_price(
+            &self,
+            token_in_id: usize,
+            amount_in: u128,
+            amount_out: u128,
+        ) -> Option<u128> {
+            const MIN_AMOUNT: u128 = 100_000;
+            if amount_in <= MIN_AMOUNT || amount_out <= MIN_AMOUNT {
+                return None;
+            }
+            let (amount_0, amount_1) = if token_in_id == 0 {
+                (amount_in, amount_out)
+            } else {
+                (amount_out, amount_in)
+            };
+            let amount_0 = casted_mul(amount_0, self.pool.precisions[0]);
+            let amount_1 = casted_mul(amount_1, self.pool.precisions[1]);
+            (amount_0 * PRECISION / amount_1).try_into().ok()
+        }
+
+        fn _swap_exact_in(
+            &mut self,
+            token_in: AccountId,
+            token_out: AccountId,
+            token_in_amount: Option<u128>,
+            min_token_out_amount: u128,
+            to: AccountId,
+        ) -> Result<(u128, u128), CryptoPoolError> {
+            //check token ids
+            let (token_in_id, token_out_id) = self.check_tokens(token_in, token_out)?;
+
+            // get transfered token_in amount
+            let token_in_amount = self._transfer_in(token_in_id, token_in_amount)?;
+
+            // calc amount_out and fees
+            let (token_out_amount, fee) = self.compute_swap_to(token_in_id, token_in_amount)?;
+
+            // Check if swapped amount is not less than min_token_out_amount
+            ensure!(
+                token_out_amount >= min_token_out_amount,
+                CryptoPoolError::InsufficientOutputAmount
+            );
+            // update reserves
+            self.increase_reserve(token_in_id, token_in_amount)?;
+            self.decrease_reserve(token_out_id, token_out_amount)?;
+
+            // update price oracle and repeg
+            let price = self.swap_price(token_in_id, token_in_amount, token_out_amount);
+            self.tweak_price(price, None)?;
+
+            // transfer token_out
+            self.token_by_address(token_out)
+                .transfer(to, token_out_amount, vec![])?;
+
+            self.env().emit_event(Swap {
+                sender: self.env().caller(),
+                token_in,
+                amount_in: token_in_amount,
+                token_out,
+                amount_out: token_out_amount,
+                to,
+            });
+            self.env().emit_event(Sync {
+                reserves: self.reserves(),
+            });
+            Ok((token_out_amount, fee))
+        }
+
+        fn _swap_exact_out(
+            &mut self,
+            token_in: AccountId,
+            token_out: AccountId,
+            token_out_amount: u128,
+            max_token_in_amount: u128,
+            to: AccountId,
+        ) -> Result<(u128, u128), CryptoPoolError> {
+            //check token ids
+            let (token_in_id, token_out_id) = self.check_tokens(token_in, token_out)?;
+
+            ensure!(
+                token_out_amount > 0,
+                CryptoPoolError::InsufficientOutputAmount
+            );
+
+            // calc amount_in and fees
+            let (token_in_amount, fee) = self.compute_swap_from(token_out_id, token_out_amount)?;
+
+            // Check if in token_in_amount is as constrained by the user
+            ensure!(
+                token_in_amount <= max_token_in_amount,
+                CryptoPoolError::InsufficientInputAmount
+            );
+            // update reserves
+            self.increase_reserve(token_in_id, token_in_amount)?;
+            self.decrease_reserve(token_out_id, token_out_amount)?;
+
+            // update price oracle and repeg
+            let price = self.swap_price(token_in_id, token_in_amount, token_out_amount);
+            self.tweak_price(price, None)?;
+
+            // transfer token_in
+            _ = self._transfer_in(token_in_id, Some(token_in_amount))?;
+
+            // transfer token_out
+            self.token_by_address(token_out)
+                .transfer(to, token_out_amount, vec![])?;
+
+            self.env().emit_event(Swap {
+                sender: self.env().caller(),
+                token_in,
+                amount_in: token_in_amount,
+                token_out,
+                amount_out: token_out_amount,
+                to,
+            });
+            self.env().emit_event(Sync {
+                reserves: self.reserves(),
+            });
+            // note that fee is applied to token_out (same as in _swap_exact_in)
+            Ok((token_in_amount, fee))
+        }
+
+        /// Handles PSP22 token transfer,
+        ///
+        /// If `amount` is `Some(amount)`, transfer this amount of `token_id`
+        /// from the caller to this contract.
+        ///
+        /// If `amount` of `None`, calculate the difference between
+        /// this contract balance and recorded reserve of `token_id`.
+        fn _transfer_in(
+            &self,
+            token_id: usize,
+            amount: Option<u128>,
+        ) -> Result<u128, CryptoPoolError> {
+            let mut token = self.token_by_id(token_id);
+            let amount = if let Some(token_amount) = amount {
+                token.transfer_from(
+                    self.env().caller(),
+                    self.env().account_id(),
+                    token_amount,
+                    vec![],
+                )?;
+                token_amount
+            } else {
+                token
+                    .balance_of(self.env().account_id())
+                    .checked_sub(self.pool.reserves[token_id])
+                    .ok_or(MathError::SubUnderflow(189))?
+            };
+            ensure!(amount > 0, CryptoPoolError::InsufficientInputAmount);
+            Ok(amount)
+        }
+    }
+
+    impl CryptoPool for CryptoPoolContract {
+        #[ink(message)]
+        fn add_liquidity(
+            &mut self,
+            min_share_amount: u128,
+            amounts: Vec<u128>,
+            to: AccountId,
+        ) -> Result<u128, CryptoPoolError> {
+            let is_initial = self.psp22.total_supply() == 0;
+            let (shares, d) = self.compute_deposit(&amounts)?;
+
+            // Check min shares
+            ensure!(
+                shares > 0 && shares >= min_share_amount,
+                CryptoPoolError::InsufficientLiquidityMinted
+            );
+
+            // transfer amounts
+            for (id, &token) in self.pool.tokens.iter().enumerate() {
+                if amounts[id] > 0 {
+                    self.token_by_address(token).transfer_from(
+                        self.env().caller(),
+                        self.env().account_id(),
+                        amounts[id],
+                        vec![],
+                    )?;
+                }
+            }
+
+            // mint shares
+            let events = self.psp22.mint(to, shares)?;
+            self.emit_events(events);
+
+            // update reserves
+            for (i, &amount) in amounts.iter().enumerate() {
+                self.increase_reserve(i, amount)?;
+            }
+
+            if is_initial {
+                self.pool.d = d;
+                self.pool.virtual_price = PRECISION;
+                self.pool.xcp_profit = PRECISION;
+                self.pool.xcp_profit_a = PRECISION;
+            } else {
+                self.tweak_price(None, Some(d))?;
+            }
+
+            self.env().emit_event(AddLiquidity {
+                provider: self.env().caller(),
+                token_amounts: amounts,
+                shares,
+                to,
+            });
+            self.env().emit_event(Sync {
+                reserves: self.reserves(),
+            });
+            Ok(shares)
+        }
+
+        #[ink(message)]
+        fn remove_liquidity_by_shares(
+            &mut self,
+            shares: u128,
+            min_amounts: Vec<u128>,
+            to: AccountId,
+        ) -> Result<Vec<u128>, CryptoPoolError> {
+            let total_supply = self.psp22.total_supply();
+            let amounts = compute_amounts_given_lp(shares, &self.pool.reserves, total_supply)?;
+
+            // Check if enough tokens are withdrawn
+            ensure!(
+                amounts
+                    .iter()
+                    .zip(min_amounts.iter())
+                    .all(|(amount, min_amount)| amount >= min_amount),
+                CryptoPoolError::InsufficientOutputAmount
+            );
+            // Check that at least one amount is non-zero
+            ensure!(
+                amounts.iter().any(|&amount| amount > 0),
+                CryptoPoolError::ZeroAmounts
+            );
+
+            // transfer tokens
+            for (&token, &amount) in self.pool.tokens.iter().zip(amounts.iter()) {
+                if amount > 0 {
+                    self.token_by_address(token).transfer(to, amount, vec![])?;
+                }
+            }
+
+            // update reserves
+            for (i, &amount) in amounts.iter().enumerate() {
+                self.decrease_reserve(i, amount)?;
+            }
+
+            // balanced withdrawal decreases the invariant proportionally
+            let d_decrease: u128 = (casted_mul(self.pool.d, shares) / total_supply)
+                .try_into()
+                .map_err(|_| MathError::CastOverflow(183))?;
+            self.pool.d -= d_decrease;
+
+            // Burn liquidity
+            let events = self.psp22.burn(self.env().caller(), shares)?;
+            self.emit_events(events);
+
+            self.env().emit_event(RemoveLiquidity {
+                provider: self.env().caller(),
+                token_amounts: amounts.clone(),
+                shares,
+                to,
+            });
+            self.env().emit_event(Sync {
+                reserves: self.reserves(),
+            });
+            Ok(amounts)
+        }
+
+        #[ink(message)]
+        fn remove_liquidity_one_coin(
+            &mut self,
+            shares: u128,
+            token: AccountId,
+            min_amount: u128,
+            to: AccountId,
+        ) -> Result<u128, CryptoPoolError> {
+            let token_id = self.token_id(token)?;
+            ensure!(shares > 0, CryptoPoolError::ZeroAmounts);
+
+            // calc token amount and the new invariant
+            let (amount, d) = self.compute_one_coin_withdraw(token_id, shares)?;
+
+            // check min amount
+            ensure!(
+                amount >= min_amount,
+                CryptoPoolError::InsufficientOutputAmount
+            );
+            ensure!(amount > 0, CryptoPoolError::ZeroAmounts);
+
+            // burn shares
+            let events = self.psp22.burn(self.env().caller(), shares)?;
+            self.emit_events(events);
+
+            // transfer token
+            self.token_by_address(token).transfer(to, amount, vec![])?;
+
+            // update reserves
+            self.decrease_reserve(token_id, amount)?;
+
+            // update price oracle and repeg
+            self.tweak_price(None, Some(d))?;
+
+            let mut token_amounts = vec![0; N_COINS];
+            token_amounts[token_id] = amount;
+            self.env().emit_event(RemoveLiquidity {
+                provider: self.env().caller(),
+                token_amounts,
+                shares,
+                to,
+            });
+            self.env().emit_event(Sync {
+                reserves: self.reserves(),
+            });
+            Ok(amount)
+        }
+
+        #[ink(message)]
+        fn swap_exact_in(
+            &mut self,
+            token_in: AccountId,
+            token_out: AccountId,
+            token_in_amount: u128,
+            min_token_out_amount: u128,
+            to: AccountId,
+        ) -> Result<(u128, u128), CryptoPoolError> {
+            self._swap_exact_in(
+                token_in,
+                token_out,
+                Some(token_in_amount),
+                min_token_out_amount,
+                to,
+            )
+        }
+
+        #[ink(message)]
+        fn swap_exact_out(
+            &mut self,
+            token_in: AccountId,
+            token_out: AccountId,
+            token_out_amount: u128,
+            max_token_in_amount: u128,
+            to: AccountId,
+        ) -> Result<(u128, u128), CryptoPoolError> {
+            self._swap_exact_out(
+                token_in,
+                token_out,
+                token_out_amount,
+                max_token_in_amount,
+                to,
+            )
+        }
+
+        #[ink(message)]
+        fn swap_received(
+            &mut self,
+            token_in: AccountId,
+            token_out: AccountId,
+            min_token_out_amount: u128,
+            to: AccountId,
+        ) -> Result<(u128, u128), CryptoPoolError> {
+            self._swap_exact_in(token_in, token_out, None, min_token_out_amount, to)
+        }
+
+        #[ink(message)]
+        fn set_fee_receiver(
+            &mut self,
+            fee_receiver: Option<AccountId>,
+        ) -> Result<(), CryptoPoolError> {
+            self.ensure_owner()?;
+            self.pool.fee_receiver = fee_receiver;
+            self.env().emit_event(FeeReceiverChanged {
+                new_fee_receiver: fee_receiver,
+            });
+            Ok(())
+        }
+
+        #[ink(message)]
+        fn set_fees(
+            &mut self,
+            mid_fee: u32,
+            out_fee: u32,
+            fee_gamma: u128,
+            protocol_fee: u32,
+        ) -> Result<(), CryptoPoolError> {
+            self.ensure_owner()?;
+            ensure!(
+                protocol_fee <= MAX_PROTOCOL_FEE,
+                CryptoPoolError::InvalidFee
+            );
+            self.pool.fees =
+                DynamicFees::new(mid_fee, out_fee, fee_gamma).ok_or(CryptoPoolError::InvalidFee)?;
+            self.pool.protocol_fee = protocol_fee;
+            self.env().emit_event(FeeChanged {
+                mid_fee,
+                out_fee,
+                fee_gamma,
+                protocol_fee,
+            });
+            Ok(())
+        }
+
+        #[ink(message)]
+        fn set_rebalancing_params(
+            &mut self,
+            allowed_extra_profit: u128,
+            adjustment_step: u128,
+            ma_half_time: u64,
+        ) -> Result<(), CryptoPoolError> {
+            self.ensure_owner()?;
+            Self::check_rebalancing_params(allowed_extra_profit, adjustment_step, ma_half_time)?;
+            self.pool.allowed_extra_profit = allowed_extra_profit;
+            self.pool.adjustment_step = adjustment_step;
+            self.pool
+                .oracle
+                .set_ma_half_time(self.env().block_timestamp(), ma_half_time)?;
+            self.env().emit_event(RebalancingParamsChanged {
+                allowed_extra_profit,
+                adjustment_step,
+                ma_half_time,
+            });
+            Ok(())
+        }
+
+        #[ink(message)]
+        fn tokens(&self) -> Vec<AccountId> {
+            self.pool.tokens.clone()
+        }
+
+        #[ink(message)]
+        fn reserves(&self) -> Vec<u128> {
+            self.pool.reserves.clone()
+        }
+
+        #[ink(message)]
+        fn amp_gamma(&self) -> (u128, u128) {
+            (self.pool.amp, self.pool.gamma)
+        }
+
+        #[ink(message)]
+        fn fees(&self) -> (u32, u32, u128, u32) {
+            (
+                self.pool.fees.mid_fee,
+                self.pool.fees.out_fee,
+                self.pool.fees.fee_gamma,
+                self.pool.protocol_fee,
+            )
+        }
+
+        #[ink(message)]
+        fn fee_receiver(&self) -> Option<AccountId> {
+            self.pool.fee_receiver
+        }
+
+        #[ink(message)]
+        fn price_scale(&self) -> u128 {
+            self.pool.price_scale
+        }
+
+        #[ink(message)]
+        fn price_oracle(&self) -> Result<u128, CryptoPoolError> {
+            Ok(self
+                .pool
+                .oracle
+                .price_oracle(self.env().block_timestamp())?)
+        }
+
+        #[ink(message)]
+        fn last_price(&self) -> u128 {
+            self.pool.oracle.last_price()
+        }
+
+        #[ink(message)]
+        fn virtual_price(&self) -> u128 {
+            self.pool.virtual_price
+        }
+
+        #[ink(message)]
+        fn get_swap_amount_out(
+            &self,
+            token_in: AccountId,
+            token_out: AccountId,
+            token_in_amount: u128,
+        ) -> Result<(u128, u128), CryptoPoolError> {
+            let (token_in_id, _) = self.check_tokens(token_in, token_out)?;
+            self.compute_swap_to(token_in_id, token_in_amount)
+        }
+
+        #[ink(message)]
+        fn get_swap_amount_in(
+            &self,
+            token_in: AccountId,
+            token_out: AccountId,
+            token_out_amount: u128,
+        ) -> Result<(u128, u128), CryptoPoolError> {
+            let (_, token_out_id) = self.check_tokens(token_in, token_out)?;
+            self.compute_swap_from(token_out_id, token_out_amount)
+        }
+
+        #[ink(message)]
+        fn get_mint_liquidity_for_amounts(
+            &self,
+            amounts: Vec<u128>,
+        ) -> Result<u128, CryptoPoolError> {
+            self.compute_deposit(&amounts).map(|(shares, _)| shares)
+        }
+
+        #[ink(message)]
+        fn get_amounts_for_liquidity_burn(
+            &self,
+            liquidity: u128,
+        ) -> Result<Vec<u128>, CryptoPoolError> {
+            ensure!(
+                liquidity <= self.psp22.total_supply(),
+                CryptoPoolError::InsufficientLiquidity
+            );
+            Ok(compute_amounts_given_lp(
+                liquidity,
+                &self.pool.reserves,
+                self.psp22.total_supply(),
+            )?)
+        }
+
+        #[ink(message)]
+        fn get_amount_for_one_coin_burn(
+            &self,
+            shares: u128,
+            token: AccountId,
+        ) -> Result<u128, CryptoPoolError> {
+            let token_id = self.token_id(token)?;
+            self.compute_one_coin_withdraw(token_id, shares)
+                .map(|(amount, _)| amount)
+        }
+    }
+
+    impl PSP22 for CryptoPoolContract {
+        #[ink(message)]
+        fn total_supply(&self) -> u128 {
+            self.psp22.total_supply()
+        }
+
+        #[ink(message)]
+        fn balance_of(&self, owner: AccountId) -> u128 {
+            self.psp22.balance_of(owner)
+        }
+
+        #[ink(message)]
+        fn allowance(&self, owner: AccountId, spender: AccountId) -> u128 {
+            self.psp22.allowance(owner, spender)
+        }
+
+        #[ink(message)]
+        fn transfer(
+            &mut self,
+            to: AccountId,
+            value: u128,
+            _data: Vec<u8>,
+        ) -> Result<(), PSP22Error> {
+            let events = self.psp22.transfer(self.env().caller(), to, value)?;
+            self.emit_events(events);
+            Ok(())
+        }
+
+        #[ink(message)]
+        fn transfer_from(
+            &mut self,
+            from: AccountId,
+            to: AccountId,
+            value: u128,
+            _data: Vec<u8>,
+        ) -> Result<(), PSP22Error> {
+            let events = self
+                .psp22
+                .transfer_from(self.env().caller(), from, to, value)?;
+            self.emit_events(events);
+            Ok(())
+        }
+
+        #[ink(message)]
+        fn approve(&mut self, spender: AccountId, value: u128) -> Result<(), PSP22Error> {
+            let events = self.psp22.approve(self.env().caller(), spender, value)?;
+            self.emit_events(events);
+            Ok(())
+        }
+
+        #[ink(message)]
+        fn increase_allowance(
+            &mut self,
+            spender: AccountId,
+            delta_value: u128,
+        ) -> Result<(), PSP22Error> {
+            let events =
+                self.psp22
+                    .increase_allowance(self.env().caller(), spender, delta_value)?;
+            self.emit_events(events);
+            Ok(())
+        }
+
+        #[ink(message)]
+        fn decrease_allowance(
+            &mut self,
+            spender: AccountId,
+            delta_value: u128,
+        ) -> Result<(), PSP22Error> {
+            let events =
+                self.psp22
+                    .decrease_allowance(self.env().caller(), spender, delta_value)?;
+            self.emit_events(events);
+            Ok(())
+        }
+    }
+
+    impl PSP22Metadata for CryptoPoolContract {
+        #[ink(message)]
+        fn token_name(&self) -> Option<String> {
+            Some("CommonCryptoPool".to_string())
+        }
+
+        #[ink(message)]
+        fn token_symbol(&self) -> Option<String> {
+            Some("CMNCP".to_string())
+        }
+
+        #[ink(message)]
+        fn token_decimals(&self) -> u8 {
+            TOKEN_TARGET_DECIMALS
+        }
+    }
+
+    impl Ownable2Step for CryptoPoolContract {
+        #[ink(message)]
+        fn get_owner(&self) -> Ownable2StepResult<AccountId> {
+            self.ownable.get_owner()
+        }
+
+        #[ink(message)]
+        fn get_pending_owner(&self) -> Ownable2StepResult<AccountId> {
+            self.ownable.get_pending_owner()
+        }
+
+        #[ink(message)]
+        fn transfer_ownership(&mut self, new_owner: AccountId) -> Ownable2StepResult<()> {
+            self.ownable
+                .transfer_ownership(self.env().caller(), new_owner)?;
+            self.env()
+                .emit_event(TransferOwnershipInitiated { new_owner });
+            Ok(())
+        }
+
+        #[ink(message)]
+        fn accept_ownership(&mut self) -> Ownable2StepResult<()> {
+            let new_owner = self.env().caller();
+            self.ownable.accept_ownership(new_owner)?;
+            self.env()
+                .emit_event(TransferOwnershipAccepted { new_owner });
+            Ok(())
+        }
+
+        #[ink(message)]
+        fn renounce_ownership(&mut self) -> Ownable2StepResult<()> {
+            self.ownable
+                .renounce_ownership(self.env().caller(), self.env().account_id())?;
+            self.env().emit_event(OwnershipRenounced {});
+            Ok(())
+        }
+
+        #[ink(message)]
+        fn ensure_owner(&self) -> Ownable2StepResult<()> {
+            self.ownable.ensure_owner(self.env().caller())
+        }
+    }
+}
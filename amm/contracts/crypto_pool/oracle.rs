@@ -0,0 +1,66 @@
+use amm_helpers::{constants::crypto_pool::PRECISION, crypto_swap_math::halfpow};
+use primitive_types::U256;
+use traits::MathError;
+
+/// Exponential moving average of the pool's spot price.
+#[ink::storage_item]
+#[derive(Debug)]
+pub struct EmaOracle {
+    /// EMA price as of `last_timestamp`.
+    price_oracle: u128,
+    /// Spot price after the last update.
+    last_price: u128,
+    /// Block timestamp of the last update, in milisec.
+    last_timestamp: u64,
+    /// Half-life of the EMA, in milisec.
+    ma_half_time: u64,
+}
+
+impl EmaOracle {
+    pub fn new(price: u128, now: u64, ma_half_time: u64) -> Self {
+        Self {
+            price_oracle: price,
+            last_price: price,
+            last_timestamp: now,
+            ma_half_time,
+        }
+    }
+
+    /// Returns the EMA price as of `now`, assuming that the spot price
+    /// did not change since the last update.
+    pub fn price_oracle(&self, now: u64) -> Result<u128, MathError> {
+        if now <= self.last_timestamp {
+            return Ok(self.price_oracle);
+        }
+        let time_elapsed = (now - self.last_timestamp) as u128;
+        // weight of the previous EMA, halves every `ma_half_time`
+        let alpha = halfpow(time_elapsed.saturating_mul(PRECISION) / self.ma_half_time as u128)?;
+        let price = (U256::from(self.last_price) * (PRECISION - alpha)
+            + U256::from(self.price_oracle) * alpha)
+            / PRECISION;
+        price.try_into().map_err(|_| MathError::CastOverflow(180))
+    }
+
+    /// Accumulates the spot price which held since the last update into the EMA
+    /// and sets the new `last_price`.
+    ///
+    /// Returns the updated EMA price.
+    pub fn update(&mut self, now: u64, last_price: u128) -> Result<u128, MathError> {
+        self.price_oracle = self.price_oracle(now)?;
+        self.last_timestamp = self.last_timestamp.max(now);
+        self.last_price = last_price;
+        Ok(self.price_oracle)
+    }
+
+    pub fn last_price(&self) -> u128 {
+        self.last_price
+    }
+
+    pub fn set_ma_half_time(&mut self, now: u64, ma_half_time: u64) -> Result<(), MathError> {
+        // accumulate the EMA with the old half-life first
+        self.price_oracle = self.price_oracle(now)?;
+        self.last_timestamp = self.last_timestamp.max(now);
+        self.ma_half_time = ma_half_time;
+        Ok(())
+    }
+}
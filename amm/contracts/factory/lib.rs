@@ -2,12 +2,23 @@
 
 #[ink::contract]
 pub mod factory {
-    // All pairs created via this factory have fixed fee of 0.3%
-    const DEFAULT_FEE: u8 = 3;
-    use amm_helpers::ensure;
-    use ink::{codegen::EmitEvent, env::hash::Blake2x256, storage::Mapping, ToAccountId};
+    use amm_helpers::{
+        constants::pair::{
+            DEFAULT_FEE, DEFAULT_FLASH_LOAN_FEE, DEFAULT_PROTOCOL_FEE_DENOM, MAX_FEE,
+            MAX_FLASH_LOAN_FEE, MIN_PROTOCOL_FEE_DENOM,
+        },
+        ensure,
+    };
+    use ink::{
+        codegen::EmitEvent,
+        contract_ref,
+        env::hash::Blake2x256,
+        prelude::{vec, vec::Vec},
+        storage::Mapping,
+        ToAccountId,
+    };
     use pair_contract::pair::PairContractRef;
-    use traits::{Factory, FactoryError};
+    use traits::{Factory, FactoryError, Pair};
 
     #[ink(event)]
     pub struct PairCreated {
@@ -17,21 +28,28 @@ pub mod factory {
         pub token_1: AccountId,
         pub pair: AccountId,
         pub pair_len: u64,
+        pub fee: u8,
     }
 
     #[ink(storage)]
     pub struct FactoryContract {
-        get_pair: Mapping<(AccountId, AccountId), AccountId>,
+        get_pair: Mapping<(AccountId, AccountId, u8), AccountId>,
         all_pairs: Mapping<u64, AccountId>,
         all_pairs_length: u64,
         pair_contract_code_hash: Hash,
         fee_to: Option<AccountId>,
         fee_to_setter: AccountId,
+        flash_loan_fee: u16,
+        fee_tiers: Vec<u8>,
+        pair_fee_to: Mapping<AccountId, AccountId>,
+        protocol_fee_denom: u8,
+        is_pair: Mapping<AccountId, bool>,
+        genesis_hash: Hash,
     }
 
     impl FactoryContract {
         #[ink(constructor)]
-        pub fn new(fee_to_setter: AccountId, pair_code_hash: Hash) -> Self {
+        pub fn new(fee_to_setter: AccountId, pair_code_hash: Hash, genesis_hash: Hash) -> Self {
             Self {
                 get_pair: Default::default(),
                 all_pairs: Default::default(),
@@ -39,6 +57,12 @@ pub mod factory {
                 pair_contract_code_hash: pair_code_hash,
                 fee_to: None,
                 fee_to_setter,
+                flash_loan_fee: DEFAULT_FLASH_LOAN_FEE,
+                fee_tiers: vec![DEFAULT_FEE],
+                pair_fee_to: Default::default(),
+                protocol_fee_denom: DEFAULT_PROTOCOL_FEE_DENOM,
+                is_pair: Default::default(),
+                genesis_hash,
             }
         }
 
@@ -47,18 +71,24 @@ pub mod factory {
             salt_bytes: &[u8],
             token_0: AccountId,
             token_1: AccountId,
+            fee: u8,
         ) -> Result<AccountId, FactoryError> {
             let pair_hash = self.pair_contract_code_hash;
-            let pair =
-                match PairContractRef::new(token_0, token_1, self.env().account_id(), DEFAULT_FEE)
-                    .endowment(0)
-                    .code_hash(pair_hash)
-                    .salt_bytes(&salt_bytes)
-                    .try_instantiate()
-                {
-                    Ok(Ok(res)) => Ok(res),
-                    _ => Err(FactoryError::PairInstantiationFailed),
-                }?;
+            let pair = match PairContractRef::new(
+                token_0,
+                token_1,
+                self.env().account_id(),
+                fee,
+                self.genesis_hash,
+            )
+            .endowment(0)
+            .code_hash(pair_hash)
+            .salt_bytes(&salt_bytes)
+            .try_instantiate()
+            {
+                Ok(Ok(res)) => Ok(res),
+                _ => Err(FactoryError::PairInstantiationFailed),
+            }?;
             Ok(pair.to_account_id())
         }
 
@@ -68,6 +98,7 @@ pub mod factory {
             token_1: AccountId,
             pair: AccountId,
             pair_len: u64,
+            fee: u8,
         ) {
             EmitEvent::<FactoryContract>::emit_event(
                 self.env(),
@@ -76,6 +107,7 @@ pub mod factory {
                     token_1,
                     pair,
                     pair_len,
+                    fee,
                 },
             )
         }
@@ -83,6 +115,7 @@ pub mod factory {
         fn _add_new_pair(&mut self, pair: AccountId) {
             let pair_len = self.all_pairs_length;
             self.all_pairs.insert(pair_len, &pair);
+            self.is_pair.insert(pair, &true);
             self.all_pairs_length += 1;
         }
 
@@ -115,26 +148,35 @@ pub mod factory {
             &mut self,
             token_0: AccountId,
             token_1: AccountId,
+            fee: u8,
         ) -> Result<AccountId, FactoryError> {
             ensure!(token_0 != token_1, FactoryError::IdenticalAddresses);
+            ensure!(
+                self.fee_tiers.contains(&fee),
+                FactoryError::FeeTierNotEnabled
+            );
             let token_pair = if token_0 < token_1 {
                 (token_0, token_1)
             } else {
                 (token_1, token_0)
             };
             ensure!(
-                self.get_pair.get(token_pair).is_none(),
+                self.get_pair
+                    .get((token_pair.0, token_pair.1, fee))
+                    .is_none(),
                 FactoryError::PairExists
             );
 
-            let salt = self.env().hash_encoded::<Blake2x256, _>(&token_pair);
+            let salt = self
+                .env()
+                .hash_encoded::<Blake2x256, _>(&(token_pair.0, token_pair.1, fee));
             let pair_contract =
-                self._instantiate_pair(salt.as_ref(), token_pair.0, token_pair.1)?;
+                self._instantiate_pair(salt.as_ref(), token_pair.0, token_pair.1, fee)?;
 
             self.get_pair
-                .insert((token_pair.0, token_pair.1), &pair_contract);
+                .insert((token_pair.0, token_pair.1, fee), &pair_contract);
             self.get_pair
-                .insert((token_pair.1, token_pair.0), &pair_contract);
+                .insert((token_pair.1, token_pair.0, fee), &pair_contract);
 
             self._add_new_pair(pair_contract);
 
@@ -143,11 +185,34 @@ pub mod factory {
                 token_pair.1,
                 pair_contract,
                 self.all_pairs_length(),
+                fee,
             );
 
             Ok(pair_contract)
         }
 
+        #[ink(message)]
+        fn fee_tiers(&self) -> Vec<u8> {
+            self.fee_tiers.clone()
+        }
+
+        #[ink(message)]
+        fn enable_fee_tier(&mut self, fee: u8) -> Result<(), FactoryError> {
+            self._only_fee_setter()?;
+            ensure!(fee <= MAX_FEE, FactoryError::InvalidFee);
+            if !self.fee_tiers.contains(&fee) {
+                self.fee_tiers.push(fee);
+            }
+            Ok(())
+        }
+
+        #[ink(message)]
+        fn disable_fee_tier(&mut self, fee: u8) -> Result<(), FactoryError> {
+            self._only_fee_setter()?;
+            self.fee_tiers.retain(|&tier| tier != fee);
+            Ok(())
+        }
+
         #[ink(message)]
         fn set_fee_to(&mut self, fee_to: AccountId) -> Result<(), FactoryError> {
             self._only_fee_setter()?;
@@ -167,14 +232,80 @@ pub mod factory {
             self.fee_to
         }
 
+        #[ink(message)]
+        fn set_pair_fee_to(
+            &mut self,
+            pair: AccountId,
+            fee_to: Option<AccountId>,
+        ) -> Result<(), FactoryError> {
+            self._only_fee_setter()?;
+            match fee_to {
+                Some(fee_to) => self.pair_fee_to.insert(pair, &fee_to),
+                None => self.pair_fee_to.remove(pair),
+            };
+            Ok(())
+        }
+
+        #[ink(message)]
+        fn pair_fee_to(&self, pair: AccountId) -> Option<AccountId> {
+            self.pair_fee_to.get(pair).or(self.fee_to)
+        }
+
+        #[ink(message)]
+        fn protocol_fee_denom(&self) -> u8 {
+            self.protocol_fee_denom
+        }
+
+        #[ink(message)]
+        fn set_protocol_fee_denom(&mut self, denom: u8) -> Result<(), FactoryError> {
+            self._only_fee_setter()?;
+            ensure!(
+                denom >= MIN_PROTOCOL_FEE_DENOM,
+                FactoryError::InvalidProtocolFee
+            );
+            self.protocol_fee_denom = denom;
+            Ok(())
+        }
+
         #[ink(message)]
         fn fee_to_setter(&self) -> AccountId {
             self.fee_to_setter
         }
 
         #[ink(message)]
-        fn get_pair(&self, token_0: AccountId, token_1: AccountId) -> Option<AccountId> {
-            self.get_pair.get((token_0, token_1))
+        fn get_pair(&self, token_0: AccountId, token_1: AccountId, fee: u8) -> Option<AccountId> {
+            self.get_pair.get((token_0, token_1, fee))
+        }
+
+        #[ink(message)]
+        fn is_pair(&self, pair: AccountId) -> bool {
+            self.is_pair.get(pair).unwrap_or(false)
+        }
+
+        #[ink(message)]
+        fn set_pair_fee(
+            &mut self,
+            pair: AccountId,
+            fee: u8,
+            effective_time: u64,
+        ) -> Result<(), FactoryError> {
+            self._only_fee_setter()?;
+            let mut pair: contract_ref!(Pair) = pair.into();
+            pair.set_fee(fee, effective_time)?;
+            Ok(())
+        }
+
+        #[ink(message)]
+        fn flash_loan_fee(&self) -> u16 {
+            self.flash_loan_fee
+        }
+
+        #[ink(message)]
+        fn set_flash_loan_fee(&mut self, fee: u16) -> Result<(), FactoryError> {
+            self._only_fee_setter()?;
+            ensure!(fee <= MAX_FLASH_LOAN_FEE, FactoryError::InvalidFlashLoanFee);
+            self.flash_loan_fee = fee;
+            Ok(())
         }
     }
 
@@ -187,8 +318,11 @@ pub mod factory {
         #[ink::test]
         fn initialize_works() {
             let accounts = default_accounts::<ink::env::DefaultEnvironment>();
-            let factory = FactoryContract::new(accounts.alice, Hash::default());
+            let factory = FactoryContract::new(accounts.alice, Hash::default(), Hash::default());
             assert_eq!(factory.fee_to, None);
+            assert_eq!(factory.flash_loan_fee, DEFAULT_FLASH_LOAN_FEE);
+            assert_eq!(factory.fee_tiers, vec![DEFAULT_FEE]);
+            assert_eq!(factory.protocol_fee_denom, DEFAULT_PROTOCOL_FEE_DENOM);
         }
     }
 }
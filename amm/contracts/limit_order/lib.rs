@@ -0,0 +1,219 @@
+#![cfg_attr(not(feature = "std"), no_std, no_main)]
+
+/// Book of limit orders executed through `RouterV2`.
+///
+/// Users escrow the input tokens of their orders in the contract, together with a native tip.
+/// Anyone can fill an order once the pools along its path offer at least the target price
+/// of the order, and receives the tip for doing so. The owner gets the output tokens directly
+/// from the router.
+#[ink::contract]
+pub mod limit_order {
+    use amm_helpers::ensure;
+    use ink::{contract_ref, prelude::vec::Vec, storage::Mapping};
+    use psp22::PSP22;
+    use traits::{LimitOrderBook, LimitOrderError, Order, RouterV2, Step};
+
+    #[ink(event)]
+    pub struct OrderPlaced {
+        #[ink(topic)]
+        pub order_id: u64,
+        #[ink(topic)]
+        pub owner: AccountId,
+        pub token_in: AccountId,
+        pub token_out: AccountId,
+        pub amount_in: u128,
+        pub amount_out_min: u128,
+        pub tip: Balance,
+        pub expiry: u64,
+    }
+
+    #[ink(event)]
+    pub struct OrderCancelled {
+        #[ink(topic)]
+        pub order_id: u64,
+        #[ink(topic)]
+        pub owner: AccountId,
+    }
+
+    #[ink(event)]
+    pub struct OrderFilled {
+        #[ink(topic)]
+        pub order_id: u64,
+        #[ink(topic)]
+        pub owner: AccountId,
+        #[ink(topic)]
+        pub keeper: AccountId,
+        pub amount_in: u128,
+        pub amount_out: u128,
+        pub tip: Balance,
+    }
+
+    #[ink(storage)]
+    pub struct LimitOrderContract {
+        router: AccountId,
+        next_order_id: u64,
+        orders: Mapping<u64, Order>,
+    }
+
+    impl LimitOrderContract {
+        #[ink(constructor)]
+        pub fn new(router: AccountId) -> Self {
+            Self {
+                router,
+                next_order_id: 0,
+                orders: Default::default(),
+            }
+        }
+
+        fn router_ref(&self) -> contract_ref!(RouterV2) {
+            self.router.into()
+        }
+
+        /// Removes the order from the book and returns it.
+        fn take_order(&mut self, order_id: u64) -> Result<Order, LimitOrderError> {
+            let order = self
+                .orders
+                .get(order_id)
+                .ok_or(LimitOrderError::OrderNotFound)?;
+            self.orders.remove(order_id);
+            Ok(order)
+        }
+
+        fn transfer_native(&self, to: AccountId, amount: Balance) -> Result<(), LimitOrderError> {
+            if amount > 0 {
+                self.env()
+                    .transfer(to, amount)
+                    .map_err(|_| LimitOrderError::TransferError)?;
+            }
+            Ok(())
+        }
+    }
+
+    impl LimitOrderBook for LimitOrderContract {
+        #[ink(message)]
+        fn router(&self) -> AccountId {
+            self.router
+        }
+
+        #[ink(message)]
+        fn get_order(&self, order_id: u64) -> Option<Order> {
+            self.orders.get(order_id)
+        }
+
+        #[ink(message, payable)]
+        fn place_order(
+            &mut self,
+            path: Vec<Step>,
+            token_out: AccountId,
+            amount_in: u128,
+            amount_out_min: u128,
+            expiry: u64,
+        ) -> Result<u64, LimitOrderError> {
+            ensure!(!path.is_empty(), LimitOrderError::EmptyPath);
+            ensure!(amount_in > 0, LimitOrderError::ZeroAmount);
+            ensure!(
+                expiry >= self.env().block_timestamp(),
+                LimitOrderError::Expired
+            );
+            let last_step = &path[path.len() - 1];
+            ensure!(
+                token_out != last_step.token_in
+                    && self
+                        .router_ref()
+                        .pool_tokens(last_step.pool_id)?
+                        .contains(&token_out),
+                LimitOrderError::InvalidTokenOut
+            );
+            let owner = self.env().caller();
+            let token_in = path[0].token_in;
+            let mut token: contract_ref!(PSP22) = token_in.into();
+            token.transfer_from(owner, self.env().account_id(), amount_in, Vec::new())?;
+
+            let order_id = self.next_order_id;
+            self.next_order_id += 1;
+            let order = Order {
+                owner,
+                path,
+                token_out,
+                amount_in,
+                amount_out_min,
+                tip: self.env().transferred_value(),
+                expiry,
+            };
+            self.orders.insert(order_id, &order);
+
+            self.env().emit_event(OrderPlaced {
+                order_id,
+                owner,
+                token_in,
+                token_out,
+                amount_in,
+                amount_out_min,
+                tip: order.tip,
+                expiry,
+            });
+            Ok(order_id)
+        }
+
+        #[ink(message)]
+        fn cancel_order(&mut self, order_id: u64) -> Result<(), LimitOrderError> {
+            let order = self.take_order(order_id)?;
+            ensure!(
+                self.env().caller() == order.owner || self.env().block_timestamp() > order.expiry,
+                LimitOrderError::CallerIsNotOwner
+            );
+            let mut token: contract_ref!(PSP22) = order.path[0].token_in.into();
+            token.transfer(order.owner, order.amount_in, Vec::new())?;
+            self.transfer_native(order.owner, order.tip)?;
+
+            self.env().emit_event(OrderCancelled {
+                order_id,
+                owner: order.owner,
+            });
+            Ok(())
+        }
+
+        #[ink(message)]
+        fn quote_order(&mut self, order_id: u64) -> Result<u128, LimitOrderError> {
+            let order = self
+                .orders
+                .get(order_id)
+                .ok_or(LimitOrderError::OrderNotFound)?;
+            let amounts =
+                self.router_ref()
+                    .get_amounts_out(order.amount_in, order.path, order.token_out)?;
+            Ok(amounts[amounts.len() - 1])
+        }
+
+        #[ink(message)]
+        fn fill_order(&mut self, order_id: u64) -> Result<u128, LimitOrderError> {
+            let order = self.take_order(order_id)?;
+            let now = self.env().block_timestamp();
+            ensure!(now <= order.expiry, LimitOrderError::Expired);
+            let mut token: contract_ref!(PSP22) = order.path[0].token_in.into();
+            token.approve(self.router, order.amount_in)?;
+            // The router checks that the output satisfies the target price of the order.
+            let amounts = self.router_ref().swap_exact_tokens_for_tokens(
+                order.amount_in,
+                order.amount_out_min,
+                order.path,
+                order.token_out,
+                order.owner,
+                now,
+            )?;
+            let amount_out = amounts[amounts.len() - 1];
+            let keeper = self.env().caller();
+            self.transfer_native(keeper, order.tip)?;
+
+            self.env().emit_event(OrderFilled {
+                order_id,
+                owner: order.owner,
+                keeper,
+                amount_in: order.amount_in,
+                amount_out,
+                tip: order.tip,
+            });
+            Ok(amount_out)
+        }
+    }
+}
@@ -0,0 +1,47 @@
+#![cfg_attr(not(feature = "std"), no_std, no_main)]
+
+#[ink::contract]
+mod mock_flash_loan_receiver {
+    use ink::{contract_ref, prelude::vec::Vec};
+    use psp22::PSP22;
+
+    #[ink(storage)]
+    pub struct MockFlashLoanReceiverContract {
+        repay: bool,
+    }
+
+    impl MockFlashLoanReceiverContract {
+        /// Creates the receiver which repays the loans with fees if `repay` is set.
+        #[ink(constructor)]
+        pub fn new(repay: bool) -> Self {
+            Self { repay }
+        }
+
+        #[ink(message)]
+        pub fn set_repay(&mut self, repay: bool) {
+            self.repay = repay;
+        }
+    }
+
+    impl traits::FlashBorrower for MockFlashLoanReceiverContract {
+        #[ink(message)]
+        fn on_flash_loan(
+            &mut self,
+            _initiator: AccountId,
+            tokens: Vec<AccountId>,
+            amounts: Vec<u128>,
+            fees: Vec<u128>,
+            _data: Vec<u8>,
+        ) -> [u8; 32] {
+            if self.repay {
+                for ((token, amount), fee) in tokens.into_iter().zip(amounts).zip(fees) {
+                    let mut token: contract_ref!(PSP22) = token.into();
+                    token
+                        .transfer(self.env().caller(), amount + fee, Vec::new())
+                        .expect("Should repay the loan");
+                }
+            }
+            traits::FLASH_BORROWER_CALLBACK_SUCCESS
+        }
+    }
+}
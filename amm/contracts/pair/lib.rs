@@ -20,13 +20,13 @@ pub mod pair {
     // Whitepaper 3.2.1, equation (11)
     const TRADING_FEE_ADJ_RESERVES: u128 = 1000;
 
-    // Whitepaper 2.4, equation (7)
-    const PROTOCOL_FEE_ADJ_DENOM: u128 = 5;
-
     const TWO_POW_32: u64 = 4294967296;
 
     use amm_helpers::{
-        constants::{BURN_ADDRESS, MINIMUM_LIQUIDITY},
+        constants::{
+            pair::{FLASH_LOAN_FEE_DENOM, MAX_FEE, MIN_FEE_CHANGE_DELAY},
+            BURN_ADDRESS, MINIMUM_LIQUIDITY,
+        },
         ensure,
         math::casted_mul,
         types::WrappedU256,
@@ -35,13 +35,17 @@ pub mod pair {
         contract_ref,
         prelude::{
             string::{String, ToString},
+            vec,
             vec::Vec,
         },
     };
 
     use primitive_types::U256;
     use psp22::{PSP22Data, PSP22Error, PSP22Event, PSP22Metadata, PSP22};
-    use traits::{Factory, MathError, Pair, PairError, SwapCallee};
+    use traits::{
+        Factory, FlashBorrower, MathError, Pair, PairError, Permit, PermitData, PermitError,
+        PermitSignature, SwapCallee, FLASH_BORROWER_CALLBACK_SUCCESS,
+    };
 
     #[ink(event)]
     pub struct Mint {
@@ -73,12 +77,29 @@ pub mod pair {
         pub to: AccountId,
     }
 
+    #[ink(event)]
+    pub struct FlashLoan {
+        #[ink(topic)]
+        pub initiator: AccountId,
+        #[ink(topic)]
+        pub receiver: AccountId,
+        pub token: AccountId,
+        pub amount: u128,
+        pub fee: u128,
+    }
+
     #[ink(event)]
     pub struct Sync {
         reserve_0: u128,
         reserve_1: u128,
     }
 
+    #[ink(event)]
+    pub struct FeeChanged {
+        pub fee: u8,
+        pub effective_time: u64,
+    }
+
     #[ink(event)]
     pub struct Transfer {
         #[ink(topic)]
@@ -110,6 +131,8 @@ pub mod pair {
         pub price_1_cumulative_last: WrappedU256,
         pub k_last: Option<WrappedU256>,
         pub fee: u8,
+        /// Scheduled fee and the time it takes effect at.
+        pub future_fee: Option<(u8, u64)>,
     }
 
     impl PairData {
@@ -125,6 +148,7 @@ pub mod pair {
                 price_1_cumulative_last: 0.into(),
                 k_last: None,
                 fee,
+                future_fee: None,
             }
         }
     }
@@ -133,11 +157,18 @@ pub mod pair {
     pub struct PairContract {
         psp22: PSP22Data,
         pair: PairData,
+        permit: PermitData,
     }
 
     impl PairContract {
         #[ink(constructor)]
-        pub fn new(token_0: AccountId, token_1: AccountId, factory: AccountId, fee: u8) -> Self {
+        pub fn new(
+            token_0: AccountId,
+            token_1: AccountId,
+            factory: AccountId,
+            fee: u8,
+            genesis_hash: Hash,
+        ) -> Self {
             let pair = if token_0 < token_1 {
                 PairData::new(token_0, token_1, factory, fee)
             } else {
@@ -146,6 +177,7 @@ pub mod pair {
             Self {
                 psp22: PSP22Data::default(),
                 pair,
+                permit: PermitData::new(genesis_hash),
             }
         }
 
@@ -172,36 +204,82 @@ pub mod pair {
             )
         }
 
+        /// Returns the fee in effect, taking into account the scheduled fee change.
+        #[inline]
+        fn current_fee(&self) -> u8 {
+            match self.pair.future_fee {
+                Some((fee, effective_time)) if effective_time <= self.env().block_timestamp() => {
+                    fee
+                }
+                _ => self.pair.fee,
+            }
+        }
+
+        /// Applies the scheduled fee change if it is already in effect.
+        fn apply_future_fee(&mut self) {
+            if let Some((fee, effective_time)) = self.pair.future_fee {
+                if effective_time <= self.env().block_timestamp() {
+                    self.pair.fee = fee;
+                    self.pair.future_fee = None;
+                }
+            }
+        }
+
+        /// Returns the reserve of `token` or `None` if it is not one of the pair's tokens.
+        #[inline]
+        fn reserve_of(&self, token: AccountId) -> Option<u128> {
+            if token == self.pair.token_0 {
+                Some(self.pair.reserve_0)
+            } else if token == self.pair.token_1 {
+                Some(self.pair.reserve_1)
+            } else {
+                None
+            }
+        }
+
+        /// Returns the amount of liquidity tokens the protocol is owed for the growth
+        /// of `sqrt(k)` since the last liquidity event, given the current reserves.
+        fn protocol_fee(&self, reserve_0: u128, reserve_1: u128) -> Result<u128, PairError> {
+            // Section 2.4 Protocol fee in the whitepaper.
+            let k_last: U256 = match self.pair.k_last {
+                Some(k_last) => k_last.into(),
+                None => return Ok(0),
+            };
+            let root_k: U256 = casted_mul(reserve_0, reserve_1).integer_sqrt();
+            let root_k_last = k_last.integer_sqrt();
+            if root_k <= root_k_last {
+                return Ok(0);
+            }
+            // Whitepaper 2.4, equation (7), with the protocol's share of 1/N
+            // the root_k coefficient in the denominator is (N - 1).
+            let protocol_fee_adj_denom = self.factory().protocol_fee_denom().saturating_sub(1);
+            let total_supply: U256 = self.psp22.total_supply().into();
+            let numerator = total_supply
+                .checked_mul(
+                    root_k
+                        .checked_sub(root_k_last)
+                        .ok_or(MathError::SubUnderflow(1))?,
+                )
+                .ok_or(MathError::MulOverflow(1))?;
+            let denominator = root_k
+                .checked_mul(protocol_fee_adj_denom.into())
+                .ok_or(MathError::MulOverflow(2))?
+                .checked_add(root_k_last)
+                .ok_or(MathError::AddOverflow(1))?;
+            let liquidity: u128 = numerator
+                .checked_div(denominator)
+                .ok_or(MathError::DivByZero(1))?
+                .try_into()
+                .map_err(|_| MathError::CastOverflow(1))?;
+            Ok(liquidity)
+        }
+
         fn mint_fee(&mut self, reserve_0: u128, reserve_1: u128) -> Result<bool, PairError> {
-            if let Some(fee_to) = self.factory().fee_to() {
-                // Section 2.4 Protocol fee in the whitepaper.
-                if let Some(k_last) = self.pair.k_last.map(Into::<U256>::into) {
-                    let root_k: U256 = casted_mul(reserve_0, reserve_1).integer_sqrt();
-                    let root_k_last = k_last.integer_sqrt();
-                    if root_k > root_k_last {
-                        let total_supply: U256 = self.psp22.total_supply().into();
-                        let numerator = total_supply
-                            .checked_mul(
-                                root_k
-                                    .checked_sub(root_k_last)
-                                    .ok_or(MathError::SubUnderflow(1))?,
-                            )
-                            .ok_or(MathError::MulOverflow(1))?;
-                        let denominator = root_k
-                            .checked_mul(PROTOCOL_FEE_ADJ_DENOM.into())
-                            .ok_or(MathError::MulOverflow(2))?
-                            .checked_add(root_k_last)
-                            .ok_or(MathError::AddOverflow(1))?;
-                        let liquidity: u128 = numerator
-                            .checked_div(denominator)
-                            .ok_or(MathError::DivByZero(1))?
-                            .try_into()
-                            .map_err(|_| MathError::CastOverflow(1))?;
-                        if liquidity > 0 {
-                            let events = self.psp22.mint(fee_to, liquidity)?;
-                            self.emit_events(events)
-                        }
-                    }
+            if let Some(fee_to) = self.factory().pair_fee_to(self.env().account_id()) {
+                let liquidity = self.protocol_fee(reserve_0, reserve_1)?;
+                if liquidity > 0 {
+                    let events = self.psp22.mint(fee_to, liquidity)?;
+                    self.emit_events(events)
                 }
                 Ok(true)
             } else if self.pair.k_last.is_some() {
@@ -439,6 +517,7 @@ pub mod pair {
                 amount_0_out < reserves.0 && amount_1_out < reserves.1,
                 PairError::InsufficientLiquidity
             );
+            self.apply_future_fee();
 
             let token_0 = self.pair.token_0;
             let token_1 = self.pair.token_1;
@@ -572,7 +651,139 @@ pub mod pair {
 
         #[ink(message)]
         fn get_fee(&self) -> u8 {
-            self.pair.fee
+            self.current_fee()
+        }
+
+        #[ink(message)]
+        fn get_future_fee(&self) -> Option<(u8, u64)> {
+            let now = self.env().block_timestamp();
+            self.pair
+                .future_fee
+                .filter(|&(_, effective_time)| effective_time > now)
+        }
+
+        #[ink(message)]
+        fn set_fee(&mut self, fee: u8, effective_time: u64) -> Result<(), PairError> {
+            ensure!(
+                self.env().caller() == self.pair.factory,
+                PairError::CallerIsNotFactory
+            );
+            ensure!(fee <= MAX_FEE, PairError::InvalidFee);
+            ensure!(
+                effective_time
+                    >= self
+                        .env()
+                        .block_timestamp()
+                        .saturating_add(MIN_FEE_CHANGE_DELAY),
+                PairError::InvalidFeeChangeTime
+            );
+            self.apply_future_fee();
+            self.pair.future_fee = Some((fee, effective_time));
+            self.env().emit_event(FeeChanged {
+                fee,
+                effective_time,
+            });
+            Ok(())
+        }
+
+        #[ink(message)]
+        fn pending_protocol_fee(&self) -> Result<u128, PairError> {
+            if self
+                .factory()
+                .pair_fee_to(self.env().account_id())
+                .is_none()
+            {
+                return Ok(0);
+            }
+            self.protocol_fee(self.pair.reserve_0, self.pair.reserve_1)
+        }
+
+        #[ink(message)]
+        fn collect_protocol_fee(&mut self) -> Result<u128, PairError> {
+            let reserves = self.get_reserves();
+            let total_supply_before = self.psp22.total_supply();
+            if self.mint_fee(reserves.0, reserves.1)? {
+                self.pair.k_last = Some(casted_mul(reserves.0, reserves.1).into());
+            }
+            Ok(self
+                .psp22
+                .total_supply()
+                .checked_sub(total_supply_before)
+                .ok_or(MathError::SubUnderflow(13))?)
+        }
+
+        #[ink(message)]
+        fn max_flash_loan(&self, token: AccountId) -> u128 {
+            self.reserve_of(token).unwrap_or_default()
+        }
+
+        #[ink(message)]
+        fn flash_fee(&self, token: AccountId, amount: u128) -> Result<u128, PairError> {
+            ensure!(
+                self.reserve_of(token).is_some(),
+                PairError::UnsupportedFlashLoanToken
+            );
+            let fee = amount
+                .checked_mul(self.factory().flash_loan_fee().into())
+                .ok_or(MathError::MulOverflow(8))?
+                .checked_div(FLASH_LOAN_FEE_DENOM)
+                .ok_or(MathError::DivByZero(6))?;
+            Ok(fee)
+        }
+
+        #[ink(message)]
+        fn flash_loan(
+            &mut self,
+            token: AccountId,
+            amount: u128,
+            receiver: AccountId,
+            data: Vec<u8>,
+        ) -> Result<u128, PairError> {
+            let reserve = self
+                .reserve_of(token)
+                .ok_or(PairError::UnsupportedFlashLoanToken)?;
+            ensure!(amount > 0, PairError::InsufficientOutputAmount);
+            ensure!(amount <= reserve, PairError::InsufficientLiquidity);
+            ensure!(
+                receiver != self.pair.token_0 && receiver != self.pair.token_1,
+                PairError::InvalidTo
+            );
+            let fee = self.flash_fee(token, amount)?;
+            let reserves = self.get_reserves();
+            let initiator = self.env().caller();
+
+            let mut psp22: contract_ref!(PSP22) = token.into();
+            psp22.transfer(receiver, amount, Vec::new())?;
+            let mut borrower: contract_ref!(FlashBorrower) = receiver.into();
+            ensure!(
+                borrower.on_flash_loan(initiator, vec![token], vec![amount], vec![fee], data)
+                    == FLASH_BORROWER_CALLBACK_SUCCESS,
+                PairError::InvalidFlashLoanCallback
+            );
+
+            let contract = self.env().account_id();
+            let (balance_0, balance_1) = self.token_balances(contract);
+            let balance = if token == self.pair.token_0 {
+                balance_0
+            } else {
+                balance_1
+            };
+            ensure!(
+                balance >= reserve.checked_add(fee).ok_or(MathError::AddOverflow(2))?,
+                PairError::InsufficientFlashLoanRepayment
+            );
+
+            // The fee is added to the reserves.
+            self.update(balance_0, balance_1, reserves.0, reserves.1)?;
+
+            self.env().emit_event(FlashLoan {
+                initiator,
+                receiver,
+                token,
+                amount,
+                fee,
+            });
+            Ok(fee)
         }
     }
 
@@ -670,6 +881,35 @@ pub mod pair {
         }
     }
 
+    impl Permit for PairContract {
+        #[ink(message)]
+        fn nonces(&self, owner: AccountId) -> u64 {
+            self.permit.nonce(owner)
+        }
+
+        #[ink(message)]
+        fn permit(
+            &mut self,
+            owner: AccountId,
+            spender: AccountId,
+            value: u128,
+            deadline: u64,
+            signature: PermitSignature,
+        ) -> Result<(), PermitError> {
+            self.permit.use_permit(
+                self.env().account_id(),
+                owner,
+                spender,
+                value,
+                deadline,
+                &signature,
+            )?;
+            let events = self.psp22.approve(owner, spender, value)?;
+            self.emit_events(events);
+            Ok(())
+        }
+    }
+
     // Reserves are at most 2^112 - 1.
     // Consumer of the `price_cumulative_last` should use `overflowing_sub` to get the correct value.
     #[inline]
@@ -747,7 +987,7 @@ pub mod pair {
             let token_1 = AccountId::from([0x04; 32]);
             let factory = AccountId::from([0x05; 32]);
             let fee = 3;
-            let pair = PairContract::new(token_0, token_1, factory, fee);
+            let pair = PairContract::new(token_0, token_1, factory, fee, Hash::default());
             assert_eq!(pair.get_token_0(), token_0);
             assert_eq!(pair.get_token_1(), token_1);
             assert_eq!(pair.get_factory(), factory);
@@ -9,7 +9,7 @@ pub mod router {
     const TRADING_FEE_DENOM: u128 = 1000;
 
     use crate::CallerIsNotOwner;
-    use amm_helpers::{ensure, math::casted_mul};
+    use amm_helpers::{constants::pair::DEFAULT_FEE, ensure, math::casted_mul};
     use ink::{
         codegen::TraitCallBuilder,
         contract_ref,
@@ -105,12 +105,15 @@ pub mod router {
             token_0: AccountId,
             token_1: AccountId,
         ) -> Result<(AccountId, u8), RouterError> {
-            if let Some(result) = self.pairs.get((token_0, token_1)) {
-                Ok(result)
+            if let Some((pair, _)) = self.pairs.get((token_0, token_1)) {
+                // The fee is not taken from the cache, as it can be changed by the Factory.
+                let pair_ref: contract_ref!(Pair) = pair.into();
+                Ok((pair, pair_ref.get_fee()))
             } else {
+                // Pairs with other fee tiers have to be added to the cache.
                 let pair = self
                     .factory_ref()
-                    .get_pair(token_0, token_1)
+                    .get_pair(token_0, token_1, DEFAULT_FEE)
                     .ok_or(RouterError::PairNotFound)?;
                 let pair_ref: contract_ref!(Pair) = pair.into();
                 let fee = pair_ref.get_fee();
@@ -167,7 +170,9 @@ pub mod router {
             amount_1_min: u128,
         ) -> Result<(u128, u128), RouterError> {
             if self.get_pair_and_fee(token_0, token_1).is_err() {
-                let new_pair = self.factory_ref().create_pair(token_0, token_1)?;
+                let new_pair = self
+                    .factory_ref()
+                    .create_pair(token_0, token_1, DEFAULT_FEE)?;
                 self.cache_pair(new_pair);
             };
 
@@ -1,33 +1,58 @@
 #![cfg_attr(not(feature = "std"), no_std, no_main)]
 
+mod concentrated_pool;
+mod crypto_pool;
 mod pair;
+mod path_finder;
 mod pool;
 mod stable_pool;
 mod utils;
-
-#[derive(Debug, PartialEq, Eq, scale::Encode, scale::Decode)]
-#[cfg_attr(feature = "std", derive(scale_info::TypeInfo))]
-pub struct CallerIsNotOwner;
+mod weighted_pool;
 
 #[ink::contract]
 pub mod router_v2 {
     use crate::{
+        path_finder::{MAX_HOPS, MAX_POOLS},
         pool::{Pair, Pool, StablePool},
         utils::*,
     };
-    use amm_helpers::ensure;
+    use amm_helpers::{
+        constants::pair::{DEFAULT_FEE, MAX_FEE},
+        ensure,
+        math::casted_mul,
+    };
     use ink::{
         contract_ref,
         prelude::{vec, vec::Vec},
         storage::Mapping,
     };
-    use traits::{Factory, RouterV2, RouterV2Error, Step};
+    use traits::{
+        find_best_path, CallAmount, Factory, Farm, MathError, Ownable2Step, Ownable2StepData,
+        Ownable2StepResult, PairError, PermitSignature, RouterCall, RouterV2, RouterV2Error,
+        StablePoolError, StablePoolFactory, Step, WeightedPath,
+    };
+
+    #[ink(event)]
+    pub struct TransferOwnershipInitiated {
+        pub new_owner: AccountId,
+    }
+
+    #[ink(event)]
+    pub struct TransferOwnershipAccepted {
+        pub new_owner: AccountId,
+    }
+
+    #[ink(event)]
+    pub struct OwnershipRenounced {}
 
     #[ink(storage)]
     pub struct RouterV2Contract {
         pair_factory: AccountId,
         wnative: AccountId,
         cached_pools: Mapping<AccountId, Pool>,
+        ownable: Ownable2StepData,
+        stable_pools: Vec<AccountId>,
+        stable_pool_factory: Option<AccountId>,
     }
 
     impl RouterV2Contract {
@@ -37,6 +62,9 @@ pub mod router_v2 {
                 pair_factory,
                 wnative,
                 cached_pools: Default::default(),
+                ownable: Ownable2StepData::new(Self::env().caller()),
+                stable_pools: Vec::new(),
+                stable_pool_factory: None,
             }
         }
 
@@ -45,6 +73,54 @@ pub mod router_v2 {
             self.cached_pools.get(pool_id)
         }
 
+        /// Returns stable pools registered in the router.
+        #[ink(message)]
+        pub fn stable_pools(&self) -> Vec<AccountId> {
+            self.stable_pools.clone()
+        }
+
+        /// Registers `pool`, so that it is searched by `find_best_path`.
+        ///
+        /// Registered pools are trusted even if they were not created
+        /// via the StablePool factory.
+        ///
+        /// Fails if `pool` is not a StablePool contract.
+        #[ink(message)]
+        pub fn add_stable_pool(&mut self, pool: AccountId) -> Result<(), RouterV2Error> {
+            self.ensure_owner()?;
+            if !self.stable_pools.contains(&pool) {
+                self.stable_pools.push(pool);
+            }
+            self.get_and_cache_stable_pool(pool)?;
+            Ok(())
+        }
+
+        #[ink(message)]
+        pub fn remove_stable_pool(&mut self, pool: AccountId) -> Result<(), RouterV2Error> {
+            self.ensure_owner()?;
+            self.stable_pools.retain(|&id| id != pool);
+            Ok(())
+        }
+
+        #[ink(message)]
+        pub fn stable_pool_factory(&self) -> Option<AccountId> {
+            self.stable_pool_factory
+        }
+
+        /// Sets the StablePool factory.
+        ///
+        /// When set, only stable pools created via the factory or registered
+        /// in the router can be used. Pools which are already cached are not affected.
+        #[ink(message)]
+        pub fn set_stable_pool_factory(
+            &mut self,
+            stable_pool_factory: Option<AccountId>,
+        ) -> Result<(), RouterV2Error> {
+            self.ensure_owner()?;
+            self.stable_pool_factory = stable_pool_factory;
+            Ok(())
+        }
+
         // ----------- HELPER METHODS ----------- //
 
         /// Returns Pool for `pool_id` if it exists.
@@ -54,13 +130,65 @@ pub mod router_v2 {
             match self.cached_pools.get(pool_id) {
                 Some(pool) => Ok(pool),
                 None => {
-                    let pool = Pool::try_new(pool_id).ok_or(RouterV2Error::InvalidPoolAddress)?;
+                    let pool = self.fetch_pool(pool_id)?;
                     self.cached_pools.insert(pool_id, &pool);
                     Ok(pool)
                 }
             }
         }
 
+        /// Returns Pool for `pool_id` if it exists, without using the cache.
+        /// If the StablePool factory is set, a StablePool has to be created via
+        /// the factory or registered in the router.
+        fn fetch_pool(&self, pool_id: AccountId) -> Result<Pool, RouterV2Error> {
+            let pool = Pool::try_new(pool_id).ok_or(RouterV2Error::InvalidPoolAddress)?;
+            if let Pool::StablePool(_) = pool {
+                self.ensure_known_stable_pool(pool_id)?;
+            }
+            Ok(pool)
+        }
+
+        /// Returns ids of the pools searched by the path finder: all pairs of the pair Factory,
+        /// all pools of the StablePool factory (if set) and the stable pools registered
+        /// in the router.
+        ///
+        /// Fails if there are more than `MAX_POOLS` of them.
+        fn known_pool_ids(&self) -> Result<Vec<AccountId>, RouterV2Error> {
+            let pair_factory = self.pair_factory_ref();
+            let stable_pool_factory = self.stable_pool_factory_ref();
+            let pairs_count = pair_factory.all_pairs_length();
+            let factory_pools_count = stable_pool_factory
+                .as_ref()
+                .map_or(0, |factory| factory.all_pools_length());
+            ensure!(
+                pairs_count
+                    .saturating_add(factory_pools_count)
+                    .saturating_add(self.stable_pools.len() as u64)
+                    <= MAX_POOLS as u64,
+                RouterV2Error::TooManyPools
+            );
+            let mut pool_ids: Vec<AccountId> = (0..pairs_count)
+                .filter_map(|pid| pair_factory.all_pairs(pid))
+                .collect();
+            if let Some(factory) = stable_pool_factory {
+                pool_ids.extend((0..factory_pools_count).filter_map(|pid| factory.all_pools(pid)));
+            }
+            for &pool_id in self.stable_pools.iter() {
+                if !pool_ids.contains(&pool_id) {
+                    pool_ids.push(pool_id);
+                }
+            }
+            Ok(pool_ids)
+        }
+
+        /// Returns Pool for `pool_id` from the cache, or fetches it without caching.
+        fn get_pool(&self, pool_id: AccountId) -> Result<Pool, RouterV2Error> {
+            match self.cached_pools.get(pool_id) {
+                Some(pool) => Ok(pool),
+                None => self.fetch_pool(pool_id),
+            }
+        }
+
         /// Returns StablePool for `pool_id`.
         /// Adds the StablePool to the cache.
         #[inline]
@@ -70,13 +198,13 @@ pub mod router_v2 {
         ) -> Result<StablePool, RouterV2Error> {
             match self.get_and_cache_pool(pool_id)? {
                 Pool::StablePool(pool) => Ok(pool),
-                Pool::Pair(_) => Err(RouterV2Error::InvalidPoolAddress),
+                _ => Err(RouterV2Error::InvalidPoolAddress),
             }
         }
 
         /// Returns Pair for `pool_id`.
-        /// If `pool_id` is `None`, it creates a new Pair for
-        /// `(token_0, token_1)` tokens if the Pair does not
+        /// If `pool_id` is `None`, it creates a new Pair with the default
+        /// fee tier for `(token_0, token_1)` tokens if the Pair does not
         /// exist in the pair Factory.
         /// Adds the Pair to the cache.
         #[inline]
@@ -88,11 +216,21 @@ pub mod router_v2 {
         ) -> Result<Pair, RouterV2Error> {
             let pool_id = match pool_id {
                 Some(pool_id) => pool_id,
-                None => self.pair_factory_ref().create_pair(token_0, token_1)?,
+                None => self
+                    .pair_factory_ref()
+                    .create_pair(token_0, token_1, DEFAULT_FEE)?,
             };
+            self.get_and_cache_existing_pair(pool_id)
+        }
+
+        /// Returns the `Pair` under `pool_id`, caching it if necessary.
+        fn get_and_cache_existing_pair(
+            &mut self,
+            pool_id: AccountId,
+        ) -> Result<Pair, RouterV2Error> {
             match self.get_and_cache_pool(pool_id)? {
                 Pool::Pair(pair) => Ok(pair),
-                Pool::StablePool(_) => Err(RouterV2Error::InvalidPoolAddress),
+                _ => Err(RouterV2Error::InvalidPoolAddress),
             }
         }
 
@@ -174,10 +312,256 @@ pub mod router_v2 {
             Ok(amounts)
         }
 
+        /// Splits `amount_in` between the `paths` proportionally to their weights.
+        /// The rounding leftover is added to the share of the last path.
+        fn split_amount_in(
+            amount_in: u128,
+            paths: &[WeightedPath],
+        ) -> Result<Vec<u128>, RouterV2Error> {
+            let total_weight = paths.iter().try_fold(0u128, |acc, path| {
+                acc.checked_add(path.weight.into())
+                    .ok_or(MathError::AddOverflow(20))
+            })?;
+            let mut amounts = Vec::with_capacity(paths.len());
+            let mut remaining = amount_in;
+            for path in &paths[..paths.len() - 1] {
+                let amount: u128 = casted_mul(amount_in, path.weight.into())
+                    .checked_div(total_weight.into())
+                    .ok_or(MathError::DivByZero(20))?
+                    .try_into()
+                    .map_err(|_| MathError::CastOverflow(20))?;
+                remaining = remaining
+                    .checked_sub(amount)
+                    .ok_or(MathError::SubUnderflow(20))?;
+                amounts.push(amount);
+            }
+            amounts.push(remaining);
+            Ok(amounts)
+        }
+
+        /// Deposits `amount` of `pool` LP tokens held by the router to the `farm`
+        /// on behalf of the caller.
+        fn stake(
+            &self,
+            farm: AccountId,
+            pool: AccountId,
+            amount: u128,
+        ) -> Result<(), RouterV2Error> {
+            let mut farm_ref: contract_ref!(Farm) = farm.into();
+            psp22_approve(pool, farm, amount)?;
+            farm_ref.deposit_for(self.env().caller(), amount)?;
+            Ok(())
+        }
+
+        /// Returns error if the `farm` is not created for the `pool`.
+        #[inline]
+        fn ensure_farm_pool(&self, farm: AccountId, pool: AccountId) -> Result<(), RouterV2Error> {
+            let farm_ref: contract_ref!(Farm) = farm.into();
+            ensure!(farm_ref.pool_id() == pool, RouterV2Error::InvalidFarm);
+            Ok(())
+        }
+
+        /// Returns the amount of `token` to be used by a `multicall` call.
+        #[inline]
+        fn resolve_amount(&self, amount: CallAmount, token: AccountId) -> u128 {
+            match amount {
+                CallAmount::Exact(value) => value,
+                CallAmount::RouterBalance => psp22_balance_of(token, self.env().account_id()),
+            }
+        }
+
+        /// Executes a single call of the `multicall` batch.
+        ///
+        /// The input tokens are paid by the router.
+        fn execute_call(&mut self, call: RouterCall) -> Result<Vec<u128>, RouterV2Error> {
+            let router = self.env().account_id();
+            match call {
+                RouterCall::Pull { token, amount } => {
+                    psp22_transfer_from(token, self.env().caller(), router, amount)?;
+                    Ok(vec![amount])
+                }
+                RouterCall::Sweep { token, to } => {
+                    let amount = psp22_balance_of(token, router);
+                    if amount > 0 {
+                        psp22_transfer(token, to, amount)?;
+                    }
+                    Ok(vec![amount])
+                }
+                RouterCall::SwapExactTokensForTokens {
+                    amount_in,
+                    amount_out_min,
+                    path,
+                    token_out,
+                    to,
+                } => {
+                    ensure!(to != token_out, RouterV2Error::InvalidRecipient);
+                    ensure!(!path.is_empty(), RouterV2Error::EmptyPath);
+                    let amount_in = self.resolve_amount(amount_in, path[0].token_in);
+                    let amounts = self.calculate_amounts_out(amount_in, &path, token_out)?;
+                    ensure!(
+                        amounts[amounts.len() - 1] >= amount_out_min,
+                        RouterV2Error::InsufficientOutputAmount
+                    );
+                    psp22_transfer(path[0].token_in, path[0].pool_id, amounts[0])?;
+                    self.swap(&amounts, &path, token_out, to)?;
+                    Ok(amounts)
+                }
+                RouterCall::AddPairLiquidity {
+                    pair,
+                    token_0,
+                    token_1,
+                    amount_0_desired,
+                    amount_1_desired,
+                    amount_0_min,
+                    amount_1_min,
+                    to,
+                } => {
+                    let amount_0_desired = self.resolve_amount(amount_0_desired, token_0);
+                    let amount_1_desired = self.resolve_amount(amount_1_desired, token_1);
+                    let (amount_0, amount_1, liquidity) = self
+                        .get_and_cache_pair(pair, token_0, token_1)?
+                        .add_liquidity(
+                            token_0,
+                            token_1,
+                            amount_0_desired,
+                            amount_1_desired,
+                            amount_0_min,
+                            amount_1_min,
+                            to,
+                            router,
+                        )?;
+                    Ok(vec![amount_0, amount_1, liquidity])
+                }
+                RouterCall::RemovePairLiquidity {
+                    pair,
+                    token_0,
+                    token_1,
+                    liquidity,
+                    amount_0_min,
+                    amount_1_min,
+                    to,
+                } => {
+                    let liquidity = self.resolve_amount(liquidity, pair);
+                    let (amount_0, amount_1) = self
+                        .get_and_cache_pair(Some(pair), token_0, token_1)?
+                        .remove_liquidity(
+                            token_0,
+                            token_1,
+                            liquidity,
+                            amount_0_min,
+                            amount_1_min,
+                            to,
+                            router,
+                        )?;
+                    Ok(vec![amount_0, amount_1])
+                }
+                RouterCall::AddStablePoolLiquidity {
+                    pool,
+                    min_share_amount,
+                    amounts,
+                    to,
+                } => {
+                    let stable_pool = self.get_and_cache_stable_pool(pool)?;
+                    let tokens = stable_pool.tokens();
+                    ensure!(
+                        amounts.len() == tokens.len(),
+                        StablePoolError::IncorrectAmountsCount
+                    );
+                    let amounts = amounts
+                        .into_iter()
+                        .zip(tokens)
+                        .map(|(amount, token)| self.resolve_amount(amount, token))
+                        .collect();
+                    let (shares, fee) = stable_pool.add_liquidity(
+                        min_share_amount,
+                        amounts,
+                        to,
+                        router,
+                        self.wnative,
+                    )?;
+                    Ok(vec![shares, fee])
+                }
+                RouterCall::RemoveStablePoolLiquidityByShare {
+                    pool,
+                    share_amount,
+                    min_amounts,
+                    to,
+                } => {
+                    let share_amount = self.resolve_amount(share_amount, pool);
+                    self.get_and_cache_stable_pool(pool)?
+                        .remove_liquidity_by_share(
+                            share_amount,
+                            min_amounts,
+                            to,
+                            router,
+                            self.wnative,
+                        )
+                }
+                RouterCall::RemoveStablePoolLiquidityOneCoin {
+                    pool,
+                    share_amount,
+                    token,
+                    min_amount,
+                    to,
+                } => {
+                    let share_amount = self.resolve_amount(share_amount, pool);
+                    let (amount, fee) = self
+                        .get_and_cache_stable_pool(pool)?
+                        .remove_liquidity_one_coin(
+                            share_amount,
+                            token,
+                            min_amount,
+                            to,
+                            router,
+                            self.wnative,
+                        )?;
+                    Ok(vec![amount, fee])
+                }
+            }
+        }
+
+        /// Uses the caller's permit to let the router spend `value` of the `pool` LP tokens.
+        ///
+        /// The permit is skipped if the router is already allowed to spend `value`,
+        /// e.g. because someone has front-run the call by submitting the same permit
+        /// directly to the `pool`, which would fail on the used nonce.
+        #[inline]
+        fn permit_router(
+            &self,
+            pool: AccountId,
+            value: u128,
+            deadline: u64,
+            signature: PermitSignature,
+        ) -> Result<(), RouterV2Error> {
+            check_timestamp(deadline)?;
+            let owner = self.env().caller();
+            let router = self.env().account_id();
+            if psp22_allowance(pool, owner, router) >= value {
+                return Ok(());
+            }
+            psp22_permit(pool, owner, router, value, deadline, signature)?;
+            Ok(())
+        }
+
         #[inline]
         fn pair_factory_ref(&self) -> contract_ref!(Factory) {
             self.pair_factory.into()
         }
+
+        #[inline]
+        fn stable_pool_factory_ref(&self) -> Option<contract_ref!(StablePoolFactory)> {
+            self.stable_pool_factory.map(|factory| factory.into())
+        }
+
+        fn ensure_known_stable_pool(&self, pool_id: AccountId) -> Result<(), RouterV2Error> {
+            if let Some(stable_pool_factory) = self.stable_pool_factory_ref() {
+                ensure!(
+                    self.stable_pools.contains(&pool_id) || stable_pool_factory.is_pool(pool_id),
+                    RouterV2Error::InvalidPoolAddress
+                );
+            }
+            Ok(())
+        }
     }
 
     impl RouterV2 for RouterV2Contract {
@@ -224,6 +608,55 @@ pub mod router_v2 {
             Ok(amounts)
         }
 
+        #[ink(message)]
+        fn swap_exact_tokens_for_tokens_split(
+            &mut self,
+            amount_in: u128,
+            amount_out_min: u128,
+            paths: Vec<WeightedPath>,
+            token_out: AccountId,
+            to: AccountId,
+            deadline: u64,
+        ) -> Result<Vec<Vec<u128>>, RouterV2Error> {
+            check_timestamp(deadline)?;
+            ensure!(
+                to != token_out && to != self.env().account_id(),
+                RouterV2Error::InvalidRecipient
+            );
+            ensure!(
+                !paths.is_empty() && paths.iter().all(|path| !path.path.is_empty()),
+                RouterV2Error::EmptyPath
+            );
+            let token_in = paths[0].path[0].token_in;
+            ensure!(
+                paths.iter().all(|path| path.path[0].token_in == token_in),
+                RouterV2Error::InvalidToken
+            );
+            let amounts_in = Self::split_amount_in(amount_in, &paths)?;
+            let mut amounts = Vec::with_capacity(paths.len());
+            let mut total_amount_out: u128 = 0;
+            for (path, path_amount_in) in paths.iter().zip(amounts_in) {
+                let path_amounts =
+                    self.calculate_amounts_out(path_amount_in, &path.path, token_out)?;
+                psp22_transfer_from(
+                    token_in,
+                    self.env().caller(),
+                    path.path[0].pool_id,
+                    path_amounts[0],
+                )?;
+                self.swap(&path_amounts, &path.path, token_out, to)?;
+                total_amount_out = total_amount_out
+                    .checked_add(path_amounts[path_amounts.len() - 1])
+                    .ok_or(MathError::AddOverflow(21))?;
+                amounts.push(path_amounts);
+            }
+            ensure!(
+                total_amount_out >= amount_out_min,
+                RouterV2Error::InsufficientOutputAmount
+            );
+            Ok(amounts)
+        }
+
         #[ink(message)]
         fn swap_tokens_for_exact_tokens(
             &mut self,
@@ -407,6 +840,117 @@ pub mod router_v2 {
             self.calculate_amounts_in(amount_out, &path, token_out)
         }
 
+        #[ink(message)]
+        fn pool_tokens(&self, pool_id: AccountId) -> Result<Vec<AccountId>, RouterV2Error> {
+            Ok(self.get_pool(pool_id)?.tokens())
+        }
+
+        #[ink(message)]
+        fn quote(
+            &self,
+            amount_0: u128,
+            reserve_0: u128,
+            reserve_1: u128,
+        ) -> Result<u128, RouterV2Error> {
+            ensure!(amount_0 > 0, RouterV2Error::InsufficientAmount);
+            ensure!(
+                reserve_0 > 0 && reserve_1 > 0,
+                RouterV2Error::InsufficientLiquidity
+            );
+            Pair::quote(amount_0, reserve_0, reserve_1)
+        }
+
+        #[ink(message)]
+        fn get_amount_out(
+            &self,
+            amount_in: u128,
+            reserve_0: u128,
+            reserve_1: u128,
+            fee: u8,
+        ) -> Result<u128, RouterV2Error> {
+            ensure!(fee <= MAX_FEE, PairError::InvalidFee);
+            Pair::compute_amount_out(amount_in, reserve_0, reserve_1, fee)
+        }
+
+        #[ink(message)]
+        fn get_amount_in(
+            &self,
+            amount_out: u128,
+            reserve_0: u128,
+            reserve_1: u128,
+            fee: u8,
+        ) -> Result<u128, RouterV2Error> {
+            ensure!(fee <= MAX_FEE, PairError::InvalidFee);
+            Pair::compute_amount_in(amount_out, reserve_0, reserve_1, fee)
+        }
+
+        #[ink(message)]
+        fn get_stable_amount_out(
+            &self,
+            amount_in: u128,
+            token_in_id: u8,
+            token_out_id: u8,
+            reserves: Vec<u128>,
+            scaled_rates: Vec<u128>,
+            amp_coef: u128,
+            trade_fee: u32,
+        ) -> Result<(u128, u128), RouterV2Error> {
+            StablePool::compute_amount_out(
+                amount_in,
+                token_in_id,
+                token_out_id,
+                &reserves,
+                &scaled_rates,
+                amp_coef,
+                trade_fee,
+            )
+        }
+
+        #[ink(message)]
+        fn get_stable_amount_in(
+            &self,
+            amount_out: u128,
+            token_in_id: u8,
+            token_out_id: u8,
+            reserves: Vec<u128>,
+            scaled_rates: Vec<u128>,
+            amp_coef: u128,
+            trade_fee: u32,
+        ) -> Result<(u128, u128), RouterV2Error> {
+            StablePool::compute_amount_in(
+                amount_out,
+                token_in_id,
+                token_out_id,
+                &reserves,
+                &scaled_rates,
+                amp_coef,
+                trade_fee,
+            )
+        }
+
+        #[ink(message)]
+        fn find_best_path(
+            &self,
+            token_in: AccountId,
+            token_out: AccountId,
+            amount_in: u128,
+            max_hops: u8,
+        ) -> Result<(Vec<Step>, u128), RouterV2Error> {
+            ensure!(token_in != token_out, RouterV2Error::InvalidToken);
+            ensure!(amount_in > 0, RouterV2Error::InsufficientAmount);
+            ensure!(
+                max_hops > 0 && max_hops <= MAX_HOPS,
+                RouterV2Error::InvalidMaxHops
+            );
+            let pools = self
+                .known_pool_ids()?
+                .into_iter()
+                .map(|pool_id| self.get_pool(pool_id))
+                .collect::<Result<Vec<Pool>, RouterV2Error>>()?;
+            find_best_path(&pools, token_in, token_out, amount_in, max_hops)
+                .ok_or(RouterV2Error::PathNotFound)
+        }
+
         // ----------- PAIR LIQUIDITY METHODS ----------- //
 
         #[ink(message)]
@@ -422,6 +966,7 @@ pub mod router_v2 {
             to: AccountId,
             deadline: u64,
         ) -> Result<(u128, u128, u128), RouterV2Error> {
+            check_timestamp(deadline)?;
             let pair = self.get_and_cache_pair(pair, token_0, token_1)?;
             pair.add_liquidity(
                 token_0,
@@ -431,7 +976,7 @@ pub mod router_v2 {
                 amount_0_min,
                 amount_1_min,
                 to,
-                deadline,
+                self.env().caller(),
             )
         }
 
@@ -446,6 +991,7 @@ pub mod router_v2 {
             to: AccountId,
             deadline: u64,
         ) -> Result<(u128, Balance, u128), RouterV2Error> {
+            check_timestamp(deadline)?;
             let wnative = self.wnative;
             let pair = self.get_and_cache_pair(pair, token, wnative)?;
             pair.add_liquidity_native(
@@ -455,7 +1001,6 @@ pub mod router_v2 {
                 amount_token_min,
                 amount_native_min,
                 to,
-                deadline,
             )
         }
 
@@ -471,6 +1016,7 @@ pub mod router_v2 {
             to: AccountId,
             deadline: u64,
         ) -> Result<(u128, u128), RouterV2Error> {
+            check_timestamp(deadline)?;
             let pair = self.get_and_cache_pair(Some(pair), token_0, token_1)?;
             pair.remove_liquidity(
                 token_0,
@@ -479,7 +1025,7 @@ pub mod router_v2 {
                 amount_0_min,
                 amount_1_min,
                 to,
-                deadline,
+                self.env().caller(),
             )
         }
 
@@ -494,6 +1040,7 @@ pub mod router_v2 {
             to: AccountId,
             deadline: u64,
         ) -> Result<(u128, Balance), RouterV2Error> {
+            check_timestamp(deadline)?;
             let wnative = self.wnative;
             let pair = self.get_and_cache_pair(Some(pair), token, wnative)?;
             pair.remove_liquidity_native(
@@ -503,7 +1050,89 @@ pub mod router_v2 {
                 amount_token_min,
                 amount_native_min,
                 to,
-                deadline,
+            )
+        }
+
+        // ----------- ZAP METHODS ----------- //
+
+        #[ink(message)]
+        fn zap_in_pair(
+            &mut self,
+            pair: AccountId,
+            token_in: AccountId,
+            amount_in: u128,
+            min_liquidity: u128,
+            to: AccountId,
+            deadline: u64,
+        ) -> Result<(u128, u128), RouterV2Error> {
+            check_timestamp(deadline)?;
+            let pair = self.get_and_cache_existing_pair(pair)?;
+            pair.zap_in(token_in, amount_in, min_liquidity, to)
+        }
+
+        #[ink(message)]
+        fn zap_out_pair(
+            &mut self,
+            pair: AccountId,
+            liquidity: u128,
+            token_out: AccountId,
+            min_amount_out: u128,
+            to: AccountId,
+            deadline: u64,
+        ) -> Result<u128, RouterV2Error> {
+            check_timestamp(deadline)?;
+            let pair = self.get_and_cache_existing_pair(pair)?;
+            pair.zap_out(liquidity, token_out, min_amount_out, to)
+        }
+
+        #[ink(message, payable)]
+        fn zap_in_stable_pool(
+            &mut self,
+            pool: AccountId,
+            amount_in: u128,
+            path: Vec<Step>,
+            token: AccountId,
+            min_share_amount: u128,
+            to: AccountId,
+            deadline: u64,
+        ) -> Result<(u128, u128), RouterV2Error> {
+            check_timestamp(deadline)?;
+            ensure!(!path.is_empty(), RouterV2Error::EmptyPath);
+            let stable_pool = self.get_and_cache_stable_pool(pool)?;
+            ensure!(
+                stable_pool.tokens().contains(&token),
+                RouterV2Error::InvalidToken
+            );
+            let amounts = self.calculate_amounts_out(amount_in, &path, token)?;
+
+            let native_received = self.env().transferred_value();
+            if native_received > 0 {
+                let wnative = self.wnative;
+                ensure!(path[0].token_in == wnative, RouterV2Error::InvalidToken);
+                ensure!(
+                    native_received >= amount_in,
+                    RouterV2Error::InsufficientTransferredAmount
+                );
+                wrap(wnative, amount_in)?;
+                psp22_transfer(wnative, path[0].pool_id, amount_in)?;
+                if native_received > amount_in {
+                    transfer_native(self.env().caller(), native_received - amount_in)?;
+                }
+            } else {
+                psp22_transfer_from(
+                    path[0].token_in,
+                    self.env().caller(),
+                    path[0].pool_id,
+                    amount_in,
+                )?;
+            }
+            self.swap(&amounts, &path, token, self.env().account_id())?;
+
+            stable_pool.add_liquidity_one_coin(
+                token,
+                amounts[amounts.len() - 1],
+                min_share_amount,
+                to,
             )
         }
 
@@ -518,11 +1147,12 @@ pub mod router_v2 {
             to: AccountId,
             deadline: u64,
         ) -> Result<(u128, u128), RouterV2Error> {
+            check_timestamp(deadline)?;
             self.get_and_cache_stable_pool(pool)?.add_liquidity(
                 min_share_amount,
                 amounts,
                 to,
-                deadline,
+                self.env().caller(),
                 self.wnative,
             )
         }
@@ -536,11 +1166,12 @@ pub mod router_v2 {
             to: AccountId,
             deadline: u64,
         ) -> Result<(u128, u128), RouterV2Error> {
+            check_timestamp(deadline)?;
             self.get_and_cache_stable_pool(pool)?.remove_liquidity(
                 max_share_amount,
                 amounts,
                 to,
-                deadline,
+                self.env().caller(),
                 self.wnative,
             )
         }
@@ -554,8 +1185,258 @@ pub mod router_v2 {
             to: AccountId,
             deadline: u64,
         ) -> Result<Vec<u128>, RouterV2Error> {
+            check_timestamp(deadline)?;
+            self.get_and_cache_stable_pool(pool)?
+                .remove_liquidity_by_share(
+                    share_amount,
+                    min_amounts,
+                    to,
+                    self.env().caller(),
+                    self.wnative,
+                )
+        }
+
+        #[ink(message)]
+        fn remove_stable_pool_liquidity_one_coin(
+            &mut self,
+            pool: AccountId,
+            share_amount: u128,
+            token: AccountId,
+            min_amount: u128,
+            to: AccountId,
+            deadline: u64,
+        ) -> Result<(u128, u128), RouterV2Error> {
+            check_timestamp(deadline)?;
             self.get_and_cache_stable_pool(pool)?
-                .remove_liquidity_by_share(share_amount, min_amounts, to, deadline, self.wnative)
+                .remove_liquidity_one_coin(
+                    share_amount,
+                    token,
+                    min_amount,
+                    to,
+                    self.env().caller(),
+                    self.wnative,
+                )
+        }
+
+        // ----------- FARM METHODS ----------- //
+
+        #[ink(message)]
+        fn add_pair_liquidity_and_stake(
+            &mut self,
+            pair: AccountId,
+            token_0: AccountId,
+            token_1: AccountId,
+            amount_0_desired: u128,
+            amount_1_desired: u128,
+            amount_0_min: u128,
+            amount_1_min: u128,
+            farm: AccountId,
+            deadline: u64,
+        ) -> Result<(u128, u128, u128), RouterV2Error> {
+            check_timestamp(deadline)?;
+            self.ensure_farm_pool(farm, pair)?;
+            let (amount_0, amount_1, liquidity) = self
+                .get_and_cache_pair(Some(pair), token_0, token_1)?
+                .add_liquidity(
+                    token_0,
+                    token_1,
+                    amount_0_desired,
+                    amount_1_desired,
+                    amount_0_min,
+                    amount_1_min,
+                    self.env().account_id(),
+                    self.env().caller(),
+                )?;
+            self.stake(farm, pair, liquidity)?;
+            Ok((amount_0, amount_1, liquidity))
+        }
+
+        #[ink(message, payable)]
+        fn add_stable_pool_liquidity_and_stake(
+            &mut self,
+            pool: AccountId,
+            min_share_amount: u128,
+            amounts: Vec<u128>,
+            farm: AccountId,
+            deadline: u64,
+        ) -> Result<(u128, u128), RouterV2Error> {
+            check_timestamp(deadline)?;
+            self.ensure_farm_pool(farm, pool)?;
+            let (shares, fee) = self.get_and_cache_stable_pool(pool)?.add_liquidity(
+                min_share_amount,
+                amounts,
+                self.env().account_id(),
+                self.env().caller(),
+                self.wnative,
+            )?;
+            self.stake(farm, pool, shares)?;
+            Ok((shares, fee))
+        }
+
+        // ----------- MULTICALL METHODS ----------- //
+
+        #[ink(message)]
+        fn multicall(
+            &mut self,
+            calls: Vec<RouterCall>,
+            deadline: u64,
+        ) -> Result<Vec<Vec<u128>>, RouterV2Error> {
+            check_timestamp(deadline)?;
+            calls
+                .into_iter()
+                .map(|call| self.execute_call(call))
+                .collect()
+        }
+
+        // ----------- PERMIT METHODS ----------- //
+
+        #[ink(message)]
+        fn remove_pair_liquidity_with_permit(
+            &mut self,
+            pair: AccountId,
+            token_0: AccountId,
+            token_1: AccountId,
+            liquidity: u128,
+            amount_0_min: u128,
+            amount_1_min: u128,
+            to: AccountId,
+            deadline: u64,
+            signature: PermitSignature,
+        ) -> Result<(u128, u128), RouterV2Error> {
+            self.permit_router(pair, liquidity, deadline, signature)?;
+            self.remove_pair_liquidity(
+                pair,
+                token_0,
+                token_1,
+                liquidity,
+                amount_0_min,
+                amount_1_min,
+                to,
+                deadline,
+            )
+        }
+
+        #[ink(message)]
+        fn remove_pair_liquidity_native_with_permit(
+            &mut self,
+            pair: AccountId,
+            token: AccountId,
+            liquidity: u128,
+            amount_token_min: u128,
+            amount_native_min: Balance,
+            to: AccountId,
+            deadline: u64,
+            signature: PermitSignature,
+        ) -> Result<(u128, Balance), RouterV2Error> {
+            self.permit_router(pair, liquidity, deadline, signature)?;
+            self.remove_pair_liquidity_native(
+                pair,
+                token,
+                liquidity,
+                amount_token_min,
+                amount_native_min,
+                to,
+                deadline,
+            )
+        }
+
+        #[ink(message)]
+        fn remove_stable_pool_liquidity_with_permit(
+            &mut self,
+            pool: AccountId,
+            max_share_amount: u128,
+            amounts: Vec<u128>,
+            to: AccountId,
+            deadline: u64,
+            signature: PermitSignature,
+        ) -> Result<(u128, u128), RouterV2Error> {
+            self.permit_router(pool, max_share_amount, deadline, signature)?;
+            self.remove_stable_pool_liquidity(pool, max_share_amount, amounts, to, deadline)
+        }
+
+        #[ink(message)]
+        fn remove_stable_pool_liquidity_by_share_with_permit(
+            &mut self,
+            pool: AccountId,
+            share_amount: u128,
+            min_amounts: Vec<u128>,
+            to: AccountId,
+            deadline: u64,
+            signature: PermitSignature,
+        ) -> Result<Vec<u128>, RouterV2Error> {
+            self.permit_router(pool, share_amount, deadline, signature)?;
+            self.remove_stable_pool_liquidity_by_share(
+                pool,
+                share_amount,
+                min_amounts,
+                to,
+                deadline,
+            )
+        }
+
+        #[ink(message)]
+        fn remove_stable_pool_liquidity_one_coin_with_permit(
+            &mut self,
+            pool: AccountId,
+            share_amount: u128,
+            token: AccountId,
+            min_amount: u128,
+            to: AccountId,
+            deadline: u64,
+            signature: PermitSignature,
+        ) -> Result<(u128, u128), RouterV2Error> {
+            self.permit_router(pool, share_amount, deadline, signature)?;
+            self.remove_stable_pool_liquidity_one_coin(
+                pool,
+                share_amount,
+                token,
+                min_amount,
+                to,
+                deadline,
+            )
+        }
+    }
+
+    impl Ownable2Step for RouterV2Contract {
+        #[ink(message)]
+        fn get_owner(&self) -> Ownable2StepResult<AccountId> {
+            self.ownable.get_owner()
+        }
+
+        #[ink(message)]
+        fn get_pending_owner(&self) -> Ownable2StepResult<AccountId> {
+            self.ownable.get_pending_owner()
+        }
+
+        #[ink(message)]
+        fn transfer_ownership(&mut self, new_owner: AccountId) -> Ownable2StepResult<()> {
+            self.ownable
+                .transfer_ownership(self.env().caller(), new_owner)?;
+            self.env()
+                .emit_event(TransferOwnershipInitiated { new_owner });
+            Ok(())
+        }
+
+        #[ink(message)]
+        fn accept_ownership(&mut self) -> Ownable2StepResult<()> {
+            let new_owner = self.env().caller();
+            self.ownable.accept_ownership(new_owner)?;
+            self.env()
+                .emit_event(TransferOwnershipAccepted { new_owner });
+            Ok(())
+        }
+
+        #[ink(message)]
+        fn renounce_ownership(&mut self) -> Ownable2StepResult<()> {
+            self.ownable
+                .renounce_ownership(self.env().caller(), self.env().account_id())?;
+            self.env().emit_event(OwnershipRenounced {});
+            Ok(())
+        }
+
+        #[ink(message)]
+        fn ensure_owner(&self) -> Ownable2StepResult<()> {
+            self.ownable.ensure_owner(self.env().caller())
         }
     }
 
@@ -570,6 +1451,12 @@ pub mod router_v2 {
             let router = RouterV2Contract::new(factory, wnative);
             assert_eq!(router.pair_factory(), factory);
             assert_eq!(router.wnative(), wnative);
+            assert_eq!(
+                router.get_owner(),
+                Ok(ink::env::test::default_accounts::<ink::env::DefaultEnvironment>().alice)
+            );
+            assert!(router.stable_pools().is_empty());
+            assert_eq!(router.stable_pool_factory(), None);
         }
     }
 }
@@ -4,8 +4,10 @@ use ink::{
     codegen::TraitCallBuilder,
     contract_ref,
     env::{account_id, caller, transferred_value, DefaultEnvironment as Env},
+    prelude::{vec, vec::Vec},
     primitives::AccountId,
 };
+use primitive_types::U256;
 use traits::{Balance, MathError, Pair as PairTrait, RouterV2Error};
 
 const PAIR_TRADING_FEE_DENOM: u128 = 1000;
@@ -19,7 +21,6 @@ pub struct Pair {
     id: AccountId,
     token_0: AccountId,
     token_1: AccountId,
-    fee: u8,
 }
 
 impl Pair {
@@ -31,17 +32,15 @@ impl Pair {
         // Assume that the `pair_id` is a Pair contract and try to get the fee value.
         // If the call is not successful return None indicating that the `pair_id`
         // is not a Pair contract
-        let fee = match contract_ref.call().get_fee().try_invoke() {
-            Ok(Ok(fee)) => fee,
-            _ => return None,
-        };
+        if !matches!(contract_ref.call().get_fee().try_invoke(), Ok(Ok(_))) {
+            return None;
+        }
         let token_0 = contract_ref.get_token_0();
         let token_1 = contract_ref.get_token_1();
         Some(Pair {
             id: pair_id,
             token_0,
             token_1,
-            fee,
         })
     }
 
@@ -49,6 +48,20 @@ impl Pair {
         self.id.into()
     }
 
+    pub fn id(&self) -> AccountId {
+        self.id
+    }
+
+    /// Returns the fee currently in effect.
+    /// It is not cached, as it can be changed by the Factory.
+    fn fee(&self) -> u8 {
+        self.contract_ref().get_fee()
+    }
+
+    pub fn tokens(&self) -> Vec<AccountId> {
+        vec![self.token_0, self.token_1]
+    }
+
     fn check_tokens(&self, token_0: AccountId, token_1: AccountId) -> Result<(), RouterV2Error> {
         ensure!(
             (self.token_0 == token_0 && self.token_1 == token_1)
@@ -74,7 +87,7 @@ impl Pair {
     /// Returns how much of `token_1` tokens should be added
     /// to the pool to maintain the constant ratio `k = reserve_0 / reserve_1`,
     /// given `amount_0` of `token_0`.
-    fn quote(amount_0: u128, reserve_0: u128, reserve_1: u128) -> Result<u128, RouterV2Error> {
+    pub fn quote(amount_0: u128, reserve_0: u128, reserve_1: u128) -> Result<u128, RouterV2Error> {
         let amount_1: u128 = casted_mul(amount_0, reserve_1)
             .checked_div(reserve_0.into())
             .ok_or(MathError::DivByZero(6))?
@@ -132,9 +145,8 @@ impl Pair {
         amount_0_min: u128,
         amount_1_min: u128,
         to: AccountId,
-        deadline: u64,
+        payer: AccountId,
     ) -> Result<(u128, u128, u128), RouterV2Error> {
-        check_timestamp(deadline)?;
         self.check_tokens(token_0, token_1)?;
         let (amount_0, amount_1) = self.calculate_liquidity(
             token_0,
@@ -145,9 +157,8 @@ impl Pair {
             amount_1_min,
         )?;
 
-        let caller = caller::<Env>();
-        psp22_transfer_from(token_0, caller, self.id, amount_0)?;
-        psp22_transfer_from(token_1, caller, self.id, amount_1)?;
+        pay(token_0, payer, self.id, amount_0)?;
+        pay(token_1, payer, self.id, amount_1)?;
 
         let liquidity = self.contract_ref().mint(to)?;
 
@@ -162,9 +173,7 @@ impl Pair {
         amount_token_min: u128,
         amount_native_min: Balance,
         to: AccountId,
-        deadline: u64,
     ) -> Result<(u128, Balance, u128), RouterV2Error> {
-        check_timestamp(deadline)?;
         self.check_tokens(token, wnative)?;
         let received_value = transferred_value::<Env>();
         let (amount_0, amount_native) = self.calculate_liquidity(
@@ -198,11 +207,10 @@ impl Pair {
         amount_0_min: u128,
         amount_1_min: u128,
         to: AccountId,
-        deadline: u64,
+        payer: AccountId,
     ) -> Result<(u128, u128), RouterV2Error> {
-        check_timestamp(deadline)?;
         self.check_tokens(token_0, token_1)?;
-        psp22_transfer_from(self.id, caller::<Env>(), self.id, liquidity)?;
+        pay(self.id, payer, self.id, liquidity)?;
 
         let (amount_0, amount_1) = self.contract_ref().burn(to)?;
         let (amount_0, amount_1) = if token_0 < token_1 {
@@ -225,7 +233,6 @@ impl Pair {
         amount_token_min: u128,
         amount_native_min: Balance,
         to: AccountId,
-        deadline: u64,
     ) -> Result<(u128, Balance), RouterV2Error> {
         let (amount_token, amount_native) = self.remove_liquidity(
             token,
@@ -234,7 +241,7 @@ impl Pair {
             amount_token_min,
             amount_native_min,
             account_id::<Env>(),
-            deadline,
+            caller::<Env>(),
         )?;
         psp22_transfer(token, to, amount_token)?;
         withdraw(wnative, amount_native)?;
@@ -242,6 +249,112 @@ impl Pair {
         Ok((amount_token, amount_native))
     }
 
+    /// Returns the other token of the pair.
+    fn other_token(&self, token: AccountId) -> Result<AccountId, RouterV2Error> {
+        if token == self.token_0 {
+            Ok(self.token_1)
+        } else if token == self.token_1 {
+            Ok(self.token_0)
+        } else {
+            Err(RouterV2Error::InvalidToken)
+        }
+    }
+
+    /// Adds liquidity to the pair from `amount_in` of a single `token_in`.
+    ///
+    /// Swaps the optimal part of `amount_in` to the other token and deposits both tokens.
+    /// Only the used part of `amount_in` is transferred from the caller,
+    /// the leftover of the other token is refunded to the caller.
+    ///
+    /// Returns a tuple of (used amount of `token_in`, minted liquidity).
+    pub fn zap_in(
+        &self,
+        token_in: AccountId,
+        amount_in: u128,
+        min_liquidity: u128,
+        to: AccountId,
+    ) -> Result<(u128, u128), RouterV2Error> {
+        let token_out = self.other_token(token_in)?;
+        ensure!(amount_in > 0, RouterV2Error::InsufficientAmount);
+        let (reserve_in, reserve_out) = self.get_reserves(&token_in, &token_out);
+        ensure!(
+            reserve_in > 0 && reserve_out > 0,
+            RouterV2Error::InsufficientLiquidity
+        );
+
+        let fee = self.fee();
+        let swap_amount = Pair::compute_zap_swap_amount(amount_in, reserve_in, fee)?;
+        let amount_out = Pair::compute_amount_out(swap_amount, reserve_in, reserve_out, fee)?;
+
+        let caller = caller::<Env>();
+        let router = account_id::<Env>();
+        psp22_transfer_from(token_in, caller, self.id, swap_amount)?;
+        self.swap(token_in, token_out, amount_out, router)?;
+
+        let (amount_0, amount_1) = self.calculate_liquidity(
+            token_in,
+            token_out,
+            amount_in - swap_amount,
+            amount_out,
+            0,
+            0,
+        )?;
+        psp22_transfer_from(token_in, caller, self.id, amount_0)?;
+        psp22_transfer(token_out, self.id, amount_1)?;
+
+        let liquidity = self.contract_ref().mint(to)?;
+        ensure!(
+            liquidity >= min_liquidity,
+            RouterV2Error::InsufficientOutputAmount
+        );
+
+        if amount_out > amount_1 {
+            psp22_transfer(token_out, caller, amount_out - amount_1)?;
+        }
+
+        Ok((swap_amount + amount_0, liquidity))
+    }
+
+    /// Removes `liquidity` from the pair and swaps the withdrawn other token to `token_out`.
+    ///
+    /// Returns the total amount of `token_out` transferred to `to` account.
+    pub fn zap_out(
+        &self,
+        liquidity: u128,
+        token_out: AccountId,
+        min_amount_out: u128,
+        to: AccountId,
+    ) -> Result<u128, RouterV2Error> {
+        let token_in = self.other_token(token_out)?;
+        psp22_transfer_from(self.id, caller::<Env>(), self.id, liquidity)?;
+
+        let (amount_0, amount_1) = self.contract_ref().burn(account_id::<Env>())?;
+        let (amount_in, amount_out) = if token_in < token_out {
+            (amount_0, amount_1)
+        } else {
+            (amount_1, amount_0)
+        };
+
+        let mut total_amount_out = amount_out;
+        if amount_in > 0 {
+            let (reserve_in, reserve_out) = self.get_reserves(&token_in, &token_out);
+            let swapped_amount =
+                Pair::compute_amount_out(amount_in, reserve_in, reserve_out, self.fee())?;
+            psp22_transfer(token_in, self.id, amount_in)?;
+            self.swap(token_in, token_out, swapped_amount, to)?;
+            total_amount_out = total_amount_out
+                .checked_add(swapped_amount)
+                .ok_or(MathError::AddOverflow(22))?;
+        }
+        ensure!(
+            total_amount_out >= min_amount_out,
+            RouterV2Error::InsufficientOutputAmount
+        );
+        psp22_transfer(token_out, to, amount_out)?;
+
+        Ok(total_amount_out)
+    }
+
     pub fn swap(
         &self,
         token_in: AccountId,
@@ -268,6 +381,28 @@ impl Pair {
     ) -> Result<u128, RouterV2Error> {
         self.check_tokens(token_in, token_out)?;
         let (reserve_in, reserve_out) = self.get_reserves(&token_in, &token_out);
+        Pair::compute_amount_in(amount_out, reserve_in, reserve_out, self.fee())
+    }
+
+    pub fn get_amount_out(
+        &self,
+        token_in: AccountId,
+        token_out: AccountId,
+        amount_in: u128,
+    ) -> Result<u128, RouterV2Error> {
+        self.check_tokens(token_in, token_out)?;
+        let (reserve_in, reserve_out) = self.get_reserves(&token_in, &token_out);
+        Pair::compute_amount_out(amount_in, reserve_in, reserve_out, self.fee())
+    }
+
+    /// Returns amount of `token_in` required to receive `amount_out` of `token_out`
+    /// from a pair with given reserves and `fee` (in millis).
+    pub fn compute_amount_in(
+        amount_out: u128,
+        reserve_in: u128,
+        reserve_out: u128,
+        fee: u8,
+    ) -> Result<u128, RouterV2Error> {
         ensure!(amount_out > 0, RouterV2Error::InsufficientAmount);
         ensure!(
             reserve_in > 0 && reserve_out > 0,
@@ -282,7 +417,7 @@ impl Pair {
             reserve_out
                 .checked_sub(amount_out)
                 .ok_or(MathError::SubUnderflow(15))?,
-            PAIR_TRADING_FEE_DENOM - (self.fee as u128),
+            PAIR_TRADING_FEE_DENOM - (fee as u128),
         );
 
         let amount_in: u128 = numerator
@@ -296,14 +431,14 @@ impl Pair {
         Ok(amount_in)
     }
 
-    pub fn get_amount_out(
-        &self,
-        token_in: AccountId,
-        token_out: AccountId,
+    /// Returns amount of `token_out` received for `amount_in` of `token_in`
+    /// from a pair with given reserves and `fee` (in millis).
+    pub fn compute_amount_out(
         amount_in: u128,
+        reserve_in: u128,
+        reserve_out: u128,
+        fee: u8,
     ) -> Result<u128, RouterV2Error> {
-        self.check_tokens(token_in, token_out)?;
-        let (reserve_in, reserve_out) = self.get_reserves(&token_in, &token_out);
         ensure!(amount_in > 0, RouterV2Error::InsufficientAmount);
         ensure!(
             reserve_in > 0 && reserve_out > 0,
@@ -311,7 +446,7 @@ impl Pair {
         );
 
         // Adjusts for fees paid in the `token_in`.
-        let amount_in_with_fee = casted_mul(amount_in, PAIR_TRADING_FEE_DENOM - (self.fee as u128));
+        let amount_in_with_fee = casted_mul(amount_in, PAIR_TRADING_FEE_DENOM - (fee as u128));
 
         let numerator = amount_in_with_fee
             .checked_mul(reserve_out.into())
@@ -329,4 +464,33 @@ impl Pair {
 
         Ok(amount_out)
     }
+
+    /// Returns the part of `amount_in` of a token with `reserve_in` which should be swapped
+    /// to the other token, so that the rest of `amount_in` and the swap output are in
+    /// the pair's ratio after the swap with `fee` (in millis).
+    ///
+    /// It is the positive root `s` of `(1 - f) * s^2 + (2 - f) * r * s - a * r = 0`.
+    pub fn compute_zap_swap_amount(
+        amount_in: u128,
+        reserve_in: u128,
+        fee: u8,
+    ) -> Result<u128, RouterV2Error> {
+        let fee = fee as u128;
+        // (2 - f) * r, scaled by the fee denominator
+        let b = casted_mul(2 * PAIR_TRADING_FEE_DENOM - fee, reserve_in);
+        // 4 * (1 - f) * a * r, scaled by the squared fee denominator
+        let c = casted_mul(amount_in, reserve_in)
+            .checked_mul((4 * (PAIR_TRADING_FEE_DENOM - fee) * PAIR_TRADING_FEE_DENOM).into())
+            .ok_or(MathError::MulOverflow(22))?;
+        let discriminant = b
+            .checked_mul(b)
+            .and_then(|b_squared| b_squared.checked_add(c))
+            .ok_or(MathError::MulOverflow(22))?;
+        let swap_amount = (discriminant.integer_sqrt() - b)
+            .checked_div((2 * (PAIR_TRADING_FEE_DENOM - fee)).into())
+            .ok_or(MathError::DivByZero(22))?
+            .try_into()
+            .map_err(|_| MathError::CastOverflow(22))?;
+        Ok(swap_amount)
+    }
 }
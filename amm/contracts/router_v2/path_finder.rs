@@ -0,0 +1,28 @@
+use ink::{prelude::vec::Vec, primitives::AccountId};
+use traits::PathFinderPool;
+
+use crate::pool::Pool;
+
+/// Max number of steps of a path considered by the path finder.
+pub const MAX_HOPS: u8 = 4;
+
+/// Max number of pools searched by the path finder, i.e. all pairs of the pair `Factory`
+/// and all known stable pools. The query fails once there are more of them.
+///
+/// Every pool is fetched and every step considered by the search is quoted with
+/// cross-contract calls, so both the number of pools and hops have to be bounded.
+pub const MAX_POOLS: usize = 32;
+
+impl PathFinderPool for Pool {
+    fn id(&self) -> AccountId {
+        Pool::id(self)
+    }
+
+    fn tokens(&self) -> Vec<AccountId> {
+        Pool::tokens(self)
+    }
+
+    fn quote(&self, token_in: AccountId, token_out: AccountId, amount_in: u128) -> Option<u128> {
+        self.get_amount_out(token_in, token_out, amount_in).ok()
+    }
+}
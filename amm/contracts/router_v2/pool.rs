@@ -1,8 +1,11 @@
-use ink::primitives::AccountId;
+use ink::{prelude::vec::Vec, primitives::AccountId};
 use traits::RouterV2Error;
 
+pub use crate::concentrated_pool::*;
+pub use crate::crypto_pool::*;
 pub use crate::pair::*;
 pub use crate::stable_pool::*;
+pub use crate::weighted_pool::*;
 
 #[derive(scale::Decode, scale::Encode)]
 #[cfg_attr(
@@ -12,6 +15,9 @@ pub use crate::stable_pool::*;
 pub enum Pool {
     Pair(Pair),
     StablePool(StablePool),
+    ConcentratedPool(ConcentratedPool),
+    WeightedPool(WeightedPool),
+    CryptoPool(CryptoPool),
 }
 
 impl Pool {
@@ -19,6 +25,30 @@ impl Pool {
         Pair::try_new(pool_id)
             .map(Self::Pair)
             .or(StablePool::try_new(pool_id).map(Self::StablePool))
+            .or_else(|| ConcentratedPool::try_new(pool_id).map(Self::ConcentratedPool))
+            .or_else(|| WeightedPool::try_new(pool_id).map(Self::WeightedPool))
+            .or_else(|| CryptoPool::try_new(pool_id).map(Self::CryptoPool))
+    }
+
+    pub fn id(&self) -> AccountId {
+        match self {
+            Pool::Pair(pool) => pool.id(),
+            Pool::StablePool(pool) => pool.id(),
+            Pool::ConcentratedPool(pool) => pool.id(),
+            Pool::WeightedPool(pool) => pool.id(),
+            Pool::CryptoPool(pool) => pool.id(),
+        }
+    }
+
+    /// Returns tokens which can be exchanged in the pool.
+    pub fn tokens(&self) -> Vec<AccountId> {
+        match self {
+            Pool::Pair(pool) => pool.tokens(),
+            Pool::StablePool(pool) => pool.tokens(),
+            Pool::ConcentratedPool(pool) => pool.tokens(),
+            Pool::WeightedPool(pool) => pool.tokens(),
+            Pool::CryptoPool(pool) => pool.tokens(),
+        }
     }
 
     pub fn get_amount_in(
@@ -30,6 +60,9 @@ impl Pool {
         match self {
             Pool::Pair(pool) => pool.get_amount_in(token_in, token_out, amount_out),
             Pool::StablePool(pool) => pool.get_amount_in(token_in, token_out, amount_out),
+            Pool::ConcentratedPool(pool) => pool.get_amount_in(token_in, token_out, amount_out),
+            Pool::WeightedPool(pool) => pool.get_amount_in(token_in, token_out, amount_out),
+            Pool::CryptoPool(pool) => pool.get_amount_in(token_in, token_out, amount_out),
         }
     }
 
@@ -42,6 +75,9 @@ impl Pool {
         match self {
             Pool::Pair(pool) => pool.get_amount_out(token_in, token_out, amount_in),
             Pool::StablePool(pool) => pool.get_amount_out(token_in, token_out, amount_in),
+            Pool::ConcentratedPool(pool) => pool.get_amount_out(token_in, token_out, amount_in),
+            Pool::WeightedPool(pool) => pool.get_amount_out(token_in, token_out, amount_in),
+            Pool::CryptoPool(pool) => pool.get_amount_out(token_in, token_out, amount_in),
         }
     }
 
@@ -55,6 +91,9 @@ impl Pool {
         match self {
             Pool::Pair(pool) => pool.swap(token_in, token_out, amount_out, to),
             Pool::StablePool(pool) => pool.swap(token_in, token_out, amount_out, to),
+            Pool::ConcentratedPool(pool) => pool.swap(token_in, token_out, amount_out, to),
+            Pool::WeightedPool(pool) => pool.swap(token_in, token_out, amount_out, to),
+            Pool::CryptoPool(pool) => pool.swap(token_in, token_out, amount_out, to),
         }
     }
 }
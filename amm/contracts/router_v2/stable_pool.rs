@@ -1,4 +1,8 @@
-use amm_helpers::ensure;
+use amm_helpers::{
+    constants::stable_pool::{MAX_AMP, MAX_COINS, MIN_AMP},
+    ensure,
+    stable_swap_math::{fees::Fees, rated_swap_from, rated_swap_to},
+};
 use ink::{
     codegen::TraitCallBuilder,
     contract_ref,
@@ -6,12 +10,9 @@ use ink::{
     prelude::vec::Vec,
     primitives::AccountId,
 };
-use traits::{RouterV2Error, StablePool as StablePoolTrait};
+use traits::{RouterV2Error, StablePool as StablePoolTrait, StablePoolError};
 
-use crate::utils::{
-    check_timestamp, psp22_approve, psp22_transfer, psp22_transfer_from, transfer_native, withdraw,
-    wrap,
-};
+use crate::utils::{pay, psp22_approve, psp22_transfer, transfer_native, withdraw, wrap};
 
 #[derive(scale::Decode, scale::Encode)]
 #[cfg_attr(
@@ -53,6 +54,14 @@ impl StablePool {
         self.id.into()
     }
 
+    pub fn id(&self) -> AccountId {
+        self.id
+    }
+
+    pub fn tokens(&self) -> Vec<AccountId> {
+        self.tokens.clone()
+    }
+
     /// Adds liquidity to the pool.
     ///
     /// If a non-zero native amount is transferred, it attempts to wrap the transferred
@@ -62,10 +71,9 @@ impl StablePool {
         min_share_amount: u128,
         amounts: Vec<u128>,
         to: AccountId,
-        deadline: u64,
+        payer: AccountId,
         wnative: AccountId,
     ) -> Result<(u128, u128), RouterV2Error> {
-        check_timestamp(deadline)?;
         let native_received = transferred_value::<Env>();
         let (wnative_idx, native_surplus) = if native_received > 0 {
             let wnative_idx = self
@@ -85,18 +93,31 @@ impl StablePool {
             transfer_native(caller::<Env>(), native_surplus)?;
         }
         for i in (0..self.tokens.len()).filter(|&idx| idx != wnative_idx) {
-            psp22_transfer_from(
-                self.tokens[i],
-                caller::<Env>(),
-                account_id::<Env>(),
-                amounts[i],
-            )?;
+            pay(self.tokens[i], payer, account_id::<Env>(), amounts[i])?;
         }
         Ok(self
             .contract_ref()
             .add_liquidity(min_share_amount, amounts, to)?)
     }
 
+    /// Adds `amount` of a single `token` held by the router to the pool.
+    pub fn add_liquidity_one_coin(
+        &self,
+        token: AccountId,
+        amount: u128,
+        min_share_amount: u128,
+        to: AccountId,
+    ) -> Result<(u128, u128), RouterV2Error> {
+        let amounts = self
+            .tokens
+            .iter()
+            .map(|&id| if id == token { amount } else { 0 })
+            .collect();
+        Ok(self
+            .contract_ref()
+            .add_liquidity(min_share_amount, amounts, to)?)
+    }
+
     /// Withdraws liquidity from the pool by the specified amounts.
     ///
     /// If the native token is present in the pool, it attempts to unwrap the wrapped
@@ -106,16 +127,10 @@ impl StablePool {
         max_share_amount: u128,
         amounts: Vec<u128>,
         to: AccountId,
-        deadline: u64,
+        payer: AccountId,
         wnative: AccountId,
     ) -> Result<(u128, u128), RouterV2Error> {
-        check_timestamp(deadline)?;
-        psp22_transfer_from(
-            self.id,
-            caller::<Env>(),
-            account_id::<Env>(),
-            max_share_amount,
-        )?;
+        pay(self.id, payer, account_id::<Env>(), max_share_amount)?;
         let (lp_burned, fee_part) = match self.wnative_idx(wnative) {
             Some(wnative_idx) => {
                 let res = self.contract_ref().remove_liquidity_by_amounts(
@@ -132,7 +147,12 @@ impl StablePool {
             }
         };
         if max_share_amount > lp_burned {
-            psp22_transfer(self.id, caller::<Env>(), max_share_amount - lp_burned)?;
+            pay(
+                self.id,
+                account_id::<Env>(),
+                payer,
+                max_share_amount - lp_burned,
+            )?;
         }
         Ok((lp_burned, fee_part))
     }
@@ -146,11 +166,10 @@ impl StablePool {
         share_amount: u128,
         min_amounts: Vec<u128>,
         to: AccountId,
-        deadline: u64,
+        payer: AccountId,
         wnative: AccountId,
     ) -> Result<Vec<u128>, RouterV2Error> {
-        check_timestamp(deadline)?;
-        psp22_transfer_from(self.id, caller::<Env>(), account_id::<Env>(), share_amount)?;
+        pay(self.id, payer, account_id::<Env>(), share_amount)?;
         match self.wnative_idx(wnative) {
             Some(wnative_idx) => {
                 let amounts = self.contract_ref().remove_liquidity_by_shares(
@@ -169,6 +188,40 @@ impl StablePool {
         }
     }
 
+    /// Withdraws liquidity from the pool in a single `token`.
+    ///
+    /// If the `token` is the wrapped native token, it attempts to unwrap it
+    /// and withdraw the native token to the `to` account.
+    pub fn remove_liquidity_one_coin(
+        &self,
+        share_amount: u128,
+        token: AccountId,
+        min_amount: u128,
+        to: AccountId,
+        payer: AccountId,
+        wnative: AccountId,
+    ) -> Result<(u128, u128), RouterV2Error> {
+        pay(self.id, payer, account_id::<Env>(), share_amount)?;
+        if token == wnative {
+            let (amount, fee) = self.contract_ref().remove_liquidity_one_coin(
+                share_amount,
+                token,
+                min_amount,
+                account_id::<Env>(),
+            )?;
+            withdraw(wnative, amount)?;
+            transfer_native(to, amount)?;
+            Ok((amount, fee))
+        } else {
+            Ok(self.contract_ref().remove_liquidity_one_coin(
+                share_amount,
+                token,
+                min_amount,
+                to,
+            )?)
+        }
+    }
+
     pub fn swap(
         &self,
         token_in: AccountId,
@@ -205,6 +258,74 @@ impl StablePool {
             .map(|(amount_out, _)| amount_out)?)
     }
 
+    /// Returns a tuple of (amount out, fee) for swapping `amount_in` of `token_in_id`
+    /// to `token_out_id` in a stable pool with given `reserves`, `scaled_rates`,
+    /// `amp_coef` and `trade_fee`.
+    pub fn compute_amount_out(
+        amount_in: u128,
+        token_in_id: u8,
+        token_out_id: u8,
+        reserves: &[u128],
+        scaled_rates: &[u128],
+        amp_coef: u128,
+        trade_fee: u32,
+    ) -> Result<(u128, u128), RouterV2Error> {
+        let fees = check_quote_params(
+            token_in_id,
+            token_out_id,
+            reserves,
+            scaled_rates,
+            amp_coef,
+            trade_fee,
+        )?;
+        ensure!(amount_in > 0, RouterV2Error::InsufficientAmount);
+        Ok(rated_swap_to(
+            scaled_rates,
+            token_in_id as usize,
+            amount_in,
+            token_out_id as usize,
+            reserves,
+            &fees,
+            amp_coef,
+        )?)
+    }
+
+    /// Returns a tuple of (amount in, fee) for receiving `amount_out` of `token_out_id`
+    /// for `token_in_id` from a stable pool with given `reserves`, `scaled_rates`,
+    /// `amp_coef` and `trade_fee`.
+    pub fn compute_amount_in(
+        amount_out: u128,
+        token_in_id: u8,
+        token_out_id: u8,
+        reserves: &[u128],
+        scaled_rates: &[u128],
+        amp_coef: u128,
+        trade_fee: u32,
+    ) -> Result<(u128, u128), RouterV2Error> {
+        let fees = check_quote_params(
+            token_in_id,
+            token_out_id,
+            reserves,
+            scaled_rates,
+            amp_coef,
+            trade_fee,
+        )?;
+        ensure!(amount_out > 0, RouterV2Error::InsufficientAmount);
+        ensure!(
+            amount_out < reserves[token_out_id as usize],
+            RouterV2Error::InsufficientLiquidity
+        );
+        Ok(rated_swap_from(
+            scaled_rates,
+            token_in_id as usize,
+            amount_out,
+            token_out_id as usize,
+            reserves,
+            &fees,
+            amp_coef,
+        )?)
+    }
+
     fn wnative_idx(&self, wnative: AccountId) -> Option<usize> {
         self.tokens.iter().position(|&token| wnative == token)
     }
@@ -223,3 +344,39 @@ impl StablePool {
         Ok(())
     }
 }
+
+/// Validates caller-supplied stable pool parameters for the stateless quotes.
+///
+/// Returns the trade fee (without the protocol fee).
+fn check_quote_params(
+    token_in_id: u8,
+    token_out_id: u8,
+    reserves: &[u128],
+    scaled_rates: &[u128],
+    amp_coef: u128,
+    trade_fee: u32,
+) -> Result<Fees, RouterV2Error> {
+    ensure!(
+        (2..=MAX_COINS).contains(&reserves.len()) && scaled_rates.len() == reserves.len(),
+        StablePoolError::IncorrectTokenCount
+    );
+    ensure!(
+        token_in_id != token_out_id,
+        StablePoolError::IdenticalTokenId
+    );
+    ensure!(
+        (token_in_id as usize) < reserves.len() && (token_out_id as usize) < reserves.len(),
+        RouterV2Error::InvalidToken
+    );
+    ensure!(
+        reserves.iter().all(|&r| r > 0),
+        StablePoolError::InsufficientLiquidity
+    );
+    ensure!(
+        scaled_rates.iter().all(|&r| r > 0),
+        RouterV2Error::InvalidRate
+    );
+    ensure!(amp_coef >= MIN_AMP, StablePoolError::AmpCoefTooLow);
+    ensure!(amp_coef <= MAX_AMP, StablePoolError::AmpCoefTooHigh);
+    Fees::new(trade_fee, 0).ok_or_else(|| StablePoolError::InvalidFee.into())
+}
@@ -2,12 +2,12 @@ use amm_helpers::ensure;
 use ink::{
     codegen::TraitCallBuilder,
     contract_ref,
-    env::{block_timestamp, transfer, DefaultEnvironment as Env},
+    env::{account_id, block_timestamp, transfer, DefaultEnvironment as Env},
     prelude::vec::Vec,
     primitives::AccountId,
 };
 use psp22::{PSP22Error, PSP22};
-use traits::{Balance, RouterV2Error};
+use traits::{Balance, Permit, PermitError, PermitSignature, RouterV2Error};
 use wrapped_azero::WrappedAZERO;
 
 /// Checks if the current block timestamp is not after the deadline.
@@ -34,12 +34,56 @@ pub fn psp22_transfer_from(
     token.transfer_from(from, to, value, Vec::new())
 }
 
+/// Transfers `value` of `token` from `payer` to `to` account.
+///
+/// If `payer` is the router, its own balance is used instead of the allowance.
+#[inline]
+pub fn pay(
+    token: AccountId,
+    payer: AccountId,
+    to: AccountId,
+    value: u128,
+) -> Result<(), PSP22Error> {
+    if payer != account_id::<Env>() {
+        psp22_transfer_from(token, payer, to, value)
+    } else if to != payer {
+        psp22_transfer(token, to, value)
+    } else {
+        Ok(())
+    }
+}
+
+#[inline]
+pub fn psp22_balance_of(token: AccountId, owner: AccountId) -> u128 {
+    let token: contract_ref!(PSP22, Env) = token.into();
+    token.balance_of(owner)
+}
+
+#[inline]
+pub fn psp22_allowance(token: AccountId, owner: AccountId, spender: AccountId) -> u128 {
+    let token: contract_ref!(PSP22, Env) = token.into();
+    token.allowance(owner, spender)
+}
+
 #[inline]
 pub fn psp22_approve(token: AccountId, spender: AccountId, value: u128) -> Result<(), PSP22Error> {
     let mut token: contract_ref!(PSP22, Env) = token.into();
     token.approve(spender, value)
 }
 
+#[inline]
+pub fn psp22_permit(
+    token: AccountId,
+    owner: AccountId,
+    spender: AccountId,
+    value: u128,
+    deadline: u64,
+    signature: PermitSignature,
+) -> Result<(), PermitError> {
+    let mut token: contract_ref!(Permit, Env) = token.into();
+    token.permit(owner, spender, value, deadline, signature)
+}
+
 #[inline]
 pub fn wrap(wnative: AccountId, value: Balance) -> Result<(), RouterV2Error> {
     let mut wnative_ref: contract_ref!(WrappedAZERO, Env) = wnative.into();
@@ -1,5 +1,6 @@
 #![cfg_attr(not(feature = "std"), no_std, no_main)]
 mod amp_coef;
+mod oracle;
 mod token_rate;
 /// Stabelswap implementation based on the CurveFi stableswap model.
 ///
@@ -14,10 +15,14 @@ mod token_rate;
 /// its total supply to try and maintain a stable price a.k.a. rebasing tokens.
 #[ink::contract]
 pub mod stable_pool {
-    use crate::{amp_coef::AmpCoef, token_rate::TokenRate};
+    use crate::{amp_coef::AmpCoef, oracle::Oracle, token_rate::TokenRate};
     use amm_helpers::{
-        constants::stable_pool::{MAX_COINS, RATE_PRECISION, TOKEN_TARGET_DECIMALS},
+        constants::stable_pool::{
+            FEE_DENOM, MAX_COINS, MAX_RATE_CHANGE, PRICE_PRECISION, RATE_PRECISION,
+            RATE_PROVIDER_CHANGE_DELAY, TOKEN_TARGET_DECIMALS,
+        },
         ensure,
+        math::casted_mul,
         stable_swap_math::{self as math, fees::Fees},
     };
     use ink::contract_ref;
@@ -25,9 +30,12 @@ pub mod stable_pool {
         string::{String, ToString},
         {vec, vec::Vec},
     };
+    use primitive_types::U256;
     use psp22::{PSP22Data, PSP22Error, PSP22Event, PSP22Metadata, PSP22};
     use traits::{
-        MathError, Ownable2Step, Ownable2StepData, Ownable2StepResult, StablePool, StablePoolError,
+        FlashBorrower, MathError, Ownable2Step, Ownable2StepData, Ownable2StepResult, Permit,
+        PermitData, PermitError, PermitSignature, StablePool, StablePoolError,
+        FLASH_BORROWER_CALLBACK_SUCCESS,
     };
 
     #[ink(event)]
@@ -62,6 +70,17 @@ pub mod stable_pool {
         pub to: AccountId,
     }
 
+    #[ink(event)]
+    pub struct FlashLoan {
+        #[ink(topic)]
+        pub initiator: AccountId,
+        #[ink(topic)]
+        pub receiver: AccountId,
+        pub tokens: Vec<AccountId>,
+        pub amounts: Vec<u128>,
+        pub fees: Vec<u128>,
+    }
+
     #[ink(event)]
     pub struct Sync {
         pub reserves: Vec<u128>,
@@ -133,6 +152,22 @@ pub mod stable_pool {
         pub protocol_fee: u32,
     }
 
+    #[ink(event)]
+    pub struct TokenRateProviderChangeScheduled {
+        #[ink(topic)]
+        pub token: AccountId,
+        pub rate_provider: Option<AccountId>,
+        pub effective_time: u64,
+    }
+
+    #[ink(event)]
+    pub struct TokenRateProviderChanged {
+        #[ink(topic)]
+        pub token: AccountId,
+        pub rate_provider: Option<AccountId>,
+        pub rate: u128,
+    }
+
     #[ink::storage_item]
     #[derive(Debug)]
     pub struct StablePoolData {
@@ -144,6 +179,8 @@ pub mod stable_pool {
         reserves: Vec<u128>,
         /// Means of getting token rates, either constant or external contract call.
         token_rates: Vec<TokenRate>,
+        /// Scheduled rate providers of tokens and the times from which they can be applied.
+        future_token_rate_providers: Vec<Option<(Option<AccountId>, u64)>>,
         /// Amplification coefficient.
         amp_coef: AmpCoef,
         /// Fees
@@ -157,6 +194,8 @@ pub mod stable_pool {
         ownable: Ownable2StepData,
         pool: StablePoolData,
         psp22: PSP22Data,
+        oracle: Oracle,
+        permit: PermitData,
     }
 
     impl StablePoolContract {
@@ -168,6 +207,7 @@ pub mod stable_pool {
             owner: AccountId,
             fees: Option<Fees>,
             fee_receiver: Option<AccountId>,
+            genesis_hash: Hash,
         ) -> Result<Self, StablePoolError> {
             let mut unique_tokens = tokens.clone();
             unique_tokens.sort();
@@ -202,15 +242,19 @@ pub mod stable_pool {
                     reserves: vec![0; token_count],
                     precisions,
                     token_rates,
+                    future_token_rate_providers: vec![None; token_count],
                     amp_coef: AmpCoef::new(amp_coef)?,
                     fees: fees.ok_or(StablePoolError::InvalidFee)?,
                     fee_receiver,
                 },
                 psp22: PSP22Data::default(),
+                oracle: Oracle::new(token_count),
+                permit: PermitData::new(genesis_hash),
             })
         }
 
         #[ink(constructor)]
+        #[allow(clippy::too_many_arguments)]
         pub fn new_stable(
             tokens: Vec<AccountId>,
             tokens_decimals: Vec<u8>,
@@ -219,6 +263,7 @@ pub mod stable_pool {
             trade_fee: u32,
             protocol_fee: u32,
             fee_receiver: Option<AccountId>,
+            genesis_hash: Hash,
         ) -> Result<Self, StablePoolError> {
             let token_rates = vec![TokenRate::new_constant(RATE_PRECISION); tokens.len()];
             Self::new_pool(
@@ -229,6 +274,7 @@ pub mod stable_pool {
                 owner,
                 Fees::new(trade_fee, protocol_fee),
                 fee_receiver,
+                genesis_hash,
             )
         }
 
@@ -243,13 +289,11 @@ pub mod stable_pool {
             trade_fee: u32,
             protocol_fee: u32,
             fee_receiver: Option<AccountId>,
+            genesis_hash: Hash,
         ) -> Result<Self, StablePoolError> {
             let token_rates: Vec<TokenRate> = external_rates
                 .into_iter()
-                .map(|rate| match rate {
-                    Some(contract) => TokenRate::new_external(contract),
-                    None => TokenRate::new_constant(RATE_PRECISION),
-                })
+                .map(TokenRate::from_rate_provider)
                 .collect();
             Self::new_pool(
                 tokens,
@@ -259,6 +303,7 @@ pub mod stable_pool {
                 owner,
                 Fees::new(trade_fee, protocol_fee),
                 fee_receiver,
+                genesis_hash,
             )
         }
 
@@ -358,6 +403,36 @@ pub mod stable_pool {
             Ok(())
         }
 
+        /// Updates the price oracle with prices after the change of reserves or total supply.
+        ///
+        /// NOTE: Rates should be updated prior to calling this function
+        fn update_oracle(&mut self, rates: &[u128]) -> Result<(), StablePoolError> {
+            let now = self.env().block_timestamp();
+            let total_supply = self.psp22.total_supply();
+            if total_supply == 0 || self.pool.reserves.iter().any(|&reserve| reserve == 0) {
+                self.oracle.update(now, Vec::new(), 0);
+                return Ok(());
+            }
+            let amp_coef = self.amp_coef()?;
+            let d = math::rated_compute_d(rates, &self.pool.reserves, amp_coef)?;
+            let prices = math::rated_spot_prices(rates, &self.pool.reserves, d, amp_coef, 0)?;
+            let virtual_price = self.compute_virtual_price(d)?;
+            self.oracle.update(now, prices, virtual_price);
+            Ok(())
+        }
+
+        /// Computes the value of one LP token in terms of the invariant `d`, with `PRICE_PRECISION`.
+        fn compute_virtual_price(&self, d: U256) -> Result<u128, StablePoolError> {
+            let total_supply = self.psp22.total_supply();
+            ensure!(total_supply > 0, StablePoolError::InsufficientLiquidity);
+            Ok(d.checked_mul(PRICE_PRECISION.into())
+                .ok_or(MathError::MulOverflow(107))?
+                .checked_div(total_supply.into())
+                .ok_or(MathError::DivByZero(107))?
+                .try_into()
+                .map_err(|_| MathError::CastOverflow(107))?)
+        }
+
         fn decrease_reserve(
             &mut self,
             token_id: usize,
@@ -420,6 +495,8 @@ pub mod stable_pool {
             // mint protocol fee
             self.mint_protocol_fee(fee, token_out_id)?;
 
+            self.update_oracle(&rates)?;
+
             // transfer token_out
             self.token_by_address(token_out)
                 .transfer(to, token_out_amount, vec![])?;
@@ -480,6 +557,8 @@ pub mod stable_pool {
             // mint protocol fee
             self.mint_protocol_fee(fee, token_out_id)?;
 
+            self.update_oracle(&rates)?;
+
             // transfer token_in
             _ = self._transfer_in(token_in_id, Some(token_in_amount))?;
 
@@ -601,6 +680,8 @@ pub mod stable_pool {
                 self.increase_reserve(i, amount)?;
             }
 
+            self.update_oracle(&rates)?;
+
             self.env().emit_event(AddLiquidity {
                 provider: self.env().caller(),
                 token_amounts: amounts,
@@ -658,6 +739,15 @@ pub mod stable_pool {
             let events = self.psp22.burn(self.env().caller(), shares)?;
             self.emit_events(events);
 
+            // Withdrawing proportionally does not change the prices, so there is no need
+            // to compute them (which would require the rates) unless the pool got empty.
+            let now = self.env().block_timestamp();
+            if self.psp22.total_supply() == 0 {
+                self.oracle.update(now, Vec::new(), 0);
+            } else {
+                self.oracle.accumulate(now);
+            }
+
             self.env().emit_event(RemoveLiquidity {
                 provider: self.env().caller(),
                 token_amounts: amounts.clone(),
@@ -726,6 +816,8 @@ pub mod stable_pool {
                 self.decrease_reserve(i, amount)?;
             }
 
+            self.update_oracle(&rates)?;
+
             self.env().emit_event(RemoveLiquidity {
                 provider: self.env().caller(),
                 token_amounts: amounts,
@@ -738,6 +830,70 @@ pub mod stable_pool {
             Ok((shares_to_burn, fee_part))
         }
 
+        #[ink(message)]
+        fn remove_liquidity_one_coin(
+            &mut self,
+            shares: u128,
+            token: AccountId,
+            min_amount: u128,
+            to: AccountId,
+        ) -> Result<(u128, u128), StablePoolError> {
+            let token_id = self.token_id(token)?;
+            ensure!(shares > 0, StablePoolError::ZeroAmounts);
+            ensure!(
+                shares <= self.psp22.total_supply(),
+                StablePoolError::InsufficientLiquidity
+            );
+
+            let rates = self.get_scaled_rates()?;
+
+            // calc token amount and fee
+            let (amount, fee) = math::rated_compute_amount_for_one_coin_withdraw(
+                &rates,
+                shares,
+                token_id,
+                &self.reserves(),
+                self.psp22.total_supply(),
+                Some(&self.pool.fees),
+                self.amp_coef()?,
+            )?;
+
+            // check min amount
+            ensure!(
+                amount >= min_amount,
+                StablePoolError::InsufficientOutputAmount
+            );
+            ensure!(amount > 0, StablePoolError::ZeroAmounts);
+
+            // burn shares
+            let events = self.psp22.burn(self.env().caller(), shares)?;
+            self.emit_events(events);
+
+            // transfer token
+            self.token_by_address(token).transfer(to, amount, vec![])?;
+
+            // update reserves
+            self.decrease_reserve(token_id, amount)?;
+
+            // mint protocol fee
+            self.mint_protocol_fee(fee, token_id)?;
+
+            self.update_oracle(&rates)?;
+
+            let mut token_amounts = vec![0; self.pool.tokens.len()];
+            token_amounts[token_id] = amount;
+            self.env().emit_event(RemoveLiquidity {
+                provider: self.env().caller(),
+                token_amounts,
+                shares,
+                to,
+            });
+            self.env().emit_event(Sync {
+                reserves: self.reserves(),
+            });
+            Ok((amount, fee))
+        }
+
         #[ink(message)]
         fn swap_exact_in(
             &mut self,
@@ -785,6 +941,97 @@ pub mod stable_pool {
             self._swap_exact_in(token_in, token_out, None, min_token_out_amount, to)
         }
 
+        #[ink(message)]
+        fn flash_loan(
+            &mut self,
+            tokens: Vec<AccountId>,
+            amounts: Vec<u128>,
+            receiver: AccountId,
+            data: Vec<u8>,
+        ) -> Result<Vec<u128>, StablePoolError> {
+            ensure!(
+                amounts.len() == tokens.len(),
+                StablePoolError::IncorrectAmountsCount
+            );
+            let token_ids = tokens
+                .iter()
+                .map(|&token| self.token_id(token))
+                .collect::<Result<Vec<usize>, StablePoolError>>()?;
+            let mut unique_token_ids = token_ids.clone();
+            unique_token_ids.sort();
+            unique_token_ids.dedup();
+            ensure!(
+                unique_token_ids.len() == token_ids.len(),
+                StablePoolError::IdenticalTokenId
+            );
+            // Check that at least one amount is non-zero
+            ensure!(
+                amounts.iter().any(|&amount| amount > 0),
+                StablePoolError::ZeroAmounts
+            );
+
+            let mut fees = Vec::with_capacity(tokens.len());
+            for (&token_id, &amount) in token_ids.iter().zip(amounts.iter()) {
+                ensure!(
+                    amount <= self.pool.reserves[token_id],
+                    StablePoolError::InsufficientLiquidity
+                );
+                fees.push(self.pool.fees.trade_fee_from_gross(amount)?);
+            }
+
+            // transfer tokens
+            for (&token, &amount) in tokens.iter().zip(amounts.iter()) {
+                if amount > 0 {
+                    self.token_by_address(token)
+                        .transfer(receiver, amount, vec![])?;
+                }
+            }
+
+            let mut borrower: contract_ref!(FlashBorrower) = receiver.into();
+            ensure!(
+                borrower.on_flash_loan(
+                    self.env().caller(),
+                    tokens.clone(),
+                    amounts.clone(),
+                    fees.clone(),
+                    data,
+                ) == FLASH_BORROWER_CALLBACK_SUCCESS,
+                StablePoolError::InvalidFlashLoanCallback
+            );
+
+            // Check that the loan is repaid with fees. Any surplus is treated as a fee.
+            for (&token_id, &fee) in token_ids.iter().zip(fees.iter()) {
+                let received_fee = self
+                    .token_by_id(token_id)
+                    .balance_of(self.env().account_id())
+                    .checked_sub(self.pool.reserves[token_id])
+                    .ok_or(StablePoolError::InsufficientFlashLoanRepayment)?;
+                ensure!(
+                    received_fee >= fee,
+                    StablePoolError::InsufficientFlashLoanRepayment
+                );
+                // update reserves
+                self.increase_reserve(token_id, received_fee)?;
+                // mint protocol fee
+                self.mint_protocol_fee(fee, token_id)?;
+            }
+
+            let rates = self.get_scaled_rates()?;
+            self.update_oracle(&rates)?;
+
+            self.env().emit_event(FlashLoan {
+                initiator: self.env().caller(),
+                receiver,
+                tokens,
+                amounts,
+                fees: fees.clone(),
+            });
+            self.env().emit_event(Sync {
+                reserves: self.reserves(),
+            });
+            Ok(fees)
+        }
+
         #[ink(message)]
         fn set_fee_receiver(
             &mut self,
@@ -841,6 +1088,62 @@ pub mod stable_pool {
             Ok(())
         }
 
+        #[ink(message)]
+        fn set_token_rate_provider(
+            &mut self,
+            token: AccountId,
+            rate_provider: Option<AccountId>,
+        ) -> Result<(), StablePoolError> {
+            self.ensure_owner()?;
+            let token_id = self.token_id(token)?;
+            let effective_time = self
+                .env()
+                .block_timestamp()
+                .saturating_add(RATE_PROVIDER_CHANGE_DELAY);
+            self.pool.future_token_rate_providers[token_id] = Some((rate_provider, effective_time));
+            self.env().emit_event(TokenRateProviderChangeScheduled {
+                token,
+                rate_provider,
+                effective_time,
+            });
+            Ok(())
+        }
+
+        #[ink(message)]
+        fn apply_token_rate_provider(&mut self, token: AccountId) -> Result<(), StablePoolError> {
+            self.ensure_owner()?;
+            let token_id = self.token_id(token)?;
+            let (rate_provider, effective_time) = self.pool.future_token_rate_providers[token_id]
+                .ok_or(StablePoolError::NoRateProviderChange)?;
+            ensure!(
+                effective_time <= self.env().block_timestamp(),
+                StablePoolError::RateProviderChangeTooEarly
+            );
+            // Accumulate the prices of the old provider, so they are not attributed to the new one.
+            let rates = self.get_scaled_rates()?;
+            self.update_oracle(&rates)?;
+            let cached_rate = self.pool.token_rates[token_id].get_rate();
+            // New token rate has an empty cache, so the rate is queried from the new provider.
+            let mut token_rate = TokenRate::from_rate_provider(rate_provider);
+            let rate = token_rate.get_rate();
+            ensure!(
+                casted_mul(cached_rate.abs_diff(rate), FEE_DENOM.into())
+                    <= casted_mul(cached_rate, MAX_RATE_CHANGE.into()),
+                StablePoolError::RateChangeTooLarge
+            );
+            self.pool.token_rates[token_id] = token_rate;
+            self.pool.future_token_rate_providers[token_id] = None;
+            // Prices accumulated from now on are computed with the rate of the new provider.
+            let rates = self.get_scaled_rates()?;
+            self.update_oracle(&rates)?;
+            self.env().emit_event(TokenRateProviderChanged {
+                token,
+                rate_provider,
+                rate,
+            });
+            Ok(())
+        }
+
         #[ink(message)]
         fn tokens(&self) -> Vec<AccountId> {
             self.pool.tokens.clone()
@@ -889,6 +1192,12 @@ pub mod stable_pool {
                 .collect()
         }
 
+        #[ink(message)]
+        fn future_token_rate_provider(&self, token: AccountId) -> Option<(Option<AccountId>, u64)> {
+            let token_id = self.token_id(token).ok()?;
+            self.pool.future_token_rate_providers[token_id]
+        }
+
         #[ink(message)]
         fn get_swap_amount_out(
             &mut self,
@@ -997,6 +1306,65 @@ pub mod stable_pool {
                 self.psp22.total_supply(),
             )?)
         }
+
+        #[ink(message)]
+        fn get_amount_for_one_coin_burn(
+            &mut self,
+            shares: u128,
+            token: AccountId,
+        ) -> Result<(u128, u128), StablePoolError> {
+            let token_id = self.token_id(token)?;
+            ensure!(
+                shares <= self.psp22.total_supply(),
+                StablePoolError::InsufficientLiquidity
+            );
+            let rates = self.get_scaled_rates()?;
+            Ok(math::rated_compute_amount_for_one_coin_withdraw(
+                &rates,
+                shares,
+                token_id,
+                &self.reserves(),
+                self.psp22.total_supply(),
+                Some(&self.pool.fees),
+                self.amp_coef()?,
+            )?)
+        }
+
+        #[ink(message)]
+        fn virtual_price(&mut self) -> Result<u128, StablePoolError> {
+            let rates = self.get_scaled_rates()?;
+            let d = math::rated_compute_d(&rates, &self.reserves(), self.amp_coef()?)?;
+            self.compute_virtual_price(d)
+        }
+
+        #[ink(message)]
+        fn invariant_d(&mut self) -> Result<u128, StablePoolError> {
+            let rates = self.get_scaled_rates()?;
+            let d = math::rated_compute_d(&rates, &self.reserves(), self.amp_coef()?)?;
+            Ok(d.try_into().map_err(|_| MathError::CastOverflow(108))?)
+        }
+
+        #[ink(message)]
+        fn get_twap_price(
+            &self,
+            token_in: AccountId,
+            token_out: AccountId,
+            window: u64,
+        ) -> Result<u128, StablePoolError> {
+            let (token_in_id, token_out_id) = self.check_tokens(token_in, token_out)?;
+            self.oracle.twap_price(
+                self.env().block_timestamp(),
+                window,
+                token_in_id,
+                token_out_id,
+            )
+        }
+
+        #[ink(message)]
+        fn get_twap_virtual_price(&self, window: u64) -> Result<u128, StablePoolError> {
+            self.oracle
+                .twap_virtual_price(self.env().block_timestamp(), window)
+        }
     }
 
     impl PSP22 for StablePoolContract {
@@ -1093,6 +1461,35 @@ pub mod stable_pool {
         }
     }
 
+    impl Permit for StablePoolContract {
+        #[ink(message)]
+        fn nonces(&self, owner: AccountId) -> u64 {
+            self.permit.nonce(owner)
+        }
+
+        #[ink(message)]
+        fn permit(
+            &mut self,
+            owner: AccountId,
+            spender: AccountId,
+            value: u128,
+            deadline: u64,
+            signature: PermitSignature,
+        ) -> Result<(), PermitError> {
+            self.permit.use_permit(
+                self.env().account_id(),
+                owner,
+                spender,
+                value,
+                deadline,
+                &signature,
+            )?;
+            let events = self.psp22.approve(owner, spender, value)?;
+            self.emit_events(events);
+            Ok(())
+        }
+    }
+
     impl Ownable2Step for StablePoolContract {
         #[ink(message)]
         fn get_owner(&self) -> Ownable2StepResult<AccountId> {
@@ -0,0 +1,216 @@
+use amm_helpers::{
+    constants::stable_pool::{ORACLE_MAX_OBSERVATIONS, ORACLE_OBSERVATION_PERIOD, PRICE_PRECISION},
+    ensure,
+    types::WrappedU256,
+};
+use ink::{
+    prelude::{vec, vec::Vec},
+    storage::Mapping,
+};
+use primitive_types::U256;
+use traits::{MathError, StablePoolError};
+
+/// Cumulative prices recorded at `timestamp`.
+#[derive(Debug, Clone, PartialEq, Eq, scale::Encode, scale::Decode)]
+#[cfg_attr(
+    feature = "std",
+    derive(scale_info::TypeInfo, ink::storage::traits::StorageLayout)
+)]
+pub struct Observation {
+    /// Block timestamp, in milisec.
+    pub timestamp: u64,
+    /// Sums of prices of each token in terms of the first token, multiplied by the time they held.
+    pub price_cumulatives: Vec<WrappedU256>,
+    /// Sum of virtual prices multiplied by the time they held.
+    pub virtual_price_cumulative: WrappedU256,
+}
+
+/// Price oracle keeping a bounded ring buffer of observations of the cumulative prices.
+///
+/// Cumulative prices are allowed to overflow. The difference between two of them
+/// is computed with wrapping subtraction, so it is correct as long as it fits in U256.
+#[ink::storage_item]
+#[derive(Debug)]
+pub struct Oracle {
+    /// Ring buffer of observations.
+    observations: Mapping<u32, Observation>,
+    /// Index of the most recent observation.
+    last_index: u32,
+    /// Number of stored observations, at most `ORACLE_MAX_OBSERVATIONS`.
+    length: u32,
+    /// Cumulative prices as of the last update.
+    cumulative: Observation,
+    /// Prices of each token in terms of the first token since the last update.
+    /// Empty if the pool has no liquidity.
+    prices: Vec<u128>,
+    /// Virtual price since the last update.
+    virtual_price: u128,
+}
+
+impl Oracle {
+    pub fn new(token_count: usize) -> Self {
+        Self {
+            observations: Default::default(),
+            last_index: 0,
+            length: 0,
+            cumulative: Observation {
+                timestamp: 0,
+                price_cumulatives: vec![WrappedU256::ZERO; token_count],
+                virtual_price_cumulative: WrappedU256::ZERO,
+            },
+            prices: Vec::new(),
+            virtual_price: 0,
+        }
+    }
+
+    /// Returns cumulative prices as of `now`, assuming that the prices
+    /// did not change since the last update.
+    fn cumulative_at(&self, now: u64) -> Observation {
+        if self.prices.is_empty() {
+            // no liquidity since the last update, nothing to accumulate
+            return Observation {
+                timestamp: now,
+                ..self.cumulative.clone()
+            };
+        }
+        let time_elapsed = U256::from(now.saturating_sub(self.cumulative.timestamp));
+        let accumulate = |cumulative: WrappedU256, price: u128| -> WrappedU256 {
+            U256::from(cumulative)
+                .overflowing_add(U256::from(price).saturating_mul(time_elapsed))
+                .0
+                .into()
+        };
+        Observation {
+            timestamp: now,
+            price_cumulatives: self
+                .cumulative
+                .price_cumulatives
+                .iter()
+                .zip(self.prices.iter())
+                .map(|(&cumulative, &price)| accumulate(cumulative, price))
+                .collect(),
+            virtual_price_cumulative: accumulate(
+                self.cumulative.virtual_price_cumulative,
+                self.virtual_price,
+            ),
+        }
+    }
+
+    /// Accumulates the prices which held since the last update and sets new `prices`
+    /// (of each token in terms of the first token) and `virtual_price`.
+    ///
+    /// Records an observation if at least `ORACLE_OBSERVATION_PERIOD` elapsed since the last one.
+    /// Should be called whenever the reserves change, with prices computed after the change.
+    pub fn update(&mut self, now: u64, prices: Vec<u128>, virtual_price: u128) {
+        self.cumulative = self.cumulative_at(now);
+        self.prices = prices;
+        self.virtual_price = virtual_price;
+        if self.prices.is_empty() {
+            return;
+        }
+        let observation_due = self.length == 0
+            || self.observations.get(self.last_index).map_or(true, |last| {
+                now.saturating_sub(last.timestamp) >= ORACLE_OBSERVATION_PERIOD
+            });
+        if observation_due {
+            if self.length > 0 {
+                self.last_index = (self.last_index + 1) % ORACLE_MAX_OBSERVATIONS;
+            }
+            self.length = ORACLE_MAX_OBSERVATIONS.min(self.length + 1);
+            self.observations.insert(self.last_index, &self.cumulative);
+        }
+    }
+
+    /// Accumulates the prices which held since the last update without changing them.
+    pub fn accumulate(&mut self, now: u64) {
+        self.update(now, self.prices.clone(), self.virtual_price);
+    }
+
+    /// Returns the most recent observation at least `window` milisec old
+    /// together with its age.
+    fn observation_before(&self, now: u64, window: u64) -> Option<(Observation, u64)> {
+        (0..self.length)
+            .map(|age| {
+                if age <= self.last_index {
+                    self.last_index - age
+                } else {
+                    ORACLE_MAX_OBSERVATIONS - (age - self.last_index)
+                }
+            })
+            .filter_map(|index| self.observations.get(index))
+            .map(|observation| {
+                let time_elapsed = now.saturating_sub(observation.timestamp);
+                (observation, time_elapsed)
+            })
+            .find(|(_, time_elapsed)| *time_elapsed >= window)
+    }
+
+    /// Returns the start and end cumulative prices of the period
+    /// covering at least the last `window` milisec and its duration.
+    fn cumulative_range(
+        &self,
+        now: u64,
+        window: u64,
+    ) -> Result<(Observation, Observation, u64), StablePoolError> {
+        ensure!(window > 0, StablePoolError::InvalidWindow);
+        let (start, time_elapsed) = self
+            .observation_before(now, window)
+            .ok_or(StablePoolError::InsufficientObservations)?;
+        Ok((start, self.cumulative_at(now), time_elapsed))
+    }
+
+    /// Returns time-weighted average price of the `token_in_id` token
+    /// in terms of the `token_out_id` token over at least the last `window` milisec,
+    /// with `PRICE_PRECISION`.
+    pub fn twap_price(
+        &self,
+        now: u64,
+        window: u64,
+        token_in_id: usize,
+        token_out_id: usize,
+    ) -> Result<u128, StablePoolError> {
+        let (start, end, time_elapsed) = self.cumulative_range(now, window)?;
+        let average = |id: usize| {
+            average_price(
+                start.price_cumulatives[id],
+                end.price_cumulatives[id],
+                time_elapsed,
+            )
+        };
+        // both prices are in terms of the first token
+        let price_in = average(token_in_id)?;
+        let price_out = average(token_out_id)?;
+        Ok(price_in
+            .checked_mul(PRICE_PRECISION.into())
+            .ok_or(MathError::MulOverflow(105))?
+            .checked_div(price_out)
+            .ok_or(MathError::DivByZero(105))?
+            .try_into()
+            .map_err(|_| MathError::CastOverflow(105))?)
+    }
+
+    /// Returns time-weighted average virtual price over at least the last `window` milisec,
+    /// with `PRICE_PRECISION`.
+    pub fn twap_virtual_price(&self, now: u64, window: u64) -> Result<u128, StablePoolError> {
+        let (start, end, time_elapsed) = self.cumulative_range(now, window)?;
+        Ok(average_price(
+            start.virtual_price_cumulative,
+            end.virtual_price_cumulative,
+            time_elapsed,
+        )?
+        .try_into()
+        .map_err(|_| MathError::CastOverflow(106))?)
+    }
+}
+
+fn average_price(
+    start: WrappedU256,
+    end: WrappedU256,
+    time_elapsed: u64,
+) -> Result<U256, MathError> {
+    U256::from(end)
+        .overflowing_sub(start.into())
+        .0
+        .checked_div(time_elapsed.into())
+        .ok_or(MathError::DivByZero(106))
+}
@@ -1,3 +1,4 @@
+use amm_helpers::constants::stable_pool::RATE_PRECISION;
 use ink::{contract_ref, env::DefaultEnvironment, primitives::AccountId};
 use scale::{Decode, Encode};
 use traits::RateProvider;
@@ -26,6 +27,15 @@ impl TokenRate {
         Self::External(ExternalTokenRate::new(rate_provider))
     }
 
+    /// Creates an external rate for `Some` rate provider and a constant
+    /// `RATE_PRECISION` rate otherwise.
+    pub fn from_rate_provider(rate_provider: Option<AccountId>) -> Self {
+        match rate_provider {
+            Some(contract) => Self::new_external(contract),
+            None => Self::new_constant(RATE_PRECISION),
+        }
+    }
+
     /// Get current rate and update the cache.
     pub fn get_rate(&mut self) -> u128 {
         match self {
@@ -0,0 +1,305 @@
+#![cfg_attr(not(feature = "std"), no_std, no_main)]
+
+/// Registry of stable pools, instantiating `StablePoolContract`s from a stored code hash.
+///
+/// There is at most one pool for each set of tokens. Pools can be looked up
+/// by their (sorted) token set, which allows routers and indexers to discover them.
+#[ink::contract]
+pub mod stable_pool_factory {
+    use amm_helpers::ensure;
+    use ink::{env::hash::Blake2x256, prelude::vec::Vec, storage::Mapping, ToAccountId};
+    use stable_pool_contract::stable_pool::StablePoolContractRef;
+    use traits::{
+        Ownable2Step, Ownable2StepData, Ownable2StepResult, StablePoolFactory,
+        StablePoolFactoryError,
+    };
+
+    #[ink(event)]
+    pub struct PoolCreated {
+        #[ink(topic)]
+        pub pool: AccountId,
+        pub tokens: Vec<AccountId>,
+        pub pool_len: u64,
+    }
+
+    #[ink(event)]
+    pub struct PoolContractCodeHashChanged {
+        pub code_hash: Hash,
+    }
+
+    #[ink(event)]
+    pub struct TransferOwnershipInitiated {
+        pub new_owner: AccountId,
+    }
+
+    #[ink(event)]
+    pub struct TransferOwnershipAccepted {
+        pub new_owner: AccountId,
+    }
+
+    #[ink(event)]
+    pub struct OwnershipRenounced {}
+
+    #[ink(storage)]
+    pub struct StablePoolFactoryContract {
+        ownable: Ownable2StepData,
+        get_pool: Mapping<Vec<AccountId>, AccountId>,
+        is_pool: Mapping<AccountId, bool>,
+        all_pools: Mapping<u64, AccountId>,
+        all_pools_length: u64,
+        pool_contract_code_hash: Hash,
+        genesis_hash: Hash,
+    }
+
+    impl StablePoolFactoryContract {
+        #[ink(constructor)]
+        pub fn new(owner: AccountId, pool_code_hash: Hash, genesis_hash: Hash) -> Self {
+            Self {
+                ownable: Ownable2StepData::new(owner),
+                get_pool: Default::default(),
+                is_pool: Default::default(),
+                all_pools: Default::default(),
+                all_pools_length: 0,
+                pool_contract_code_hash: pool_code_hash,
+                genesis_hash,
+            }
+        }
+
+        #[allow(clippy::too_many_arguments)]
+        fn instantiate_pool(
+            &self,
+            salt_bytes: &[u8],
+            tokens: Vec<AccountId>,
+            tokens_decimals: Vec<u8>,
+            external_rates: Vec<Option<AccountId>>,
+            init_amp_coef: u128,
+            owner: AccountId,
+            trade_fee: u32,
+            protocol_fee: u32,
+            fee_receiver: Option<AccountId>,
+        ) -> Result<AccountId, StablePoolFactoryError> {
+            let pool = match StablePoolContractRef::new_rated(
+                tokens,
+                tokens_decimals,
+                external_rates,
+                init_amp_coef,
+                owner,
+                trade_fee,
+                protocol_fee,
+                fee_receiver,
+                self.genesis_hash,
+            )
+            .endowment(0)
+            .code_hash(self.pool_contract_code_hash)
+            .salt_bytes(salt_bytes)
+            .try_instantiate()
+            {
+                Ok(Ok(Ok(pool))) => Ok(pool),
+                Ok(Ok(Err(err))) => Err(err.into()),
+                _ => Err(StablePoolFactoryError::PoolInstantiationFailed),
+            }?;
+            Ok(pool.to_account_id())
+        }
+
+        fn add_new_pool(&mut self, pool: AccountId) {
+            self.is_pool.insert(pool, &true);
+            self.all_pools.insert(self.all_pools_length, &pool);
+            self.all_pools_length += 1;
+        }
+    }
+
+    impl StablePoolFactory for StablePoolFactoryContract {
+        #[ink(message)]
+        fn all_pools(&self, pid: u64) -> Option<AccountId> {
+            self.all_pools.get(pid)
+        }
+
+        #[ink(message)]
+        fn all_pools_length(&self) -> u64 {
+            self.all_pools_length
+        }
+
+        #[ink(message)]
+        fn pool_contract_code_hash(&self) -> Hash {
+            self.pool_contract_code_hash
+        }
+
+        #[ink(message)]
+        fn get_pool(&self, tokens: Vec<AccountId>) -> Option<AccountId> {
+            self.get_pool.get(tokens)
+        }
+
+        #[ink(message)]
+        fn is_pool(&self, pool: AccountId) -> bool {
+            self.is_pool.get(pool).unwrap_or(false)
+        }
+
+        #[ink(message)]
+        fn create_pool(
+            &mut self,
+            tokens: Vec<AccountId>,
+            tokens_decimals: Vec<u8>,
+            external_rates: Vec<Option<AccountId>>,
+            init_amp_coef: u128,
+            owner: AccountId,
+            trade_fee: u32,
+            protocol_fee: u32,
+            fee_receiver: Option<AccountId>,
+        ) -> Result<AccountId, StablePoolFactoryError> {
+            self.ensure_owner()?;
+            // strictly ascending order ensures there are no duplicates
+            ensure!(
+                tokens.windows(2).all(|pair| pair[0] < pair[1]),
+                StablePoolFactoryError::UnsortedTokens
+            );
+            ensure!(
+                !self.get_pool.contains(&tokens),
+                StablePoolFactoryError::PoolExists
+            );
+
+            let salt = self.env().hash_encoded::<Blake2x256, _>(&tokens);
+            let pool = self.instantiate_pool(
+                salt.as_ref(),
+                tokens.clone(),
+                tokens_decimals,
+                external_rates,
+                init_amp_coef,
+                owner,
+                trade_fee,
+                protocol_fee,
+                fee_receiver,
+            )?;
+            self.get_pool.insert(&tokens, &pool);
+            self.add_new_pool(pool);
+
+            self.env().emit_event(PoolCreated {
+                pool,
+                tokens,
+                pool_len: self.all_pools_length,
+            });
+
+            Ok(pool)
+        }
+
+        #[ink(message)]
+        fn set_pool_contract_code_hash(
+            &mut self,
+            code_hash: Hash,
+        ) -> Result<(), StablePoolFactoryError> {
+            self.ensure_owner()?;
+            self.pool_contract_code_hash = code_hash;
+            self.env()
+                .emit_event(PoolContractCodeHashChanged { code_hash });
+            Ok(())
+        }
+    }
+
+    impl Ownable2Step for StablePoolFactoryContract {
+        #[ink(message)]
+        fn get_owner(&self) -> Ownable2StepResult<AccountId> {
+            self.ownable.get_owner()
+        }
+
+        #[ink(message)]
+        fn get_pending_owner(&self) -> Ownable2StepResult<AccountId> {
+            self.ownable.get_pending_owner()
+        }
+
+        #[ink(message)]
+        fn transfer_ownership(&mut self, new_owner: AccountId) -> Ownable2StepResult<()> {
+            self.ownable
+                .transfer_ownership(self.env().caller(), new_owner)?;
+            self.env()
+                .emit_event(TransferOwnershipInitiated { new_owner });
+            Ok(())
+        }
+
+        #[ink(message)]
+        fn accept_ownership(&mut self) -> Ownable2StepResult<()> {
+            let new_owner = self.env().caller();
+            self.ownable.accept_ownership(new_owner)?;
+            self.env()
+                .emit_event(TransferOwnershipAccepted { new_owner });
+            Ok(())
+        }
+
+        #[ink(message)]
+        fn renounce_ownership(&mut self) -> Ownable2StepResult<()> {
+            self.ownable
+                .renounce_ownership(self.env().caller(), self.env().account_id())?;
+            self.env().emit_event(OwnershipRenounced {});
+            Ok(())
+        }
+
+        #[ink(message)]
+        fn ensure_owner(&self) -> Ownable2StepResult<()> {
+            self.ownable.ensure_owner(self.env().caller())
+        }
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use ink::env::test::{default_accounts, set_caller};
+
+        use super::*;
+
+        #[ink::test]
+        fn initialize_works() {
+            let accounts = default_accounts::<ink::env::DefaultEnvironment>();
+            let factory =
+                StablePoolFactoryContract::new(accounts.alice, Hash::default(), Hash::default());
+            assert_eq!(factory.get_owner(), Ok(accounts.alice));
+            assert_eq!(factory.all_pools_length(), 0);
+            assert_eq!(factory.pool_contract_code_hash(), Hash::default());
+        }
+
+        #[ink::test]
+        fn create_pool_rejects_unsorted_tokens() {
+            let accounts = default_accounts::<ink::env::DefaultEnvironment>();
+            set_caller::<ink::env::DefaultEnvironment>(accounts.alice);
+            let mut factory =
+                StablePoolFactoryContract::new(accounts.alice, Hash::default(), Hash::default());
+            for tokens in [
+                vec![accounts.django, accounts.charlie],
+                vec![accounts.charlie, accounts.charlie],
+            ] {
+                assert_eq!(
+                    factory.create_pool(
+                        tokens,
+                        vec![6, 6],
+                        vec![None, None],
+                        100,
+                        accounts.alice,
+                        0,
+                        0,
+                        None,
+                    ),
+                    Err(StablePoolFactoryError::UnsortedTokens)
+                );
+            }
+        }
+
+        #[ink::test]
+        fn create_pool_is_owner_restricted() {
+            let accounts = default_accounts::<ink::env::DefaultEnvironment>();
+            let mut factory =
+                StablePoolFactoryContract::new(accounts.alice, Hash::default(), Hash::default());
+            set_caller::<ink::env::DefaultEnvironment>(accounts.bob);
+            assert_eq!(
+                factory.create_pool(
+                    vec![accounts.charlie, accounts.django],
+                    vec![6, 6],
+                    vec![None, None],
+                    100,
+                    accounts.bob,
+                    0,
+                    0,
+                    None,
+                ),
+                Err(StablePoolFactoryError::Ownable2StepError(
+                    traits::Ownable2StepError::CallerNotOwner(accounts.bob)
+                ))
+            );
+        }
+    }
+}
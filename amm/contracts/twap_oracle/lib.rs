@@ -0,0 +1,341 @@
+#![cfg_attr(not(feature = "std"), no_std, no_main)]
+
+/// Time-weighted average price oracle for `Pair` contracts.
+///
+/// Periodically records cumulative prices of pairs in per-pair ring buffers of observations
+/// and computes prices averaged over the requested time windows. The average price over
+/// a window cannot be moved significantly without keeping the pair imbalanced
+/// for most of the window, which makes it resistant to manipulation within a single block.
+#[ink::contract]
+pub mod twap_oracle {
+    // 2^112
+    const Q112: u128 = 5192296858534827628530496329220096;
+
+    const TWO_POW_32: u64 = 4294967296;
+
+    use amm_helpers::{ensure, types::WrappedU256};
+    use ink::{contract_ref, storage::Mapping};
+    use primitive_types::U256;
+    use traits::{MathError, Observation, Pair, TwapOracle, TwapOracleError};
+
+    #[ink(event)]
+    pub struct ObservationRecorded {
+        #[ink(topic)]
+        pub pair: AccountId,
+        pub timestamp: u32,
+        pub price_0_cumulative: WrappedU256,
+        pub price_1_cumulative: WrappedU256,
+    }
+
+    /// Position in the ring buffer of observations of a pair.
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, scale::Encode, scale::Decode)]
+    #[cfg_attr(
+        feature = "std",
+        derive(scale_info::TypeInfo, ink::storage::traits::StorageLayout)
+    )]
+    pub struct ObservationsState {
+        /// Index of the most recent observation.
+        pub last_index: u32,
+        /// Number of stored observations, at most `capacity`.
+        pub length: u32,
+    }
+
+    #[ink(storage)]
+    pub struct TwapOracleContract {
+        period: u32,
+        capacity: u32,
+        observations: Mapping<(AccountId, u32), Observation>,
+        observations_state: Mapping<AccountId, ObservationsState>,
+    }
+
+    impl TwapOracleContract {
+        /// Creates the oracle which records an observation of a pair at most once per
+        /// `period` seconds and stores `capacity` most recent observations of each pair.
+        ///
+        /// The longest window that can be consulted is `period * (capacity - 1)` seconds.
+        #[ink(constructor)]
+        pub fn new(period: u32, capacity: u32) -> Result<Self, TwapOracleError> {
+            ensure!(period > 0 && capacity > 1, TwapOracleError::InvalidConfig);
+            Ok(Self {
+                period,
+                capacity,
+                observations: Default::default(),
+                observations_state: Default::default(),
+            })
+        }
+
+        /// Returns current block timestamp in seconds, modulo 2^32.
+        /// Mirrors the timestamps used by the `Pair` contract.
+        fn now_seconds(&self) -> u32 {
+            u32::try_from(
+                self.env()
+                    .block_timestamp()
+                    .checked_div(1000)
+                    .unwrap_or_default()
+                    % TWO_POW_32,
+            )
+            .unwrap() // mod u32::MAX is guaranteed to not exceed 2^32-1
+        }
+
+        /// Returns cumulative prices of the `pair` as of `now`.
+        ///
+        /// Accounts for the time elapsed since the last update of the pair reserves,
+        /// so that the pair does not have to be synced before the observation.
+        fn current_cumulative_prices(
+            &self,
+            pair: &contract_ref!(Pair),
+            now: u32,
+        ) -> Result<(U256, U256), TwapOracleError> {
+            let (reserve_0, reserve_1, block_timestamp_last) = pair.get_reserves();
+            let mut price_0_cumulative: U256 = pair.price_0_cumulative_last().into();
+            let mut price_1_cumulative: U256 = pair.price_1_cumulative_last().into();
+            // Wrapping subtraction so that the time_elapsed works correctly over the 2^32 boundary.
+            let time_elapsed = now.wrapping_sub(block_timestamp_last);
+            if time_elapsed > 0 && reserve_0 > 0 && reserve_1 > 0 {
+                price_0_cumulative =
+                    price_cumulative(reserve_1, reserve_0, time_elapsed, price_0_cumulative)?;
+                price_1_cumulative =
+                    price_cumulative(reserve_0, reserve_1, time_elapsed, price_1_cumulative)?;
+            }
+            Ok((price_0_cumulative, price_1_cumulative))
+        }
+
+        /// Returns index of the observation which is `age` observations older than the most recent one.
+        fn index_before(&self, state: ObservationsState, age: u32) -> u32 {
+            if age <= state.last_index {
+                state.last_index - age
+            } else {
+                self.capacity - (age - state.last_index)
+            }
+        }
+    }
+
+    impl TwapOracle for TwapOracleContract {
+        #[ink(message)]
+        fn period(&self) -> u32 {
+            self.period
+        }
+
+        #[ink(message)]
+        fn capacity(&self) -> u32 {
+            self.capacity
+        }
+
+        #[ink(message)]
+        fn last_observation(&self, pair: AccountId) -> Option<Observation> {
+            let state = self.observations_state.get(pair)?;
+            self.observations.get((pair, state.last_index))
+        }
+
+        #[ink(message)]
+        fn update(&mut self, pair: AccountId) -> Result<(), TwapOracleError> {
+            let now = self.now_seconds();
+            let state = self.observations_state.get(pair);
+            if let Some(last_observation) =
+                state.and_then(|state| self.observations.get((pair, state.last_index)))
+            {
+                if now.wrapping_sub(last_observation.timestamp) < self.period {
+                    return Ok(());
+                }
+            }
+
+            let pair_ref: contract_ref!(Pair) = pair.into();
+            let (price_0_cumulative, price_1_cumulative) =
+                self.current_cumulative_prices(&pair_ref, now)?;
+            let observation = Observation {
+                timestamp: now,
+                price_0_cumulative: price_0_cumulative.into(),
+                price_1_cumulative: price_1_cumulative.into(),
+            };
+            let state = match state {
+                Some(state) => ObservationsState {
+                    last_index: (state.last_index + 1) % self.capacity,
+                    length: self.capacity.min(state.length + 1),
+                },
+                None => ObservationsState {
+                    last_index: 0,
+                    length: 1,
+                },
+            };
+            self.observations
+                .insert((pair, state.last_index), &observation);
+            self.observations_state.insert(pair, &state);
+
+            self.env().emit_event(ObservationRecorded {
+                pair,
+                timestamp: observation.timestamp,
+                price_0_cumulative: observation.price_0_cumulative,
+                price_1_cumulative: observation.price_1_cumulative,
+            });
+            Ok(())
+        }
+
+        #[ink(message)]
+        fn consult(
+            &self,
+            pair: AccountId,
+            token_in: AccountId,
+            amount_in: u128,
+            window: u32,
+        ) -> Result<u128, TwapOracleError> {
+            ensure!(window > 0, TwapOracleError::InvalidWindow);
+            let pair_ref: contract_ref!(Pair) = pair.into();
+            let is_token_0 = if token_in == pair_ref.get_token_0() {
+                true
+            } else if token_in == pair_ref.get_token_1() {
+                false
+            } else {
+                return Err(TwapOracleError::InvalidToken);
+            };
+
+            let state = self
+                .observations_state
+                .get(pair)
+                .ok_or(TwapOracleError::InsufficientObservations)?;
+            let now = self.now_seconds();
+            // Observations are iterated from the most recent one,
+            // so the first one at least `window` seconds old is the closest to the window start.
+            let (start, time_elapsed) = (0..state.length)
+                .filter_map(|age| self.observations.get((pair, self.index_before(state, age))))
+                .map(|observation| (observation, now.wrapping_sub(observation.timestamp)))
+                .find(|(_, time_elapsed)| *time_elapsed >= window)
+                .ok_or(TwapOracleError::InsufficientObservations)?;
+            ensure!(
+                time_elapsed - window <= self.period,
+                TwapOracleError::StaleObservations
+            );
+
+            let (price_0_cumulative, price_1_cumulative) =
+                self.current_cumulative_prices(&pair_ref, now)?;
+            if is_token_0 {
+                average_amount_out(
+                    start.price_0_cumulative.into(),
+                    price_0_cumulative,
+                    time_elapsed,
+                    amount_in,
+                )
+            } else {
+                average_amount_out(
+                    start.price_1_cumulative.into(),
+                    price_1_cumulative,
+                    time_elapsed,
+                    amount_in,
+                )
+            }
+        }
+    }
+
+    /// Mirrors the cumulative price computation of the `Pair` contract.
+    #[inline]
+    fn price_cumulative(
+        num: u128,
+        denom: u128,
+        time_elapsed_seconds: u32,
+        last_price: U256,
+    ) -> Result<U256, TwapOracleError> {
+        Ok(uq112x112(num, denom)
+            .ok_or(TwapOracleError::ReservesOverflow)?
+            .saturating_mul(time_elapsed_seconds.into())
+            .overflowing_add(last_price)
+            .0)
+    }
+
+    /// Returns `num / denom` in UQ112x112 format.
+    fn uq112x112(num: u128, denom: u128) -> Option<U256> {
+        if num >= Q112 || denom >= Q112 || denom == 0 {
+            None
+        } else {
+            Some(U256::from(num).checked_mul(Q112.into()).unwrap() / U256::from(denom))
+        }
+    }
+
+    /// Returns `amount_in` multiplied by the average price between two cumulative prices
+    /// recorded `time_elapsed_seconds` apart.
+    fn average_amount_out(
+        price_cumulative_start: U256,
+        price_cumulative_end: U256,
+        time_elapsed_seconds: u32,
+        amount_in: u128,
+    ) -> Result<u128, TwapOracleError> {
+        // Cumulative prices are expected to overflow, in which case the wrapped difference is correct.
+        let price_average = price_cumulative_end
+            .overflowing_sub(price_cumulative_start)
+            .0
+            .checked_div(time_elapsed_seconds.into())
+            .ok_or(MathError::DivByZero(1))?;
+        let amount_out = price_average.full_mul(amount_in.into()) >> 112;
+        U256::try_from(amount_out)
+            .ok()
+            .and_then(|amount_out| u128::try_from(amount_out).ok())
+            .ok_or(MathError::CastOverflow(1).into())
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+
+        #[test]
+        fn consts() {
+            assert_eq!(Q112, 2u128.pow(112));
+            assert_eq!(TWO_POW_32, 2u64.pow(32));
+        }
+
+        #[ink::test]
+        fn initialize_works() {
+            let oracle = TwapOracleContract::new(60, 10).unwrap();
+            assert_eq!(oracle.period(), 60);
+            assert_eq!(oracle.capacity(), 10);
+            assert_eq!(oracle.last_observation(AccountId::from([0x03; 32])), None);
+        }
+
+        #[ink::test]
+        fn new_rejects_invalid_config() {
+            assert!(matches!(
+                TwapOracleContract::new(0, 10),
+                Err(TwapOracleError::InvalidConfig)
+            ));
+            assert!(matches!(
+                TwapOracleContract::new(60, 1),
+                Err(TwapOracleError::InvalidConfig)
+            ));
+        }
+
+        #[test]
+        fn average_amount_out_works() {
+            // price of 2 for 100 seconds
+            let start = U256::from(12345u128);
+            let end = start + U256::from(2 * Q112) * 100;
+            assert_eq!(average_amount_out(start, end, 100, 1000), Ok(2000));
+            // price of 0.5
+            let end = start + U256::from(Q112 / 2) * 100;
+            assert_eq!(average_amount_out(start, end, 100, 1000), Ok(500));
+        }
+
+        #[test]
+        fn average_amount_out_over_cumulative_overflow() {
+            let start = U256::MAX - U256::from(Q112) * 10 + 1;
+            let end = price_cumulative(3, 1, 20, start).unwrap();
+            assert!(end < start, "cumulative price should overflow");
+            assert_eq!(average_amount_out(start, end, 20, 1000), Ok(3000));
+        }
+
+        #[test]
+        fn average_amount_out_over_timestamp_wraparound() {
+            let before_wrap = u32::MAX - 9;
+            let after_wrap = 10u32;
+            let time_elapsed = after_wrap.wrapping_sub(before_wrap);
+            assert_eq!(time_elapsed, 20);
+            let start = U256::from(Q112) * 1000;
+            let end = price_cumulative(1, 4, time_elapsed, start).unwrap();
+            assert_eq!(average_amount_out(start, end, time_elapsed, 1000), Ok(250));
+        }
+
+        #[test]
+        fn average_amount_out_rejects_zero_time_elapsed() {
+            assert_eq!(
+                average_amount_out(0.into(), 0.into(), 0, 1000),
+                Err(TwapOracleError::MathError(MathError::DivByZero(1)))
+            );
+        }
+    }
+}
@@ -0,0 +1,297 @@
+#![cfg_attr(not(feature = "std"), no_std, no_main)]
+
+/// Executes large swaps as series of smaller slices spread over time.
+///
+/// Users deposit the whole input amount of an order and permissionless keepers swap it
+/// slice by slice through `RouterV2`. Each slice has to receive at least its quote from
+/// the `TwapOracle` reduced by the max deviation of the order, so no slice can be executed
+/// while the pairs along the path are pushed away from their average prices.
+#[ink::contract]
+pub mod twap_order {
+    const DEVIATION_DENOM: u16 = 10_000;
+    const MILLIS_PER_SECOND: u64 = 1000;
+
+    use amm_helpers::{ensure, math::casted_mul};
+    use ink::{codegen::TraitCallBuilder, contract_ref, prelude::vec::Vec, storage::Mapping};
+    use primitive_types::U256;
+    use psp22::PSP22;
+    use traits::{
+        MathError, Pair, RouterV2, Step, TwapOracle, TwapOrder, TwapOrderBook, TwapOrderError,
+    };
+
+    #[ink(event)]
+    pub struct OrderCreated {
+        #[ink(topic)]
+        pub order_id: u64,
+        #[ink(topic)]
+        pub owner: AccountId,
+        pub token_in: AccountId,
+        pub token_out: AccountId,
+        pub amount_in: u128,
+        pub slices: u32,
+        pub interval: u64,
+        pub max_deviation: u16,
+    }
+
+    #[ink(event)]
+    pub struct SliceExecuted {
+        #[ink(topic)]
+        pub order_id: u64,
+        #[ink(topic)]
+        pub owner: AccountId,
+        #[ink(topic)]
+        pub keeper: AccountId,
+        pub amount_in: u128,
+        pub amount_out: u128,
+        pub slices_remaining: u32,
+    }
+
+    #[ink(event)]
+    pub struct OrderCancelled {
+        #[ink(topic)]
+        pub order_id: u64,
+        #[ink(topic)]
+        pub owner: AccountId,
+        pub amount_refunded: u128,
+    }
+
+    #[ink(storage)]
+    pub struct TwapOrderContract {
+        router: AccountId,
+        oracle: AccountId,
+        window: u64,
+        next_order_id: u64,
+        orders: Mapping<u64, TwapOrder>,
+    }
+
+    impl TwapOrderContract {
+        /// Creates the contract swapping through the `router` and checking each slice
+        /// against the prices averaged by the `oracle` over the last `window` milliseconds.
+        /// The oracle works with whole seconds, so `window` is rounded down to them
+        /// and has to be at least one second.
+        #[ink(constructor)]
+        pub fn new(
+            router: AccountId,
+            oracle: AccountId,
+            window: u64,
+        ) -> Result<Self, TwapOrderError> {
+            ensure!(window >= MILLIS_PER_SECOND, TwapOrderError::InvalidWindow);
+            Ok(Self {
+                router,
+                oracle,
+                window,
+                next_order_id: 0,
+                orders: Default::default(),
+            })
+        }
+
+        /// Returns the least amount out of swapping `amount_in` along the `path`
+        /// which is within `max_deviation` from the TWAP quote.
+        ///
+        /// Records new observations of the pairs, so that the oracle stays up to date
+        /// as long as the orders are executed.
+        fn min_amount_out(
+            &self,
+            path: &[Step],
+            amount_in: u128,
+            max_deviation: u16,
+        ) -> Result<u128, TwapOrderError> {
+            let window = u32::try_from(self.window / MILLIS_PER_SECOND)
+                .map_err(|_| MathError::CastOverflow(2))?;
+            let mut oracle: contract_ref!(TwapOracle) = self.oracle.into();
+            let mut amount = amount_in;
+            for step in path {
+                oracle.update(step.pool_id)?;
+                amount = oracle.consult(step.pool_id, step.token_in, amount, window)?;
+            }
+            let min_amount_out = casted_mul(amount, (DEVIATION_DENOM - max_deviation).into())
+                / U256::from(DEVIATION_DENOM);
+            u128::try_from(min_amount_out).map_err(|_| MathError::CastOverflow(1).into())
+        }
+
+        /// Checks that every step of the `path` swaps through a Pair,
+        /// as the oracle observes only pairs.
+        fn ensure_pairs_only(path: &[Step]) -> Result<(), TwapOrderError> {
+            for step in path {
+                let pair: contract_ref!(Pair) = step.pool_id.into();
+                // The call fails if `step.pool_id` is not a Pair contract.
+                ensure!(
+                    matches!(pair.call().get_fee().try_invoke(), Ok(Ok(_))),
+                    TwapOrderError::PoolIsNotPair
+                );
+            }
+            Ok(())
+        }
+    }
+
+    impl TwapOrderBook for TwapOrderContract {
+        #[ink(message)]
+        fn router(&self) -> AccountId {
+            self.router
+        }
+
+        #[ink(message)]
+        fn oracle(&self) -> AccountId {
+            self.oracle
+        }
+
+        #[ink(message)]
+        fn window(&self) -> u64 {
+            self.window
+        }
+
+        #[ink(message)]
+        fn get_order(&self, order_id: u64) -> Option<TwapOrder> {
+            self.orders.get(order_id)
+        }
+
+        #[ink(message)]
+        fn create_order(
+            &mut self,
+            path: Vec<Step>,
+            token_out: AccountId,
+            amount_in: u128,
+            slices: u32,
+            interval: u64,
+            max_deviation: u16,
+        ) -> Result<u64, TwapOrderError> {
+            ensure!(!path.is_empty(), TwapOrderError::EmptyPath);
+            // Every slice has to swap a non-zero amount.
+            ensure!(
+                slices > 0 && amount_in >= slices as u128,
+                TwapOrderError::InvalidSlices
+            );
+            ensure!(
+                max_deviation <= DEVIATION_DENOM,
+                TwapOrderError::InvalidDeviation
+            );
+            Self::ensure_pairs_only(&path)?;
+            let owner = self.env().caller();
+            let token_in = path[0].token_in;
+            let mut token: contract_ref!(PSP22) = token_in.into();
+            token.transfer_from(owner, self.env().account_id(), amount_in, Vec::new())?;
+
+            let order_id = self.next_order_id;
+            self.next_order_id += 1;
+            self.orders.insert(
+                order_id,
+                &TwapOrder {
+                    owner,
+                    path,
+                    token_out,
+                    amount_remaining: amount_in,
+                    slices_remaining: slices,
+                    interval,
+                    max_deviation,
+                    next_execution: self.env().block_timestamp(),
+                },
+            );
+
+            self.env().emit_event(OrderCreated {
+                order_id,
+                owner,
+                token_in,
+                token_out,
+                amount_in,
+                slices,
+                interval,
+                max_deviation,
+            });
+            Ok(order_id)
+        }
+
+        #[ink(message)]
+        fn execute_slice(&mut self, order_id: u64) -> Result<u128, TwapOrderError> {
+            let mut order = self
+                .orders
+                .get(order_id)
+                .ok_or(TwapOrderError::OrderNotFound)?;
+            let now = self.env().block_timestamp();
+            ensure!(now >= order.next_execution, TwapOrderError::TooEarly);
+
+            // The last slice takes the rounding leftover.
+            let amount_in = order.amount_remaining / order.slices_remaining as u128;
+            let amount_out_min =
+                self.min_amount_out(&order.path, amount_in, order.max_deviation)?;
+            order.amount_remaining -= amount_in;
+            order.slices_remaining -= 1;
+            order.next_execution = now.saturating_add(order.interval);
+            if order.slices_remaining == 0 {
+                self.orders.remove(order_id);
+            } else {
+                self.orders.insert(order_id, &order);
+            }
+
+            let mut token: contract_ref!(PSP22) = order.path[0].token_in.into();
+            token.approve(self.router, amount_in)?;
+            let mut router: contract_ref!(RouterV2) = self.router.into();
+            let amounts = router.swap_exact_tokens_for_tokens(
+                amount_in,
+                amount_out_min,
+                order.path,
+                order.token_out,
+                order.owner,
+                now,
+            )?;
+            let amount_out = amounts[amounts.len() - 1];
+
+            self.env().emit_event(SliceExecuted {
+                order_id,
+                owner: order.owner,
+                keeper: self.env().caller(),
+                amount_in,
+                amount_out,
+                slices_remaining: order.slices_remaining,
+            });
+            Ok(amount_out)
+        }
+
+        #[ink(message)]
+        fn cancel_order(&mut self, order_id: u64) -> Result<(), TwapOrderError> {
+            let order = self
+                .orders
+                .get(order_id)
+                .ok_or(TwapOrderError::OrderNotFound)?;
+            ensure!(
+                self.env().caller() == order.owner,
+                TwapOrderError::CallerIsNotOwner
+            );
+            self.orders.remove(order_id);
+            let mut token: contract_ref!(PSP22) = order.path[0].token_in.into();
+            token.transfer(order.owner, order.amount_remaining, Vec::new())?;
+
+            self.env().emit_event(OrderCancelled {
+                order_id,
+                owner: order.owner,
+                amount_refunded: order.amount_remaining,
+            });
+            Ok(())
+        }
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+
+        #[ink::test]
+        fn initialize_works() {
+            let router = AccountId::from([0x01; 32]);
+            let oracle = AccountId::from([0x02; 32]);
+            let contract = TwapOrderContract::new(router, oracle, 600_000).unwrap();
+            assert_eq!(contract.router(), router);
+            assert_eq!(contract.oracle(), oracle);
+            assert_eq!(contract.window(), 600_000);
+            assert_eq!(contract.get_order(0), None);
+        }
+
+        #[ink::test]
+        fn new_rejects_sub_second_window() {
+            let router = AccountId::from([0x01; 32]);
+            let oracle = AccountId::from([0x02; 32]);
+            assert!(matches!(
+                TwapOrderContract::new(router, oracle, MILLIS_PER_SECOND - 1),
+                Err(TwapOrderError::InvalidWindow)
+            ));
+        }
+    }
+}
@@ -0,0 +1,1088 @@
+#![cfg_attr(not(feature = "std"), no_std, no_main)]
+/// Weighted-product pool implementation based on the Balancer V2 model.
+///
+/// This pool contract supports up to 8 PSP22 tokens with fixed normalized weights,
+/// e.g. 80/20 AZERO/USDC or 33/33/34 index baskets.
+///
+/// Besides swaps and proportional deposits and withdrawals, the pool supports
+/// depositing and withdrawing a single token. The part of such an operation exceeding
+/// the token's weight share is charged with the trade fee, as it is equivalent to a swap.
+#[ink::contract]
+pub mod weighted_pool {
+    use amm_helpers::{
+        constants::{
+            stable_pool::{MAX_COINS, TOKEN_TARGET_DECIMALS},
+            weighted_pool::{MAX_IN_RATIO, MAX_OUT_RATIO, MIN_WEIGHT, WEIGHT_PRECISION},
+        },
+        ensure,
+        math::casted_mul,
+        stable_swap_math::{compute_amounts_given_lp, fees::Fees},
+        weighted_math as math,
+    };
+    use ink::contract_ref;
+    use ink::prelude::{
+        string::{String, ToString},
+        {vec, vec::Vec},
+    };
+    use psp22::{PSP22Data, PSP22Error, PSP22Event, PSP22Metadata, PSP22};
+    use traits::{
+        MathError, Ownable2Step, Ownable2StepData, Ownable2StepResult, WeightedPool,
+        WeightedPoolError,
+    };
+
+    #[ink(event)]
+    pub struct AddLiquidity {
+        #[ink(topic)]
+        pub provider: AccountId,
+        pub token_amounts: Vec<u128>,
+        pub shares: u128,
+        #[ink(topic)]
+        pub to: AccountId,
+    }
+
+    #[ink(event)]
+    pub struct RemoveLiquidity {
+        #[ink(topic)]
+        pub provider: AccountId,
+        pub token_amounts: Vec<u128>,
+        pub shares: u128,
+        #[ink(topic)]
+        pub to: AccountId,
+    }
+
+    #[ink(event)]
+    pub struct Swap {
+        #[ink(topic)]
+        pub sender: AccountId,
+        pub token_in: AccountId,
+        pub amount_in: u128,
+        pub token_out: AccountId,
+        pub amount_out: u128,
+        #[ink(topic)]
+        pub to: AccountId,
+    }
+
+    #[ink(event)]
+    pub struct Sync {
+        pub reserves: Vec<u128>,
+    }
+
+    #[ink(event)]
+    pub struct Approval {
+        /// Account providing allowance.
+        #[ink(topic)]
+        pub owner: AccountId,
+        /// Allowance beneficiary.
+        #[ink(topic)]
+        pub spender: AccountId,
+        /// New allowance amount.
+        pub amount: u128,
+    }
+
+    /// Event emitted when transfer of tokens occurs.
+    #[ink(event)]
+    pub struct Transfer {
+        /// Transfer sender. `None` in case of minting new tokens.
+        #[ink(topic)]
+        pub from: Option<AccountId>,
+        /// Transfer recipient. `None` in case of burning tokens.
+        #[ink(topic)]
+        pub to: Option<AccountId>,
+        /// Amount of tokens transferred (or minted/burned).
+        pub value: u128,
+    }
+
+    #[ink(event)]
+    pub struct TransferOwnershipInitiated {
+        #[ink(topic)]
+        pub new_owner: AccountId,
+    }
+
+    #[ink(event)]
+    pub struct TransferOwnershipAccepted {
+        #[ink(topic)]
+        pub new_owner: AccountId,
+    }
+
+    #[ink(event)]
+    pub struct OwnershipRenounced {}
+
+    #[ink(event)]
+    pub struct FeeReceiverChanged {
+        #[ink(topic)]
+        pub new_fee_receiver: Option<AccountId>,
+    }
+
+    #[ink(event)]
+    pub struct FeeChanged {
+        pub trade_fee: u32,
+        pub protocol_fee: u32,
+    }
+
+    #[ink::storage_item]
+    #[derive(Debug)]
+    pub struct WeightedPoolData {
+        /// List of tokens.
+        tokens: Vec<AccountId>,
+        /// Tokens precision factors used for normalization.
+        precisions: Vec<u128>,
+        /// Normalized weights of tokens, summing up to `WEIGHT_PRECISION`.
+        weights: Vec<u128>,
+        /// Reserves of tokens
+        reserves: Vec<u128>,
+        /// Fees
+        fees: Fees,
+        /// Who receives protocol fees (if any).
+        fee_receiver: Option<AccountId>,
+    }
+
+    #[ink(storage)]
+    pub struct WeightedPoolContract {
+        ownable: Ownable2StepData,
+        pool: WeightedPoolData,
+        psp22: PSP22Data,
+    }
+
+    impl WeightedPoolContract {
+        #[ink(constructor)]
+        pub fn new(
+            tokens: Vec<AccountId>,
+            tokens_decimals: Vec<u8>,
+            weights: Vec<u128>,
+            owner: AccountId,
+            trade_fee: u32,
+            protocol_fee: u32,
+            fee_receiver: Option<AccountId>,
+        ) -> Result<Self, WeightedPoolError> {
+            let mut unique_tokens = tokens.clone();
+            unique_tokens.sort();
+            unique_tokens.dedup();
+            let token_count = tokens.len();
+            ensure!(
+                unique_tokens.len() == token_count,
+                WeightedPoolError::IdenticalTokenId
+            );
+            ensure!(
+                token_count == tokens_decimals.len()
+                    && token_count == weights.len()
+                    && (2..=MAX_COINS).contains(&token_count),
+                WeightedPoolError::IncorrectTokenCount
+            );
+
+            ensure!(
+                tokens_decimals.iter().all(|&d| d <= TOKEN_TARGET_DECIMALS),
+                WeightedPoolError::TooLargeTokenDecimal
+            );
+
+            ensure!(
+                weights.iter().all(|&weight| weight >= MIN_WEIGHT)
+                    && weights
+                        .iter()
+                        .try_fold(0u128, |acc, &weight| acc.checked_add(weight))
+                        == Some(WEIGHT_PRECISION),
+                WeightedPoolError::InvalidWeights
+            );
+
+            let precisions = tokens_decimals
+                .iter()
+                .map(|&decimal| {
+                    10u128.pow(TOKEN_TARGET_DECIMALS.checked_sub(decimal).unwrap() as u32)
+                })
+                .collect();
+            Ok(Self {
+                ownable: Ownable2StepData::new(owner),
+                pool: WeightedPoolData {
+                    tokens,
+                    precisions,
+                    weights,
+                    reserves: vec![0; token_count],
+                    fees: Fees::new(trade_fee, protocol_fee)
+                        .ok_or(WeightedPoolError::InvalidFee)?,
+                    fee_receiver,
+                },
+                psp22: PSP22Data::default(),
+            })
+        }
+
+        /// A helper function emitting events contained in a vector of PSP22Events.
+        fn emit_events(&self, events: Vec<PSP22Event>) {
+            for event in events {
+                match event {
+                    PSP22Event::Transfer { from, to, value } => {
+                        self.env().emit_event(Transfer { from, to, value })
+                    }
+                    PSP22Event::Approval {
+                        owner,
+                        spender,
+                        amount,
+                    } => self.env().emit_event(Approval {
+                        owner,
+                        spender,
+                        amount,
+                    }),
+                }
+            }
+        }
+
+        #[inline]
+        fn token_by_address(&self, address: AccountId) -> contract_ref!(PSP22) {
+            address.into()
+        }
+
+        #[inline]
+        fn token_by_id(&self, token_id: usize) -> contract_ref!(PSP22) {
+            self.pool.tokens[token_id].into()
+        }
+
+        fn token_id(&self, token: AccountId) -> Result<usize, WeightedPoolError> {
+            self.pool
+                .tokens
+                .iter()
+                .position(|&id| id == token)
+                .ok_or(WeightedPoolError::InvalidTokenId(token))
+        }
+
+        /// Checks if tokens are valid and returns the tokens ids
+        fn check_tokens(
+            &self,
+            token_in: AccountId,
+            token_out: AccountId,
+        ) -> Result<(usize, usize), WeightedPoolError> {
+            ensure!(token_in != token_out, WeightedPoolError::IdenticalTokenId);
+            //check token ids
+            let token_in_id = self.token_id(token_in)?;
+            let token_out_id = self.token_id(token_out)?;
+            Ok((token_in_id, token_out_id))
+        }
+
+        /// Checks that `amount` can be deposited to the reserve of `token_id`
+        /// in a single operation.
+        fn check_max_in_ratio(
+            &self,
+            token_id: usize,
+            amount: u128,
+        ) -> Result<(), WeightedPoolError> {
+            let max_amount =
+                casted_mul(self.pool.reserves[token_id], MAX_IN_RATIO) / WEIGHT_PRECISION;
+            ensure!(
+                max_amount >= amount.into(),
+                WeightedPoolError::MaxInRatioExceeded
+            );
+            Ok(())
+        }
+
+        /// Checks that `amount` can be withdrawn from the reserve of `token_id`
+        /// in a single operation.
+        fn check_max_out_ratio(
+            &self,
+            token_id: usize,
+            amount: u128,
+        ) -> Result<(), WeightedPoolError> {
+            let max_amount =
+                casted_mul(self.pool.reserves[token_id], MAX_OUT_RATIO) / WEIGHT_PRECISION;
+            ensure!(
+                max_amount >= amount.into(),
+                WeightedPoolError::MaxOutRatioExceeded
+            );
+            Ok(())
+        }
+
+        /// Returns a tuple of (amount out, fee) for swapping `token_in_amount`.
+        fn compute_swap_to(
+            &self,
+            token_in_id: usize,
+            token_out_id: usize,
+            token_in_amount: u128,
+        ) -> Result<(u128, u128), WeightedPoolError> {
+            self.check_max_in_ratio(token_in_id, token_in_amount)?;
+            Ok(math::swap_to(
+                self.pool.reserves[token_in_id],
+                self.pool.weights[token_in_id],
+                self.pool.reserves[token_out_id],
+                self.pool.weights[token_out_id],
+                token_in_amount,
+                &self.pool.fees,
+            )?)
+        }
+
+        /// Returns a tuple of (amount in, fee) for swapping to `token_out_amount`.
+        fn compute_swap_from(
+            &self,
+            token_in_id: usize,
+            token_out_id: usize,
+            token_out_amount: u128,
+        ) -> Result<(u128, u128), WeightedPoolError> {
+            self.check_max_out_ratio(token_out_id, token_out_amount)?;
+            Ok(math::swap_from(
+                self.pool.reserves[token_in_id],
+                self.pool.weights[token_in_id],
+                self.pool.reserves[token_out_id],
+                self.pool.weights[token_out_id],
+                token_out_amount,
+                &self.pool.fees,
+            )?)
+        }
+
+        /// Returns a tuple of (lpt amount, fee) for depositing `amount` of `token_id`.
+        fn compute_one_coin_deposit(
+            &self,
+            token_id: usize,
+            amount: u128,
+        ) -> Result<(u128, u128), WeightedPoolError> {
+            ensure!(
+                self.psp22.total_supply() > 0,
+                WeightedPoolError::InsufficientLiquidity
+            );
+            self.check_max_in_ratio(token_id, amount)?;
+            Ok(math::compute_lp_amount_for_one_coin_deposit(
+                self.pool.reserves[token_id],
+                self.pool.weights[token_id],
+                amount,
+                self.psp22.total_supply(),
+                Some(&self.pool.fees),
+            )?)
+        }
+
+        /// Returns a tuple of (withdraw amount, fee) for burning `shares` in `token_id`.
+        fn compute_one_coin_withdraw(
+            &self,
+            token_id: usize,
+            shares: u128,
+        ) -> Result<(u128, u128), WeightedPoolError> {
+            ensure!(
+                shares < self.psp22.total_supply(),
+                WeightedPoolError::InsufficientLiquidity
+            );
+            let (amount, fee) = math::compute_amount_for_one_coin_withdraw(
+                self.pool.reserves[token_id],
+                self.pool.weights[token_id],
+                shares,
+                self.psp22.total_supply(),
+                Some(&self.pool.fees),
+            )?;
+            self.check_max_out_ratio(token_id, amount)?;
+            Ok((amount, fee))
+        }
+
+        /// Calculates lpt equivalent of the protocol fee and mints it to the `fee_to` if one is set.
+        fn mint_protocol_fee(
+            &mut self,
+            fee: u128,
+            token_id: usize,
+        ) -> Result<(), WeightedPoolError> {
+            if let Some(fee_to) = self.fee_receiver() {
+                let protocol_fee = self.pool.fees.protocol_trade_fee(fee)?;
+                if protocol_fee > 0 {
+                    let reserve = self.pool.reserves[token_id]
+                        .checked_sub(protocol_fee)
+                        .ok_or(MathError::SubUnderflow(150))?;
+                    let (protocol_fee_lp, _) = math::compute_lp_amount_for_one_coin_deposit(
+                        reserve,
+                        self.pool.weights[token_id],
+                        protocol_fee,
+                        self.psp22.total_supply(),
+                        None, // no fees
+                    )?;
+                    // mint fee (shares) to protocol
+                    let events = self.psp22.mint(fee_to, protocol_fee_lp)?;
+                    self.emit_events(events);
+                }
+            }
+            Ok(())
+        }
+
+        fn decrease_reserve(
+            &mut self,
+            token_id: usize,
+            amount: u128,
+        ) -> Result<(), WeightedPoolError> {
+            self.pool.reserves[token_id] = self.pool.reserves[token_id]
+                .checked_sub(amount)
+                .ok_or(MathError::SubUnderflow(151))?;
+            Ok(())
+        }
+
+        fn increase_reserve(
+            &mut self,
+            token_id: usize,
+            amount: u128,
+        ) -> Result<(), WeightedPoolError> {
+            self.pool.reserves[token_id] = self.pool.reserves[token_id]
+                .checked_add(amount)
+                .ok_or(MathError::AddOverflow(151))?;
+            Ok(())
+        }
+
+        fn _swap_exact_in(
+            &mut self,
+            token_in: AccountId,
+            token_out: AccountId,
+            token_in_amount: Option<u128>,
+            min_token_out_amount: u128,
+            to: AccountId,
+        ) -> Result<(u128, u128), WeightedPoolError> {
+            //check token ids
+            let (token_in_id, token_out_id) = self.check_tokens(token_in, token_out)?;
+
+            // get transfered token_in amount
+            let token_in_amount = self._transfer_in(token_in_id, token_in_amount)?;
+
+            // calc amount_out and fees
+            let (token_out_amount, fee) =
+                self.compute_swap_to(token_in_id, token_out_id, token_in_amount)?;
+
+            // Check if swapped amount is not less than min_token_out_amount
+            ensure!(
+                token_out_amount >= min_token_out_amount,
+                WeightedPoolError::InsufficientOutputAmount
+            );
+            // update reserves
+            self.increase_reserve(token_in_id, token_in_amount)?;
+            self.decrease_reserve(token_out_id, token_out_amount)?;
+
+            // mint protocol fee
+            self.mint_protocol_fee(fee, token_in_id)?;
+
+            // transfer token_out
+            self.token_by_address(token_out)
+                .transfer(to, token_out_amount, vec![])?;
+
+            self.env().emit_event(Swap {
+                sender: self.env().caller(),
+                token_in,
+                amount_in: token_in_amount,
+                token_out,
+                amount_out: token_out_amount,
+                to,
+            });
+            self.env().emit_event(Sync {
+                reserves: self.reserves(),
+            });
+            Ok((token_out_amount, fee))
+        }
+
+        fn _swap_exact_out(
+            &mut self,
+            token_in: AccountId,
+            token_out: AccountId,
+            token_out_amount: u128,
+            max_token_in_amount: u128,
+            to: AccountId,
+        ) -> Result<(u128, u128), WeightedPoolError> {
+            //check token ids
+            let (token_in_id, token_out_id) = self.check_tokens(token_in, token_out)?;
+
+            ensure!(
+                token_out_amount > 0,
+                WeightedPoolError::InsufficientOutputAmount
+            );
+
+            // calc amount_in and fees
+            let (token_in_amount, fee) =
+                self.compute_swap_from(token_in_id, token_out_id, token_out_amount)?;
+
+            // Check if in token_in_amount is as constrained by the user
+            ensure!(
+                token_in_amount <= max_token_in_amount,
+                WeightedPoolError::InsufficientInputAmount
+            );
+            // update reserves
+            self.increase_reserve(token_in_id, token_in_amount)?;
+            self.decrease_reserve(token_out_id, token_out_amount)?;
+
+            // mint protocol fee
+            self.mint_protocol_fee(fee, token_in_id)?;
+
+            // transfer token_in
+            _ = self._transfer_in(token_in_id, Some(token_in_amount))?;
+
+            // transfer token_out
+            self.token_by_address(token_out)
+                .transfer(to, token_out_amount, vec![])?;
+
+            self.env().emit_event(Swap {
+                sender: self.env().caller(),
+                token_in,
+                amount_in: token_in_amount,
+                token_out,
+                amount_out: token_out_amount,
+                to,
+            });
+            self.env().emit_event(Sync {
+                reserves: self.reserves(),
+            });
+            // note that fee is applied to token_in (same as in _swap_exact_in)
+            Ok((token_in_amount, fee))
+        }
+
+        /// Handles PSP22 token transfer,
+        ///
+        /// If `amount` is `Some(amount)`, transfer this amount of `token_id`
+        /// from the caller to this contract.
+        ///
+        /// If `amount` of `None`, calculate the difference between
+        /// this contract balance and recorded reserve of `token_id`.
+        fn _transfer_in(
+            &self,
+            token_id: usize,
+            amount: Option<u128>,
+        ) -> Result<u128, WeightedPoolError> {
+            let mut token = self.token_by_id(token_id);
+            let amount = if let Some(token_amount) = amount {
+                token.transfer_from(
+                    self.env().caller(),
+                    self.env().account_id(),
+                    token_amount,
+                    vec![],
+                )?;
+                token_amount
+            } else {
+                token
+                    .balance_of(self.env().account_id())
+                    .checked_sub(self.pool.reserves[token_id])
+                    .ok_or(MathError::SubUnderflow(152))?
+            };
+            ensure!(amount > 0, WeightedPoolError::InsufficientInputAmount);
+            Ok(amount)
+        }
+    }
+
+    impl WeightedPool for WeightedPoolContract {
+        #[ink(message)]
+        fn add_liquidity(
+            &mut self,
+            min_share_amount: u128,
+            amounts: Vec<u128>,
+            to: AccountId,
+        ) -> Result<u128, WeightedPoolError> {
+            ensure!(
+                amounts.len() == self.pool.tokens.len(),
+                WeightedPoolError::IncorrectAmountsCount
+            );
+            let total_supply = self.psp22.total_supply();
+            let (shares, amounts) = if total_supply == 0 {
+                // The initial deposit determines the prices, so all of the tokens are required.
+                ensure!(
+                    amounts.iter().all(|&amount| amount > 0),
+                    WeightedPoolError::ZeroAmounts
+                );
+                let scaled_amounts = amounts
+                    .iter()
+                    .zip(self.pool.precisions.iter())
+                    .map(|(&amount, &precision)| {
+                        amount
+                            .checked_mul(precision)
+                            .ok_or(MathError::MulOverflow(153))
+                    })
+                    .collect::<Result<Vec<u128>, MathError>>()?;
+                let shares = math::compute_invariant(&scaled_amounts, &self.pool.weights)?;
+                (shares, amounts)
+            } else {
+                let shares = math::compute_lp_amount_for_deposit(
+                    &amounts,
+                    &self.pool.reserves,
+                    total_supply,
+                )?;
+                // Only the amounts proportional to the reserves are deposited,
+                // so the surplus of the `amounts` stays with the caller.
+                let amounts =
+                    math::compute_amounts_for_lp_mint(shares, &self.pool.reserves, total_supply)?;
+                (shares, amounts)
+            };
+
+            // Check min shares
+            ensure!(
+                shares > 0 && shares >= min_share_amount,
+                WeightedPoolError::InsufficientLiquidityMinted
+            );
+
+            // transfer amounts
+            for (id, &token) in self.pool.tokens.iter().enumerate() {
+                if amounts[id] > 0 {
+                    self.token_by_address(token).transfer_from(
+                        self.env().caller(),
+                        self.env().account_id(),
+                        amounts[id],
+                        vec![],
+                    )?;
+                }
+            }
+
+            // mint shares
+            let events = self.psp22.mint(to, shares)?;
+            self.emit_events(events);
+
+            // update reserves
+            for (i, &amount) in amounts.iter().enumerate() {
+                self.increase_reserve(i, amount)?;
+            }
+
+            self.env().emit_event(AddLiquidity {
+                provider: self.env().caller(),
+                token_amounts: amounts,
+                shares,
+                to,
+            });
+            self.env().emit_event(Sync {
+                reserves: self.reserves(),
+            });
+            Ok(shares)
+        }
+
+        #[ink(message)]
+        fn add_liquidity_one_coin(
+            &mut self,
+            token: AccountId,
+            amount: u128,
+            min_share_amount: u128,
+            to: AccountId,
+        ) -> Result<(u128, u128), WeightedPoolError> {
+            let token_id = self.token_id(token)?;
+            ensure!(amount > 0, WeightedPoolError::ZeroAmounts);
+
+            // calc lp tokens and fee
+            let (shares, fee) = self.compute_one_coin_deposit(token_id, amount)?;
+
+            // Check min shares
+            ensure!(
+                shares > 0 && shares >= min_share_amount,
+                WeightedPoolError::InsufficientLiquidityMinted
+            );
+
+            // transfer amount
+            self.token_by_address(token).transfer_from(
+                self.env().caller(),
+                self.env().account_id(),
+                amount,
+                vec![],
+            )?;
+
+            // mint shares
+            let events = self.psp22.mint(to, shares)?;
+            self.emit_events(events);
+
+            // update reserves
+            self.increase_reserve(token_id, amount)?;
+
+            // mint protocol fee
+            self.mint_protocol_fee(fee, token_id)?;
+
+            let mut token_amounts = vec![0; self.pool.tokens.len()];
+            token_amounts[token_id] = amount;
+            self.env().emit_event(AddLiquidity {
+                provider: self.env().caller(),
+                token_amounts,
+                shares,
+                to,
+            });
+            self.env().emit_event(Sync {
+                reserves: self.reserves(),
+            });
+            Ok((shares, fee))
+        }
+
+        #[ink(message)]
+        fn remove_liquidity_by_shares(
+            &mut self,
+            shares: u128,
+            min_amounts: Vec<u128>,
+            to: AccountId,
+        ) -> Result<Vec<u128>, WeightedPoolError> {
+            let amounts =
+                compute_amounts_given_lp(shares, &self.pool.reserves, self.psp22.total_supply())?;
+
+            // Check if enough tokens are withdrawn
+            ensure!(
+                amounts
+                    .iter()
+                    .zip(min_amounts.iter())
+                    .all(|(amount, min_amount)| amount >= min_amount),
+                WeightedPoolError::InsufficientOutputAmount
+            );
+            // Check that at least one amount is non-zero
+            ensure!(
+                amounts.iter().any(|&amount| amount > 0),
+                WeightedPoolError::ZeroAmounts
+            );
+
+            // transfer tokens
+            for (&token, &amount) in self.pool.tokens.iter().zip(amounts.iter()) {
+                if amount > 0 {
+                    self.token_by_address(token).transfer(to, amount, vec![])?;
+                }
+            }
+
+            // update reserves
+            for (i, &amount) in amounts.iter().enumerate() {
+                self.decrease_reserve(i, amount)?;
+            }
+
+            // Burn liquidity
+            let events = self.psp22.burn(self.env().caller(), shares)?;
+            self.emit_events(events);
+
+            self.env().emit_event(RemoveLiquidity {
+                provider: self.env().caller(),
+                token_amounts: amounts.clone(),
+                shares,
+                to,
+            });
+            self.env().emit_event(Sync {
+                reserves: self.reserves(),
+            });
+            Ok(amounts)
+        }
+
+        #[ink(message)]
+        fn remove_liquidity_one_coin(
+            &mut self,
+            shares: u128,
+            token: AccountId,
+            min_amount: u128,
+            to: AccountId,
+        ) -> Result<(u128, u128), WeightedPoolError> {
+            let token_id = self.token_id(token)?;
+            ensure!(shares > 0, WeightedPoolError::ZeroAmounts);
+
+            // calc token amount and fee
+            let (amount, fee) = self.compute_one_coin_withdraw(token_id, shares)?;
+
+            // check min amount
+            ensure!(
+                amount >= min_amount,
+                WeightedPoolError::InsufficientOutputAmount
+            );
+            ensure!(amount > 0, WeightedPoolError::ZeroAmounts);
+
+            // burn shares
+            let events = self.psp22.burn(self.env().caller(), shares)?;
+            self.emit_events(events);
+
+            // transfer token
+            self.token_by_address(token).transfer(to, amount, vec![])?;
+
+            // update reserves
+            self.decrease_reserve(token_id, amount)?;
+
+            // mint protocol fee
+            self.mint_protocol_fee(fee, token_id)?;
+
+            let mut token_amounts = vec![0; self.pool.tokens.len()];
+            token_amounts[token_id] = amount;
+            self.env().emit_event(RemoveLiquidity {
+                provider: self.env().caller(),
+                token_amounts,
+                shares,
+                to,
+            });
+            self.env().emit_event(Sync {
+                reserves: self.reserves(),
+            });
+            Ok((amount, fee))
+        }
+
+        #[ink(message)]
+        fn swap_exact_in(
+            &mut self,
+            token_in: AccountId,
+            token_out: AccountId,
+            token_in_amount: u128,
+            min_token_out_amount: u128,
+            to: AccountId,
+        ) -> Result<(u128, u128), WeightedPoolError> {
+            self._swap_exact_in(
+                token_in,
+                token_out,
+                Some(token_in_amount),
+                min_token_out_amount,
+                to,
+            )
+        }
+
+        #[ink(message)]
+        fn swap_exact_out(
+            &mut self,
+            token_in: AccountId,
+            token_out: AccountId,
+            token_out_amount: u128,
+            max_token_in_amount: u128,
+            to: AccountId,
+        ) -> Result<(u128, u128), WeightedPoolError> {
+            self._swap_exact_out(
+                token_in,
+                token_out,
+                token_out_amount,
+                max_token_in_amount,
+                to,
+            )
+        }
+
+        #[ink(message)]
+        fn swap_received(
+            &mut self,
+            token_in: AccountId,
+            token_out: AccountId,
+            min_token_out_amount: u128,
+            to: AccountId,
+        ) -> Result<(u128, u128), WeightedPoolError> {
+            self._swap_exact_in(token_in, token_out, None, min_token_out_amount, to)
+        }
+
+        #[ink(message)]
+        fn set_fee_receiver(
+            &mut self,
+            fee_receiver: Option<AccountId>,
+        ) -> Result<(), WeightedPoolError> {
+            self.ensure_owner()?;
+            self.pool.fee_receiver = fee_receiver;
+            self.env().emit_event(FeeReceiverChanged {
+                new_fee_receiver: fee_receiver,
+            });
+            Ok(())
+        }
+
+        #[ink(message)]
+        fn set_fees(&mut self, trade_fee: u32, protocol_fee: u32) -> Result<(), WeightedPoolError> {
+            self.ensure_owner()?;
+            self.pool.fees =
+                Fees::new(trade_fee, protocol_fee).ok_or(WeightedPoolError::InvalidFee)?;
+            self.env().emit_event(FeeChanged {
+                trade_fee,
+                protocol_fee,
+            });
+            Ok(())
+        }
+
+        #[ink(message)]
+        fn tokens(&self) -> Vec<AccountId> {
+            self.pool.tokens.clone()
+        }
+
+        #[ink(message)]
+        fn weights(&self) -> Vec<u128> {
+            self.pool.weights.clone()
+        }
+
+        #[ink(message)]
+        fn reserves(&self) -> Vec<u128> {
+            self.pool.reserves.clone()
+        }
+
+        #[ink(message)]
+        fn fees(&self) -> (u32, u32) {
+            (self.pool.fees.trade_fee, self.pool.fees.protocol_fee)
+        }
+
+        #[ink(message)]
+        fn fee_receiver(&self) -> Option<AccountId> {
+            self.pool.fee_receiver
+        }
+
+        #[ink(message)]
+        fn get_swap_amount_out(
+            &self,
+            token_in: AccountId,
+            token_out: AccountId,
+            token_in_amount: u128,
+        ) -> Result<(u128, u128), WeightedPoolError> {
+            let (token_in_id, token_out_id) = self.check_tokens(token_in, token_out)?;
+            self.compute_swap_to(token_in_id, token_out_id, token_in_amount)
+        }
+
+        #[ink(message)]
+        fn get_swap_amount_in(
+            &self,
+            token_in: AccountId,
+            token_out: AccountId,
+            token_out_amount: u128,
+        ) -> Result<(u128, u128), WeightedPoolError> {
+            let (token_in_id, token_out_id) = self.check_tokens(token_in, token_out)?;
+            self.compute_swap_from(token_in_id, token_out_id, token_out_amount)
+        }
+
+        #[ink(message)]
+        fn get_amounts_for_liquidity_mint(
+            &self,
+            liquidity: u128,
+        ) -> Result<Vec<u128>, WeightedPoolError> {
+            Ok(math::compute_amounts_for_lp_mint(
+                liquidity,
+                &self.pool.reserves,
+                self.psp22.total_supply(),
+            )?)
+        }
+
+        #[ink(message)]
+        fn get_amounts_for_liquidity_burn(
+            &self,
+            liquidity: u128,
+        ) -> Result<Vec<u128>, WeightedPoolError> {
+            ensure!(
+                liquidity <= self.psp22.total_supply(),
+                WeightedPoolError::InsufficientLiquidity
+            );
+            Ok(compute_amounts_given_lp(
+                liquidity,
+                &self.pool.reserves,
+                self.psp22.total_supply(),
+            )?)
+        }
+
+        #[ink(message)]
+        fn get_mint_liquidity_for_one_coin(
+            &self,
+            token: AccountId,
+            amount: u128,
+        ) -> Result<(u128, u128), WeightedPoolError> {
+            let token_id = self.token_id(token)?;
+            self.compute_one_coin_deposit(token_id, amount)
+        }
+
+        #[ink(message)]
+        fn get_amount_for_one_coin_burn(
+            &self,
+            shares: u128,
+            token: AccountId,
+        ) -> Result<(u128, u128), WeightedPoolError> {
+            let token_id = self.token_id(token)?;
+            self.compute_one_coin_withdraw(token_id, shares)
+        }
+    }
+
+    impl PSP22 for WeightedPoolContract {
+        #[ink(message)]
+        fn total_supply(&self) -> u128 {
+            self.psp22.total_supply()
+        }
+
+        #[ink(message)]
+        fn balance_of(&self, owner: AccountId) -> u128 {
+            self.psp22.balance_of(owner)
+        }
+
+        #[ink(message)]
+        fn allowance(&self, owner: AccountId, spender: AccountId) -> u128 {
+            self.psp22.allowance(owner, spender)
+        }
+
+        #[ink(message)]
+        fn transfer(
+            &mut self,
+            to: AccountId,
+            value: u128,
+            _data: Vec<u8>,
+        ) -> Result<(), PSP22Error> {
+            let events = self.psp22.transfer(self.env().caller(), to, value)?;
+            self.emit_events(events);
+            Ok(())
+        }
+
+        #[ink(message)]
+        fn transfer_from(
+            &mut self,
+            from: AccountId,
+            to: AccountId,
+            value: u128,
+            _data: Vec<u8>,
+        ) -> Result<(), PSP22Error> {
+            let events = self
+                .psp22
+                .transfer_from(self.env().caller(), from, to, value)?;
+            self.emit_events(events);
+            Ok(())
+        }
+
+        #[ink(message)]
+        fn approve(&mut self, spender: AccountId, value: u128) -> Result<(), PSP22Error> {
+            let events = self.psp22.approve(self.env().caller(), spender, value)?;
+            self.emit_events(events);
+            Ok(())
+        }
+
+        #[ink(message)]
+        fn increase_allowance(
+            &mut self,
+            spender: AccountId,
+            delta_value: u128,
+        ) -> Result<(), PSP22Error> {
+            let events =
+                self.psp22
+                    .increase_allowance(self.env().caller(), spender, delta_value)?;
+            self.emit_events(events);
+            Ok(())
+        }
+
+        #[ink(message)]
+        fn decrease_allowance(
+            &mut self,
+            spender: AccountId,
+            delta_value: u128,
+        ) -> Result<(), PSP22Error> {
+            let events =
+                self.psp22
+                    .decrease_allowance(self.env().caller(), spender, delta_value)?;
+            self.emit_events(events);
+            Ok(())
+        }
+    }
+
+    impl PSP22Metadata for WeightedPoolContract {
+        #[ink(message)]
+        fn token_name(&self) -> Option<String> {
+            Some("CommonWeightedPool".to_string())
+        }
+
+        #[ink(message)]
+        fn token_symbol(&self) -> Option<String> {
+            Some("CMNWP".to_string())
+        }
+
+        #[ink(message)]
+        fn token_decimals(&self) -> u8 {
+            TOKEN_TARGET_DECIMALS
+        }
+    }
+
+    impl Ownable2Step for WeightedPoolContract {
+        #[ink(message)]
+        fn get_owner(&self) -> Ownable2StepResult<AccountId> {
+            self.ownable.get_owner()
+        }
+
+        #[ink(message)]
+        fn get_pending_owner(&self) -> Ownable2StepResult<AccountId> {
+            self.ownable.get_pending_owner()
+        }
+
+        #[ink(message)]
+        fn transfer_ownership(&mut self, new_owner: AccountId) -> Ownable2StepResult<()> {
+            self.ownable
+                .transfer_ownership(self.env().caller(), new_owner)?;
+            self.env()
+                .emit_event(TransferOwnershipInitiated { new_owner });
+            Ok(())
+        }
+
+        #[ink(message)]
+        fn accept_ownership(&mut self) -> Ownable2StepResult<()> {
+            let new_owner = self.env().caller();
+            self.ownable.accept_ownership(new_owner)?;
+            self.env()
+                .emit_event(TransferOwnershipAccepted { new_owner });
+            Ok(())
+        }
+
+        #[ink(message)]
+        fn renounce_ownership(&mut self) -> Ownable2StepResult<()> {
+            self.ownable
+                .renounce_ownership(self.env().caller(), self.env().account_id())?;
+            self.env().emit_event(OwnershipRenounced {});
+            Ok(())
+        }
+
+        #[ink(message)]
+        fn ensure_owner(&self) -> Ownable2StepResult<()> {
+            self.ownable.ensure_owner(self.env().caller())
+        }
+    }
+}
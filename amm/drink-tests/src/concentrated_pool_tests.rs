@@ -0,0 +1,177 @@
+use crate::concentrated_pool_contract;
+use crate::utils::*;
+
+use concentrated_pool_contract::ConcentratedPoolError;
+use drink::{self, runtime::MinimalRuntime, session::Session};
+use ink_primitives::AccountId;
+
+const FEE: u32 = 3_000;
+const TICK_SPACING: i32 = 60;
+
+/// Sets up ICE (token 0) and WOOD (token 1) and a pool of them with the price 1.
+/// BOB approves the pool to spend both tokens.
+pub fn setup_concentrated_pool(
+    session: &mut Session<MinimalRuntime>,
+) -> (AccountId, AccountId, AccountId) {
+    upload_all(session);
+    let ice = psp22_utils::setup(session, ICE.to_string(), BOB);
+    let wood = psp22_utils::setup(session, WOOD.to_string(), BOB);
+    let pool = concentrated_pool::setup(
+        session,
+        ice.into(),
+        wood.into(),
+        FEE,
+        TICK_SPACING,
+        primitive_types::U256::one() << 96,
+        BOB,
+    );
+    for token in [ice, wood] {
+        psp22_utils::increase_allowance(session, token.into(), pool.into(), u128::MAX, BOB)
+            .expect("Should increase allowance");
+    }
+    (pool.into(), ice.into(), wood.into())
+}
+
+#[drink::test]
+fn test_concentrated_pool_positions_and_swaps(mut session: Session) {
+    let (pool, ice, wood) = setup_concentrated_pool(&mut session);
+
+    // position around the current price requires equal amounts of both tokens
+    let liquidity = 1_000 * TOKEN;
+    let expected =
+        concentrated_pool::get_amounts_for_liquidity(&mut session, pool, -600, 600, liquidity)
+            .expect("Should compute amounts");
+    let (position_id, amount_0, amount_1) = concentrated_pool::mint_position(
+        &mut session,
+        pool,
+        BOB,
+        -600,
+        600,
+        liquidity,
+        u128::MAX,
+        u128::MAX,
+        bob(),
+    )
+    .expect("Should mint position");
+    assert_eq!(position_id, 0);
+    assert_eq!((amount_0, amount_1), expected);
+    assert_eq!(amount_0, amount_1);
+    assert_eq!(
+        concentrated_pool::reserves(&mut session, pool),
+        (amount_0, amount_1)
+    );
+    assert_eq!(concentrated_pool::liquidity(&mut session, pool), liquidity);
+
+    // ticks must be multiples of the tick spacing
+    let res = concentrated_pool::mint_position(
+        &mut session,
+        pool,
+        BOB,
+        -601,
+        600,
+        liquidity,
+        u128::MAX,
+        u128::MAX,
+        bob(),
+    );
+    assert_eq!(res, Err(ConcentratedPoolError::InvalidTickRange()));
+
+    // position above the current price requires only token 0
+    let (position_id, _, amount_1) = concentrated_pool::mint_position(
+        &mut session,
+        pool,
+        BOB,
+        600,
+        1200,
+        500 * TOKEN,
+        u128::MAX,
+        u128::MAX,
+        bob(),
+    )
+    .expect("Should mint position");
+    assert_eq!(position_id, 1);
+    assert_eq!(amount_1, 0);
+
+    let amount_in = 10 * TOKEN;
+    let expected = concentrated_pool::get_swap_amount_out(&mut session, pool, ice, wood, amount_in)
+        .expect("Should compute swap amount");
+    let (amount_out, fee) =
+        concentrated_pool::swap_exact_in(&mut session, pool, BOB, ice, wood, amount_in, 0, bob())
+            .expect("Should swap");
+    assert_eq!((amount_out, fee), expected);
+    assert!(fee >= amount_in * FEE as u128 / 1_000_000);
+    assert!(concentrated_pool::current_tick(&mut session, pool) < 0);
+
+    // swap moving the price into the range of the second position only
+    concentrated_pool::swap_exact_in(&mut session, pool, BOB, wood, ice, 50 * TOKEN, 0, bob())
+        .expect("Should swap");
+    let tick = concentrated_pool::current_tick(&mut session, pool);
+    assert!((600..1200).contains(&tick));
+    assert_eq!(
+        concentrated_pool::liquidity(&mut session, pool),
+        500 * TOKEN
+    );
+
+    // the first position earned fees in both tokens
+    let (fee_0, fee_1) =
+        concentrated_pool::collect(&mut session, pool, BOB, 0, bob()).expect("Should collect fees");
+    assert!(fee_0 > 0 && fee_1 > 0);
+
+    seed_account(&mut session, CHARLIE);
+    let res = concentrated_pool::collect(&mut session, pool, CHARLIE, 0, charlie());
+    assert_eq!(res, Err(ConcentratedPoolError::CallerIsNotPositionOwner()));
+
+    let res = concentrated_pool::burn_position(&mut session, pool, BOB, 0);
+    assert_eq!(res, Err(ConcentratedPoolError::PositionNotEmpty()));
+
+    let (amount_0, amount_1) =
+        concentrated_pool::decrease_liquidity(&mut session, pool, BOB, 0, liquidity, 0, 0)
+            .expect("Should decrease liquidity");
+    let ice_before = psp22_utils::balance_of(&mut session, ice, bob());
+    let wood_before = psp22_utils::balance_of(&mut session, wood, bob());
+    assert_eq!(
+        concentrated_pool::collect(&mut session, pool, BOB, 0, bob()),
+        Ok((amount_0, amount_1))
+    );
+    assert_eq!(
+        psp22_utils::balance_of(&mut session, ice, bob()),
+        ice_before + amount_0
+    );
+    assert_eq!(
+        psp22_utils::balance_of(&mut session, wood, bob()),
+        wood_before + amount_1
+    );
+    concentrated_pool::burn_position(&mut session, pool, BOB, 0).expect("Should burn position");
+    assert_eq!(concentrated_pool::position(&mut session, pool, 0), None);
+}
+
+#[drink::test]
+fn test_concentrated_pool_transfer_position(mut session: Session) {
+    let (pool, _, _) = setup_concentrated_pool(&mut session);
+
+    let (position_id, _, _) = concentrated_pool::mint_position(
+        &mut session,
+        pool,
+        BOB,
+        -600,
+        600,
+        TOKEN,
+        u128::MAX,
+        u128::MAX,
+        bob(),
+    )
+    .expect("Should mint position");
+
+    concentrated_pool::transfer_position(&mut session, pool, BOB, position_id, charlie())
+        .expect("Should transfer position");
+    let position =
+        concentrated_pool::position(&mut session, pool, position_id).expect("Should exist");
+    assert_eq!(position.owner, charlie());
+
+    let res = concentrated_pool::decrease_liquidity(&mut session, pool, BOB, position_id, 1, 0, 0);
+    assert_eq!(res, Err(ConcentratedPoolError::CallerIsNotPositionOwner()));
+
+    seed_account(&mut session, CHARLIE);
+    concentrated_pool::decrease_liquidity(&mut session, pool, CHARLIE, position_id, TOKEN, 0, 0)
+        .expect("Should decrease liquidity");
+}
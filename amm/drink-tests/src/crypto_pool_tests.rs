@@ -0,0 +1,239 @@
+use crate::crypto_pool_contract;
+use crate::utils::*;
+
+use crypto_pool::CryptoPoolParams;
+use crypto_pool_contract::CryptoPoolError;
+use drink::{self, runtime::MinimalRuntime, session::Session};
+use ink_primitives::AccountId;
+
+const INITIAL_PRICE: u128 = TOKEN;
+
+fn default_params() -> CryptoPoolParams {
+    CryptoPoolParams {
+        amp: 400_000,
+        gamma: 145_000_000_000_000,
+        mid_fee: 2_600_000,
+        out_fee: 45_000_000,
+        fee_gamma: 230_000_000_000_000,
+        allowed_extra_profit: 2_000_000_000_000,
+        adjustment_step: 146_000_000_000_000,
+        ma_half_time: 600_000,
+        initial_price: INITIAL_PRICE,
+    }
+}
+
+/// Sets up ICE and WOOD and a crypto pool of them with the initial price 1.
+/// BOB approves the pool to spend both tokens.
+pub fn setup_crypto_pool(
+    session: &mut Session<MinimalRuntime>,
+) -> (AccountId, AccountId, AccountId) {
+    upload_all(session);
+    let ice = psp22_utils::setup(session, ICE.to_string(), BOB);
+    let wood = psp22_utils::setup(session, WOOD.to_string(), BOB);
+    let pool = crypto_pool::setup(
+        session,
+        vec![ice.into(), wood.into()],
+        vec![18, 18],
+        default_params(),
+        BOB,
+        500_000_000,
+        Some(FEE_RECEIVER.to_account_id()),
+    );
+    for token in [ice, wood] {
+        psp22_utils::increase_allowance(session, token.into(), pool.into(), u128::MAX, BOB)
+            .expect("Should increase allowance");
+    }
+    (pool.into(), ice.into(), wood.into())
+}
+
+#[drink::test]
+fn test_crypto_pool_swaps(mut session: Session) {
+    let (pool, ice, wood) = setup_crypto_pool(&mut session);
+
+    let res = crypto_pool::swap_exact_in(&mut session, pool, BOB, ice, wood, TOKEN, 0, bob());
+    assert_eq!(res, Err(CryptoPoolError::InsufficientLiquidity()));
+
+    crypto_pool::add_liquidity(
+        &mut session,
+        pool,
+        BOB,
+        1,
+        vec![1_000_000 * TOKEN, 1_000_000 * TOKEN],
+        bob(),
+    )
+    .expect("Should add liquidity");
+
+    let amount_in = 1000 * TOKEN;
+    let expected = crypto_pool::get_swap_amount_out(&mut session, pool, ice, wood, amount_in)
+        .expect("Should compute swap amount");
+    let wood_before = psp22_utils::balance_of(&mut session, wood, bob());
+    let (amount_out, fee) =
+        crypto_pool::swap_exact_in(&mut session, pool, BOB, ice, wood, amount_in, 0, bob())
+            .expect("Should swap");
+    assert_eq!((amount_out, fee), expected);
+    // the pool is balanced, so the fee is close to `mid_fee`
+    assert!(fee > 0 && fee < amount_in * 3 / 1000);
+    assert!(amount_out < amount_in - fee && amount_out > amount_in * 99 / 100);
+    assert_eq!(
+        psp22_utils::balance_of(&mut session, wood, bob()),
+        wood_before + amount_out
+    );
+
+    let amount_out = 1000 * TOKEN;
+    let expected = crypto_pool::get_swap_amount_in(&mut session, pool, wood, ice, amount_out)
+        .expect("Should compute swap amount");
+    let ice_before = psp22_utils::balance_of(&mut session, ice, bob());
+    let (amount_in, _) = crypto_pool::swap_exact_out(
+        &mut session,
+        pool,
+        BOB,
+        wood,
+        ice,
+        amount_out,
+        u128::MAX,
+        bob(),
+    )
+    .expect("Should swap");
+    assert_eq!(amount_in, expected.0);
+    assert!(amount_in > amount_out);
+    assert_eq!(
+        psp22_utils::balance_of(&mut session, ice, bob()),
+        ice_before + amount_out
+    );
+
+    // fees increase the value of the LP token
+    assert!(crypto_pool::virtual_price(&mut session, pool) > TOKEN);
+
+    let res = crypto_pool::swap_exact_in(&mut session, pool, BOB, ice, wood, TOKEN, TOKEN, bob());
+    assert_eq!(res, Err(CryptoPoolError::InsufficientOutputAmount()));
+}
+
+#[drink::test]
+fn test_crypto_pool_liquidity(mut session: Session) {
+    let (pool, ice, wood) = setup_crypto_pool(&mut session);
+
+    // the initial deposit requires both of the tokens
+    let res = crypto_pool::add_liquidity(&mut session, pool, BOB, 1, vec![1000 * TOKEN, 0], bob());
+    assert_eq!(res, Err(CryptoPoolError::ZeroAmounts()));
+    let shares = crypto_pool::add_liquidity(
+        &mut session,
+        pool,
+        BOB,
+        1,
+        vec![1000 * TOKEN, 1000 * TOKEN],
+        bob(),
+    )
+    .expect("Should add liquidity");
+    // LP token is worth the geometric mean of the deposit at the initial price
+    assert_eq!(shares, 1000 * TOKEN);
+    assert_eq!(psp22_utils::balance_of(&mut session, pool, bob()), shares);
+    assert_eq!(crypto_pool::virtual_price(&mut session, pool), TOKEN);
+
+    // imbalanced deposit is charged with a fee
+    let expected =
+        crypto_pool::get_mint_liquidity_for_amounts(&mut session, pool, vec![0, 10 * TOKEN])
+            .expect("Should compute lp amount");
+    let one_coin_shares =
+        crypto_pool::add_liquidity(&mut session, pool, BOB, 1, vec![0, 10 * TOKEN], bob())
+            .expect("Should add liquidity");
+    assert_eq!(one_coin_shares, expected);
+    assert!(one_coin_shares < 10 * TOKEN);
+
+    let expected =
+        crypto_pool::get_amount_for_one_coin_burn(&mut session, pool, one_coin_shares, wood)
+            .expect("Should compute withdraw amount");
+    let wood_before = psp22_utils::balance_of(&mut session, wood, bob());
+    let amount = crypto_pool::remove_liquidity_one_coin(
+        &mut session,
+        pool,
+        BOB,
+        one_coin_shares,
+        wood,
+        0,
+        bob(),
+    )
+    .expect("Should remove liquidity");
+    assert_eq!(amount, expected);
+    assert!(amount < 10 * TOKEN);
+    assert_eq!(
+        psp22_utils::balance_of(&mut session, wood, bob()),
+        wood_before + amount
+    );
+
+    let reserves = crypto_pool::reserves(&mut session, pool);
+    let amounts = crypto_pool::remove_liquidity_by_shares(
+        &mut session,
+        pool,
+        BOB,
+        shares / 2,
+        vec![1, 1],
+        bob(),
+    )
+    .expect("Should remove liquidity");
+    assert_eq!(
+        crypto_pool::reserves(&mut session, pool),
+        vec![reserves[0] - amounts[0], reserves[1] - amounts[1]]
+    );
+    assert_eq!(
+        psp22_utils::balance_of(&mut session, pool, bob()),
+        shares - shares / 2
+    );
+    let res =
+        crypto_pool::remove_liquidity_one_coin(&mut session, pool, BOB, shares, ice, 0, bob());
+    assert_eq!(res, Err(CryptoPoolError::InsufficientLiquidity()));
+}
+
+#[drink::test]
+fn test_crypto_pool_repeg(mut session: Session) {
+    let (pool, ice, wood) = setup_crypto_pool(&mut session);
+
+    crypto_pool::add_liquidity(
+        &mut session,
+        pool,
+        BOB,
+        1,
+        vec![1_000_000 * TOKEN, 1_000_000 * TOKEN],
+        bob(),
+    )
+    .expect("Should add liquidity");
+
+    // WOOD is bought for ICE more than sold, so its price grows
+    let mut now = get_timestamp(&mut session);
+    for i in 0..60 {
+        now += 60_000;
+        set_timestamp(&mut session, now);
+        let (token_in, token_out, amount_in) = if i % 3 == 0 {
+            (wood, ice, 900 * TOKEN)
+        } else {
+            (ice, wood, 1000 * TOKEN)
+        };
+        crypto_pool::swap_exact_in(
+            &mut session,
+            pool,
+            BOB,
+            token_in,
+            token_out,
+            amount_in,
+            0,
+            bob(),
+        )
+        .expect("Should swap");
+    }
+    let price_scale = crypto_pool::price_scale(&mut session, pool);
+    assert!(price_scale > INITIAL_PRICE);
+    assert!(crypto_pool::price_oracle(&mut session, pool) > price_scale);
+
+    for _ in 0..60 {
+        now += 60_000;
+        set_timestamp(&mut session, now);
+        crypto_pool::swap_exact_in(&mut session, pool, BOB, ice, wood, 3000 * TOKEN, 0, bob())
+            .expect("Should swap");
+    }
+    // the price scale follows the oracle price, but the LP token does not lose its value
+    let new_price_scale = crypto_pool::price_scale(&mut session, pool);
+    assert!(new_price_scale > price_scale);
+    assert!(crypto_pool::price_oracle(&mut session, pool) > new_price_scale);
+    assert!(crypto_pool::virtual_price(&mut session, pool) > TOKEN);
+    // the protocol receives its part of the profit as LP tokens
+    assert!(psp22_utils::balance_of(&mut session, pool, FEE_RECEIVER.to_account_id()) > 0);
+}
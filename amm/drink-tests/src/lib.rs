@@ -1,6 +1,22 @@
 #[cfg(test)]
+mod concentrated_pool_contract;
+#[cfg(test)]
+mod concentrated_pool_tests;
+#[cfg(test)]
+mod crypto_pool_contract;
+#[cfg(test)]
+mod crypto_pool_tests;
+#[cfg(test)]
 mod factory_contract;
 #[cfg(test)]
+mod farm_contract;
+#[cfg(test)]
+mod limit_order_contract;
+#[cfg(test)]
+mod limit_order_tests;
+#[cfg(test)]
+mod mock_flash_loan_receiver_contract;
+#[cfg(test)]
 mod mock_rate_provider_contract;
 #[cfg(test)]
 mod pair_contract;
@@ -17,8 +33,24 @@ mod router_v2_tests;
 #[cfg(test)]
 mod stable_pool_contract;
 #[cfg(test)]
+mod stable_pool_factory_contract;
+#[cfg(test)]
+mod stable_pool_factory_tests;
+#[cfg(test)]
 mod stable_swap_tests;
 #[cfg(test)]
+mod twap_oracle_contract;
+#[cfg(test)]
+mod twap_oracle_tests;
+#[cfg(test)]
+mod twap_order_contract;
+#[cfg(test)]
+mod twap_order_tests;
+#[cfg(test)]
 mod utils;
 #[cfg(test)]
+mod weighted_pool_contract;
+#[cfg(test)]
+mod weighted_pool_tests;
+#[cfg(test)]
 mod wrapped_azero;
@@ -0,0 +1,286 @@
+use crate::utils::*;
+use crate::{limit_order_contract, router_v2_contract};
+
+use drink::{self, runtime::MinimalRuntime, session::Session};
+use ink_primitives::AccountId;
+use limit_order_contract::{LimitOrderError, RouterV2Error, Step};
+
+const A: u128 = 10_000;
+const TRADE_FEE: u32 = 2_500_000;
+const PROTOCOL_FEE: u32 = 200_000_000;
+
+const LIQUIDITY: u128 = 100_000 * TOKEN;
+const AMOUNT_IN: u128 = 1_000 * TOKEN;
+const TIP: u128 = ONE_AZERO;
+
+struct Setup {
+    book: AccountId,
+    router: AccountId,
+    ice: AccountId,
+    wood: AccountId,
+    sand: AccountId,
+    ice_wood_pair: AccountId,
+    wood_sand_pool: AccountId,
+}
+
+/// Sets up ICE/WOOD pair and WOOD/SAND stable pool, both balanced, and the limit order book.
+/// BOB approves the router and the book to spend his tokens.
+fn setup_limit_order_book(session: &mut Session<MinimalRuntime>) -> Setup {
+    upload_all(session);
+
+    // Fix timestamp. Otherwise underlying UNIX clock is used.
+    let now = get_timestamp(session);
+    set_timestamp(session, now);
+
+    let factory = factory::setup(session, bob());
+    let wazero = wazero::setup(session);
+    let router: AccountId = router_v2::setup(session, factory.into(), wazero.into()).into();
+    let book: AccountId = limit_order::setup(session, router).into();
+
+    let ice = psp22_utils::setup(session, ICE.to_string(), BOB);
+    let wood = psp22_utils::setup(session, WOOD.to_string(), BOB);
+    let sand = psp22_utils::setup(session, "SAND".to_string(), BOB);
+    for token in [ice, wood, sand] {
+        for spender in [router, book] {
+            psp22_utils::increase_allowance(session, token.into(), spender, u128::MAX, BOB)
+                .expect("Should increase allowance");
+        }
+    }
+
+    router_v2::add_pair_liquidity(
+        session,
+        router,
+        None,
+        ice.into(),
+        wood.into(),
+        LIQUIDITY,
+        LIQUIDITY,
+        0,
+        0,
+        bob(),
+        BOB,
+    )
+    .expect("Should add liquidity");
+    let ice_wood_pair: AccountId =
+        factory::get_pair(session, factory.into(), ice.into(), wood.into()).into();
+
+    let wood_sand_pool: AccountId = stable_swap::setup(
+        session,
+        vec![wood.into(), sand.into()],
+        vec![18, 18],
+        A,
+        BOB,
+        TRADE_FEE,
+        PROTOCOL_FEE,
+        Some(fee_receiver()),
+    )
+    .into();
+    router_v2::add_stable_swap_liquidity(
+        session,
+        router,
+        wood_sand_pool,
+        1,
+        vec![LIQUIDITY, LIQUIDITY],
+        bob(),
+        0,
+        BOB,
+    )
+    .expect("Should add liquidity");
+
+    Setup {
+        book,
+        router,
+        ice: ice.into(),
+        wood: wood.into(),
+        sand: sand.into(),
+        ice_wood_pair,
+        wood_sand_pool,
+    }
+}
+
+/// ICE -> WOOD -> SAND through the pair and the stable pool.
+fn ice_to_sand(s: &Setup) -> Vec<Step> {
+    vec![
+        Step {
+            token_in: s.ice,
+            pool_id: s.ice_wood_pair,
+        },
+        Step {
+            token_in: s.wood,
+            pool_id: s.wood_sand_pool,
+        },
+    ]
+}
+
+#[drink::test]
+fn test_limit_order_fill(mut session: Session) {
+    let s = setup_limit_order_book(&mut session);
+    seed_account(&mut session, CHARLIE);
+    let expiry = get_timestamp(&mut session) + 1000;
+
+    let res = limit_order::place_order(
+        &mut session,
+        s.book,
+        vec![],
+        s.sand,
+        AMOUNT_IN,
+        AMOUNT_IN,
+        expiry,
+        TIP,
+        BOB,
+    );
+    assert_eq!(res, Err(LimitOrderError::EmptyPath()));
+    let res = limit_order::place_order(
+        &mut session,
+        s.book,
+        ice_to_sand(&s),
+        s.sand,
+        0,
+        AMOUNT_IN,
+        expiry,
+        TIP,
+        BOB,
+    );
+    assert_eq!(res, Err(LimitOrderError::ZeroAmount()));
+    // `token_out` has to be received from the last pool of the path
+    for token_out in [s.ice, s.wood] {
+        let res = limit_order::place_order(
+            &mut session,
+            s.book,
+            ice_to_sand(&s),
+            token_out,
+            AMOUNT_IN,
+            AMOUNT_IN,
+            expiry,
+            TIP,
+            BOB,
+        );
+        assert_eq!(res, Err(LimitOrderError::InvalidTokenOut()));
+    }
+
+    // sell ICE for SAND at the price of 1, which is above the price of the balanced pools
+    let order_id = limit_order::place_order(
+        &mut session,
+        s.book,
+        ice_to_sand(&s),
+        s.sand,
+        AMOUNT_IN,
+        AMOUNT_IN,
+        expiry,
+        TIP,
+        BOB,
+    )
+    .expect("Should place order");
+    let order = limit_order::get_order(&mut session, s.book, order_id).expect("Should exist");
+    assert_eq!(order.owner, bob());
+    assert_eq!(order.tip, TIP);
+    assert_eq!(
+        psp22_utils::balance_of(&mut session, s.ice, s.book),
+        AMOUNT_IN
+    );
+
+    let quote = limit_order::quote_order(&mut session, s.book, order_id).expect("Should quote");
+    assert!(quote < AMOUNT_IN);
+    let res = limit_order::fill_order(&mut session, s.book, order_id, CHARLIE);
+    assert_eq!(
+        res,
+        Err(LimitOrderError::RouterV2Error(
+            RouterV2Error::InsufficientOutputAmount()
+        ))
+    );
+
+    // buying ICE in the pair moves its price above the target
+    router_v2::swap_exact_tokens_for_tokens(
+        &mut session,
+        s.router,
+        10_000 * TOKEN,
+        0,
+        vec![router_v2_contract::Step {
+            token_in: s.wood,
+            pool_id: s.ice_wood_pair,
+        }],
+        s.ice,
+        bob(),
+        BOB,
+    )
+    .expect("Should swap");
+    let quote = limit_order::quote_order(&mut session, s.book, order_id).expect("Should quote");
+    assert!(quote >= AMOUNT_IN);
+
+    let sand_before = psp22_utils::balance_of(&mut session, s.sand, bob());
+    let keeper_before = native_balance_of(&mut session, charlie());
+    let book_before = native_balance_of(&mut session, s.book);
+    let amount_out =
+        limit_order::fill_order(&mut session, s.book, order_id, CHARLIE).expect("Should fill");
+    assert_eq!(amount_out, quote);
+    assert_eq!(
+        psp22_utils::balance_of(&mut session, s.sand, bob()),
+        sand_before + amount_out
+    );
+    assert_eq!(psp22_utils::balance_of(&mut session, s.ice, s.book), 0);
+    // the keeper earns the tip
+    assert_eq!(book_before - native_balance_of(&mut session, s.book), TIP);
+    assert!(native_balance_of(&mut session, charlie()) >= keeper_before + TIP);
+
+    assert!(limit_order::get_order(&mut session, s.book, order_id).is_none());
+    let res = limit_order::fill_order(&mut session, s.book, order_id, CHARLIE);
+    assert_eq!(res, Err(LimitOrderError::OrderNotFound()));
+}
+
+#[drink::test]
+fn test_limit_order_cancel(mut session: Session) {
+    let s = setup_limit_order_book(&mut session);
+    seed_account(&mut session, CHARLIE);
+    let now = get_timestamp(&mut session);
+    let expiry = now + 1000;
+
+    let ice_before = psp22_utils::balance_of(&mut session, s.ice, bob());
+    let order_id = limit_order::place_order(
+        &mut session,
+        s.book,
+        ice_to_sand(&s),
+        s.sand,
+        AMOUNT_IN,
+        0,
+        expiry,
+        TIP,
+        BOB,
+    )
+    .expect("Should place order");
+
+    // only the owner can cancel an open order
+    let res = limit_order::cancel_order(&mut session, s.book, order_id, CHARLIE);
+    assert_eq!(res, Err(LimitOrderError::CallerIsNotOwner()));
+    let book_before = native_balance_of(&mut session, s.book);
+    limit_order::cancel_order(&mut session, s.book, order_id, BOB).expect("Should cancel");
+    assert_eq!(
+        psp22_utils::balance_of(&mut session, s.ice, bob()),
+        ice_before
+    );
+    assert_eq!(book_before - native_balance_of(&mut session, s.book), TIP);
+    assert!(limit_order::get_order(&mut session, s.book, order_id).is_none());
+
+    let order_id = limit_order::place_order(
+        &mut session,
+        s.book,
+        ice_to_sand(&s),
+        s.sand,
+        AMOUNT_IN,
+        0,
+        expiry,
+        TIP,
+        BOB,
+    )
+    .expect("Should place order");
+    set_timestamp(&mut session, expiry + 1);
+    let res = limit_order::fill_order(&mut session, s.book, order_id, CHARLIE);
+    assert_eq!(res, Err(LimitOrderError::Expired()));
+
+    // anyone can cancel an expired order, returning the tokens to the owner
+    limit_order::cancel_order(&mut session, s.book, order_id, CHARLIE).expect("Should cancel");
+    assert_eq!(
+        psp22_utils::balance_of(&mut session, s.ice, bob()),
+        ice_before
+    );
+    assert_eq!(psp22_utils::balance_of(&mut session, s.ice, s.book), 0);
+}
@@ -1,6 +1,6 @@
 use crate::factory_contract;
 use crate::pair_contract;
-use crate::pair_contract::Pair;
+use crate::pair_contract::{Pair, PairError};
 use crate::router_contract;
 use crate::utils::*;
 use ink_wrapper_types::ToAccountId;
@@ -13,6 +13,7 @@ use drink::frame_support::sp_runtime::traits::Scale;
 use drink::{self, session::Session};
 use ink_primitives::AccountId;
 use ink_wrapper_types::Connection;
+use primitive_types::U256;
 
 #[drink::test]
 fn add_liquidity(mut session: Session) {
@@ -58,7 +59,7 @@ fn add_liquidity(mut session: Session) {
         .unwrap();
 
     let ice_wazero_pair: pair_contract::Instance = session
-        .query(factory.get_pair(ice.into(), wazero.into()))
+        .query(factory.get_pair(ice.into(), wazero.into(), factory::DEFAULT_FEE))
         .unwrap()
         .result
         .unwrap()
@@ -147,7 +148,7 @@ fn add_liquidity_collects_too_much_fee(mut session: Session) {
     );
 
     let ice_wood_pair: pair_contract::Instance = session
-        .query(factory.get_pair(ice.into(), wood.into()))
+        .query(factory.get_pair(ice.into(), wood.into(), factory::DEFAULT_FEE))
         .unwrap()
         .result
         .unwrap()
@@ -346,6 +347,7 @@ fn test_custom_fee(mut session: Session) {
             wood.into(),
             factory.into(),
             small_fee,
+            GENESIS_HASH.into(),
         ))
         .unwrap()
         .result
@@ -509,6 +511,7 @@ fn test_pair_cache(mut session: Session) {
             wood.into(),
             factory.into(),
             small_fee,
+            GENESIS_HASH.into(),
         ))
         .unwrap()
         .result
@@ -521,3 +524,457 @@ fn test_pair_cache(mut session: Session) {
     assert_eq!(new_pair, new_pair_in_router);
     assert!(pair_in_router != new_pair_in_router);
 }
+
+#[drink::test]
+fn test_flash_loan(mut session: Session) {
+    upload_all(&mut session);
+
+    let factory = factory::setup(&mut session, bob());
+    let ice = psp22_utils::setup(&mut session, ICE.to_string(), BOB);
+    let wood = psp22_utils::setup(&mut session, WOOD.to_string(), BOB);
+    let wazero = wazero::setup(&mut session);
+    let router = router::setup(&mut session, factory.into(), wazero.into());
+
+    let token_amount = 1_000_000 * TOKEN;
+    psp22_utils::increase_allowance(&mut session, ice.into(), router.into(), u128::MAX, BOB)
+        .unwrap();
+    psp22_utils::increase_allowance(&mut session, wood.into(), router.into(), u128::MAX, BOB)
+        .unwrap();
+    router::add_liquidity(
+        &mut session,
+        router.into(),
+        ice.into(),
+        wood.into(),
+        token_amount,
+        token_amount,
+        bob(),
+    );
+    let pair = factory::get_pair(&mut session, factory.into(), ice.into(), wood.into());
+    let (reserve_0, reserve_1, _) =
+        handle_contract_result(session.query(pair.get_reserves()).unwrap());
+
+    // the receiver needs some tokens to pay the fee
+    let receiver: AccountId = mock_flash_loan_receiver::setup(&mut session, true).into();
+    psp22_utils::transfer(&mut session, ice.into(), receiver, 100 * TOKEN, BOB).unwrap();
+
+    let amount = 10_000 * TOKEN;
+    assert_eq!(
+        handle_contract_result(session.query(pair.max_flash_loan(ice.into())).unwrap()),
+        token_amount
+    );
+    // default fee is 0.05%
+    let fee = amount * 5 / 10_000;
+    assert_eq!(
+        handle_contract_result(session.query(pair.flash_fee(ice.into(), amount)).unwrap()),
+        Ok(fee)
+    );
+    assert_eq!(
+        handle_contract_result(
+            session
+                .execute(pair.flash_loan(ice.into(), amount, receiver, vec![]))
+                .unwrap()
+        ),
+        Ok(fee)
+    );
+
+    // the fee is added to the reserves
+    let ice_is_token_0 = AccountId::from(ice) < AccountId::from(wood);
+    let expected_reserves = if ice_is_token_0 {
+        (reserve_0 + fee, reserve_1)
+    } else {
+        (reserve_0, reserve_1 + fee)
+    };
+    let (new_reserve_0, new_reserve_1, _) =
+        handle_contract_result(session.query(pair.get_reserves()).unwrap());
+    assert_eq!((new_reserve_0, new_reserve_1), expected_reserves);
+    assert_eq!(
+        psp22_utils::balance_of(&mut session, ice.into(), receiver),
+        100 * TOKEN - fee
+    );
+
+    // the fee is configured in the factory
+    assert_eq!(
+        handle_contract_result(session.execute(factory.set_flash_loan_fee(101)).unwrap()),
+        Err(factory_contract::FactoryError::InvalidFlashLoanFee())
+    );
+    handle_contract_result(session.execute(factory.set_flash_loan_fee(50)).unwrap()).unwrap();
+    assert_eq!(
+        handle_contract_result(session.query(pair.flash_fee(ice.into(), amount)).unwrap()),
+        Ok(amount * 50 / 10_000)
+    );
+
+    // the loan has to be repaid
+    let defaulting_receiver: AccountId =
+        mock_flash_loan_receiver::setup(&mut session, false).into();
+    assert_eq!(
+        handle_contract_result(
+            session
+                .execute(pair.flash_loan(ice.into(), amount, defaulting_receiver, vec![]))
+                .unwrap()
+        ),
+        Err(PairError::InsufficientFlashLoanRepayment())
+    );
+    assert_eq!(
+        psp22_utils::balance_of(&mut session, ice.into(), defaulting_receiver),
+        0
+    );
+
+    // only the pair's tokens can be borrowed
+    assert_eq!(
+        handle_contract_result(session.query(pair.max_flash_loan(wazero.into())).unwrap()),
+        0
+    );
+    assert_eq!(
+        handle_contract_result(
+            session
+                .execute(pair.flash_loan(wazero.into(), amount, receiver, vec![]))
+                .unwrap()
+        ),
+        Err(PairError::UnsupportedFlashLoanToken())
+    );
+}
+
+#[drink::test]
+fn test_set_pair_fee(mut session: Session) {
+    upload_all(&mut session);
+
+    // Fix timestamp. Otherwise underlying UNIX clock is used.
+    let now = get_timestamp(&mut session);
+    set_timestamp(&mut session, now);
+
+    let factory = factory::setup(&mut session, bob());
+    let ice = psp22_utils::setup(&mut session, ICE.to_string(), BOB);
+    let wood = psp22_utils::setup(&mut session, WOOD.to_string(), BOB);
+    let wazero = wazero::setup(&mut session);
+    let router = router::setup(&mut session, factory.into(), wazero.into());
+
+    let token_amount = 1_000_000 * TOKEN;
+    psp22_utils::increase_allowance(&mut session, ice.into(), router.into(), u128::MAX, BOB)
+        .unwrap();
+    psp22_utils::increase_allowance(&mut session, wood.into(), router.into(), u128::MAX, BOB)
+        .unwrap();
+    router::add_liquidity(
+        &mut session,
+        router.into(),
+        ice.into(),
+        wood.into(),
+        token_amount,
+        token_amount,
+        bob(),
+    );
+    let pair = factory::get_pair(&mut session, factory.into(), ice.into(), wood.into());
+
+    seed_account(&mut session, CHARLIE);
+
+    // 24h in milisec
+    let delay = 86_400_000;
+    let new_fee = 5;
+    assert_eq!(
+        factory::set_pair_fee(
+            &mut session,
+            factory.into(),
+            pair.into(),
+            new_fee,
+            now + delay,
+            CHARLIE
+        ),
+        Err(factory_contract::FactoryError::CallerIsNotFeeSetter())
+    );
+    assert_eq!(
+        factory::set_pair_fee(
+            &mut session,
+            factory.into(),
+            pair.into(),
+            11,
+            now + delay,
+            BOB
+        ),
+        Err(factory_contract::FactoryError::PairError(
+            factory_contract::PairError::InvalidFee()
+        ))
+    );
+    assert_eq!(
+        factory::set_pair_fee(
+            &mut session,
+            factory.into(),
+            pair.into(),
+            new_fee,
+            now + delay - 1,
+            BOB
+        ),
+        Err(factory_contract::FactoryError::PairError(
+            factory_contract::PairError::InvalidFeeChangeTime()
+        ))
+    );
+    // only the factory can change the fee
+    assert_eq!(
+        handle_contract_result(session.execute(pair.set_fee(new_fee, now + delay)).unwrap()),
+        Err(PairError::CallerIsNotFactory())
+    );
+
+    factory::set_pair_fee(
+        &mut session,
+        factory.into(),
+        pair.into(),
+        new_fee,
+        now + delay,
+        BOB,
+    )
+    .expect("Should schedule fee change");
+    assert_eq!(
+        handle_contract_result(session.query(pair.get_fee()).unwrap()),
+        factory::DEFAULT_FEE
+    );
+    assert_eq!(
+        handle_contract_result(session.query(pair.get_future_fee()).unwrap()),
+        Some((new_fee, now + delay))
+    );
+
+    // the new fee is in effect after the delay
+    set_timestamp(&mut session, now + delay);
+    assert_eq!(
+        handle_contract_result(session.query(pair.get_fee()).unwrap()),
+        new_fee
+    );
+    assert_eq!(
+        handle_contract_result(session.query(pair.get_future_fee()).unwrap()),
+        None
+    );
+    // the pair is still recognized by the factory
+    assert!(factory::is_pair(&mut session, factory.into(), pair.into()));
+
+    // swaps are charged with the new fee
+    let swap_amount = 10_000 * TOKEN;
+    let (reserve_0, reserve_1, _) =
+        handle_contract_result(session.query(pair.get_reserves()).unwrap());
+    let (reserve_in, reserve_out) = if AccountId::from(ice) < AccountId::from(wood) {
+        (reserve_0, reserve_1)
+    } else {
+        (reserve_1, reserve_0)
+    };
+    let amount_in_with_fee = U256::from(swap_amount) * U256::from(1000 - new_fee as u128);
+    let expected_amount_out = (amount_in_with_fee * U256::from(reserve_out)
+        / (U256::from(reserve_in) * U256::from(1000) + amount_in_with_fee))
+        .as_u128();
+    let amounts = session
+        .execute(router.swap_exact_tokens_for_tokens(
+            swap_amount,
+            expected_amount_out,
+            vec![ice.into(), wood.into()],
+            bob(),
+            now + delay + 10,
+        ))
+        .unwrap()
+        .result
+        .unwrap()
+        .unwrap();
+    assert_eq!(amounts, vec![swap_amount, expected_amount_out]);
+}
+
+#[drink::test]
+fn test_protocol_fee_share(mut session: Session) {
+    upload_all(&mut session);
+
+    // Fix timestamp. Otherwise underlying UNIX clock is used.
+    let now = get_timestamp(&mut session);
+    set_timestamp(&mut session, now);
+
+    let factory = factory::setup(&mut session, bob());
+    let ice = psp22_utils::setup(&mut session, ICE.to_string(), BOB);
+    let wood = psp22_utils::setup(&mut session, WOOD.to_string(), BOB);
+    let wazero = wazero::setup(&mut session);
+    let router = router::setup(&mut session, factory.into(), wazero.into());
+
+    session
+        .execute(factory.set_fee_to(charlie()))
+        .unwrap()
+        .result
+        .unwrap()
+        .unwrap();
+    assert_eq!(
+        factory::set_protocol_fee_denom(&mut session, factory.into(), 1, BOB),
+        Err(factory_contract::FactoryError::InvalidProtocolFee())
+    );
+    // protocol receives 1/2 of the LP fees growth
+    factory::set_protocol_fee_denom(&mut session, factory.into(), 2, BOB)
+        .expect("Should set protocol fee");
+
+    let token_amount = 1_000_000 * TOKEN;
+    psp22_utils::increase_allowance(&mut session, ice.into(), router.into(), u128::MAX, BOB)
+        .unwrap();
+    psp22_utils::increase_allowance(&mut session, wood.into(), router.into(), u128::MAX, BOB)
+        .unwrap();
+    let (_, _, liquidity_minted) = router::add_liquidity(
+        &mut session,
+        router.into(),
+        ice.into(),
+        wood.into(),
+        token_amount,
+        token_amount,
+        bob(),
+    );
+    let pair = factory::get_pair(&mut session, factory.into(), ice.into(), wood.into());
+
+    // the pair's fees go to DAVE instead of the factory's `fee_to`
+    factory::set_pair_fee_to(&mut session, factory.into(), pair.into(), Some(dave()), BOB)
+        .expect("Should set pair fee_to");
+    assert_eq!(
+        session
+            .query(factory.pair_fee_to(pair.into()))
+            .unwrap()
+            .result
+            .unwrap(),
+        Some(dave())
+    );
+
+    let (reserve_0, reserve_1, _) =
+        handle_contract_result(session.query(pair.get_reserves()).unwrap());
+    let root_k_last = (U256::from(reserve_0) * U256::from(reserve_1)).integer_sqrt();
+
+    session
+        .execute(router.swap_exact_tokens_for_tokens(
+            10_000 * TOKEN,
+            0,
+            vec![ice.into(), wood.into()],
+            bob(),
+            now + 10,
+        ))
+        .unwrap()
+        .result
+        .unwrap()
+        .unwrap();
+
+    let (reserve_0, reserve_1, _) =
+        handle_contract_result(session.query(pair.get_reserves()).unwrap());
+    let root_k = (U256::from(reserve_0) * U256::from(reserve_1)).integer_sqrt();
+    let total_supply = psp22_utils::total_supply(&mut session, pair.into());
+    // Whitepaper 2.4, equation (7) with 1/2 share
+    let expected_protocol_fee =
+        (U256::from(total_supply) * (root_k - root_k_last) / (root_k + root_k_last)).as_u128();
+
+    // Burn some liquidity to trigger fee collection.
+    psp22_utils::increase_allowance(
+        &mut session,
+        pair.into(),
+        router.into(),
+        liquidity_minted,
+        BOB,
+    )
+    .unwrap();
+    router::remove_liquidity(
+        &mut session,
+        router.into(),
+        ice.into(),
+        wood.into(),
+        liquidity_minted / 2,
+        0,
+        0,
+        bob(),
+    );
+
+    assert_eq!(
+        psp22_utils::balance_of(&mut session, pair.into(), dave()),
+        expected_protocol_fee
+    );
+    assert_eq!(
+        psp22_utils::balance_of(&mut session, pair.into(), charlie()),
+        0
+    );
+}
+
+#[drink::test]
+fn test_collect_protocol_fee(mut session: Session) {
+    upload_all(&mut session);
+
+    // Fix timestamp. Otherwise underlying UNIX clock is used.
+    let now = get_timestamp(&mut session);
+    set_timestamp(&mut session, now);
+
+    let factory = factory::setup(&mut session, bob());
+    let ice = psp22_utils::setup(&mut session, ICE.to_string(), BOB);
+    let wood = psp22_utils::setup(&mut session, WOOD.to_string(), BOB);
+    let wazero = wazero::setup(&mut session);
+    let router = router::setup(&mut session, factory.into(), wazero.into());
+
+    session
+        .execute(factory.set_fee_to(charlie()))
+        .unwrap()
+        .result
+        .unwrap()
+        .unwrap();
+
+    let token_amount = 1_000_000 * TOKEN;
+    psp22_utils::increase_allowance(&mut session, ice.into(), router.into(), u128::MAX, BOB)
+        .unwrap();
+    psp22_utils::increase_allowance(&mut session, wood.into(), router.into(), u128::MAX, BOB)
+        .unwrap();
+    router::add_liquidity(
+        &mut session,
+        router.into(),
+        ice.into(),
+        wood.into(),
+        token_amount,
+        token_amount,
+        bob(),
+    );
+    let pair = factory::get_pair(&mut session, factory.into(), ice.into(), wood.into());
+    assert_eq!(
+        handle_contract_result(session.query(pair.pending_protocol_fee()).unwrap()),
+        Ok(0)
+    );
+
+    let (reserve_0, reserve_1, _) =
+        handle_contract_result(session.query(pair.get_reserves()).unwrap());
+    let root_k_last = (U256::from(reserve_0) * U256::from(reserve_1)).integer_sqrt();
+
+    session
+        .execute(router.swap_exact_tokens_for_tokens(
+            10_000 * TOKEN,
+            0,
+            vec![ice.into(), wood.into()],
+            bob(),
+            now + 10,
+        ))
+        .unwrap()
+        .result
+        .unwrap()
+        .unwrap();
+
+    let (reserve_0, reserve_1, _) =
+        handle_contract_result(session.query(pair.get_reserves()).unwrap());
+    let root_k = (U256::from(reserve_0) * U256::from(reserve_1)).integer_sqrt();
+    let total_supply = psp22_utils::total_supply(&mut session, pair.into());
+    // Whitepaper 2.4, equation (7) with the default 1/6 share
+    let expected_protocol_fee = (U256::from(total_supply) * (root_k - root_k_last)
+        / (root_k * U256::from(5) + root_k_last))
+        .as_u128();
+    assert!(expected_protocol_fee > 0);
+    assert_eq!(
+        handle_contract_result(session.query(pair.pending_protocol_fee()).unwrap()),
+        Ok(expected_protocol_fee)
+    );
+
+    // No fees distributed until collected.
+    assert_eq!(
+        psp22_utils::balance_of(&mut session, pair.into(), charlie()),
+        0
+    );
+    assert_eq!(
+        handle_contract_result(session.execute(pair.collect_protocol_fee()).unwrap()),
+        Ok(expected_protocol_fee)
+    );
+    assert_eq!(
+        psp22_utils::balance_of(&mut session, pair.into(), charlie()),
+        expected_protocol_fee
+    );
+
+    // Nothing more to collect.
+    assert_eq!(
+        handle_contract_result(session.query(pair.pending_protocol_fee()).unwrap()),
+        Ok(0)
+    );
+    assert_eq!(
+        handle_contract_result(session.execute(pair.collect_protocol_fee()).unwrap()),
+        Ok(0)
+    );
+}
@@ -1,14 +1,20 @@
 use std::u128;
 
+use crate::concentrated_pool_tests::setup_concentrated_pool;
+use crate::crypto_pool_tests::setup_crypto_pool;
 use crate::stable_swap_tests::*;
 use crate::utils::*;
+use crate::weighted_pool_tests::setup_weighted_pool;
 use crate::{factory_contract, pair_contract, router_v2_contract, wrapped_azero};
 
 use drink::{runtime::MinimalRuntime, Weight};
 use ink_primitives::AccountId;
 use ink_wrapper_types::ToAccountId;
-use pair_contract::Pair as _;
-use router_v2_contract::{Pair, Pool, StablePool, Step};
+use pair_contract::{Pair as _, Permit as _};
+use router_v2_contract::{
+    CallAmount, ConcentratedPool, CryptoPool, Pair, PairError, PermitError, Pool, RouterCall,
+    RouterV2Error, StablePool, Step, WeightedPath, WeightedPool,
+};
 
 use drink::{self, session::Session};
 use ink_wrapper_types::Connection;
@@ -198,6 +204,7 @@ fn test_cache_pair(mut session: Session) {
             wood.into(),
             factory.into(),
             custom_fee,
+            GENESIS_HASH.into(),
         ))
         .unwrap()
         .result
@@ -247,12 +254,249 @@ fn test_cache_pair(mut session: Session) {
             id: ice_wood_pair.into(),
             token_0: ice.into(),
             token_1: wood.into(),
-            fee: custom_fee,
         }),
         "Pair cache mismatch"
     );
 }
 
+/// Tests that a ConcentratedPool is cached in the Router
+/// with the first swap and that the Router quotes it correctly.
+#[drink::test]
+fn test_cache_concentrated_pool(mut session: Session) {
+    let (pool, ice, wood) = setup_concentrated_pool(&mut session);
+
+    // Fix timestamp. Otherwise underlying UNIX clock is used.
+    let now = get_timestamp(&mut session);
+    set_timestamp(&mut session, now);
+
+    let (router, _, _, _) = setup_router(&mut session);
+
+    concentrated_pool::mint_position(
+        &mut session,
+        pool,
+        BOB,
+        -600,
+        600,
+        1_000 * TOKEN,
+        u128::MAX,
+        u128::MAX,
+        bob(),
+    )
+    .expect("Should mint position");
+
+    // ensure that the pool is not cached before the swap
+    let res = router_v2::get_cached_pool(&mut session, router.into(), pool);
+    assert_eq!(res, None, "ConcentratedPool should not be in the cache");
+
+    psp22_utils::increase_allowance(&mut session, ice, router.into(), u128::MAX, BOB)
+        .expect("Should increase allowance");
+
+    let amount_in = 10 * TOKEN;
+    let (expected_out, _) =
+        concentrated_pool::get_swap_amount_out(&mut session, pool, ice, wood, amount_in)
+            .expect("Should compute swap amount");
+    let amounts = router_v2::swap_exact_tokens_for_tokens(
+        &mut session,
+        router.into(),
+        amount_in,
+        expected_out,
+        vec![Step {
+            token_in: ice,
+            pool_id: pool,
+        }],
+        wood,
+        bob(),
+        BOB,
+    )
+    .expect("Should swap");
+    assert_eq!(amounts, vec![amount_in, expected_out]);
+
+    let res = router_v2::get_cached_pool(&mut session, router.into(), pool)
+        .expect("Should return cached ConcentratedPool");
+    assert_eq!(
+        res,
+        Pool::ConcentratedPool(ConcentratedPool {
+            id: pool,
+            token_0: ice,
+            token_1: wood,
+        }),
+        "ConcentratedPool cache mismatch"
+    );
+
+    let amount_out = TOKEN;
+    let amounts = router_v2::swap_tokens_for_exact_tokens(
+        &mut session,
+        router.into(),
+        amount_out,
+        u128::MAX,
+        vec![Step {
+            token_in: ice,
+            pool_id: pool,
+        }],
+        wood,
+        bob(),
+        BOB,
+    )
+    .expect("Should swap");
+    assert_eq!(amounts[1], amount_out);
+}
+
+/// Tests that a WeightedPool is cached in the Router
+/// with the first swap.
+#[drink::test]
+fn test_cache_weighted_pool(mut session: Session) {
+    let (pool, ice, wood) = setup_weighted_pool(&mut session);
+
+    // Fix timestamp. Otherwise underlying UNIX clock is used.
+    let now = get_timestamp(&mut session);
+    set_timestamp(&mut session, now);
+
+    let (router, _, _, _) = setup_router(&mut session);
+
+    weighted_pool::add_liquidity(
+        &mut session,
+        pool,
+        BOB,
+        1,
+        vec![800 * TOKEN, 200 * TOKEN],
+        bob(),
+    )
+    .expect("Should add liquidity");
+
+    // ensure that the pool is not cached before the swap
+    let res = router_v2::get_cached_pool(&mut session, router.into(), pool);
+    assert_eq!(res, None, "WeightedPool should not be in the cache");
+
+    psp22_utils::increase_allowance(&mut session, ice, router.into(), u128::MAX, BOB)
+        .expect("Should increase allowance");
+
+    let amount_in = 10 * TOKEN;
+    let (expected_out, _) =
+        weighted_pool::get_swap_amount_out(&mut session, pool, ice, wood, amount_in)
+            .expect("Should compute swap amount");
+    let amounts = router_v2::swap_exact_tokens_for_tokens(
+        &mut session,
+        router.into(),
+        amount_in,
+        expected_out,
+        vec![Step {
+            token_in: ice,
+            pool_id: pool,
+        }],
+        wood,
+        bob(),
+        BOB,
+    )
+    .expect("Should swap");
+    assert_eq!(amounts, vec![amount_in, expected_out]);
+
+    let res = router_v2::get_cached_pool(&mut session, router.into(), pool)
+        .expect("Should return cached WeightedPool");
+    assert_eq!(
+        res,
+        Pool::WeightedPool(WeightedPool {
+            id: pool,
+            tokens: vec![ice, wood],
+        }),
+        "WeightedPool cache mismatch"
+    );
+
+    let amount_out = TOKEN;
+    let amounts = router_v2::swap_tokens_for_exact_tokens(
+        &mut session,
+        router.into(),
+        amount_out,
+        u128::MAX,
+        vec![Step {
+            token_in: ice,
+            pool_id: pool,
+        }],
+        wood,
+        bob(),
+        BOB,
+    )
+    .expect("Should swap");
+    assert_eq!(amounts[1], amount_out);
+}
+
+/// Tests that a CryptoPool is cached in the Router
+/// with the first swap.
+#[drink::test]
+fn test_cache_crypto_pool(mut session: Session) {
+    let (pool, ice, wood) = setup_crypto_pool(&mut session);
+
+    // Fix timestamp. Otherwise underlying UNIX clock is used.
+    let now = get_timestamp(&mut session);
+    set_timestamp(&mut session, now);
+
+    let (router, _, _, _) = setup_router(&mut session);
+
+    crypto_pool::add_liquidity(
+        &mut session,
+        pool,
+        BOB,
+        1,
+        vec![1000 * TOKEN, 1000 * TOKEN],
+        bob(),
+    )
+    .expect("Should add liquidity");
+
+    // ensure that the pool is not cached before the swap
+    let res = router_v2::get_cached_pool(&mut session, router.into(), pool);
+    assert_eq!(res, None, "CryptoPool should not be in the cache");
+
+    psp22_utils::increase_allowance(&mut session, ice, router.into(), u128::MAX, BOB)
+        .expect("Should increase allowance");
+
+    let amount_in = 10 * TOKEN;
+    let (expected_out, _) =
+        crypto_pool::get_swap_amount_out(&mut session, pool, ice, wood, amount_in)
+            .expect("Should compute swap amount");
+    let amounts = router_v2::swap_exact_tokens_for_tokens(
+        &mut session,
+        router.into(),
+        amount_in,
+        expected_out,
+        vec![Step {
+            token_in: ice,
+            pool_id: pool,
+        }],
+        wood,
+        bob(),
+        BOB,
+    )
+    .expect("Should swap");
+    assert_eq!(amounts, vec![amount_in, expected_out]);
+
+    let res = router_v2::get_cached_pool(&mut session, router.into(), pool)
+        .expect("Should return cached CryptoPool");
+    assert_eq!(
+        res,
+        Pool::CryptoPool(CryptoPool {
+            id: pool,
+            tokens: vec![ice, wood],
+        }),
+        "CryptoPool cache mismatch"
+    );
+
+    let amount_out = TOKEN;
+    let amounts = router_v2::swap_tokens_for_exact_tokens(
+        &mut session,
+        router.into(),
+        amount_out,
+        u128::MAX,
+        vec![Step {
+            token_in: ice,
+            pool_id: pool,
+        }],
+        wood,
+        bob(),
+        BOB,
+    )
+    .expect("Should swap");
+    assert_eq!(amounts[1], amount_out);
+}
+
 /// Tests that a Pair is cached in the Router
 /// with the first liquidity deposit.
 #[drink::test]
@@ -276,6 +520,7 @@ fn test_cache_custom_pair_with_add_liqudity(mut session: Session) {
             wood.into(),
             factory.into(),
             custom_fee,
+            GENESIS_HASH.into(),
         ))
         .unwrap()
         .result
@@ -314,7 +559,6 @@ fn test_cache_custom_pair_with_add_liqudity(mut session: Session) {
             id: ice_wood_pair.into(),
             token_0: ice.into(),
             token_1: wood.into(),
-            fee: custom_fee,
         }),
         "Pair mismatch"
     );
@@ -355,7 +599,6 @@ fn test_create_and_cache_pair_with_add_liqudity(mut session: Session) {
     )
     .expect("Should add liquidity");
 
-    let default_pair_fee = 3;
     let ice_wood_pair = factory::get_pair(&mut session, factory.into(), ice.into(), wood.into());
     let res = router_v2::get_cached_pool(&mut session, router.into(), ice_wood_pair.into())
         .expect("Should return cached Pair");
@@ -365,17 +608,119 @@ fn test_create_and_cache_pair_with_add_liqudity(mut session: Session) {
             id: ice_wood_pair.into(),
             token_0: ice.into(),
             token_1: wood.into(),
-            fee: default_pair_fee,
         }),
         "Pair mismatch"
     );
 }
 
+/// Tests adding liquidity to a Pair from a single token and removing it into a single token.
+#[drink::test]
+fn test_zap_pair(mut session: Session) {
+    upload_all(&mut session);
+
+    // Fix timestamp. Otherwise underlying UNIX clock is used.
+    let now = get_timestamp(&mut session);
+    set_timestamp(&mut session, now);
+
+    let (router, factory, _, _) = setup_router(&mut session);
+
+    let ice = psp22_utils::setup(&mut session, ICE.to_string(), BOB);
+    let wood = psp22_utils::setup(&mut session, WOOD.to_string(), BOB);
+
+    for token in [ice, wood] {
+        psp22_utils::increase_allowance(&mut session, token.into(), router.into(), u128::MAX, BOB)
+            .expect("Should increase allowance");
+    }
+
+    router_v2::add_pair_liquidity(
+        &mut session,
+        router.into(),
+        None,
+        ice.into(),
+        wood.into(),
+        1000 * TOKEN,
+        2000 * TOKEN,
+        0,
+        0,
+        bob(),
+        BOB,
+    )
+    .expect("Should add liquidity");
+    let pair: AccountId =
+        factory::get_pair(&mut session, factory.into(), ice.into(), wood.into()).into();
+
+    let amount_in = 10 * TOKEN;
+    let res = router_v2::zap_in_pair(
+        &mut session,
+        router.into(),
+        pair,
+        ice.into(),
+        amount_in,
+        u128::MAX,
+        bob(),
+        BOB,
+    );
+    assert_eq!(res, Err(RouterV2Error::InsufficientOutputAmount()));
+
+    let lp_before = psp22_utils::balance_of(&mut session, pair, bob());
+    let ice_before = psp22_utils::balance_of(&mut session, ice.into(), bob());
+    let (used_amount, liquidity) = router_v2::zap_in_pair(
+        &mut session,
+        router.into(),
+        pair,
+        ice.into(),
+        amount_in,
+        1,
+        bob(),
+        BOB,
+    )
+    .expect("Should zap in");
+    // almost all of the input is deposited, the rest is never taken from the caller
+    assert!(used_amount <= amount_in && used_amount > amount_in * 9999 / 10000);
+    assert_eq!(
+        psp22_utils::balance_of(&mut session, ice.into(), bob()),
+        ice_before - used_amount
+    );
+    assert_eq!(
+        psp22_utils::balance_of(&mut session, pair, bob()),
+        lp_before + liquidity
+    );
+
+    psp22_utils::increase_allowance(&mut session, pair, router.into(), liquidity, BOB)
+        .expect("Should increase allowance");
+    let ice_before = psp22_utils::balance_of(&mut session, ice.into(), bob());
+    let amount_out = router_v2::zap_out_pair(
+        &mut session,
+        router.into(),
+        pair,
+        liquidity,
+        ice.into(),
+        1,
+        bob(),
+        BOB,
+    )
+    .expect("Should zap out");
+    // two swaps are charged with the pair fee
+    assert!(amount_out < used_amount && amount_out > used_amount * 99 / 100);
+    assert_eq!(
+        psp22_utils::balance_of(&mut session, ice.into(), bob()),
+        ice_before + amount_out
+    );
+
+    for token in [ice, wood] {
+        assert_eq!(
+            psp22_utils::balance_of(&mut session, token.into(), router.into()),
+            0,
+            "Router should not hold any tokens"
+        );
+    }
+}
+
 /// Tests a simple swap along [Pair -> StableSwap -> Pair] path
 /// using `swap_exact_tokens_for_tokens` and
 /// `swap_tokens_for_exact_tokens` methods
 #[drink::test]
-fn test_psp22_swap(mut session: Session) {
+fn test_zap_stable_pool(mut session: Session) {
     upload_all(&mut session);
 
     // seed test accounts with some native token
@@ -385,239 +730,1347 @@ fn test_psp22_swap(mut session: Session) {
     let now = get_timestamp(&mut session);
     set_timestamp(&mut session, now);
 
-    let (router, factory, _, _) = setup_router(&mut session);
+    let (router, factory, wnative, _) = setup_router(&mut session);
 
-    // setup stable pool
-    let initial_reserves = vec![U100K * ONE_USDT, U100K * ONE_USDC];
-    let initial_supply = initial_reserves
-        .iter()
-        .map(|amount| amount * U1M)
-        .collect::<Vec<u128>>();
+    let ice = psp22_utils::setup(&mut session, ICE.to_string(), BOB);
+    let wood = psp22_utils::setup(&mut session, WOOD.to_string(), BOB);
+    let sand = psp22_utils::setup(&mut session, "SAND".to_string(), BOB);
 
-    let (usdt_usdc_pool, tokens) = setup_stable_swap_with_tokens(
+    for token in [ice, wood, sand] {
+        psp22_utils::increase_allowance(&mut session, token.into(), router.into(), u128::MAX, BOB)
+            .expect("Should increase allowance");
+    }
+
+    let ice_wood_pool: AccountId = stable_swap::setup(
         &mut session,
-        vec![6, 6],
-        initial_supply.clone(),
+        vec![ice.into(), wood.into()],
+        vec![18, 18],
         A,
+        BOB,
         TRADE_FEE,
         PROTOCOL_FEE,
-        BOB,
-        vec![],
-    );
-
-    let (usdt, usdc) = (tokens[0], tokens[1]);
-
-    stable_swap::add_liquidity(
+        Some(fee_receiver()),
+    )
+    .into();
+    router_v2::add_stable_swap_liquidity(
         &mut session,
-        usdt_usdc_pool,
-        BOB,
+        router.into(),
+        ice_wood_pool,
         1,
-        initial_reserves.clone(),
+        vec![U100K * TOKEN, U100K * TOKEN],
         bob(),
+        0,
+        BOB,
     )
-    .expect("Should successfully add liquidity");
-
-    // setup pairs
-    let ice = psp22_utils::setup(&mut session, ICE.to_string(), BOB);
-    let wood = psp22_utils::setup(&mut session, WOOD.to_string(), BOB);
-    psp22_utils::increase_allowance(&mut session, ice.into(), router.into(), u128::MAX, BOB)
-        .expect("Should increase allowance");
-    psp22_utils::increase_allowance(&mut session, wood.into(), router.into(), u128::MAX, BOB)
-        .expect("Should increase allowance");
-    psp22_utils::increase_allowance(&mut session, usdt, router.into(), u128::MAX, BOB)
-        .expect("Should increase allowance");
-    psp22_utils::increase_allowance(&mut session, usdc, router.into(), u128::MAX, BOB)
-        .expect("Should increase allowance");
+    .expect("Should add liquidity");
 
-    let token_amount = U100K * TOKEN;
-    let stable_amount = U100K * ONE_USDC;
+    // SAND-ICE and wAZERO-WOOD pairs lead into the stable pool
+    router_v2::add_pair_liquidity(
+        &mut session,
+        router.into(),
+        None,
+        sand.into(),
+        ice.into(),
+        U100K * TOKEN,
+        U100K * TOKEN,
+        0,
+        0,
+        bob(),
+        BOB,
+    )
+    .expect("Should add liquidity");
+    router_v2::add_pair_liquidity_native(
+        &mut session,
+        router.into(),
+        None,
+        wood.into(),
+        U100K * TOKEN,
+        0,
+        0,
+        bob(),
+        1000 * ONE_AZERO,
+        BOB,
+    )
+    .expect("Should add liquidity");
+    let sand_ice_pair: AccountId =
+        factory::get_pair(&mut session, factory.into(), sand.into(), ice.into()).into();
+    let wnative_wood_pair: AccountId =
+        factory::get_pair(&mut session, factory.into(), wnative.into(), wood.into()).into();
+
+    let amount_in = 100 * TOKEN;
+    let sand_path = vec![Step {
+        token_in: sand.into(),
+        pool_id: sand_ice_pair,
+    }];
+
+    // the stable pool must hold the last token of the path
+    let res = router_v2::zap_in_stable_pool(
+        &mut session,
+        router.into(),
+        ice_wood_pool,
+        amount_in,
+        sand_path.clone(),
+        sand.into(),
+        1,
+        bob(),
+        0,
+        BOB,
+    );
+    assert_eq!(res, Err(RouterV2Error::InvalidToken()));
+
+    let res = router_v2::zap_in_stable_pool(
+        &mut session,
+        router.into(),
+        ice_wood_pool,
+        amount_in,
+        sand_path.clone(),
+        ice.into(),
+        u128::MAX,
+        bob(),
+        0,
+        BOB,
+    );
+    assert!(matches!(res, Err(RouterV2Error::StablePoolError(_))));
+
+    let lp_before = psp22_utils::balance_of(&mut session, ice_wood_pool, bob());
+    let sand_before = psp22_utils::balance_of(&mut session, sand.into(), bob());
+    let (shares, _) = router_v2::zap_in_stable_pool(
+        &mut session,
+        router.into(),
+        ice_wood_pool,
+        amount_in,
+        sand_path,
+        ice.into(),
+        1,
+        bob(),
+        0,
+        BOB,
+    )
+    .expect("Should zap in");
+    // the swap and the imbalanced deposit are charged with fees
+    assert!(shares < amount_in && shares > amount_in * 99 / 100);
+    assert_eq!(
+        psp22_utils::balance_of(&mut session, ice_wood_pool, bob()),
+        lp_before + shares
+    );
+    assert_eq!(
+        psp22_utils::balance_of(&mut session, sand.into(), bob()),
+        sand_before - amount_in
+    );
+
+    // native token is wrapped and the surplus is refunded
+    let native_path = vec![Step {
+        token_in: wnative.into(),
+        pool_id: wnative_wood_pair,
+    }];
+    let amount_in = ONE_AZERO;
+    let native_before = native_balance_of(&mut session, bob());
+    let lp_before = psp22_utils::balance_of(&mut session, ice_wood_pool, bob());
+    let (shares, _) = router_v2::zap_in_stable_pool(
+        &mut session,
+        router.into(),
+        ice_wood_pool,
+        amount_in,
+        native_path,
+        wood.into(),
+        1,
+        bob(),
+        2 * amount_in,
+        BOB,
+    )
+    .expect("Should zap in");
+    assert!(shares > 0);
+    assert_eq!(
+        psp22_utils::balance_of(&mut session, ice_wood_pool, bob()),
+        lp_before + shares
+    );
+    let native_spent = native_before - native_balance_of(&mut session, bob());
+    assert!(native_spent >= amount_in && native_spent < 2 * amount_in);
+
+    for token in [ice, wood, sand, wnative] {
+        assert_eq!(
+            psp22_utils::balance_of(&mut session, token.into(), router.into()),
+            0,
+            "Router should not hold any tokens"
+        );
+    }
+}
+
+#[drink::test]
+fn test_remove_liquidity_with_permit(mut session: Session) {
+    upload_all(&mut session);
+
+    // Fix timestamp. Otherwise underlying UNIX clock is used.
+    let now = get_timestamp(&mut session);
+    set_timestamp(&mut session, now);
+
+    let (router, factory, _, _) = setup_router(&mut session);
+
+    let ice = psp22_utils::setup(&mut session, ICE.to_string(), BOB);
+    let wood = psp22_utils::setup(&mut session, WOOD.to_string(), BOB);
+
+    for token in [ice, wood] {
+        psp22_utils::increase_allowance(&mut session, token.into(), router.into(), u128::MAX, BOB)
+            .expect("Should increase allowance");
+    }
+
+    // LP tokens are held by an ECDSA account which never calls `approve`
+    let signer = sp_core::ecdsa::Pair::from_seed(&[7u8; 32]);
+    let owner_account = permit::account(&signer);
+    let owner: AccountId = AsRef::<[u8; 32]>::as_ref(&owner_account).clone().into();
+    seed_account(&mut session, owner_account.clone());
+
+    let (_, _, liquidity) = router_v2::add_pair_liquidity(
+        &mut session,
+        router.into(),
+        None,
+        ice.into(),
+        wood.into(),
+        1000 * TOKEN,
+        1000 * TOKEN,
+        0,
+        0,
+        owner,
+        BOB,
+    )
+    .expect("Should add liquidity");
+    let pair: AccountId =
+        factory::get_pair(&mut session, factory.into(), ice.into(), wood.into()).into();
+
+    // a permit signed by another key is rejected
+    let other_signer = sp_core::ecdsa::Pair::from_seed(&[8u8; 32]);
+    let deadline = get_timestamp(&mut session) + 10;
+    let signature = permit::sign(
+        &other_signer,
+        GENESIS_HASH,
+        pair,
+        router.into(),
+        liquidity,
+        0,
+        deadline,
+    );
+    let _ = session.set_actor(owner_account.clone());
+    let res = session
+        .execute(
+            router_v2_contract::Instance::from(router).remove_pair_liquidity_with_permit(
+                pair,
+                ice.into(),
+                wood.into(),
+                liquidity,
+                0,
+                0,
+                owner,
+                deadline,
+                router_v2_contract::PermitSignature::Ecdsa(signature),
+            ),
+        )
+        .unwrap()
+        .result
+        .unwrap();
+    assert_eq!(
+        res,
+        Err(RouterV2Error::PermitError(PermitError::InvalidSignature()))
+    );
+
+    // a permit signed for another chain is rejected
+    let signature = permit::sign(
+        &signer,
+        [0u8; 32],
+        pair,
+        router.into(),
+        liquidity,
+        0,
+        deadline,
+    );
+    let res = session
+        .execute(
+            router_v2_contract::Instance::from(router).remove_pair_liquidity_with_permit(
+                pair,
+                ice.into(),
+                wood.into(),
+                liquidity,
+                0,
+                0,
+                owner,
+                deadline,
+                router_v2_contract::PermitSignature::Ecdsa(signature),
+            ),
+        )
+        .unwrap()
+        .result
+        .unwrap();
+    assert_eq!(
+        res,
+        Err(RouterV2Error::PermitError(PermitError::InvalidSignature()))
+    );
+
+    // sr25519 permits are not supported
+    let res = session
+        .execute(
+            router_v2_contract::Instance::from(router).remove_pair_liquidity_with_permit(
+                pair,
+                ice.into(),
+                wood.into(),
+                liquidity,
+                0,
+                0,
+                owner,
+                deadline,
+                router_v2_contract::PermitSignature::Sr25519([0u8; 64]),
+            ),
+        )
+        .unwrap()
+        .result
+        .unwrap();
+    assert_eq!(
+        res,
+        Err(RouterV2Error::PermitError(
+            PermitError::UnsupportedSignature()
+        ))
+    );
+
+    // a permit front-run directly on the pair does not make the removal fail
+    let signature = permit::sign(
+        &signer,
+        GENESIS_HASH,
+        pair,
+        router.into(),
+        liquidity / 2,
+        0,
+        deadline,
+    );
+    let _ = session.set_actor(BOB);
+    session
+        .execute(pair_contract::Instance::from(pair).permit(
+            owner,
+            router.into(),
+            liquidity / 2,
+            deadline,
+            pair_contract::PermitSignature::Ecdsa(signature),
+        ))
+        .unwrap()
+        .result
+        .unwrap()
+        .expect("Should use the permit");
+    let _ = session.set_actor(owner_account.clone());
+    let (amount_0, amount_1) = session
+        .execute(
+            router_v2_contract::Instance::from(router).remove_pair_liquidity_with_permit(
+                pair,
+                ice.into(),
+                wood.into(),
+                liquidity / 2,
+                1,
+                1,
+                owner,
+                deadline,
+                router_v2_contract::PermitSignature::Ecdsa(signature),
+            ),
+        )
+        .unwrap()
+        .result
+        .unwrap()
+        .expect("Should remove liquidity");
+    assert_eq!(
+        psp22_utils::balance_of(&mut session, pair, owner),
+        liquidity - liquidity / 2
+    );
+    assert_eq!(
+        psp22_utils::balance_of(&mut session, ice.into(), owner),
+        amount_0
+    );
+    assert_eq!(
+        psp22_utils::balance_of(&mut session, wood.into(), owner),
+        amount_1
+    );
+    // the permit is consumed
+    assert_eq!(permit::nonces(&mut session, pair, owner), 1);
+    assert_eq!(
+        psp22_utils::allowance(&mut session, pair, owner, router.into()),
+        0
+    );
+
+    let pool: AccountId = stable_swap::setup(
+        &mut session,
+        vec![ice.into(), wood.into()],
+        vec![18, 18],
+        A,
+        BOB,
+        TRADE_FEE,
+        PROTOCOL_FEE,
+        Some(fee_receiver()),
+    )
+    .into();
+    let (shares, _) = router_v2::add_stable_swap_liquidity(
+        &mut session,
+        router.into(),
+        pool,
+        1,
+        vec![1000 * TOKEN, 1000 * TOKEN],
+        owner,
+        0,
+        BOB,
+    )
+    .expect("Should add liquidity");
+
+    let amounts = router_v2::remove_stable_pool_liquidity_by_share_with_permit(
+        &mut session,
+        router.into(),
+        pool,
+        shares,
+        vec![1, 1],
+        owner,
+        &signer,
+    )
+    .expect("Should remove liquidity");
+    assert_eq!(psp22_utils::balance_of(&mut session, pool, owner), 0);
+    assert_eq!(
+        psp22_utils::balance_of(&mut session, ice.into(), owner),
+        amount_0 + amounts[0]
+    );
+    assert_eq!(permit::nonces(&mut session, pool, owner), 1);
+}
+
+#[drink::test]
+fn test_multicall(mut session: Session) {
+    upload_all(&mut session);
+
+    // Fix timestamp. Otherwise underlying UNIX clock is used.
+    let now = get_timestamp(&mut session);
+    set_timestamp(&mut session, now);
+
+    let (router, factory, _, _) = setup_router(&mut session);
+
+    let ice = psp22_utils::setup(&mut session, ICE.to_string(), BOB);
+    let wood = psp22_utils::setup(&mut session, WOOD.to_string(), BOB);
+    let sand = psp22_utils::setup(&mut session, "SAND".to_string(), BOB);
+
+    for token in [ice, wood, sand] {
+        psp22_utils::increase_allowance(&mut session, token.into(), router.into(), u128::MAX, BOB)
+            .expect("Should increase allowance");
+    }
+
+    let (_, _, liquidity) = router_v2::add_pair_liquidity(
+        &mut session,
+        router.into(),
+        None,
+        ice.into(),
+        wood.into(),
+        U100K * TOKEN,
+        U100K * TOKEN,
+        0,
+        0,
+        bob(),
+        BOB,
+    )
+    .expect("Should add liquidity");
+    let ice_wood_pair: AccountId =
+        factory::get_pair(&mut session, factory.into(), ice.into(), wood.into()).into();
+
+    let wood_sand_pool: AccountId = stable_swap::setup(
+        &mut session,
+        vec![wood.into(), sand.into()],
+        vec![18, 18],
+        A,
+        BOB,
+        TRADE_FEE,
+        PROTOCOL_FEE,
+        Some(fee_receiver()),
+    )
+    .into();
+    router_v2::add_stable_swap_liquidity(
+        &mut session,
+        router.into(),
+        wood_sand_pool,
+        1,
+        vec![U100K * TOKEN, U100K * TOKEN],
+        bob(),
+        0,
+        BOB,
+    )
+    .expect("Should add liquidity");
+
+    // swap ICE to WOOD and deposit all of it to the stable pool
+    let amount_in = 100 * TOKEN;
+    let ice_to_wood = vec![Step {
+        token_in: ice.into(),
+        pool_id: ice_wood_pair,
+    }];
+    let swap_and_deposit = vec![
+        RouterCall::Pull {
+            token: ice.into(),
+            amount: amount_in,
+        },
+        RouterCall::SwapExactTokensForTokens {
+            amount_in: CallAmount::RouterBalance,
+            amount_out_min: 0,
+            path: ice_to_wood.clone(),
+            token_out: wood.into(),
+            to: router.into(),
+        },
+        RouterCall::AddStablePoolLiquidity {
+            pool: wood_sand_pool,
+            min_share_amount: 1,
+            amounts: vec![CallAmount::RouterBalance, CallAmount::Exact(0)],
+            to: bob(),
+        },
+    ];
+
+    // the whole batch is reverted if any of the calls fails
+    let mut failing = swap_and_deposit.clone();
+    failing.push(RouterCall::Pull {
+        token: sand.into(),
+        amount: u128::MAX,
+    });
+    let ice_before = psp22_utils::balance_of(&mut session, ice.into(), bob());
+    assert!(router_v2::multicall(&mut session, router.into(), failing, BOB).is_err());
+    assert_eq!(
+        psp22_utils::balance_of(&mut session, ice.into(), bob()),
+        ice_before
+    );
+
+    let lp_before = psp22_utils::balance_of(&mut session, wood_sand_pool, bob());
+    let outputs = router_v2::multicall(&mut session, router.into(), swap_and_deposit, BOB)
+        .expect("Should execute calls");
+    assert_eq!(outputs.len(), 3);
+    assert_eq!(outputs[0], vec![amount_in]);
+    assert_eq!(outputs[1][0], amount_in);
+    // the whole swap output is deposited
+    let (shares, _) = stable_swap::get_mint_liquidity_for_amounts(
+        &mut session,
+        wood_sand_pool,
+        vec![outputs[1][1], 0],
+    )
+    .expect("Should compute shares");
+    assert!(outputs[2][0] > 0 && outputs[2][0] >= shares * 99 / 100);
+    assert_eq!(
+        psp22_utils::balance_of(&mut session, wood_sand_pool, bob()),
+        lp_before + outputs[2][0]
+    );
+    assert_eq!(
+        psp22_utils::balance_of(&mut session, ice.into(), bob()),
+        ice_before - amount_in
+    );
+
+    // remove the pair liquidity and swap all of the withdrawn ICE to WOOD
+    let wood_before = psp22_utils::balance_of(&mut session, wood.into(), bob());
+    psp22_utils::increase_allowance(&mut session, ice_wood_pair, router.into(), u128::MAX, BOB)
+        .expect("Should increase allowance");
+    let outputs = router_v2::multicall(
+        &mut session,
+        router.into(),
+        vec![
+            RouterCall::Pull {
+                token: ice_wood_pair,
+                amount: liquidity / 10,
+            },
+            RouterCall::RemovePairLiquidity {
+                pair: ice_wood_pair,
+                token_0: ice.into(),
+                token_1: wood.into(),
+                liquidity: CallAmount::RouterBalance,
+                amount_0_min: 1,
+                amount_1_min: 1,
+                to: router.into(),
+            },
+            RouterCall::SwapExactTokensForTokens {
+                amount_in: CallAmount::RouterBalance,
+                amount_out_min: 1,
+                path: ice_to_wood,
+                token_out: wood.into(),
+                to: bob(),
+            },
+            RouterCall::Sweep {
+                token: wood.into(),
+                to: bob(),
+            },
+        ],
+        BOB,
+    )
+    .expect("Should execute calls");
+    assert_eq!(outputs[2][0], outputs[1][0]);
+    assert_eq!(outputs[3], vec![outputs[1][1]]);
+    assert_eq!(
+        psp22_utils::balance_of(&mut session, wood.into(), bob()),
+        wood_before + outputs[1][1] + outputs[2][1]
+    );
+
+    for token in [ice.into(), wood.into(), sand.into(), ice_wood_pair] {
+        assert_eq!(
+            psp22_utils::balance_of(&mut session, token, router.into()),
+            0,
+            "Router should not hold any tokens"
+        );
+    }
+}
+
+#[drink::test]
+fn test_add_liquidity_and_stake(mut session: Session) {
+    upload_all(&mut session);
+
+    // Fix timestamp. Otherwise underlying UNIX clock is used.
+    let now = get_timestamp(&mut session);
+    set_timestamp(&mut session, now);
+
+    let (router, factory, _, _) = setup_router(&mut session);
+
+    let ice = psp22_utils::setup(&mut session, ICE.to_string(), BOB);
+    let wood = psp22_utils::setup(&mut session, WOOD.to_string(), BOB);
+    let sand = psp22_utils::setup(&mut session, "SAND".to_string(), BOB);
+
+    for token in [ice, wood, sand] {
+        psp22_utils::increase_allowance(&mut session, token.into(), router.into(), u128::MAX, BOB)
+            .expect("Should increase allowance");
+    }
+
+    router_v2::add_pair_liquidity(
+        &mut session,
+        router.into(),
+        None,
+        ice.into(),
+        wood.into(),
+        U100K * TOKEN,
+        U100K * TOKEN,
+        0,
+        0,
+        bob(),
+        BOB,
+    )
+    .expect("Should add liquidity");
+    let ice_wood_pair: AccountId =
+        factory::get_pair(&mut session, factory.into(), ice.into(), wood.into()).into();
+
+    let wood_sand_pool: AccountId = stable_swap::setup(
+        &mut session,
+        vec![wood.into(), sand.into()],
+        vec![18, 18],
+        A,
+        BOB,
+        TRADE_FEE,
+        PROTOCOL_FEE,
+        Some(fee_receiver()),
+    )
+    .into();
+
+    let pair_farm: AccountId = farm::setup(&mut session, ice_wood_pair, BOB).into();
+    let pool_farm: AccountId = farm::setup(&mut session, wood_sand_pool, BOB).into();
+
+    // the farm has to be created for the pool the liquidity is added to
+    let res = router_v2::add_pair_liquidity_and_stake(
+        &mut session,
+        router.into(),
+        ice_wood_pair,
+        ice.into(),
+        wood.into(),
+        TOKEN,
+        TOKEN,
+        0,
+        0,
+        pool_farm,
+        BOB,
+    );
+    assert_eq!(res, Err(RouterV2Error::InvalidFarm()));
+
+    let lp_before = psp22_utils::balance_of(&mut session, ice_wood_pair, bob());
+    let (_, _, liquidity) = router_v2::add_pair_liquidity_and_stake(
+        &mut session,
+        router.into(),
+        ice_wood_pair,
+        ice.into(),
+        wood.into(),
+        U100K * TOKEN,
+        U100K * TOKEN,
+        0,
+        0,
+        pair_farm,
+        BOB,
+    )
+    .expect("Should add liquidity and stake");
+    assert!(liquidity > 0);
+    assert_eq!(farm::shares_of(&mut session, pair_farm, bob()), liquidity);
+    assert_eq!(
+        psp22_utils::balance_of(&mut session, ice_wood_pair, bob()),
+        lp_before
+    );
+    assert_eq!(
+        psp22_utils::balance_of(&mut session, ice_wood_pair, pair_farm),
+        liquidity
+    );
+
+    let (shares, _) = router_v2::add_stable_pool_liquidity_and_stake(
+        &mut session,
+        router.into(),
+        wood_sand_pool,
+        1,
+        vec![U100K * TOKEN, U100K * TOKEN],
+        pool_farm,
+        BOB,
+    )
+    .expect("Should add liquidity and stake");
+    assert_eq!(farm::shares_of(&mut session, pool_farm, bob()), shares);
+    assert_eq!(
+        psp22_utils::balance_of(&mut session, wood_sand_pool, bob()),
+        0
+    );
+
+    // the router does not keep any of the LP tokens
+    for pool in [ice_wood_pair, wood_sand_pool] {
+        assert_eq!(
+            psp22_utils::balance_of(&mut session, pool, router.into()),
+            0
+        );
+    }
+}
+
+#[drink::test]
+fn test_psp22_swap(mut session: Session) {
+    upload_all(&mut session);
+
+    // seed test accounts with some native token
+    seed_account(&mut session, BOB);
+
+    // Fix timestamp. Otherwise underlying UNIX clock is used.
+    let now = get_timestamp(&mut session);
+    set_timestamp(&mut session, now);
+
+    let (router, factory, _, _) = setup_router(&mut session);
+
+    // setup stable pool
+    let initial_reserves = vec![U100K * ONE_USDT, U100K * ONE_USDC];
+    let initial_supply = initial_reserves
+        .iter()
+        .map(|amount| amount * U1M)
+        .collect::<Vec<u128>>();
+
+    let (usdt_usdc_pool, tokens) = setup_stable_swap_with_tokens(
+        &mut session,
+        vec![6, 6],
+        initial_supply.clone(),
+        A,
+        TRADE_FEE,
+        PROTOCOL_FEE,
+        BOB,
+        vec![],
+    );
+
+    let (usdt, usdc) = (tokens[0], tokens[1]);
+
+    stable_swap::add_liquidity(
+        &mut session,
+        usdt_usdc_pool,
+        BOB,
+        1,
+        initial_reserves.clone(),
+        bob(),
+    )
+    .expect("Should successfully add liquidity");
+
+    // setup pairs
+    let ice = psp22_utils::setup(&mut session, ICE.to_string(), BOB);
+    let wood = psp22_utils::setup(&mut session, WOOD.to_string(), BOB);
+    psp22_utils::increase_allowance(&mut session, ice.into(), router.into(), u128::MAX, BOB)
+        .expect("Should increase allowance");
+    psp22_utils::increase_allowance(&mut session, wood.into(), router.into(), u128::MAX, BOB)
+        .expect("Should increase allowance");
+    psp22_utils::increase_allowance(&mut session, usdt, router.into(), u128::MAX, BOB)
+        .expect("Should increase allowance");
+    psp22_utils::increase_allowance(&mut session, usdc, router.into(), u128::MAX, BOB)
+        .expect("Should increase allowance");
+
+    let token_amount = U100K * TOKEN;
+    let stable_amount = U100K * ONE_USDC;
+
+    router_v2::add_pair_liquidity(
+        &mut session,
+        router.into(),
+        None,
+        ice.into(),
+        usdc,
+        token_amount,
+        stable_amount,
+        token_amount,
+        stable_amount,
+        bob(),
+        BOB,
+    )
+    .expect("Should add liquidity");
+
+    let ice_usdc_pair: pair_contract::Instance =
+        factory::get_pair(&mut session, factory.into(), ice.into(), usdc);
+
+    router_v2::add_pair_liquidity(
+        &mut session,
+        router.into(),
+        None,
+        wood.into(),
+        usdt,
+        token_amount,
+        stable_amount,
+        token_amount,
+        stable_amount,
+        bob(),
+        BOB,
+    )
+    .expect("Should add liquidity");
+
+    let wood_usdt_pair: pair_contract::Instance =
+        factory::get_pair(&mut session, factory.into(), wood.into(), usdt);
+
+    // increase gas limit (swaps with more than 3 tokens require more gas)
+    let gas_limit = session.get_gas_limit();
+    session.set_gas_limit(Weight::from_parts(
+        10 * gas_limit.ref_time(),
+        10 * gas_limit.proof_size(),
+    ));
+
+    let swap_amount = 100 * TOKEN;
+
+    let first_step_output = v2_amounts::get_amount_out(
+        &mut session,
+        ice_usdc_pair.into(),
+        ice.into(),
+        usdc.into(),
+        swap_amount,
+    )
+    .unwrap();
+
+    let second_step_output = stable_swap::get_swap_amount_out(
+        &mut session,
+        usdt_usdc_pool,
+        usdc.into(),
+        usdt.into(),
+        first_step_output,
+    )
+    .unwrap();
+
+    let third_step_output = v2_amounts::get_amount_out(
+        &mut session,
+        wood_usdt_pair.into(),
+        usdt.into(),
+        wood.into(),
+        second_step_output.0,
+    )
+    .unwrap();
+
+    let init_bob_ice_balance = psp22_utils::balance_of(&mut session, ice.into(), bob());
+    let init_bob_wood_balance = psp22_utils::balance_of(&mut session, wood.into(), bob());
+
+    let amounts = router_v2::swap_exact_tokens_for_tokens(
+        &mut session,
+        router.into(),
+        swap_amount,
+        0,
+        vec![
+            Step {
+                token_in: ice.into(),
+                pool_id: ice_usdc_pair.into(),
+            },
+            Step {
+                token_in: usdc,
+                pool_id: usdt_usdc_pool.into(),
+            },
+            Step {
+                token_in: usdt,
+                pool_id: wood_usdt_pair.into(),
+            },
+        ],
+        wood.into(),
+        bob(),
+        BOB,
+    )
+    .expect("Should swap");
+
+    assert_eq!(amounts.len(), 4);
+    assert_eq!(amounts[0], swap_amount);
+    assert_eq!(amounts[1], first_step_output);
+    assert_eq!(amounts[2], second_step_output.0);
+    assert_eq!(amounts[3], third_step_output);
+
+    let bob_ice_balance = psp22_utils::balance_of(&mut session, ice.into(), bob());
+    let bob_wood_balance = psp22_utils::balance_of(&mut session, wood.into(), bob());
+
+    assert_eq!(init_bob_ice_balance - bob_ice_balance, swap_amount);
+    assert_eq!(bob_wood_balance - init_bob_wood_balance, third_step_output);
+
+    let init_bob_ice_balance = bob_ice_balance;
+    let init_bob_wood_balance = bob_wood_balance;
+
+    let third_step_input = v2_amounts::get_amount_in(
+        &mut session,
+        wood_usdt_pair.into(),
+        usdt.into(),
+        wood.into(),
+        swap_amount,
+    )
+    .unwrap();
+
+    let second_step_input = stable_swap::get_swap_amount_in(
+        &mut session,
+        usdt_usdc_pool,
+        usdc.into(),
+        usdt.into(),
+        third_step_input,
+    )
+    .unwrap();
+
+    let first_step_input = v2_amounts::get_amount_in(
+        &mut session,
+        ice_usdc_pair.into(),
+        ice.into(),
+        usdc.into(),
+        second_step_input.0,
+    )
+    .unwrap();
+
+    let amounts = router_v2::swap_tokens_for_exact_tokens(
+        &mut session,
+        router.into(),
+        swap_amount,
+        u128::MAX,
+        vec![
+            Step {
+                token_in: ice.into(),
+                pool_id: ice_usdc_pair.into(),
+            },
+            Step {
+                token_in: usdc,
+                pool_id: usdt_usdc_pool.into(),
+            },
+            Step {
+                token_in: usdt,
+                pool_id: wood_usdt_pair.into(),
+            },
+        ],
+        wood.into(),
+        bob(),
+        BOB,
+    )
+    .expect("Should swap");
+
+    assert_eq!(amounts.len(), 4);
+    assert_eq!(amounts[0], first_step_input);
+    assert_eq!(amounts[1], second_step_input.0);
+    assert_eq!(amounts[2], third_step_input);
+    assert_eq!(amounts[3], swap_amount);
+
+    let bob_ice_balance = psp22_utils::balance_of(&mut session, ice.into(), bob());
+    let bob_wood_balance = psp22_utils::balance_of(&mut session, wood.into(), bob());
+
+    assert_eq!(init_bob_ice_balance - bob_ice_balance, first_step_input);
+    assert_eq!(bob_wood_balance - init_bob_wood_balance, swap_amount);
+
+    for token in [usdt, usdc, ice.into(), wood.into()] {
+        assert_eq!(
+            psp22_utils::balance_of(&mut session, token, router.into()),
+            0,
+            "Router should not hold any tokens"
+        );
+    }
+}
+
+/// Tests that the path finder discovers a [Pair -> StableSwap -> Pair] path
+/// once the StableSwap is registered in the Router.
+#[drink::test]
+fn test_find_best_path(mut session: Session) {
+    upload_all(&mut session);
+
+    // Fix timestamp. Otherwise underlying UNIX clock is used.
+    let now = get_timestamp(&mut session);
+    set_timestamp(&mut session, now);
+
+    let (router, factory, _, _) = setup_router(&mut session);
+
+    // setup stable pool
+    let initial_reserves = vec![U100K * ONE_USDT, U100K * ONE_USDC];
+    let initial_supply = initial_reserves
+        .iter()
+        .map(|amount| amount * U1M)
+        .collect::<Vec<u128>>();
+
+    let (usdt_usdc_pool, tokens) = setup_stable_swap_with_tokens(
+        &mut session,
+        vec![6, 6],
+        initial_supply,
+        A,
+        TRADE_FEE,
+        PROTOCOL_FEE,
+        BOB,
+        vec![],
+    );
+
+    let (usdt, usdc) = (tokens[0], tokens[1]);
+
+    stable_swap::add_liquidity(
+        &mut session,
+        usdt_usdc_pool,
+        BOB,
+        1,
+        initial_reserves,
+        bob(),
+    )
+    .expect("Should successfully add liquidity");
+
+    // setup pairs
+    let ice = psp22_utils::setup(&mut session, ICE.to_string(), BOB);
+    let wood = psp22_utils::setup(&mut session, WOOD.to_string(), BOB);
+    for token in [ice.into(), wood.into(), usdt, usdc] {
+        psp22_utils::increase_allowance(&mut session, token, router.into(), u128::MAX, BOB)
+            .expect("Should increase allowance");
+    }
+
+    let token_amount = U100K * TOKEN;
+    let stable_amount = U100K * ONE_USDC;
+
+    for (token, stable) in [(ice.into(), usdc), (wood.into(), usdt)] {
+        router_v2::add_pair_liquidity(
+            &mut session,
+            router.into(),
+            None,
+            token,
+            stable,
+            token_amount,
+            stable_amount,
+            token_amount,
+            stable_amount,
+            bob(),
+            BOB,
+        )
+        .expect("Should add liquidity");
+    }
+
+    let ice_usdc_pair: pair_contract::Instance =
+        factory::get_pair(&mut session, factory.into(), ice.into(), usdc);
+    let wood_usdt_pair: pair_contract::Instance =
+        factory::get_pair(&mut session, factory.into(), wood.into(), usdt);
+
+    // increase gas limit (path finding queries many pools)
+    let gas_limit = session.get_gas_limit();
+    session.set_gas_limit(Weight::from_parts(
+        10 * gas_limit.ref_time(),
+        10 * gas_limit.proof_size(),
+    ));
+
+    let swap_amount = 100 * TOKEN;
+
+    // the stable pool is not registered
+    let res = router_v2::find_best_path(
+        &mut session,
+        router.into(),
+        ice.into(),
+        wood.into(),
+        swap_amount,
+        3,
+    );
+    assert_eq!(res, Err(RouterV2Error::PathNotFound()));
+
+    router_v2::add_stable_pool(&mut session, router.into(), usdt_usdc_pool, BOB)
+        .expect("Should register stable pool");
+
+    let expected_path = vec![
+        Step {
+            token_in: ice.into(),
+            pool_id: ice_usdc_pair.into(),
+        },
+        Step {
+            token_in: usdc,
+            pool_id: usdt_usdc_pool,
+        },
+        Step {
+            token_in: usdt,
+            pool_id: wood_usdt_pair.into(),
+        },
+    ];
+
+    let (path, amount_out) = router_v2::find_best_path(
+        &mut session,
+        router.into(),
+        ice.into(),
+        wood.into(),
+        swap_amount,
+        3,
+    )
+    .expect("Should find path");
+    assert_eq!(path, expected_path);
+
+    let amounts = router_v2::swap_exact_tokens_for_tokens(
+        &mut session,
+        router.into(),
+        swap_amount,
+        amount_out,
+        path,
+        wood.into(),
+        bob(),
+        BOB,
+    )
+    .expect("Should swap");
+    assert_eq!(amounts[3], amount_out);
+
+    // path requires three hops
+    let res = router_v2::find_best_path(
+        &mut session,
+        router.into(),
+        ice.into(),
+        wood.into(),
+        swap_amount,
+        2,
+    );
+    assert_eq!(res, Err(RouterV2Error::PathNotFound()));
+}
+
+/// Tests that pairs with different fee tiers coexist
+/// and the Router picks the cheaper one.
+#[drink::test]
+fn test_fee_tiers(mut session: Session) {
+    upload_all(&mut session);
+
+    // Fix timestamp. Otherwise underlying UNIX clock is used.
+    let now = get_timestamp(&mut session);
+    set_timestamp(&mut session, now);
+
+    let (router, factory, _, _) = setup_router(&mut session);
+    let ice = psp22_utils::setup(&mut session, ICE.to_string(), BOB);
+    let wood = psp22_utils::setup(&mut session, WOOD.to_string(), BOB);
+    for token in [ice, wood] {
+        psp22_utils::increase_allowance(&mut session, token.into(), router.into(), u128::MAX, BOB)
+            .expect("Should increase allowance");
+    }
+
+    let low_fee = 1;
+    assert_eq!(
+        factory::create_pair(
+            &mut session,
+            factory.into(),
+            ice.into(),
+            wood.into(),
+            low_fee,
+            BOB
+        ),
+        Err(factory_contract::FactoryError::FeeTierNotEnabled())
+    );
+    assert_eq!(
+        factory::enable_fee_tier(&mut session, factory.into(), 11, BOB),
+        Err(factory_contract::FactoryError::InvalidFee())
+    );
+    factory::enable_fee_tier(&mut session, factory.into(), low_fee, BOB)
+        .expect("Should enable fee tier");
+
+    let low_fee_pair = factory::create_pair(
+        &mut session,
+        factory.into(),
+        ice.into(),
+        wood.into(),
+        low_fee,
+        BOB,
+    )
+    .expect("Should create pair");
+    assert_eq!(
+        factory::create_pair(
+            &mut session,
+            factory.into(),
+            wood.into(),
+            ice.into(),
+            low_fee,
+            BOB
+        ),
+        Err(factory_contract::FactoryError::PairExists())
+    );
+    assert_eq!(
+        factory::get_pair_with_fee(
+            &mut session,
+            factory.into(),
+            wood.into(),
+            ice.into(),
+            low_fee
+        )
+        .into(),
+        low_fee_pair
+    );
+    assert_eq!(
+        session
+            .query(pair_contract::Instance::from(low_fee_pair).get_fee())
+            .unwrap()
+            .result
+            .unwrap(),
+        low_fee
+    );
+
+    let token_amount = U100K * TOKEN;
+    for pair in [None, Some(low_fee_pair)] {
+        router_v2::add_pair_liquidity(
+            &mut session,
+            router.into(),
+            pair,
+            ice.into(),
+            wood.into(),
+            token_amount,
+            token_amount,
+            token_amount,
+            token_amount,
+            bob(),
+            BOB,
+        )
+        .expect("Should add liquidity");
+    }
+    let default_fee_pair: AccountId =
+        factory::get_pair(&mut session, factory.into(), ice.into(), wood.into()).into();
+    assert!(default_fee_pair != low_fee_pair);
 
-    router_v2::add_pair_liquidity(
+    // of both pairs, the cheaper one is picked
+    let (path, _) = router_v2::find_best_path(
         &mut session,
         router.into(),
-        None,
         ice.into(),
-        usdc,
-        token_amount,
-        stable_amount,
-        token_amount,
-        stable_amount,
-        bob(),
-        BOB,
+        wood.into(),
+        100 * TOKEN,
+        1,
     )
-    .expect("Should add liquidity");
+    .expect("Should find path");
+    assert_eq!(
+        path,
+        vec![Step {
+            token_in: ice.into(),
+            pool_id: low_fee_pair,
+        }]
+    );
+}
 
-    let ice_usdc_pair: pair_contract::Instance =
-        factory::get_pair(&mut session, factory.into(), ice.into(), usdc);
+/// Tests a swap split between a direct [Pair] path
+/// and a [Pair -> Pair] path.
+#[drink::test]
+fn test_split_swap(mut session: Session) {
+    upload_all(&mut session);
 
-    router_v2::add_pair_liquidity(
-        &mut session,
-        router.into(),
-        None,
-        wood.into(),
-        usdt,
-        token_amount,
-        stable_amount,
-        token_amount,
-        stable_amount,
-        bob(),
-        BOB,
-    )
-    .expect("Should add liquidity");
+    // Fix timestamp. Otherwise underlying UNIX clock is used.
+    let now = get_timestamp(&mut session);
+    set_timestamp(&mut session, now);
 
-    let wood_usdt_pair: pair_contract::Instance =
-        factory::get_pair(&mut session, factory.into(), wood.into(), usdt);
+    let (router, factory, _, _) = setup_router(&mut session);
 
-    // increase gas limit (swaps with more than 3 tokens require more gas)
-    let gas_limit = session.get_gas_limit();
-    session.set_gas_limit(Weight::from_parts(
-        10 * gas_limit.ref_time(),
-        10 * gas_limit.proof_size(),
-    ));
+    let ice = psp22_utils::setup(&mut session, ICE.to_string(), BOB);
+    let wood = psp22_utils::setup(&mut session, WOOD.to_string(), BOB);
+    let sand = psp22_utils::setup(&mut session, "SAND".to_string(), BOB);
+    for token in [ice, wood, sand] {
+        psp22_utils::increase_allowance(&mut session, token.into(), router.into(), u128::MAX, BOB)
+            .expect("Should increase allowance");
+    }
 
-    let swap_amount = 100 * TOKEN;
+    let token_amount = U100K * TOKEN;
+    for (token_0, token_1) in [(ice, wood), (ice, sand), (sand, wood)] {
+        router_v2::add_pair_liquidity(
+            &mut session,
+            router.into(),
+            None,
+            token_0.into(),
+            token_1.into(),
+            token_amount,
+            token_amount,
+            token_amount,
+            token_amount,
+            bob(),
+            BOB,
+        )
+        .expect("Should add liquidity");
+    }
 
-    let first_step_output = v2_amounts::get_amount_out(
+    let ice_wood_pair = factory::get_pair(&mut session, factory.into(), ice.into(), wood.into());
+    let ice_sand_pair = factory::get_pair(&mut session, factory.into(), ice.into(), sand.into());
+    let sand_wood_pair = factory::get_pair(&mut session, factory.into(), sand.into(), wood.into());
+
+    let swap_amount = 1000 * TOKEN + 1;
+    // 3:1 split, the leftover goes to the last path
+    let direct_amount_in = swap_amount * 3 / 4;
+    let indirect_amount_in = swap_amount - direct_amount_in;
+
+    let direct_amount_out = v2_amounts::get_amount_out(
         &mut session,
-        ice_usdc_pair.into(),
+        ice_wood_pair.into(),
         ice.into(),
-        usdc.into(),
-        swap_amount,
+        wood.into(),
+        direct_amount_in,
     )
     .unwrap();
-
-    let second_step_output = stable_swap::get_swap_amount_out(
+    let sand_amount = v2_amounts::get_amount_out(
         &mut session,
-        usdt_usdc_pool,
-        usdc.into(),
-        usdt.into(),
-        first_step_output,
+        ice_sand_pair.into(),
+        ice.into(),
+        sand.into(),
+        indirect_amount_in,
     )
     .unwrap();
-
-    let third_step_output = v2_amounts::get_amount_out(
+    let indirect_amount_out = v2_amounts::get_amount_out(
         &mut session,
-        wood_usdt_pair.into(),
-        usdt.into(),
+        sand_wood_pair.into(),
+        sand.into(),
         wood.into(),
-        second_step_output.0,
+        sand_amount,
     )
     .unwrap();
+    let total_amount_out = direct_amount_out + indirect_amount_out;
 
-    let init_bob_ice_balance = psp22_utils::balance_of(&mut session, ice.into(), bob());
-    let init_bob_wood_balance = psp22_utils::balance_of(&mut session, wood.into(), bob());
+    let paths = || {
+        vec![
+            WeightedPath {
+                path: vec![Step {
+                    token_in: ice.into(),
+                    pool_id: ice_wood_pair.into(),
+                }],
+                weight: 3,
+            },
+            WeightedPath {
+                path: vec![
+                    Step {
+                        token_in: ice.into(),
+                        pool_id: ice_sand_pair.into(),
+                    },
+                    Step {
+                        token_in: sand.into(),
+                        pool_id: sand_wood_pair.into(),
+                    },
+                ],
+                weight: 1,
+            },
+        ]
+    };
 
-    let amounts = router_v2::swap_exact_tokens_for_tokens(
+    let res = router_v2::swap_exact_tokens_for_tokens_split(
         &mut session,
         router.into(),
         swap_amount,
-        0,
-        vec![
-            Step {
-                token_in: ice.into(),
-                pool_id: ice_usdc_pair.into(),
-            },
-            Step {
-                token_in: usdc,
-                pool_id: usdt_usdc_pool.into(),
-            },
-            Step {
-                token_in: usdt,
-                pool_id: wood_usdt_pair.into(),
-            },
-        ],
+        total_amount_out + 1,
+        paths(),
         wood.into(),
         bob(),
         BOB,
-    )
-    .expect("Should swap");
-
-    assert_eq!(amounts.len(), 4);
-    assert_eq!(amounts[0], swap_amount);
-    assert_eq!(amounts[1], first_step_output);
-    assert_eq!(amounts[2], second_step_output.0);
-    assert_eq!(amounts[3], third_step_output);
-
-    let bob_ice_balance = psp22_utils::balance_of(&mut session, ice.into(), bob());
-    let bob_wood_balance = psp22_utils::balance_of(&mut session, wood.into(), bob());
-
-    assert_eq!(init_bob_ice_balance - bob_ice_balance, swap_amount);
-    assert_eq!(bob_wood_balance - init_bob_wood_balance, third_step_output);
-
-    let init_bob_ice_balance = bob_ice_balance;
-    let init_bob_wood_balance = bob_wood_balance;
-
-    let third_step_input = v2_amounts::get_amount_in(
-        &mut session,
-        wood_usdt_pair.into(),
-        usdt.into(),
-        wood.into(),
-        swap_amount,
-    )
-    .unwrap();
-
-    let second_step_input = stable_swap::get_swap_amount_in(
-        &mut session,
-        usdt_usdc_pool,
-        usdc.into(),
-        usdt.into(),
-        third_step_input,
-    )
-    .unwrap();
+    );
+    assert_eq!(res, Err(RouterV2Error::InsufficientOutputAmount()));
 
-    let first_step_input = v2_amounts::get_amount_in(
-        &mut session,
-        ice_usdc_pair.into(),
-        ice.into(),
-        usdc.into(),
-        second_step_input.0,
-    )
-    .unwrap();
+    let init_bob_ice_balance = psp22_utils::balance_of(&mut session, ice.into(), bob());
+    let init_bob_wood_balance = psp22_utils::balance_of(&mut session, wood.into(), bob());
 
-    let amounts = router_v2::swap_tokens_for_exact_tokens(
+    let amounts = router_v2::swap_exact_tokens_for_tokens_split(
         &mut session,
         router.into(),
         swap_amount,
-        u128::MAX,
-        vec![
-            Step {
-                token_in: ice.into(),
-                pool_id: ice_usdc_pair.into(),
-            },
-            Step {
-                token_in: usdc,
-                pool_id: usdt_usdc_pool.into(),
-            },
-            Step {
-                token_in: usdt,
-                pool_id: wood_usdt_pair.into(),
-            },
-        ],
+        total_amount_out,
+        paths(),
         wood.into(),
         bob(),
         BOB,
     )
     .expect("Should swap");
 
-    assert_eq!(amounts.len(), 4);
-    assert_eq!(amounts[0], first_step_input);
-    assert_eq!(amounts[1], second_step_input.0);
-    assert_eq!(amounts[2], third_step_input);
-    assert_eq!(amounts[3], swap_amount);
+    assert_eq!(
+        amounts,
+        vec![
+            vec![direct_amount_in, direct_amount_out],
+            vec![indirect_amount_in, sand_amount, indirect_amount_out],
+        ]
+    );
 
     let bob_ice_balance = psp22_utils::balance_of(&mut session, ice.into(), bob());
     let bob_wood_balance = psp22_utils::balance_of(&mut session, wood.into(), bob());
-
-    assert_eq!(init_bob_ice_balance - bob_ice_balance, first_step_input);
-    assert_eq!(bob_wood_balance - init_bob_wood_balance, swap_amount);
-
-    for token in [usdt, usdc, ice.into(), wood.into()] {
-        assert_eq!(
-            psp22_utils::balance_of(&mut session, token, router.into()),
-            0,
-            "Router should not hold any tokens"
-        );
-    }
+    assert_eq!(init_bob_ice_balance - bob_ice_balance, swap_amount);
+    assert_eq!(bob_wood_balance - init_bob_wood_balance, total_amount_out);
 }
 
 /// Tests a simple swap along [Pair_native -> StableSwap -> Pair] path
@@ -1054,6 +2507,22 @@ fn test_stable_pool_liqudity(mut session: Session) {
     )
     .expect("Should successfully add liquidity");
 
+    let (min_amount, _) =
+        stable_swap::get_amount_for_one_coin_burn(&mut session, usdt_usdc_pool, share_amount, usdc)
+            .expect("Should estimate amount");
+
+    router_v2::remove_stable_pool_liquidity_one_coin(
+        &mut session,
+        router.into(),
+        usdt_usdc_pool,
+        share_amount,
+        usdc,
+        min_amount,
+        bob(),
+        BOB,
+    )
+    .expect("Should successfully remove liquidity");
+
     for token in [usdt, usdc] {
         assert_eq!(
             psp22_utils::balance_of(&mut session, token, router.into()),
@@ -1165,6 +2634,32 @@ fn test_stable_pool_liqudity_native(mut session: Session) {
     )
     .expect("Should successfully add liquidity");
 
+    let (min_amount, _) = stable_swap::get_amount_for_one_coin_burn(
+        &mut session,
+        wazero_sazero_pool,
+        share_amount,
+        wnative.into(),
+    )
+    .expect("Should estimate amount");
+    let native_balance = native_balance_of(&mut session, bob());
+
+    router_v2::remove_stable_pool_liquidity_one_coin(
+        &mut session,
+        router.into(),
+        wazero_sazero_pool,
+        share_amount,
+        wnative.into(),
+        min_amount,
+        bob(),
+        BOB,
+    )
+    .expect("Should successfully remove liquidity");
+
+    assert!(
+        native_balance_of(&mut session, bob()) > native_balance,
+        "Should withdraw native token"
+    );
+
     for token in [wnative.into(), sazero] {
         assert_eq!(
             psp22_utils::balance_of(&mut session, token, router.into()),
@@ -1179,3 +2674,148 @@ fn test_stable_pool_liqudity_native(mut session: Session) {
         "Router native balance should not change"
     );
 }
+
+/// Tests that the stateless quotes over caller-supplied parameters
+/// match the pair math and the stable pool quotes.
+#[drink::test]
+fn test_stateless_quotes(mut session: Session) {
+    upload_all(&mut session);
+
+    let (router, _, _, _) = setup_router(&mut session);
+    let router: AccountId = router.into();
+
+    assert_eq!(
+        router_v2::quote(&mut session, router, 1000, 1_000_000, 2_000_000),
+        Ok(2000)
+    );
+    assert_eq!(
+        router_v2::get_amount_out(&mut session, router, 1000, 1_000_000, 1_000_000, 3),
+        Ok(996)
+    );
+    assert_eq!(
+        router_v2::get_amount_in(&mut session, router, 996, 1_000_000, 1_000_000, 3),
+        Ok(1000)
+    );
+    assert_eq!(
+        router_v2::get_amount_out(&mut session, router, 1000, 0, 1_000_000, 3),
+        Err(RouterV2Error::InsufficientLiquidity())
+    );
+    assert_eq!(
+        router_v2::get_amount_out(&mut session, router, 1000, 1_000_000, 1_000_000, 11),
+        Err(RouterV2Error::PairError(PairError::InvalidFee()))
+    );
+
+    let initial_supply = vec![U100K * ONE_USDT * U1M, U100K * ONE_USDC * U1M];
+    let (usdt_usdc_pool, tokens) = setup_stable_swap_with_tokens(
+        &mut session,
+        vec![6, 6],
+        initial_supply,
+        A,
+        TRADE_FEE,
+        PROTOCOL_FEE,
+        BOB,
+        vec![],
+    );
+    stable_swap::add_liquidity(
+        &mut session,
+        usdt_usdc_pool,
+        BOB,
+        1,
+        vec![U100K * ONE_USDT, U100K * ONE_USDC / 2],
+        bob(),
+    )
+    .expect("Should successfully add liquidity");
+
+    let reserves = stable_swap::reserves(&mut session, usdt_usdc_pool);
+    let amp_coef = stable_swap::amp_coef(&mut session, usdt_usdc_pool).unwrap();
+    // 6 decimal tokens with the rate 1
+    let scaled_rates = vec![RATE_PRECISION * 10u128.pow(12); 2];
+    let amount = 1000 * ONE_USDT;
+
+    assert_eq!(
+        router_v2::get_stable_amount_out(
+            &mut session,
+            router,
+            amount,
+            0,
+            1,
+            reserves.clone(),
+            scaled_rates.clone(),
+            amp_coef,
+            TRADE_FEE,
+        ),
+        Ok(stable_swap::get_swap_amount_out(
+            &mut session,
+            usdt_usdc_pool,
+            tokens[0],
+            tokens[1],
+            amount
+        )
+        .unwrap())
+    );
+    assert_eq!(
+        router_v2::get_stable_amount_in(
+            &mut session,
+            router,
+            amount,
+            1,
+            0,
+            reserves.clone(),
+            scaled_rates.clone(),
+            amp_coef,
+            TRADE_FEE,
+        ),
+        Ok(stable_swap::get_swap_amount_in(
+            &mut session,
+            usdt_usdc_pool,
+            tokens[1],
+            tokens[0],
+            amount
+        )
+        .unwrap())
+    );
+    assert_eq!(
+        router_v2::get_stable_amount_out(
+            &mut session,
+            router,
+            amount,
+            0,
+            1,
+            vec![reserves[0], 0],
+            scaled_rates.clone(),
+            amp_coef,
+            TRADE_FEE,
+        ),
+        Err(RouterV2Error::StablePoolError(
+            router_v2_contract::StablePoolError::InsufficientLiquidity()
+        ))
+    );
+    assert_eq!(
+        router_v2::get_stable_amount_out(
+            &mut session,
+            router,
+            amount,
+            0,
+            1,
+            reserves.clone(),
+            vec![scaled_rates[0], 0],
+            amp_coef,
+            TRADE_FEE,
+        ),
+        Err(RouterV2Error::InvalidRate())
+    );
+    assert_eq!(
+        router_v2::get_stable_amount_out(
+            &mut session,
+            router,
+            amount,
+            0,
+            2,
+            reserves,
+            scaled_rates,
+            amp_coef,
+            TRADE_FEE,
+        ),
+        Err(RouterV2Error::InvalidToken())
+    );
+}
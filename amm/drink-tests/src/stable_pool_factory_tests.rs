@@ -0,0 +1,225 @@
+use crate::stable_swap_tests::*;
+use crate::{router_v2_contract, stable_pool_factory_contract};
+
+use drink::{self, runtime::MinimalRuntime, session::Session};
+use ink_primitives::AccountId;
+use router_v2_contract::RouterV2Error;
+use stable_pool_factory_contract::{Ownable2StepError, StablePoolFactoryError};
+
+const A: u128 = 10_000;
+const TRADE_FEE: u32 = 2_500_000;
+const PROTOCOL_FEE: u32 = 200_000_000;
+
+const U100K: u128 = 100_000;
+
+/// Instantiates `count` tokens with 6 decimals and returns them sorted in ascending order.
+fn setup_sorted_tokens(
+    session: &mut Session<MinimalRuntime>,
+    name: &str,
+    count: usize,
+) -> Vec<AccountId> {
+    let mut tokens: Vec<AccountId> = (0..count)
+        .map(|id| {
+            psp22_utils::setup_with_amounts(
+                session,
+                format!("{name} {id}"),
+                6,
+                U100K * U100K * ONE_USDT,
+                BOB,
+            )
+            .into()
+        })
+        .collect();
+    tokens.sort();
+    tokens
+}
+
+#[drink::test]
+fn test_create_pool(mut session: Session) {
+    upload_all(&mut session);
+
+    let factory = stable_pool_factory::setup(&mut session, bob());
+    let tokens = setup_sorted_tokens(&mut session, "USD", 2);
+
+    assert_eq!(
+        stable_pool_factory::get_pool(&mut session, factory.into(), tokens.clone()),
+        None
+    );
+
+    let pool = stable_pool_factory::create_pool(
+        &mut session,
+        factory.into(),
+        tokens.clone(),
+        vec![6, 6],
+        A,
+        TRADE_FEE,
+        PROTOCOL_FEE,
+        BOB,
+    )
+    .expect("Should create pool");
+
+    assert_eq!(
+        stable_pool_factory::get_pool(&mut session, factory.into(), tokens.clone()),
+        Some(pool)
+    );
+    assert!(stable_pool_factory::is_pool(
+        &mut session,
+        factory.into(),
+        pool
+    ));
+    assert_eq!(
+        stable_pool_factory::all_pools_length(&mut session, factory.into()),
+        1
+    );
+    assert_eq!(stable_swap::tokens(&mut session, pool), tokens);
+    assert_eq!(
+        stable_swap::fees(&mut session, pool),
+        (TRADE_FEE, PROTOCOL_FEE)
+    );
+
+    // only one pool per token set
+    assert_eq!(
+        stable_pool_factory::create_pool(
+            &mut session,
+            factory.into(),
+            tokens.clone(),
+            vec![6, 6],
+            A,
+            TRADE_FEE,
+            PROTOCOL_FEE,
+            BOB,
+        ),
+        Err(StablePoolFactoryError::PoolExists())
+    );
+
+    // tokens have to be sorted
+    assert_eq!(
+        stable_pool_factory::create_pool(
+            &mut session,
+            factory.into(),
+            vec![tokens[1], tokens[0]],
+            vec![6, 6],
+            A,
+            TRADE_FEE,
+            PROTOCOL_FEE,
+            BOB,
+        ),
+        Err(StablePoolFactoryError::UnsortedTokens())
+    );
+
+    // only the owner can create pools
+    let other_tokens = setup_sorted_tokens(&mut session, "EUR", 2);
+    assert_eq!(
+        stable_pool_factory::create_pool(
+            &mut session,
+            factory.into(),
+            other_tokens,
+            vec![6, 6],
+            A,
+            TRADE_FEE,
+            PROTOCOL_FEE,
+            CHARLIE,
+        ),
+        Err(StablePoolFactoryError::Ownable2StepError(
+            Ownable2StepError::CallerNotOwner(charlie())
+        ))
+    );
+}
+
+/// Tests that the Router accepts only stable pools created via the factory
+/// (or added by the Router owner) once the factory is set.
+#[drink::test]
+fn test_router_validates_stable_pools(mut session: Session) {
+    upload_all(&mut session);
+
+    let now = get_timestamp(&mut session);
+    set_timestamp(&mut session, now);
+
+    let factory = factory::setup(&mut session, bob());
+    let wazero = wazero::setup(&mut session);
+    let router = router_v2::setup(&mut session, factory.into(), wazero.into());
+    let stable_factory = stable_pool_factory::setup(&mut session, bob());
+
+    assert_eq!(
+        router_v2::set_stable_pool_factory(
+            &mut session,
+            router.into(),
+            Some(stable_factory.into()),
+            CHARLIE,
+        ),
+        Err(RouterV2Error::Ownable2StepError(
+            router_v2_contract::Ownable2StepError::CallerNotOwner(charlie())
+        ))
+    );
+    router_v2::set_stable_pool_factory(
+        &mut session,
+        router.into(),
+        Some(stable_factory.into()),
+        BOB,
+    )
+    .expect("Should set the stable pool factory");
+
+    let amounts = vec![U100K * ONE_USDT, U100K * ONE_USDT];
+
+    // pool deployed outside of the factory
+    let (foreign_pool, foreign_tokens) = setup_stable_swap_with_tokens(
+        &mut session,
+        vec![6, 6],
+        vec![U100K * U100K * ONE_USDT; 2],
+        A,
+        TRADE_FEE,
+        PROTOCOL_FEE,
+        BOB,
+        vec![],
+    );
+    for token in foreign_tokens {
+        psp22_utils::increase_allowance(&mut session, token, router.into(), u128::MAX, BOB)
+            .expect("Should increase allowance");
+    }
+    assert_eq!(
+        router_v2::add_stable_swap_liquidity(
+            &mut session,
+            router.into(),
+            foreign_pool,
+            1,
+            amounts.clone(),
+            bob(),
+            0,
+            BOB,
+        ),
+        Err(RouterV2Error::InvalidPoolAddress())
+    );
+
+    // pool created via the factory
+    let tokens = setup_sorted_tokens(&mut session, "USD", 2);
+    let pool = stable_pool_factory::create_pool(
+        &mut session,
+        stable_factory.into(),
+        tokens.clone(),
+        vec![6, 6],
+        A,
+        TRADE_FEE,
+        PROTOCOL_FEE,
+        BOB,
+    )
+    .expect("Should create pool");
+    for token in tokens {
+        psp22_utils::increase_allowance(&mut session, token, router.into(), u128::MAX, BOB)
+            .expect("Should increase allowance");
+    }
+    router_v2::add_stable_swap_liquidity(
+        &mut session,
+        router.into(),
+        pool,
+        1,
+        amounts,
+        bob(),
+        0,
+        BOB,
+    )
+    .expect("Should add liquidity to the factory pool");
+    assert!(
+        router_v2::get_cached_pool(&mut session, router.into(), pool).is_some(),
+        "Factory pool should be cached"
+    );
+}
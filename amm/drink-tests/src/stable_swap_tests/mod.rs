@@ -1,5 +1,7 @@
 mod tests_add_remove_lp;
+mod tests_flash_loan;
 mod tests_getters;
+mod tests_oracle;
 mod tests_rated;
 mod tests_swap_exact_in_received;
 mod tests_swap_exact_out;
@@ -71,6 +73,7 @@ pub fn setup_stable_swap_with_tokens(
         trade_fee,
         protocol_trade_fee,
         Some(fee_receiver()),
+        GENESIS_HASH.into(),
     )
     .with_salt(salt);
 
@@ -1369,3 +1369,98 @@ fn test_for_zero_withdrawal(mut session: Session) {
     )
     .expect("Should burn liquidity");
 }
+
+/// Tests withdrawing liquidity in a single token.
+#[drink::test]
+fn test_06(mut session: Session) {
+    let initial_reserves = vec![100000 * ONE_DAI, 100000 * ONE_USDT, 100000 * ONE_USDC];
+    let initial_supply = initial_reserves
+        .iter()
+        .map(|amount| amount * 10)
+        .collect::<Vec<u128>>();
+    let (stable_swap, tokens) = setup_stable_swap_with_tokens(
+        &mut session,
+        vec![18, 6, 6],
+        initial_supply.clone(),
+        10_000,
+        2_500_000,
+        200_000_000,
+        BOB,
+        vec![],
+    );
+
+    _ = stable_swap::add_liquidity(
+        &mut session,
+        stable_swap,
+        BOB,
+        1,
+        initial_reserves.clone(),
+        bob(),
+    )
+    .expect("Should successfully add liquidity");
+
+    let shares = 3000 * ONE_LPT; // 1%
+    let (amount, fee) =
+        stable_swap::get_amount_for_one_coin_burn(&mut session, stable_swap, shares, tokens[1])
+            .expect("Should estimate amount");
+    // balanced pool, so the amount is close to the value of the shares
+    assert!(
+        amount < 3000 * ONE_USDT && amount > 2990 * ONE_USDT,
+        "Incorrect amount {amount}"
+    );
+    assert!(fee > 0, "Fee should be charged");
+
+    let err = stable_swap::remove_liquidity_one_coin(
+        &mut session,
+        stable_swap,
+        BOB,
+        shares,
+        tokens[1],
+        amount + 1,
+        bob(),
+    )
+    .expect_err("Should return an error");
+    assert_eq!(
+        err,
+        StablePoolError::InsufficientOutputAmount(),
+        "Should return appropriate error"
+    );
+
+    assert_eq!(
+        stable_swap::remove_liquidity_one_coin(
+            &mut session,
+            stable_swap,
+            BOB,
+            shares,
+            tokens[1],
+            amount,
+            charlie(),
+        ),
+        Ok((amount, fee)),
+        "Should withdraw the estimated amount"
+    );
+
+    assert_eq!(
+        psp22_utils::balance_of(&mut session, tokens[1], charlie()),
+        amount,
+        "Incorrect Users tokens balance"
+    );
+    assert_eq!(
+        psp22_utils::balance_of(&mut session, stable_swap, bob()),
+        297000 * ONE_LPT,
+        "Incorrect Users LP token balance"
+    );
+    assert_eq!(
+        stable_swap::reserves(&mut session, stable_swap),
+        vec![
+            initial_reserves[0],
+            initial_reserves[1] - amount,
+            initial_reserves[2]
+        ],
+        "Incorrect reserves"
+    );
+    assert!(
+        psp22_utils::balance_of(&mut session, stable_swap, fee_receiver()) > 0,
+        "Protocol fee should be minted"
+    );
+}
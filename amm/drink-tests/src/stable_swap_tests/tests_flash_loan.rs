@@ -0,0 +1,128 @@
+use super::*;
+
+const TRADE_FEE: u32 = 2_500_000;
+const PROTOCOL_FEE: u32 = 200_000_000;
+
+#[drink::test]
+fn test_flash_loan(mut session: Session) {
+    let initial_reserves = vec![100000 * ONE_USDT, 100000 * ONE_USDC];
+    let initial_supply: Vec<u128> = initial_reserves.iter().map(|amount| amount * 10).collect();
+    let (stable_swap, tokens) = setup_stable_swap_with_tokens(
+        &mut session,
+        vec![6, 6],
+        initial_supply,
+        10_000,
+        TRADE_FEE,
+        PROTOCOL_FEE,
+        BOB,
+        vec![],
+    );
+    _ = stable_swap::add_liquidity(
+        &mut session,
+        stable_swap,
+        BOB,
+        1,
+        initial_reserves.clone(),
+        bob(),
+    )
+    .expect("Should successfully add liquidity");
+
+    // the receiver needs some tokens to pay the fees
+    let receiver: AccountId = mock_flash_loan_receiver::setup(&mut session, true).into();
+    for &token in tokens.iter() {
+        psp22_utils::transfer(&mut session, token, receiver, 100 * ONE_USDT, BOB)
+            .expect("Should transfer tokens");
+    }
+
+    let amounts = vec![10000 * ONE_USDT, 5000 * ONE_USDC];
+    let expected_fees: Vec<u128> = amounts
+        .iter()
+        .map(|amount| amount * TRADE_FEE as u128 / FEE_DENOM)
+        .collect();
+    assert_eq!(
+        stable_swap::flash_loan(
+            &mut session,
+            stable_swap,
+            BOB,
+            tokens.clone(),
+            amounts.clone(),
+            receiver,
+        ),
+        Ok(expected_fees.clone()),
+        "Should charge the trade fee"
+    );
+
+    // fees are added to the reserves
+    assert_eq!(
+        stable_swap::reserves(&mut session, stable_swap),
+        vec![
+            initial_reserves[0] + expected_fees[0],
+            initial_reserves[1] + expected_fees[1]
+        ],
+        "Incorrect reserves"
+    );
+    for (&token, &fee) in tokens.iter().zip(expected_fees.iter()) {
+        assert_eq!(
+            psp22_utils::balance_of(&mut session, token, receiver),
+            100 * ONE_USDT - fee,
+            "Incorrect receiver balance"
+        );
+    }
+    assert!(
+        psp22_utils::balance_of(&mut session, stable_swap, fee_receiver()) > 0,
+        "Protocol fee should be minted"
+    );
+
+    // the loan has to be repaid
+    let reserves = stable_swap::reserves(&mut session, stable_swap);
+    let defaulting_receiver: AccountId =
+        mock_flash_loan_receiver::setup(&mut session, false).into();
+    assert_eq!(
+        stable_swap::flash_loan(
+            &mut session,
+            stable_swap,
+            BOB,
+            vec![tokens[0]],
+            vec![ONE_USDT],
+            defaulting_receiver,
+        ),
+        Err(StablePoolError::InsufficientFlashLoanRepayment()),
+        "Should fail if the loan is not repaid"
+    );
+    assert_eq!(
+        psp22_utils::balance_of(&mut session, tokens[0], defaulting_receiver),
+        0,
+        "Loan should be reverted"
+    );
+
+    // cannot borrow more than the reserves
+    assert_eq!(
+        stable_swap::flash_loan(
+            &mut session,
+            stable_swap,
+            BOB,
+            vec![tokens[0]],
+            vec![reserves[0] + 1],
+            receiver,
+        ),
+        Err(StablePoolError::InsufficientLiquidity()),
+        "Should not lend more than the reserves"
+    );
+    assert_eq!(
+        stable_swap::flash_loan(
+            &mut session,
+            stable_swap,
+            BOB,
+            vec![tokens[0], tokens[0]],
+            vec![ONE_USDT, ONE_USDT],
+            receiver,
+        ),
+        Err(StablePoolError::IdenticalTokenId()),
+        "Should not accept duplicated tokens"
+    );
+    assert_eq!(
+        stable_swap::reserves(&mut session, stable_swap),
+        reserves,
+        "Reserves should not change"
+    );
+}
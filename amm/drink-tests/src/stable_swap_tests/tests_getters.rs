@@ -133,3 +133,65 @@ fn test_01(mut session: Session) {
         "Incorrect LP token supply"
     );
 }
+
+#[drink::test]
+fn test_virtual_price(mut session: Session) {
+    let initial_reserves = vec![100000 * ONE_DAI, 100000 * ONE_USDT];
+    let initial_supply: Vec<u128> = initial_reserves.iter().map(|amount| amount * 10).collect();
+    let (stable_swap, tokens) = setup_stable_swap_with_tokens(
+        &mut session,
+        vec![18, 6],
+        initial_supply,
+        10_000,
+        2_500_000,
+        200_000_000,
+        BOB,
+        vec![],
+    );
+
+    assert_eq!(
+        stable_swap::virtual_price(&mut session, stable_swap),
+        Err(StablePoolError::InsufficientLiquidity()),
+        "Virtual price of an empty pool should be undefined"
+    );
+    assert_eq!(stable_swap::invariant_d(&mut session, stable_swap), Ok(0));
+
+    _ = stable_swap::add_liquidity(&mut session, stable_swap, BOB, 1, initial_reserves, bob())
+        .expect("Should successfully add liquidity");
+
+    // balanced pool, D equals the sum of the rated reserves
+    assert_eq!(
+        stable_swap::invariant_d(&mut session, stable_swap),
+        Ok(200000 * ONE_LPT),
+        "Incorrect D"
+    );
+    assert_eq!(
+        stable_swap::virtual_price(&mut session, stable_swap),
+        Ok(ONE_LPT),
+        "Incorrect virtual price"
+    );
+
+    _ = stable_swap::swap_exact_in(
+        &mut session,
+        stable_swap,
+        BOB,
+        tokens[1],
+        tokens[0],
+        10000 * ONE_USDT,
+        0,
+        bob(),
+    )
+    .expect("Should successfully swap");
+
+    // trade fees accrue to the LP token holders
+    let d = stable_swap::invariant_d(&mut session, stable_swap).expect("Should compute D");
+    let total_supply = psp22_utils::total_supply(&mut session, stable_swap);
+    let virtual_price = stable_swap::virtual_price(&mut session, stable_swap)
+        .expect("Should compute virtual price");
+    assert!(virtual_price > ONE_LPT, "Virtual price should increase");
+    assert_eq!(
+        U256::from(d) * U256::from(ONE_LPT) / U256::from(total_supply),
+        U256::from(virtual_price),
+        "Incorrect virtual price"
+    );
+}
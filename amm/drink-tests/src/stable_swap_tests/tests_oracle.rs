@@ -0,0 +1,109 @@
+use super::*;
+
+const PRICE_PRECISION: u128 = 10u128.pow(18);
+/// Observation period of the pool oracle, in milisec.
+const OBSERVATION_PERIOD: u64 = 300_000;
+const TEN_MINUTES: u64 = 600_000;
+
+#[drink::test]
+fn test_twap(mut session: Session) {
+    let initial_reserves = vec![100000 * ONE_DAI, 100000 * ONE_USDT];
+    let initial_supply: Vec<u128> = initial_reserves.iter().map(|amount| amount * 10).collect();
+    let (stable_swap, tokens) = setup_stable_swap_with_tokens(
+        &mut session,
+        vec![18, 6],
+        initial_supply,
+        10_000,
+        2_500_000,
+        200_000_000,
+        BOB,
+        vec![],
+    );
+    let (dai, usdt) = (tokens[0], tokens[1]);
+
+    let start = get_timestamp(&mut session) + TEN_MINUTES;
+    set_timestamp(&mut session, start);
+
+    // no observations before the liquidity is added
+    assert_eq!(
+        stable_swap::get_twap_price(&mut session, stable_swap, usdt, dai, OBSERVATION_PERIOD),
+        Err(StablePoolError::InsufficientObservations())
+    );
+
+    _ = stable_swap::add_liquidity(&mut session, stable_swap, BOB, 1, initial_reserves, bob())
+        .expect("Should successfully add liquidity");
+
+    // the only observation is not older than the window
+    assert_eq!(
+        stable_swap::get_twap_price(&mut session, stable_swap, usdt, dai, OBSERVATION_PERIOD),
+        Err(StablePoolError::InsufficientObservations())
+    );
+    assert_eq!(
+        stable_swap::get_twap_price(&mut session, stable_swap, usdt, dai, 0),
+        Err(StablePoolError::InvalidWindow())
+    );
+
+    // balanced pool for the first 10 minutes
+    set_timestamp(&mut session, start + TEN_MINUTES);
+    // 1 USDT (6 decimals) is worth 1 DAI (18 decimals)
+    let balanced_price = 10u128.pow(12) * PRICE_PRECISION;
+    assert_eq!(
+        stable_swap::get_twap_price(&mut session, stable_swap, usdt, dai, TEN_MINUTES),
+        Ok(balanced_price)
+    );
+    assert_eq!(
+        stable_swap::get_twap_price(&mut session, stable_swap, dai, usdt, TEN_MINUTES),
+        Ok(PRICE_PRECISION / 10u128.pow(12))
+    );
+    assert_eq!(
+        stable_swap::get_twap_virtual_price(&mut session, stable_swap, TEN_MINUTES),
+        Ok(PRICE_PRECISION)
+    );
+
+    // imbalance the pool, USDT gets cheaper for the next 10 minutes
+    _ = stable_swap::swap_exact_in(
+        &mut session,
+        stable_swap,
+        BOB,
+        usdt,
+        dai,
+        50000 * ONE_USDT,
+        0,
+        bob(),
+    )
+    .expect("Should swap");
+    set_timestamp(&mut session, start + 2 * TEN_MINUTES);
+
+    // the window covers only the new price
+    let imbalanced_price =
+        stable_swap::get_twap_price(&mut session, stable_swap, usdt, dai, TEN_MINUTES)
+            .expect("Should return TWAP");
+    assert!(
+        imbalanced_price < balanced_price && imbalanced_price > balanced_price * 9 / 10,
+        "Unexpected price {imbalanced_price}"
+    );
+    // the window covers both prices for equal time
+    let average_price =
+        stable_swap::get_twap_price(&mut session, stable_swap, usdt, dai, 2 * TEN_MINUTES)
+            .expect("Should return TWAP");
+    assert!(
+        average_price.abs_diff((balanced_price + imbalanced_price) / 2) <= 1,
+        "Unexpected average price {average_price}"
+    );
+    // the fees accrued to the pool increase the virtual price
+    assert!(
+        stable_swap::get_twap_virtual_price(&mut session, stable_swap, TEN_MINUTES)
+            .expect("Should return TWAP")
+            > PRICE_PRECISION
+    );
+
+    // there is no observation old enough for a longer window
+    assert_eq!(
+        stable_swap::get_twap_price(&mut session, stable_swap, usdt, dai, 3 * TEN_MINUTES),
+        Err(StablePoolError::InsufficientObservations())
+    );
+    assert_eq!(
+        stable_swap::get_twap_price(&mut session, stable_swap, usdt, usdt, TEN_MINUTES),
+        Err(StablePoolError::IdenticalTokenId())
+    );
+}
@@ -15,6 +15,9 @@ const ONE_LPT: u128 = 10u128.pow(18);
 const ONE_WAZERO: u128 = 10u128.pow(WAZERO_DEC as u32);
 const ONE_SAZERO: u128 = 10u128.pow(SAZERO_DEC as u32);
 
+/// Time between scheduling a change of a token's rate provider and applying it (24h).
+const RATE_PROVIDER_CHANGE_DELAY: u64 = 86_400_000;
+
 fn deploy_rate_provider(session: &mut Session<MinimalRuntime>, salt: Vec<u8>) -> AccountId {
     let instance = mock_rate_provider_contract::Instance::new().with_salt(salt);
     session
@@ -60,6 +63,7 @@ fn setup_rated_swap_with_tokens(
         trade_fee,
         protocol_fee,
         Some(fee_receiver()),
+        GENESIS_HASH.into(),
     );
 
     let rated_swap = session
@@ -547,3 +551,102 @@ fn test_04(mut session: Session) {
         "Incorrect reserves"
     );
 }
+
+#[drink::test]
+fn test_set_token_rate_provider(mut session: Session) {
+    seed_account(&mut session, CHARLIE);
+
+    upload_all(&mut session);
+
+    let now = get_timestamp(&mut session);
+    set_timestamp(&mut session, now);
+    let initial_token_supply: u128 = 1_000_000_000;
+    let old_rate_provider = deploy_rate_provider(&mut session, vec![0]);
+    let new_rate_provider = deploy_rate_provider(&mut session, vec![1]);
+    let (rated_swap, tokens) = setup_rated_swap_with_tokens(
+        &mut session,
+        BOB,
+        vec![Some(old_rate_provider), None],
+        initial_token_supply,
+        10000,
+        2_500_000,
+        200_000_000,
+    );
+    let sazero = tokens[0];
+
+    set_mock_rate(&mut session, old_rate_provider, 2 * RATE_PRECISION);
+    stable_swap::add_liquidity(
+        &mut session,
+        rated_swap,
+        BOB,
+        1,
+        vec![50000 * ONE_SAZERO, 100000 * ONE_WAZERO],
+        bob(),
+    )
+    .expect("Should successfully add liquidity");
+
+    let res = stable_swap::set_token_rate_provider(
+        &mut session,
+        rated_swap,
+        sazero,
+        Some(new_rate_provider),
+        CHARLIE,
+    );
+    assert!(matches!(res, Err(StablePoolError::Ownable2StepError(_))));
+    let res = stable_swap::apply_token_rate_provider(&mut session, rated_swap, sazero, BOB);
+    assert_eq!(res, Err(StablePoolError::NoRateProviderChange()));
+
+    stable_swap::set_token_rate_provider(
+        &mut session,
+        rated_swap,
+        sazero,
+        Some(new_rate_provider),
+        BOB,
+    )
+    .expect("Should schedule rate provider change");
+    let res = stable_swap::apply_token_rate_provider(&mut session, rated_swap, sazero, BOB);
+    assert_eq!(res, Err(StablePoolError::RateProviderChangeTooEarly()));
+
+    // the new provider reports a rate 5% higher than the cached one
+    set_timestamp(&mut session, now + RATE_PROVIDER_CHANGE_DELAY);
+    set_mock_rate(&mut session, new_rate_provider, 21 * RATE_PRECISION / 10);
+    let res = stable_swap::apply_token_rate_provider(&mut session, rated_swap, sazero, BOB);
+    assert_eq!(res, Err(StablePoolError::RateChangeTooLarge()));
+
+    set_mock_rate(&mut session, new_rate_provider, 201 * RATE_PRECISION / 100);
+    let twap_window = 600_000;
+    let old_price =
+        stable_swap::get_twap_price(&mut session, rated_swap, sazero, tokens[1], twap_window)
+            .expect("Should return TWAP");
+    stable_swap::apply_token_rate_provider(&mut session, rated_swap, sazero, BOB)
+        .expect("Should apply rate provider change");
+    // the window after the change covers only the prices with the rate of the new provider
+    set_timestamp(&mut session, now + RATE_PROVIDER_CHANGE_DELAY + twap_window);
+    let new_price =
+        stable_swap::get_twap_price(&mut session, rated_swap, sazero, tokens[1], twap_window)
+            .expect("Should return TWAP");
+    assert!(
+        new_price > old_price,
+        "Unexpected price {new_price}, before the change {old_price}"
+    );
+    assert_eq!(
+        stable_swap::token_rates_providers(&mut session, rated_swap),
+        vec![Some(new_rate_provider), None]
+    );
+    assert_eq!(
+        stable_swap::token_rates(&mut session, rated_swap),
+        vec![201 * RATE_PRECISION / 100, RATE_PRECISION]
+    );
+    let res = stable_swap::apply_token_rate_provider(&mut session, rated_swap, sazero, BOB);
+    assert_eq!(res, Err(StablePoolError::NoRateProviderChange()));
+
+    // removing the provider would reset the rate to 1
+    stable_swap::set_token_rate_provider(&mut session, rated_swap, sazero, None, BOB)
+        .expect("Should schedule rate provider change");
+    set_timestamp(&mut session, now + 2 * RATE_PROVIDER_CHANGE_DELAY);
+    let res = stable_swap::apply_token_rate_provider(&mut session, rated_swap, sazero, BOB);
+    assert_eq!(res, Err(StablePoolError::RateChangeTooLarge()));
+
+    let res = stable_swap::set_token_rate_provider(&mut session, rated_swap, rated_swap, None, BOB);
+    assert_eq!(res, Err(StablePoolError::InvalidTokenId(rated_swap)));
+}
@@ -0,0 +1,203 @@
+use crate::utils::*;
+use crate::{pair_contract, router_v2_contract, twap_oracle_contract};
+
+use drink::{self, session::Session};
+use ink_primitives::AccountId;
+use pair_contract::Pair as _;
+use router_v2_contract::Step;
+use twap_oracle_contract::TwapOracleError;
+
+const PERIOD: u32 = 60;
+const CAPACITY: u32 = 10;
+const AMOUNT_IN: u128 = 1_000_000_000;
+
+// Close to the 2^32 seconds boundary, so that the timestamps used by the pair wrap around.
+const START_SECONDS: u64 = (1 << 32) - 1_000;
+
+fn set_seconds(session: &mut Session<drink::runtime::MinimalRuntime>, seconds: u64) {
+    set_timestamp(session, seconds * 1000);
+}
+
+#[drink::test]
+fn test_twap_oracle(mut session: Session) {
+    upload_all(&mut session);
+    set_seconds(&mut session, START_SECONDS);
+
+    let factory = factory::setup(&mut session, bob());
+    let wazero = wazero::setup(&mut session);
+    let router = router_v2::setup(&mut session, factory.into(), wazero.into());
+    let oracle = twap_oracle::setup(&mut session, PERIOD, CAPACITY);
+
+    let ice = psp22_utils::setup(&mut session, ICE.to_string(), BOB);
+    let wood = psp22_utils::setup(&mut session, WOOD.to_string(), BOB);
+    for token in [ice, wood] {
+        psp22_utils::increase_allowance(&mut session, token.into(), router.into(), u128::MAX, BOB)
+            .expect("Should increase allowance");
+    }
+
+    let liquidity = 100_000 * TOKEN;
+    router_v2::add_pair_liquidity(
+        &mut session,
+        router.into(),
+        None,
+        ice.into(),
+        wood.into(),
+        liquidity,
+        liquidity,
+        liquidity,
+        liquidity,
+        bob(),
+        BOB,
+    )
+    .expect("Should add liquidity");
+    let pair: AccountId =
+        factory::get_pair(&mut session, factory.into(), ice.into(), wood.into()).into();
+
+    twap_oracle::update(&mut session, oracle.into(), pair, BOB).expect("Should update");
+    let first_observation =
+        twap_oracle::last_observation(&mut session, oracle.into(), pair).expect("Should observe");
+    let first_timestamp = first_observation.timestamp;
+    assert_eq!(first_timestamp as u64, START_SECONDS);
+
+    // no observations older than the window yet
+    assert_eq!(
+        twap_oracle::consult(
+            &mut session,
+            oracle.into(),
+            pair,
+            ice.into(),
+            AMOUNT_IN,
+            PERIOD
+        ),
+        Err(TwapOracleError::InsufficientObservations())
+    );
+
+    // updates within the period are ignored
+    set_seconds(&mut session, START_SECONDS + 10);
+    twap_oracle::update(&mut session, oracle.into(), pair, BOB).expect("Should update");
+    assert_eq!(
+        twap_oracle::last_observation(&mut session, oracle.into(), pair),
+        Some(first_observation)
+    );
+
+    // price 1:1 for the first 600 seconds
+    set_seconds(&mut session, START_SECONDS + 600);
+    router_v2::swap_exact_tokens_for_tokens(
+        &mut session,
+        router.into(),
+        10_000 * TOKEN,
+        0,
+        vec![Step {
+            token_in: ice.into(),
+            pool_id: pair,
+        }],
+        wood.into(),
+        bob(),
+        BOB,
+    )
+    .expect("Should swap");
+    twap_oracle::update(&mut session, oracle.into(), pair, BOB).expect("Should update");
+
+    let pair_contract = pair_contract::Instance::from(pair);
+    let token_0: AccountId =
+        handle_contract_result(session.query(pair_contract.get_token_0()).unwrap());
+    let (reserve_0, reserve_1, _) =
+        handle_contract_result(session.query(pair_contract.get_reserves()).unwrap());
+    let (reserve_ice, reserve_wood) = if token_0 == ice.into() {
+        (reserve_0, reserve_1)
+    } else {
+        (reserve_1, reserve_0)
+    };
+    let spot_ice_amount_out = AMOUNT_IN * reserve_wood / reserve_ice;
+    let spot_wood_amount_out = AMOUNT_IN * reserve_ice / reserve_wood;
+    assert!(spot_ice_amount_out < AMOUNT_IN);
+
+    // past the 2^32 seconds boundary, the new price holds for the next 600 seconds
+    set_seconds(&mut session, START_SECONDS + 1_200);
+    assert!(
+        (START_SECONDS + 1_200) as u32 < first_timestamp,
+        "Timestamp should wrap around"
+    );
+
+    let assert_close = |actual: u128, expected: u128| {
+        assert!(
+            actual.abs_diff(expected) <= 1,
+            "Expected {expected}, got {actual}"
+        );
+    };
+
+    // the window covers only the new price
+    assert_close(
+        twap_oracle::consult(
+            &mut session,
+            oracle.into(),
+            pair,
+            ice.into(),
+            AMOUNT_IN,
+            600,
+        )
+        .expect("Should consult"),
+        spot_ice_amount_out,
+    );
+    assert_close(
+        twap_oracle::consult(
+            &mut session,
+            oracle.into(),
+            pair,
+            wood.into(),
+            AMOUNT_IN,
+            600,
+        )
+        .expect("Should consult"),
+        spot_wood_amount_out,
+    );
+    // the window covers both prices for equal time
+    assert_close(
+        twap_oracle::consult(
+            &mut session,
+            oracle.into(),
+            pair,
+            ice.into(),
+            AMOUNT_IN,
+            1_200,
+        )
+        .expect("Should consult"),
+        (AMOUNT_IN + spot_ice_amount_out) / 2,
+    );
+
+    // the most recent observation is too old for a short window
+    assert_eq!(
+        twap_oracle::consult(
+            &mut session,
+            oracle.into(),
+            pair,
+            ice.into(),
+            AMOUNT_IN,
+            PERIOD
+        ),
+        Err(TwapOracleError::StaleObservations())
+    );
+    // there is no observation old enough for a long window
+    assert_eq!(
+        twap_oracle::consult(
+            &mut session,
+            oracle.into(),
+            pair,
+            ice.into(),
+            AMOUNT_IN,
+            2_400
+        ),
+        Err(TwapOracleError::InsufficientObservations())
+    );
+    assert_eq!(
+        twap_oracle::consult(
+            &mut session,
+            oracle.into(),
+            pair,
+            wazero.into(),
+            AMOUNT_IN,
+            600
+        ),
+        Err(TwapOracleError::InvalidToken())
+    );
+}
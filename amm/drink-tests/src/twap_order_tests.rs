@@ -0,0 +1,267 @@
+use crate::utils::*;
+use crate::{router_v2_contract, twap_order_contract};
+
+use drink::{self, runtime::MinimalRuntime, session::Session};
+use ink_primitives::AccountId;
+use twap_order_contract::{RouterV2Error, Step, TwapOrderError};
+
+const PERIOD: u32 = 60;
+const CAPACITY: u32 = 10;
+const WINDOW: u64 = 120_000;
+
+const LIQUIDITY: u128 = 100_000 * TOKEN;
+const SLICE: u128 = 1_000 * TOKEN;
+const SLICES: u32 = 3;
+const INTERVAL: u64 = PERIOD as u64 * 1000;
+// 3%
+const MAX_DEVIATION: u16 = 300;
+
+struct Setup {
+    book: AccountId,
+    router: AccountId,
+    oracle: AccountId,
+    ice: AccountId,
+    wood: AccountId,
+    pair: AccountId,
+}
+
+/// Sets up balanced ICE/WOOD pair, the TWAP oracle with `WINDOW` worth of its observations
+/// and the TWAP order book. BOB approves the router and the book to spend his tokens.
+fn setup_twap_order_book(session: &mut Session<MinimalRuntime>) -> Setup {
+    upload_all(session);
+
+    // Fix timestamp to a full second. Otherwise underlying UNIX clock is used.
+    let now = get_timestamp(session) / 1000 * 1000;
+    set_timestamp(session, now);
+
+    let factory = factory::setup(session, bob());
+    let wazero = wazero::setup(session);
+    let router: AccountId = router_v2::setup(session, factory.into(), wazero.into()).into();
+    let oracle: AccountId = twap_oracle::setup(session, PERIOD, CAPACITY).into();
+    let book: AccountId = twap_order::setup(session, router, oracle, WINDOW).into();
+
+    let ice = psp22_utils::setup(session, ICE.to_string(), BOB);
+    let wood = psp22_utils::setup(session, WOOD.to_string(), BOB);
+    for token in [ice, wood] {
+        for spender in [router, book] {
+            psp22_utils::increase_allowance(session, token.into(), spender, u128::MAX, BOB)
+                .expect("Should increase allowance");
+        }
+    }
+
+    router_v2::add_pair_liquidity(
+        session,
+        router,
+        None,
+        ice.into(),
+        wood.into(),
+        LIQUIDITY,
+        LIQUIDITY,
+        0,
+        0,
+        bob(),
+        BOB,
+    )
+    .expect("Should add liquidity");
+    let pair: AccountId =
+        factory::get_pair(session, factory.into(), ice.into(), wood.into()).into();
+
+    let s = Setup {
+        book,
+        router,
+        oracle,
+        ice: ice.into(),
+        wood: wood.into(),
+        pair,
+    };
+    twap_oracle::update(session, oracle, pair, BOB).expect("Should update");
+    advance(session, &s, (WINDOW / 1000) as u32);
+    s
+}
+
+/// Moves time forward by `seconds`, recording an observation of the pair every `PERIOD`,
+/// as keepers would do.
+fn advance(session: &mut Session<MinimalRuntime>, s: &Setup, seconds: u32) {
+    for _ in 0..seconds / PERIOD {
+        let now = get_timestamp(session);
+        set_timestamp(session, now + PERIOD as u64 * 1000);
+        twap_oracle::update(session, s.oracle, s.pair, BOB).expect("Should update");
+    }
+}
+
+fn swap(
+    session: &mut Session<MinimalRuntime>,
+    s: &Setup,
+    token_in: AccountId,
+    token_out: AccountId,
+    amount_in: u128,
+) -> u128 {
+    let amounts = router_v2::swap_exact_tokens_for_tokens(
+        session,
+        s.router,
+        amount_in,
+        0,
+        vec![router_v2_contract::Step {
+            token_in,
+            pool_id: s.pair,
+        }],
+        token_out,
+        bob(),
+        BOB,
+    )
+    .expect("Should swap");
+    amounts[1]
+}
+
+fn ice_to_wood(s: &Setup) -> Vec<Step> {
+    vec![Step {
+        token_in: s.ice,
+        pool_id: s.pair,
+    }]
+}
+
+#[drink::test]
+fn test_twap_order_slices(mut session: Session) {
+    let s = setup_twap_order_book(&mut session);
+
+    let res = twap_order::create_order(
+        &mut session,
+        s.book,
+        vec![],
+        s.wood,
+        SLICES as u128 * SLICE,
+        SLICES,
+        INTERVAL,
+        MAX_DEVIATION,
+        BOB,
+    );
+    assert_eq!(res, Err(TwapOrderError::EmptyPath()));
+    let res = twap_order::create_order(
+        &mut session,
+        s.book,
+        ice_to_wood(&s),
+        s.wood,
+        SLICES as u128 * SLICE,
+        0,
+        INTERVAL,
+        MAX_DEVIATION,
+        BOB,
+    );
+    assert_eq!(res, Err(TwapOrderError::InvalidSlices()));
+    let res = twap_order::create_order(
+        &mut session,
+        s.book,
+        ice_to_wood(&s),
+        s.wood,
+        SLICES as u128 * SLICE,
+        SLICES,
+        INTERVAL,
+        10_001,
+        BOB,
+    );
+    assert_eq!(res, Err(TwapOrderError::InvalidDeviation()));
+    // the oracle observes only pairs
+    let res = twap_order::create_order(
+        &mut session,
+        s.book,
+        vec![Step {
+            token_in: s.ice,
+            pool_id: s.wood,
+        }],
+        s.wood,
+        SLICES as u128 * SLICE,
+        SLICES,
+        INTERVAL,
+        MAX_DEVIATION,
+        BOB,
+    );
+    assert_eq!(res, Err(TwapOrderError::PoolIsNotPair()));
+
+    // the last slice takes the rounding leftover
+    let amount_in = SLICES as u128 * SLICE + 2;
+    let ice_before = psp22_utils::balance_of(&mut session, s.ice, bob());
+    let order_id = twap_order::create_order(
+        &mut session,
+        s.book,
+        ice_to_wood(&s),
+        s.wood,
+        amount_in,
+        SLICES,
+        INTERVAL,
+        MAX_DEVIATION,
+        BOB,
+    )
+    .expect("Should create order");
+    assert_eq!(
+        psp22_utils::balance_of(&mut session, s.ice, bob()),
+        ice_before - amount_in
+    );
+
+    let wood_before = psp22_utils::balance_of(&mut session, s.wood, bob());
+    let first_out =
+        twap_order::execute_slice(&mut session, s.book, order_id, CHARLIE).expect("Should execute");
+    assert!(first_out > SLICE * 97 / 100 && first_out < SLICE);
+    assert_eq!(
+        psp22_utils::balance_of(&mut session, s.wood, bob()),
+        wood_before + first_out
+    );
+    let order = twap_order::get_order(&mut session, s.book, order_id).expect("Should exist");
+    assert_eq!(order.slices_remaining, SLICES - 1);
+    assert_eq!(order.amount_remaining, amount_in - SLICE);
+
+    let res = twap_order::execute_slice(&mut session, s.book, order_id, CHARLIE);
+    assert_eq!(res, Err(TwapOrderError::TooEarly()));
+
+    // dumping ICE moves its spot price far below the TWAP
+    let wood_received = swap(&mut session, &s, s.ice, s.wood, 20_000 * TOKEN);
+    advance(&mut session, &s, PERIOD);
+    let res = twap_order::execute_slice(&mut session, s.book, order_id, CHARLIE);
+    assert_eq!(
+        res,
+        Err(TwapOrderError::RouterV2Error(
+            RouterV2Error::InsufficientOutputAmount()
+        ))
+    );
+
+    // once the price is back, the slices can be executed again
+    swap(&mut session, &s, s.wood, s.ice, wood_received);
+    advance(&mut session, &s, PERIOD);
+    twap_order::execute_slice(&mut session, s.book, order_id, CHARLIE).expect("Should execute");
+    advance(&mut session, &s, PERIOD);
+    twap_order::execute_slice(&mut session, s.book, order_id, CHARLIE).expect("Should execute");
+
+    assert!(twap_order::get_order(&mut session, s.book, order_id).is_none());
+    assert_eq!(psp22_utils::balance_of(&mut session, s.ice, s.book), 0);
+    let res = twap_order::execute_slice(&mut session, s.book, order_id, CHARLIE);
+    assert_eq!(res, Err(TwapOrderError::OrderNotFound()));
+}
+
+#[drink::test]
+fn test_twap_order_cancel(mut session: Session) {
+    let s = setup_twap_order_book(&mut session);
+
+    let ice_before = psp22_utils::balance_of(&mut session, s.ice, bob());
+    let order_id = twap_order::create_order(
+        &mut session,
+        s.book,
+        ice_to_wood(&s),
+        s.wood,
+        SLICES as u128 * SLICE,
+        SLICES,
+        INTERVAL,
+        MAX_DEVIATION,
+        BOB,
+    )
+    .expect("Should create order");
+    twap_order::execute_slice(&mut session, s.book, order_id, CHARLIE).expect("Should execute");
+
+    let res = twap_order::cancel_order(&mut session, s.book, order_id, CHARLIE);
+    assert_eq!(res, Err(TwapOrderError::CallerIsNotOwner()));
+    twap_order::cancel_order(&mut session, s.book, order_id, BOB).expect("Should cancel");
+    assert_eq!(
+        psp22_utils::balance_of(&mut session, s.ice, bob()),
+        ice_before - SLICE
+    );
+    assert_eq!(psp22_utils::balance_of(&mut session, s.ice, s.book), 0);
+    assert!(twap_order::get_order(&mut session, s.book, order_id).is_none());
+}
@@ -19,6 +19,9 @@ pub const ONE_AZERO: u128 = 10u128.pow(12);
 
 pub const FEE_RECEIVER: AccountId32 = AccountId32::new([42u8; 32]);
 
+/// Genesis hash the LP tokens bind their permits to.
+pub const GENESIS_HASH: [u8; 32] = [9u8; 32];
+
 pub fn fee_receiver() -> ink_primitives::AccountId {
     AsRef::<[u8; 32]>::as_ref(&FEE_RECEIVER).clone().into()
 }
@@ -59,18 +62,45 @@ pub fn upload_all(session: &mut Session<MinimalRuntime>) {
     session
         .upload_code(stable_pool_contract::upload())
         .expect("Upload stable_pool_contract code");
+    session
+        .upload_code(concentrated_pool_contract::upload())
+        .expect("Upload concentrated_pool_contract code");
+    session
+        .upload_code(weighted_pool_contract::upload())
+        .expect("Upload weighted_pool_contract code");
+    session
+        .upload_code(crypto_pool_contract::upload())
+        .expect("Upload crypto_pool_contract code");
+    session
+        .upload_code(stable_pool_factory_contract::upload())
+        .expect("Upload stable_pool_factory_contract code");
+    session
+        .upload_code(twap_oracle_contract::upload())
+        .expect("Upload twap_oracle_contract code");
+    session
+        .upload_code(twap_order_contract::upload())
+        .expect("Upload twap_order_contract code");
+    session
+        .upload_code(limit_order_contract::upload())
+        .expect("Upload limit_order_contract code");
     session
         .upload_code(router_contract::upload())
         .expect("Upload router_contract code");
     session
         .upload_code(router_v2_contract::upload())
         .expect("Upload router_v2_contract code");
+    session
+        .upload_code(farm_contract::upload())
+        .expect("Upload farm_contract code");
     session
         .upload_code(wrapped_azero::upload())
         .expect("Upload wrapped_azero code");
     session
         .upload_code(mock_rate_provider_contract::upload())
         .expect("Upload mock_rate_provider_contract code");
+    session
+        .upload_code(mock_flash_loan_receiver_contract::upload())
+        .expect("Upload mock_flash_loan_receiver_contract code");
 }
 
 pub mod wazero {
@@ -90,14 +120,17 @@ pub mod wazero {
 
 pub mod factory {
     use super::*;
-    use factory_contract::Factory as _;
+    use factory_contract::{Factory as _, FactoryError};
 
     pub fn setup(
         session: &mut Session<MinimalRuntime>,
         fee_to_setter: AccountId,
     ) -> factory_contract::Instance {
-        let instance =
-            factory_contract::Instance::new(fee_to_setter, pair_contract::CODE_HASH.into());
+        let instance = factory_contract::Instance::new(
+            fee_to_setter,
+            pair_contract::CODE_HASH.into(),
+            GENESIS_HASH.into(),
+        );
 
         session
             .instantiate(instance)
@@ -107,14 +140,27 @@ pub mod factory {
             .into()
     }
 
+    /// Trading fee of the pairs created with the default fee tier.
+    pub const DEFAULT_FEE: u8 = 3;
+
     pub fn get_pair(
         session: &mut Session<MinimalRuntime>,
         factory: AccountId,
         token0: AccountId,
         token1: AccountId,
+    ) -> pair_contract::Instance {
+        get_pair_with_fee(session, factory, token0, token1, DEFAULT_FEE)
+    }
+
+    pub fn get_pair_with_fee(
+        session: &mut Session<MinimalRuntime>,
+        factory: AccountId,
+        token0: AccountId,
+        token1: AccountId,
+        fee: u8,
     ) -> pair_contract::Instance {
         session
-            .query(factory_contract::Instance::from(factory).get_pair(token0, token1))
+            .query(factory_contract::Instance::from(factory).get_pair(token0, token1, fee))
             .unwrap()
             .result
             .unwrap()
@@ -122,18 +168,112 @@ pub mod factory {
             .to_account_id()
             .into()
     }
+
+    pub fn is_pair(
+        session: &mut Session<MinimalRuntime>,
+        factory: AccountId,
+        pair: AccountId,
+    ) -> bool {
+        session
+            .query(factory_contract::Instance::from(factory).is_pair(pair))
+            .unwrap()
+            .result
+            .unwrap()
+    }
+
+    pub fn create_pair(
+        session: &mut Session<MinimalRuntime>,
+        factory: AccountId,
+        token0: AccountId,
+        token1: AccountId,
+        fee: u8,
+        caller: drink::AccountId32,
+    ) -> Result<AccountId, FactoryError> {
+        let _ = session.set_actor(caller);
+        handle_contract_result(
+            session
+                .execute(factory_contract::Instance::from(factory).create_pair(token0, token1, fee))
+                .unwrap(),
+        )
+    }
+
+    pub fn set_pair_fee(
+        session: &mut Session<MinimalRuntime>,
+        factory: AccountId,
+        pair: AccountId,
+        fee: u8,
+        effective_time: u64,
+        caller: drink::AccountId32,
+    ) -> Result<(), FactoryError> {
+        let _ = session.set_actor(caller);
+        handle_contract_result(
+            session
+                .execute(factory_contract::Instance::from(factory).set_pair_fee(
+                    pair,
+                    fee,
+                    effective_time,
+                ))
+                .unwrap(),
+        )
+    }
+
+    pub fn set_pair_fee_to(
+        session: &mut Session<MinimalRuntime>,
+        factory: AccountId,
+        pair: AccountId,
+        fee_to: Option<AccountId>,
+        caller: drink::AccountId32,
+    ) -> Result<(), FactoryError> {
+        let _ = session.set_actor(caller);
+        handle_contract_result(
+            session
+                .execute(factory_contract::Instance::from(factory).set_pair_fee_to(pair, fee_to))
+                .unwrap(),
+        )
+    }
+
+    pub fn set_protocol_fee_denom(
+        session: &mut Session<MinimalRuntime>,
+        factory: AccountId,
+        denom: u8,
+        caller: drink::AccountId32,
+    ) -> Result<(), FactoryError> {
+        let _ = session.set_actor(caller);
+        handle_contract_result(
+            session
+                .execute(factory_contract::Instance::from(factory).set_protocol_fee_denom(denom))
+                .unwrap(),
+        )
+    }
+
+    pub fn enable_fee_tier(
+        session: &mut Session<MinimalRuntime>,
+        factory: AccountId,
+        fee: u8,
+        caller: drink::AccountId32,
+    ) -> Result<(), FactoryError> {
+        let _ = session.set_actor(caller);
+        handle_contract_result(
+            session
+                .execute(factory_contract::Instance::from(factory).enable_fee_tier(fee))
+                .unwrap(),
+        )
+    }
 }
 
-pub mod router {
+pub mod stable_pool_factory {
     use super::*;
-    use router_contract::Router as _;
+    use stable_pool_factory_contract::{StablePoolFactory as _, StablePoolFactoryError};
 
     pub fn setup(
         session: &mut Session<MinimalRuntime>,
-        factory: AccountId,
-        wazero: AccountId,
-    ) -> router_contract::Instance {
-        let instance = router_contract::Instance::new(factory, wazero);
+        owner: AccountId,
+    ) -> stable_pool_factory_contract::Instance {
+        let instance = stable_pool_factory_contract::Instance::new(
+            owner,
+            stable_pool_contract::CODE_HASH.into(),
+            GENESIS_HASH.into(),
+        );
 
         session
             .instantiate(instance)
@@ -143,91 +283,79 @@ pub mod router {
             .into()
     }
 
-    pub fn add_liquidity(
+    pub fn create_pool(
         session: &mut Session<MinimalRuntime>,
-        router: AccountId,
-        first_token: AccountId,
-        second_token: AccountId,
-        desired_token_amount: u128,
-        min_token_amount: u128,
-        caller: AccountId,
-    ) -> (u128, u128, u128) {
-        let now = get_timestamp(session);
-        let deadline = now + 10;
-
+        factory: AccountId,
+        tokens: Vec<AccountId>,
+        tokens_decimals: Vec<u8>,
+        init_amp_coef: u128,
+        trade_fee: u32,
+        protocol_fee: u32,
+        caller: drink::AccountId32,
+    ) -> Result<AccountId, StablePoolFactoryError> {
+        let _ = session.set_actor(caller.clone());
+        let external_rates = vec![None; tokens.len()];
         session
-            .execute(router_contract::Instance::from(router).add_liquidity(
-                first_token,
-                second_token,
-                desired_token_amount,
-                desired_token_amount,
-                min_token_amount,
-                min_token_amount,
-                caller,
-                deadline,
-            ))
+            .execute(
+                stable_pool_factory_contract::Instance::from(factory).create_pool(
+                    tokens,
+                    tokens_decimals,
+                    external_rates,
+                    init_amp_coef,
+                    caller.to_account_id(),
+                    trade_fee,
+                    protocol_fee,
+                    Some(fee_receiver()),
+                ),
+            )
             .unwrap()
             .result
             .unwrap()
-            .unwrap()
     }
 
-    pub fn remove_liquidity(
+    pub fn get_pool(
         session: &mut Session<MinimalRuntime>,
-        router: AccountId,
-        first_token: AccountId,
-        second_token: AccountId,
-        liquidity: u128,
-        min_token0: u128,
-        min_token1: u128,
-        caller: AccountId,
-    ) -> (u128, u128) {
-        let now = get_timestamp(session);
-        let deadline = now + 10;
-
+        factory: AccountId,
+        tokens: Vec<AccountId>,
+    ) -> Option<AccountId> {
         session
-            .execute(router_contract::Instance::from(router).remove_liquidity(
-                first_token,
-                second_token,
-                liquidity,
-                min_token0,
-                min_token1,
-                caller,
-                deadline,
-            ))
+            .query(stable_pool_factory_contract::Instance::from(factory).get_pool(tokens))
             .unwrap()
             .result
             .unwrap()
-            .unwrap()
     }
 
-    pub fn get_cached_pair(
+    pub fn is_pool(
         session: &mut Session<MinimalRuntime>,
-        router: AccountId,
-        token0: AccountId,
-        token1: AccountId,
-    ) -> AccountId {
+        factory: AccountId,
+        pool: AccountId,
+    ) -> bool {
         session
-            .query(router_contract::Instance::from(router).read_cache(token0, token1))
+            .query(stable_pool_factory_contract::Instance::from(factory).is_pool(pool))
             .unwrap()
             .result
             .unwrap()
+    }
+
+    pub fn all_pools_length(session: &mut Session<MinimalRuntime>, factory: AccountId) -> u64 {
+        session
+            .query(stable_pool_factory_contract::Instance::from(factory).all_pools_length())
+            .unwrap()
+            .result
             .unwrap()
-            .0
     }
 }
 
-pub mod router_v2 {
+pub mod twap_oracle {
     use super::*;
-    use router_v2_contract::RouterV2 as _;
-    use router_v2_contract::{Pool, RouterV2Error, Step};
+    use twap_oracle_contract::{Observation, TwapOracle as _, TwapOracleError};
 
     pub fn setup(
         session: &mut Session<MinimalRuntime>,
-        factory: AccountId,
-        wazero: AccountId,
-    ) -> router_v2_contract::Instance {
-        let instance = router_v2_contract::Instance::new(factory, wazero);
+        period: u32,
+        capacity: u32,
+    ) -> twap_oracle_contract::Instance {
+        let instance = twap_oracle_contract::Instance::new(period, capacity);
 
         session
             .instantiate(instance)
@@ -237,393 +365,243 @@ pub mod router_v2 {
             .into()
     }
 
-    pub fn add_pair_liquidity(
+    pub fn update(
         session: &mut Session<MinimalRuntime>,
-        router: AccountId,
-        pair: Option<AccountId>,
-        token_0: AccountId,
-        token_1: AccountId,
-        desired_amount_0: u128,
-        desired_amount_1: u128,
-        min_amount_0: u128,
-        min_amount_1: u128,
-        to: AccountId,
+        oracle: AccountId,
+        pair: AccountId,
         caller: drink::AccountId32,
-    ) -> Result<(u128, u128, u128), RouterV2Error> {
-        let now = get_timestamp(session);
-        let deadline = now + 10;
+    ) -> Result<(), TwapOracleError> {
         let _ = session.set_actor(caller);
-
         session
-            .execute(
-                router_v2_contract::Instance::from(router).add_pair_liquidity(
-                    pair,
-                    token_0,
-                    token_1,
-                    desired_amount_0,
-                    desired_amount_1,
-                    min_amount_0,
-                    min_amount_1,
-                    to,
-                    deadline,
-                ),
-            )
+            .execute(twap_oracle_contract::Instance::from(oracle).update(pair))
             .unwrap()
             .result
             .unwrap()
     }
 
-    pub fn add_pair_liquidity_native(
+    pub fn consult(
         session: &mut Session<MinimalRuntime>,
-        router: AccountId,
-        pair: Option<AccountId>,
-        token: AccountId,
-        amount_token_desired: u128,
-        amount_token_min: u128,
-        amount_native_min: u128,
-        to: AccountId,
-        native_amount: u128,
-        caller: drink::AccountId32,
-    ) -> Result<(u128, u128, u128), RouterV2Error> {
-        let now = get_timestamp(session);
-        let deadline = now + 10;
-        let _ = session.set_actor(caller);
-
+        oracle: AccountId,
+        pair: AccountId,
+        token_in: AccountId,
+        amount_in: u128,
+        window: u32,
+    ) -> Result<u128, TwapOracleError> {
         session
-            .execute(
-                router_v2_contract::Instance::from(router)
-                    .add_pair_liquidity_native(
-                        pair,
-                        token,
-                        amount_token_desired,
-                        amount_token_min,
-                        amount_native_min,
-                        to,
-                        deadline,
-                    )
-                    .with_value(native_amount),
+            .query(
+                twap_oracle_contract::Instance::from(oracle)
+                    .consult(pair, token_in, amount_in, window),
             )
             .unwrap()
             .result
             .unwrap()
     }
 
-    #[allow(dead_code)]
-    pub fn remove_pair_liquidity(
+    pub fn last_observation(
         session: &mut Session<MinimalRuntime>,
-        router: AccountId,
+        oracle: AccountId,
         pair: AccountId,
-        first_token: AccountId,
-        second_token: AccountId,
-        liquidity: u128,
-        min_token0: u128,
-        min_token1: u128,
-        to: AccountId,
-        caller: drink::AccountId32,
-    ) -> Result<(u128, u128), RouterV2Error> {
-        let now = get_timestamp(session);
-        let deadline = now + 10;
-        let _ = session.set_actor(caller);
-
+    ) -> Option<Observation> {
         session
-            .execute(
-                router_v2_contract::Instance::from(router).remove_pair_liquidity(
-                    pair,
-                    first_token,
-                    second_token,
-                    liquidity,
-                    min_token0,
-                    min_token1,
-                    to,
-                    deadline,
-                ),
-            )
+            .query(twap_oracle_contract::Instance::from(oracle).last_observation(pair))
             .unwrap()
             .result
             .unwrap()
     }
+}
 
-    pub fn add_stable_swap_liquidity(
-        session: &mut Session<MinimalRuntime>,
-        router: AccountId,
-        pool: AccountId,
-        min_share_amount: u128,
-        amounts: Vec<u128>,
-        to: AccountId,
-        native_amount: u128,
+pub mod twap_order {
+    use super::*;
+    use twap_order_contract::{Step, TwapOrder, TwapOrderBook as _, TwapOrderError};
+
+    pub fn setup(
+        session: &mut Session<MinimalRuntime>,
+        router: AccountId,
+        oracle: AccountId,
+        window: u64,
+    ) -> twap_order_contract::Instance {
+        let instance = twap_order_contract::Instance::new(router, oracle, window);
+
+        session
+            .instantiate(instance)
+            .unwrap()
+            .result
+            .to_account_id()
+            .into()
+    }
+
+    pub fn create_order(
+        session: &mut Session<MinimalRuntime>,
+        book: AccountId,
+        path: Vec<Step>,
+        token_out: AccountId,
+        amount_in: u128,
+        slices: u32,
+        interval: u64,
+        max_deviation: u16,
         caller: drink::AccountId32,
-    ) -> Result<(u128, u128), RouterV2Error> {
-        let now = get_timestamp(session);
-        let deadline = now + 10;
+    ) -> Result<u64, TwapOrderError> {
         let _ = session.set_actor(caller);
-
         session
-            .execute(
-                router_v2_contract::Instance::from(router)
-                    .add_stable_pool_liquidity(
-                        pool,
-                        min_share_amount,
-                        amounts,
-                        to,
-                        deadline,
-                    )
-                    .with_value(native_amount),
-            )
+            .execute(twap_order_contract::Instance::from(book).create_order(
+                path,
+                token_out,
+                amount_in,
+                slices,
+                interval,
+                max_deviation,
+            ))
             .unwrap()
             .result
             .unwrap()
     }
 
-    pub fn remove_stable_pool_liquidity(
+    pub fn execute_slice(
         session: &mut Session<MinimalRuntime>,
-        router: AccountId,
-        pool: AccountId,
-        max_share_amount: u128,
-        amounts: Vec<u128>,
-        to: AccountId,
+        book: AccountId,
+        order_id: u64,
         caller: drink::AccountId32,
-    ) -> Result<(u128, u128), RouterV2Error> {
-        let now = get_timestamp(session);
-        let deadline = now + 10;
+    ) -> Result<u128, TwapOrderError> {
         let _ = session.set_actor(caller);
-
         session
-            .execute(
-                router_v2_contract::Instance::from(router).remove_stable_pool_liquidity(
-                    pool,
-                    max_share_amount,
-                    amounts,
-                    to,
-                    deadline,
-                ),
-            )
+            .execute(twap_order_contract::Instance::from(book).execute_slice(order_id))
             .unwrap()
             .result
             .unwrap()
     }
 
-    pub fn remove_stable_pool_liquidity_by_share(
+    pub fn cancel_order(
         session: &mut Session<MinimalRuntime>,
-        router: AccountId,
-        pool: AccountId,
-        share_amount: u128,
-        min_amounts: Vec<u128>,
-        to: AccountId,
+        book: AccountId,
+        order_id: u64,
         caller: drink::AccountId32,
-    ) -> Result<Vec<u128>, RouterV2Error> {
-        let now = get_timestamp(session);
-        let deadline = now + 10;
+    ) -> Result<(), TwapOrderError> {
         let _ = session.set_actor(caller);
-
         session
-            .execute(
-                router_v2_contract::Instance::from(router).remove_stable_pool_liquidity_by_share(
-                    pool,
-                    share_amount,
-                    min_amounts,
-                    to,
-                    deadline,
-                ),
-            )
+            .execute(twap_order_contract::Instance::from(book).cancel_order(order_id))
             .unwrap()
             .result
             .unwrap()
     }
 
-    pub fn get_cached_pool(
+    pub fn get_order(
         session: &mut Session<MinimalRuntime>,
-        router: AccountId,
-        pool_id: AccountId,
-    ) -> Option<Pool> {
+        book: AccountId,
+        order_id: u64,
+    ) -> Option<TwapOrder> {
         session
-            .query(router_v2_contract::Instance::from(router).read_cached_pool(pool_id))
+            .query(twap_order_contract::Instance::from(book).get_order(order_id))
             .unwrap()
             .result
             .unwrap()
     }
+}
 
-    pub fn swap_exact_tokens_for_tokens(
+pub mod limit_order {
+    use super::*;
+    use limit_order_contract::{LimitOrderBook as _, LimitOrderError, Order, Step};
+
+    pub fn setup(
         session: &mut Session<MinimalRuntime>,
         router: AccountId,
-        amount_in: u128,
-        amount_out_min: u128,
-        path: Vec<Step>,
-        token_out: ink_primitives::AccountId,
-        to: ink_primitives::AccountId,
-        caller: drink::AccountId32,
-    ) -> Result<Vec<u128>, RouterV2Error> {
-        let now = get_timestamp(session);
-        let deadline = now + 10;
-        let _ = session.set_actor(caller);
+    ) -> limit_order_contract::Instance {
+        let instance = limit_order_contract::Instance::new(router);
+
         session
-            .execute(
-                router_v2_contract::Instance::from(router).swap_exact_tokens_for_tokens(
-                    amount_in,
-                    amount_out_min,
-                    path,
-                    token_out,
-                    to,
-                    deadline,
-                ),
-            )
+            .instantiate(instance)
             .unwrap()
             .result
-            .unwrap()
+            .to_account_id()
+            .into()
     }
 
-    pub fn swap_tokens_for_exact_tokens(
+    pub fn place_order(
         session: &mut Session<MinimalRuntime>,
-        router: AccountId,
-        amount_out: u128,
-        amount_in_max: u128,
+        book: AccountId,
         path: Vec<Step>,
-        token_out: ink_primitives::AccountId,
-        to: ink_primitives::AccountId,
+        token_out: AccountId,
+        amount_in: u128,
+        amount_out_min: u128,
+        expiry: u64,
+        tip: u128,
         caller: drink::AccountId32,
-    ) -> Result<Vec<u128>, RouterV2Error> {
-        let now = get_timestamp(session);
-        let deadline = now + 10;
+    ) -> Result<u64, LimitOrderError> {
         let _ = session.set_actor(caller);
         session
             .execute(
-                router_v2_contract::Instance::from(router).swap_tokens_for_exact_tokens(
-                    amount_out,
-                    amount_in_max,
-                    path,
-                    token_out,
-                    to,
-                    deadline,
-                ),
+                limit_order_contract::Instance::from(book)
+                    .place_order(path, token_out, amount_in, amount_out_min, expiry)
+                    .with_value(tip),
             )
             .unwrap()
             .result
             .unwrap()
     }
 
-    pub fn swap_exact_native_for_tokens(
+    pub fn cancel_order(
         session: &mut Session<MinimalRuntime>,
-        router: AccountId,
-        native_amount: u128,
-        amount_out_min: u128,
-        path: Vec<Step>,
-        token_out: ink_primitives::AccountId,
-        to: ink_primitives::AccountId,
+        book: AccountId,
+        order_id: u64,
         caller: drink::AccountId32,
-    ) -> Result<Vec<u128>, RouterV2Error> {
-        let now = get_timestamp(session);
-        let deadline = now + 10;
+    ) -> Result<(), LimitOrderError> {
         let _ = session.set_actor(caller);
         session
-            .execute(
-                router_v2_contract::Instance::from(router)
-                    .swap_exact_native_for_tokens(amount_out_min, path, token_out, to, deadline)
-                    .with_value(native_amount),
-            )
+            .execute(limit_order_contract::Instance::from(book).cancel_order(order_id))
             .unwrap()
             .result
             .unwrap()
     }
 
-    pub fn swap_exact_tokens_for_native(
+    pub fn quote_order(
         session: &mut Session<MinimalRuntime>,
-        router: AccountId,
-        amount_in: u128,
-        amount_out_min: u128,
-        path: Vec<Step>,
-        to: ink_primitives::AccountId,
-        caller: drink::AccountId32,
-    ) -> Result<Vec<u128>, RouterV2Error> {
-        let now = get_timestamp(session);
-        let deadline = now + 10;
-        let _ = session.set_actor(caller);
+        book: AccountId,
+        order_id: u64,
+    ) -> Result<u128, LimitOrderError> {
         session
-            .execute(
-                router_v2_contract::Instance::from(router).swap_exact_tokens_for_native(
-                    amount_in,
-                    amount_out_min,
-                    path,
-                    to,
-                    deadline,
-                ),
-            )
+            .query(limit_order_contract::Instance::from(book).quote_order(order_id))
             .unwrap()
             .result
             .unwrap()
     }
 
-    pub fn swap_tokens_for_exact_native(
+    pub fn fill_order(
         session: &mut Session<MinimalRuntime>,
-        router: AccountId,
-        amount_out: u128,
-        amount_in_max: u128,
-        path: Vec<Step>,
-        to: ink_primitives::AccountId,
+        book: AccountId,
+        order_id: u64,
         caller: drink::AccountId32,
-    ) -> Result<Vec<u128>, RouterV2Error> {
-        let now = get_timestamp(session);
-        let deadline = now + 10;
+    ) -> Result<u128, LimitOrderError> {
         let _ = session.set_actor(caller);
         session
-            .execute(
-                router_v2_contract::Instance::from(router).swap_tokens_for_exact_native(
-                    amount_out,
-                    amount_in_max,
-                    path,
-                    to,
-                    deadline,
-                ),
-            )
+            .execute(limit_order_contract::Instance::from(book).fill_order(order_id))
             .unwrap()
             .result
             .unwrap()
     }
 
-    pub fn swap_native_for_exact_tokens(
+    pub fn get_order(
         session: &mut Session<MinimalRuntime>,
-        router: AccountId,
-        native_amount: u128,
-        amount_out: u128,
-        path: Vec<Step>,
-        token_out: ink_primitives::AccountId,
-        to: ink_primitives::AccountId,
-        caller: drink::AccountId32,
-    ) -> Result<Vec<u128>, RouterV2Error> {
-        let now = get_timestamp(session);
-        let deadline = now + 10;
-        let _ = session.set_actor(caller);
+        book: AccountId,
+        order_id: u64,
+    ) -> Option<Order> {
         session
-            .execute(
-                router_v2_contract::Instance::from(router)
-                    .swap_native_for_exact_tokens(amount_out, path, token_out, to, deadline)
-                    .with_value(native_amount),
-            )
+            .query(limit_order_contract::Instance::from(book).get_order(order_id))
             .unwrap()
             .result
             .unwrap()
     }
 }
 
-pub mod psp22_utils {
+pub mod farm {
     use super::*;
-    use psp22::{Instance as PSP22, PSP22Metadata as _, PSP22 as _};
+    use farm_contract::Farm as _;
 
-    /// Uploads and creates a PSP22 instance with 1B*10^18 issuance and given names.
-    /// Returns its AccountId casted to PSP22 interface.
     pub fn setup(
         session: &mut Session<MinimalRuntime>,
-        name: String,
+        pool_id: AccountId,
         caller: drink::AccountId32,
-    ) -> psp22::Instance {
-        let _code_hash = session.upload_code(psp22::upload()).unwrap();
-
+    ) -> farm_contract::Instance {
         let _ = session.set_actor(caller);
-
-        let instance = PSP22::new(
-            1_000_000_000u128 * TOKEN,
-            Some(name.clone()),
-            Some(name),
-            18,
-        );
+        let instance = farm_contract::Instance::new(pool_id, vec![]);
 
         session
             .instantiate(instance)
@@ -633,18 +611,29 @@ pub mod psp22_utils {
             .into()
     }
 
-    pub fn setup_with_amounts(
+    pub fn shares_of(
         session: &mut Session<MinimalRuntime>,
-        name: String,
-        decimals: u8,
-        init_supply: u128,
-        caller: drink::AccountId32,
-    ) -> psp22::Instance {
-        let _code_hash = session.upload_code(psp22::upload()).unwrap();
+        farm: AccountId,
+        account: AccountId,
+    ) -> u128 {
+        handle_contract_result(
+            session
+                .query(farm_contract::Instance::from(farm).shares_of(account))
+                .unwrap(),
+        )
+    }
+}
 
-        let _ = session.set_actor(caller);
+pub mod router {
+    use super::*;
+    use router_contract::Router as _;
 
-        let instance = PSP22::new(init_supply, Some(name.clone()), Some(name), decimals);
+    pub fn setup(
+        session: &mut Session<MinimalRuntime>,
+        factory: AccountId,
+        wazero: AccountId,
+    ) -> router_contract::Instance {
+        let instance = router_contract::Instance::new(factory, wazero);
 
         session
             .instantiate(instance)
@@ -654,84 +643,2150 @@ pub mod psp22_utils {
             .into()
     }
 
-    /// Increases allowance of given token to given spender by given amount.
-    pub fn increase_allowance(
+    pub fn add_liquidity(
         session: &mut Session<MinimalRuntime>,
-        token: AccountId,
-        spender: AccountId,
-        amount: u128,
-        caller: drink::AccountId32,
-    ) -> Result<(), psp22::PSP22Error> {
-        let _ = session.set_actor(caller);
+        router: AccountId,
+        first_token: AccountId,
+        second_token: AccountId,
+        desired_token_amount: u128,
+        min_token_amount: u128,
+        caller: AccountId,
+    ) -> (u128, u128, u128) {
+        let now = get_timestamp(session);
+        let deadline = now + 10;
+
+        session
+            .execute(router_contract::Instance::from(router).add_liquidity(
+                first_token,
+                second_token,
+                desired_token_amount,
+                desired_token_amount,
+                min_token_amount,
+                min_token_amount,
+                caller,
+                deadline,
+            ))
+            .unwrap()
+            .result
+            .unwrap()
+            .unwrap()
+    }
+
+    pub fn remove_liquidity(
+        session: &mut Session<MinimalRuntime>,
+        router: AccountId,
+        first_token: AccountId,
+        second_token: AccountId,
+        liquidity: u128,
+        min_token0: u128,
+        min_token1: u128,
+        caller: AccountId,
+    ) -> (u128, u128) {
+        let now = get_timestamp(session);
+        let deadline = now + 10;
+
+        session
+            .execute(router_contract::Instance::from(router).remove_liquidity(
+                first_token,
+                second_token,
+                liquidity,
+                min_token0,
+                min_token1,
+                caller,
+                deadline,
+            ))
+            .unwrap()
+            .result
+            .unwrap()
+            .unwrap()
+    }
+
+    pub fn get_cached_pair(
+        session: &mut Session<MinimalRuntime>,
+        router: AccountId,
+        token0: AccountId,
+        token1: AccountId,
+    ) -> AccountId {
+        session
+            .query(router_contract::Instance::from(router).read_cache(token0, token1))
+            .unwrap()
+            .result
+            .unwrap()
+            .unwrap()
+            .0
+    }
+}
+
+pub mod router_v2 {
+    use super::*;
+    use router_v2_contract::RouterV2 as _;
+    use router_v2_contract::{Pool, RouterCall, RouterV2Error, Step, WeightedPath};
+
+    pub fn setup(
+        session: &mut Session<MinimalRuntime>,
+        factory: AccountId,
+        wazero: AccountId,
+    ) -> router_v2_contract::Instance {
+        let instance = router_v2_contract::Instance::new(factory, wazero);
+
+        session
+            .instantiate(instance)
+            .unwrap()
+            .result
+            .to_account_id()
+            .into()
+    }
+
+    pub fn add_pair_liquidity(
+        session: &mut Session<MinimalRuntime>,
+        router: AccountId,
+        pair: Option<AccountId>,
+        token_0: AccountId,
+        token_1: AccountId,
+        desired_amount_0: u128,
+        desired_amount_1: u128,
+        min_amount_0: u128,
+        min_amount_1: u128,
+        to: AccountId,
+        caller: drink::AccountId32,
+    ) -> Result<(u128, u128, u128), RouterV2Error> {
+        let now = get_timestamp(session);
+        let deadline = now + 10;
+        let _ = session.set_actor(caller);
+
+        session
+            .execute(
+                router_v2_contract::Instance::from(router).add_pair_liquidity(
+                    pair,
+                    token_0,
+                    token_1,
+                    desired_amount_0,
+                    desired_amount_1,
+                    min_amount_0,
+                    min_amount_1,
+                    to,
+                    deadline,
+                ),
+            )
+            .unwrap()
+            .result
+            .unwrap()
+    }
+
+    pub fn add_pair_liquidity_native(
+        session: &mut Session<MinimalRuntime>,
+        router: AccountId,
+        pair: Option<AccountId>,
+        token: AccountId,
+        amount_token_desired: u128,
+        amount_token_min: u128,
+        amount_native_min: u128,
+        to: AccountId,
+        native_amount: u128,
+        caller: drink::AccountId32,
+    ) -> Result<(u128, u128, u128), RouterV2Error> {
+        let now = get_timestamp(session);
+        let deadline = now + 10;
+        let _ = session.set_actor(caller);
+
+        session
+            .execute(
+                router_v2_contract::Instance::from(router)
+                    .add_pair_liquidity_native(
+                        pair,
+                        token,
+                        amount_token_desired,
+                        amount_token_min,
+                        amount_native_min,
+                        to,
+                        deadline,
+                    )
+                    .with_value(native_amount),
+            )
+            .unwrap()
+            .result
+            .unwrap()
+    }
+
+    #[allow(dead_code)]
+    pub fn remove_pair_liquidity(
+        session: &mut Session<MinimalRuntime>,
+        router: AccountId,
+        pair: AccountId,
+        first_token: AccountId,
+        second_token: AccountId,
+        liquidity: u128,
+        min_token0: u128,
+        min_token1: u128,
+        to: AccountId,
+        caller: drink::AccountId32,
+    ) -> Result<(u128, u128), RouterV2Error> {
+        let now = get_timestamp(session);
+        let deadline = now + 10;
+        let _ = session.set_actor(caller);
+
+        session
+            .execute(
+                router_v2_contract::Instance::from(router).remove_pair_liquidity(
+                    pair,
+                    first_token,
+                    second_token,
+                    liquidity,
+                    min_token0,
+                    min_token1,
+                    to,
+                    deadline,
+                ),
+            )
+            .unwrap()
+            .result
+            .unwrap()
+    }
+
+    pub fn remove_pair_liquidity_with_permit(
+        session: &mut Session<MinimalRuntime>,
+        router: AccountId,
+        pair: AccountId,
+        first_token: AccountId,
+        second_token: AccountId,
+        liquidity: u128,
+        min_token0: u128,
+        min_token1: u128,
+        to: AccountId,
+        signer: &sp_core::ecdsa::Pair,
+    ) -> Result<(u128, u128), RouterV2Error> {
+        let now = get_timestamp(session);
+        let deadline = now + 10;
+        let caller = permit::account(signer);
+        let owner: AccountId = AsRef::<[u8; 32]>::as_ref(&caller).clone().into();
+        let nonce = permit::nonces(session, pair, owner);
+        let signature = router_v2_contract::PermitSignature::Ecdsa(permit::sign(
+            signer,
+            GENESIS_HASH,
+            pair,
+            router,
+            liquidity,
+            nonce,
+            deadline,
+        ));
+        let _ = session.set_actor(caller);
+
+        session
+            .execute(
+                router_v2_contract::Instance::from(router).remove_pair_liquidity_with_permit(
+                    pair,
+                    first_token,
+                    second_token,
+                    liquidity,
+                    min_token0,
+                    min_token1,
+                    to,
+                    deadline,
+                    signature,
+                ),
+            )
+            .unwrap()
+            .result
+            .unwrap()
+    }
+
+    pub fn multicall(
+        session: &mut Session<MinimalRuntime>,
+        router: AccountId,
+        calls: Vec<RouterCall>,
+        caller: drink::AccountId32,
+    ) -> Result<Vec<Vec<u128>>, RouterV2Error> {
+        let now = get_timestamp(session);
+        let deadline = now + 10;
+        let _ = session.set_actor(caller);
+
+        session
+            .execute(router_v2_contract::Instance::from(router).multicall(calls, deadline))
+            .unwrap()
+            .result
+            .unwrap()
+    }
+
+    pub fn add_pair_liquidity_and_stake(
+        session: &mut Session<MinimalRuntime>,
+        router: AccountId,
+        pair: AccountId,
+        token_0: AccountId,
+        token_1: AccountId,
+        desired_amount_0: u128,
+        desired_amount_1: u128,
+        min_amount_0: u128,
+        min_amount_1: u128,
+        farm: AccountId,
+        caller: drink::AccountId32,
+    ) -> Result<(u128, u128, u128), RouterV2Error> {
+        let now = get_timestamp(session);
+        let deadline = now + 10;
+        let _ = session.set_actor(caller);
+
+        session
+            .execute(
+                router_v2_contract::Instance::from(router).add_pair_liquidity_and_stake(
+                    pair,
+                    token_0,
+                    token_1,
+                    desired_amount_0,
+                    desired_amount_1,
+                    min_amount_0,
+                    min_amount_1,
+                    farm,
+                    deadline,
+                ),
+            )
+            .unwrap()
+            .result
+            .unwrap()
+    }
+
+    pub fn add_stable_pool_liquidity_and_stake(
+        session: &mut Session<MinimalRuntime>,
+        router: AccountId,
+        pool: AccountId,
+        min_share_amount: u128,
+        amounts: Vec<u128>,
+        farm: AccountId,
+        caller: drink::AccountId32,
+    ) -> Result<(u128, u128), RouterV2Error> {
+        let now = get_timestamp(session);
+        let deadline = now + 10;
+        let _ = session.set_actor(caller);
+
+        session
+            .execute(
+                router_v2_contract::Instance::from(router).add_stable_pool_liquidity_and_stake(
+                    pool,
+                    min_share_amount,
+                    amounts,
+                    farm,
+                    deadline,
+                ),
+            )
+            .unwrap()
+            .result
+            .unwrap()
+    }
+
+    pub fn zap_in_pair(
+        session: &mut Session<MinimalRuntime>,
+        router: AccountId,
+        pair: AccountId,
+        token_in: AccountId,
+        amount_in: u128,
+        min_liquidity: u128,
+        to: AccountId,
+        caller: drink::AccountId32,
+    ) -> Result<(u128, u128), RouterV2Error> {
+        let now = get_timestamp(session);
+        let deadline = now + 10;
+        let _ = session.set_actor(caller);
+
+        session
+            .execute(router_v2_contract::Instance::from(router).zap_in_pair(
+                pair,
+                token_in,
+                amount_in,
+                min_liquidity,
+                to,
+                deadline,
+            ))
+            .unwrap()
+            .result
+            .unwrap()
+    }
+
+    pub fn zap_out_pair(
+        session: &mut Session<MinimalRuntime>,
+        router: AccountId,
+        pair: AccountId,
+        liquidity: u128,
+        token_out: AccountId,
+        min_amount_out: u128,
+        to: AccountId,
+        caller: drink::AccountId32,
+    ) -> Result<u128, RouterV2Error> {
+        let now = get_timestamp(session);
+        let deadline = now + 10;
+        let _ = session.set_actor(caller);
+
+        session
+            .execute(router_v2_contract::Instance::from(router).zap_out_pair(
+                pair,
+                liquidity,
+                token_out,
+                min_amount_out,
+                to,
+                deadline,
+            ))
+            .unwrap()
+            .result
+            .unwrap()
+    }
+
+    pub fn zap_in_stable_pool(
+        session: &mut Session<MinimalRuntime>,
+        router: AccountId,
+        pool: AccountId,
+        amount_in: u128,
+        path: Vec<Step>,
+        token: AccountId,
+        min_share_amount: u128,
+        to: AccountId,
+        native_amount: u128,
+        caller: drink::AccountId32,
+    ) -> Result<(u128, u128), RouterV2Error> {
+        let now = get_timestamp(session);
+        let deadline = now + 10;
+        let _ = session.set_actor(caller);
+
+        session
+            .execute(
+                router_v2_contract::Instance::from(router)
+                    .zap_in_stable_pool(
+                        pool,
+                        amount_in,
+                        path,
+                        token,
+                        min_share_amount,
+                        to,
+                        deadline,
+                    )
+                    .with_value(native_amount),
+            )
+            .unwrap()
+            .result
+            .unwrap()
+    }
+
+    pub fn add_stable_swap_liquidity(
+        session: &mut Session<MinimalRuntime>,
+        router: AccountId,
+        pool: AccountId,
+        min_share_amount: u128,
+        amounts: Vec<u128>,
+        to: AccountId,
+        native_amount: u128,
+        caller: drink::AccountId32,
+    ) -> Result<(u128, u128), RouterV2Error> {
+        let now = get_timestamp(session);
+        let deadline = now + 10;
+        let _ = session.set_actor(caller);
+
+        session
+            .execute(
+                router_v2_contract::Instance::from(router)
+                    .add_stable_pool_liquidity(
+                        pool,
+                        min_share_amount,
+                        amounts,
+                        to,
+                        deadline,
+                    )
+                    .with_value(native_amount),
+            )
+            .unwrap()
+            .result
+            .unwrap()
+    }
+
+    pub fn remove_stable_pool_liquidity(
+        session: &mut Session<MinimalRuntime>,
+        router: AccountId,
+        pool: AccountId,
+        max_share_amount: u128,
+        amounts: Vec<u128>,
+        to: AccountId,
+        caller: drink::AccountId32,
+    ) -> Result<(u128, u128), RouterV2Error> {
+        let now = get_timestamp(session);
+        let deadline = now + 10;
+        let _ = session.set_actor(caller);
+
+        session
+            .execute(
+                router_v2_contract::Instance::from(router).remove_stable_pool_liquidity(
+                    pool,
+                    max_share_amount,
+                    amounts,
+                    to,
+                    deadline,
+                ),
+            )
+            .unwrap()
+            .result
+            .unwrap()
+    }
+
+    pub fn remove_stable_pool_liquidity_by_share(
+        session: &mut Session<MinimalRuntime>,
+        router: AccountId,
+        pool: AccountId,
+        share_amount: u128,
+        min_amounts: Vec<u128>,
+        to: AccountId,
+        caller: drink::AccountId32,
+    ) -> Result<Vec<u128>, RouterV2Error> {
+        let now = get_timestamp(session);
+        let deadline = now + 10;
+        let _ = session.set_actor(caller);
+
+        session
+            .execute(
+                router_v2_contract::Instance::from(router).remove_stable_pool_liquidity_by_share(
+                    pool,
+                    share_amount,
+                    min_amounts,
+                    to,
+                    deadline,
+                ),
+            )
+            .unwrap()
+            .result
+            .unwrap()
+    }
+
+    pub fn remove_stable_pool_liquidity_by_share_with_permit(
+        session: &mut Session<MinimalRuntime>,
+        router: AccountId,
+        pool: AccountId,
+        share_amount: u128,
+        min_amounts: Vec<u128>,
+        to: AccountId,
+        signer: &sp_core::ecdsa::Pair,
+    ) -> Result<Vec<u128>, RouterV2Error> {
+        let now = get_timestamp(session);
+        let deadline = now + 10;
+        let caller = permit::account(signer);
+        let owner: AccountId = AsRef::<[u8; 32]>::as_ref(&caller).clone().into();
+        let nonce = permit::nonces(session, pool, owner);
+        let signature = router_v2_contract::PermitSignature::Ecdsa(permit::sign(
+            signer,
+            GENESIS_HASH,
+            pool,
+            router,
+            share_amount,
+            nonce,
+            deadline,
+        ));
+        let _ = session.set_actor(caller);
+
+        session
+            .execute(
+                router_v2_contract::Instance::from(router)
+                    .remove_stable_pool_liquidity_by_share_with_permit(
+                        pool,
+                        share_amount,
+                        min_amounts,
+                        to,
+                        deadline,
+                        signature,
+                    ),
+            )
+            .unwrap()
+            .result
+            .unwrap()
+    }
+
+    pub fn remove_stable_pool_liquidity_one_coin(
+        session: &mut Session<MinimalRuntime>,
+        router: AccountId,
+        pool: AccountId,
+        share_amount: u128,
+        token: AccountId,
+        min_amount: u128,
+        to: AccountId,
+        caller: drink::AccountId32,
+    ) -> Result<(u128, u128), RouterV2Error> {
+        let now = get_timestamp(session);
+        let deadline = now + 10;
+        let _ = session.set_actor(caller);
+
+        session
+            .execute(
+                router_v2_contract::Instance::from(router).remove_stable_pool_liquidity_one_coin(
+                    pool,
+                    share_amount,
+                    token,
+                    min_amount,
+                    to,
+                    deadline,
+                ),
+            )
+            .unwrap()
+            .result
+            .unwrap()
+    }
+
+    pub fn get_cached_pool(
+        session: &mut Session<MinimalRuntime>,
+        router: AccountId,
+        pool_id: AccountId,
+    ) -> Option<Pool> {
+        session
+            .query(router_v2_contract::Instance::from(router).read_cached_pool(pool_id))
+            .unwrap()
+            .result
+            .unwrap()
+    }
+
+    pub fn add_stable_pool(
+        session: &mut Session<MinimalRuntime>,
+        router: AccountId,
+        pool: AccountId,
+        caller: drink::AccountId32,
+    ) -> Result<(), RouterV2Error> {
+        let _ = session.set_actor(caller);
+        session
+            .execute(router_v2_contract::Instance::from(router).add_stable_pool(pool))
+            .unwrap()
+            .result
+            .unwrap()
+    }
+
+    pub fn set_stable_pool_factory(
+        session: &mut Session<MinimalRuntime>,
+        router: AccountId,
+        stable_pool_factory: Option<AccountId>,
+        caller: drink::AccountId32,
+    ) -> Result<(), RouterV2Error> {
+        let _ = session.set_actor(caller);
+        session
+            .execute(
+                router_v2_contract::Instance::from(router)
+                    .set_stable_pool_factory(stable_pool_factory),
+            )
+            .unwrap()
+            .result
+            .unwrap()
+    }
+
+    pub fn find_best_path(
+        session: &mut Session<MinimalRuntime>,
+        router: AccountId,
+        token_in: AccountId,
+        token_out: AccountId,
+        amount_in: u128,
+        max_hops: u8,
+    ) -> Result<(Vec<Step>, u128), RouterV2Error> {
+        session
+            .query(
+                router_v2_contract::Instance::from(router)
+                    .find_best_path(token_in, token_out, amount_in, max_hops),
+            )
+            .unwrap()
+            .result
+            .unwrap()
+    }
+
+    pub fn quote(
+        session: &mut Session<MinimalRuntime>,
+        router: AccountId,
+        amount_0: u128,
+        reserve_0: u128,
+        reserve_1: u128,
+    ) -> Result<u128, RouterV2Error> {
+        session
+            .query(router_v2_contract::Instance::from(router).quote(amount_0, reserve_0, reserve_1))
+            .unwrap()
+            .result
+            .unwrap()
+    }
+
+    pub fn get_amount_out(
+        session: &mut Session<MinimalRuntime>,
+        router: AccountId,
+        amount_in: u128,
+        reserve_0: u128,
+        reserve_1: u128,
+        fee: u8,
+    ) -> Result<u128, RouterV2Error> {
+        session
+            .query(
+                router_v2_contract::Instance::from(router)
+                    .get_amount_out(amount_in, reserve_0, reserve_1, fee),
+            )
+            .unwrap()
+            .result
+            .unwrap()
+    }
+
+    pub fn get_amount_in(
+        session: &mut Session<MinimalRuntime>,
+        router: AccountId,
+        amount_out: u128,
+        reserve_0: u128,
+        reserve_1: u128,
+        fee: u8,
+    ) -> Result<u128, RouterV2Error> {
+        session
+            .query(
+                router_v2_contract::Instance::from(router)
+                    .get_amount_in(amount_out, reserve_0, reserve_1, fee),
+            )
+            .unwrap()
+            .result
+            .unwrap()
+    }
+
+    pub fn get_stable_amount_out(
+        session: &mut Session<MinimalRuntime>,
+        router: AccountId,
+        amount_in: u128,
+        token_in_id: u8,
+        token_out_id: u8,
+        reserves: Vec<u128>,
+        scaled_rates: Vec<u128>,
+        amp_coef: u128,
+        trade_fee: u32,
+    ) -> Result<(u128, u128), RouterV2Error> {
+        session
+            .query(
+                router_v2_contract::Instance::from(router).get_stable_amount_out(
+                    amount_in,
+                    token_in_id,
+                    token_out_id,
+                    reserves,
+                    scaled_rates,
+                    amp_coef,
+                    trade_fee,
+                ),
+            )
+            .unwrap()
+            .result
+            .unwrap()
+    }
+
+    pub fn get_stable_amount_in(
+        session: &mut Session<MinimalRuntime>,
+        router: AccountId,
+        amount_out: u128,
+        token_in_id: u8,
+        token_out_id: u8,
+        reserves: Vec<u128>,
+        scaled_rates: Vec<u128>,
+        amp_coef: u128,
+        trade_fee: u32,
+    ) -> Result<(u128, u128), RouterV2Error> {
+        session
+            .query(
+                router_v2_contract::Instance::from(router).get_stable_amount_in(
+                    amount_out,
+                    token_in_id,
+                    token_out_id,
+                    reserves,
+                    scaled_rates,
+                    amp_coef,
+                    trade_fee,
+                ),
+            )
+            .unwrap()
+            .result
+            .unwrap()
+    }
+
+    pub fn swap_exact_tokens_for_tokens(
+        session: &mut Session<MinimalRuntime>,
+        router: AccountId,
+        amount_in: u128,
+        amount_out_min: u128,
+        path: Vec<Step>,
+        token_out: ink_primitives::AccountId,
+        to: ink_primitives::AccountId,
+        caller: drink::AccountId32,
+    ) -> Result<Vec<u128>, RouterV2Error> {
+        let now = get_timestamp(session);
+        let deadline = now + 10;
+        let _ = session.set_actor(caller);
+        session
+            .execute(
+                router_v2_contract::Instance::from(router).swap_exact_tokens_for_tokens(
+                    amount_in,
+                    amount_out_min,
+                    path,
+                    token_out,
+                    to,
+                    deadline,
+                ),
+            )
+            .unwrap()
+            .result
+            .unwrap()
+    }
+
+    pub fn swap_exact_tokens_for_tokens_split(
+        session: &mut Session<MinimalRuntime>,
+        router: AccountId,
+        amount_in: u128,
+        amount_out_min: u128,
+        paths: Vec<WeightedPath>,
+        token_out: ink_primitives::AccountId,
+        to: ink_primitives::AccountId,
+        caller: drink::AccountId32,
+    ) -> Result<Vec<Vec<u128>>, RouterV2Error> {
+        let now = get_timestamp(session);
+        let deadline = now + 10;
+        let _ = session.set_actor(caller);
+        session
+            .execute(
+                router_v2_contract::Instance::from(router).swap_exact_tokens_for_tokens_split(
+                    amount_in,
+                    amount_out_min,
+                    paths,
+                    token_out,
+                    to,
+                    deadline,
+                ),
+            )
+            .unwrap()
+            .result
+            .unwrap()
+    }
+
+    pub fn swap_tokens_for_exact_tokens(
+        session: &mut Session<MinimalRuntime>,
+        router: AccountId,
+        amount_out: u128,
+        amount_in_max: u128,
+        path: Vec<Step>,
+        token_out: ink_primitives::AccountId,
+        to: ink_primitives::AccountId,
+        caller: drink::AccountId32,
+    ) -> Result<Vec<u128>, RouterV2Error> {
+        let now = get_timestamp(session);
+        let deadline = now + 10;
+        let _ = session.set_actor(caller);
+        session
+            .execute(
+                router_v2_contract::Instance::from(router).swap_tokens_for_exact_tokens(
+                    amount_out,
+                    amount_in_max,
+                    path,
+                    token_out,
+                    to,
+                    deadline,
+                ),
+            )
+            .unwrap()
+            .result
+            .unwrap()
+    }
+
+    pub fn swap_exact_native_for_tokens(
+        session: &mut Session<MinimalRuntime>,
+        router: AccountId,
+        native_amount: u128,
+        amount_out_min: u128,
+        path: Vec<Step>,
+        token_out: ink_primitives::AccountId,
+        to: ink_primitives::AccountId,
+        caller: drink::AccountId32,
+    ) -> Result<Vec<u128>, RouterV2Error> {
+        let now = get_timestamp(session);
+        let deadline = now + 10;
+        let _ = session.set_actor(caller);
+        session
+            .execute(
+                router_v2_contract::Instance::from(router)
+                    .swap_exact_native_for_tokens(amount_out_min, path, token_out, to, deadline)
+                    .with_value(native_amount),
+            )
+            .unwrap()
+            .result
+            .unwrap()
+    }
+
+    pub fn swap_exact_tokens_for_native(
+        session: &mut Session<MinimalRuntime>,
+        router: AccountId,
+        amount_in: u128,
+        amount_out_min: u128,
+        path: Vec<Step>,
+        to: ink_primitives::AccountId,
+        caller: drink::AccountId32,
+    ) -> Result<Vec<u128>, RouterV2Error> {
+        let now = get_timestamp(session);
+        let deadline = now + 10;
+        let _ = session.set_actor(caller);
+        session
+            .execute(
+                router_v2_contract::Instance::from(router).swap_exact_tokens_for_native(
+                    amount_in,
+                    amount_out_min,
+                    path,
+                    to,
+                    deadline,
+                ),
+            )
+            .unwrap()
+            .result
+            .unwrap()
+    }
+
+    pub fn swap_tokens_for_exact_native(
+        session: &mut Session<MinimalRuntime>,
+        router: AccountId,
+        amount_out: u128,
+        amount_in_max: u128,
+        path: Vec<Step>,
+        to: ink_primitives::AccountId,
+        caller: drink::AccountId32,
+    ) -> Result<Vec<u128>, RouterV2Error> {
+        let now = get_timestamp(session);
+        let deadline = now + 10;
+        let _ = session.set_actor(caller);
+        session
+            .execute(
+                router_v2_contract::Instance::from(router).swap_tokens_for_exact_native(
+                    amount_out,
+                    amount_in_max,
+                    path,
+                    to,
+                    deadline,
+                ),
+            )
+            .unwrap()
+            .result
+            .unwrap()
+    }
+
+    pub fn swap_native_for_exact_tokens(
+        session: &mut Session<MinimalRuntime>,
+        router: AccountId,
+        native_amount: u128,
+        amount_out: u128,
+        path: Vec<Step>,
+        token_out: ink_primitives::AccountId,
+        to: ink_primitives::AccountId,
+        caller: drink::AccountId32,
+    ) -> Result<Vec<u128>, RouterV2Error> {
+        let now = get_timestamp(session);
+        let deadline = now + 10;
+        let _ = session.set_actor(caller);
+        session
+            .execute(
+                router_v2_contract::Instance::from(router)
+                    .swap_native_for_exact_tokens(amount_out, path, token_out, to, deadline)
+                    .with_value(native_amount),
+            )
+            .unwrap()
+            .result
+            .unwrap()
+    }
+}
+
+pub mod psp22_utils {
+    use super::*;
+    use psp22::{Instance as PSP22, PSP22Metadata as _, PSP22 as _};
+
+    /// Uploads and creates a PSP22 instance with 1B*10^18 issuance and given names.
+    /// Returns its AccountId casted to PSP22 interface.
+    pub fn setup(
+        session: &mut Session<MinimalRuntime>,
+        name: String,
+        caller: drink::AccountId32,
+    ) -> psp22::Instance {
+        let _code_hash = session.upload_code(psp22::upload()).unwrap();
+
+        let _ = session.set_actor(caller);
+
+        let instance = PSP22::new(
+            1_000_000_000u128 * TOKEN,
+            Some(name.clone()),
+            Some(name),
+            18,
+        );
+
+        session
+            .instantiate(instance)
+            .unwrap()
+            .result
+            .to_account_id()
+            .into()
+    }
+
+    pub fn setup_with_amounts(
+        session: &mut Session<MinimalRuntime>,
+        name: String,
+        decimals: u8,
+        init_supply: u128,
+        caller: drink::AccountId32,
+    ) -> psp22::Instance {
+        let _code_hash = session.upload_code(psp22::upload()).unwrap();
+
+        let _ = session.set_actor(caller);
+
+        let instance = PSP22::new(init_supply, Some(name.clone()), Some(name), decimals);
+
+        session
+            .instantiate(instance)
+            .unwrap()
+            .result
+            .to_account_id()
+            .into()
+    }
+
+    /// Increases allowance of given token to given spender by given amount.
+    pub fn increase_allowance(
+        session: &mut Session<MinimalRuntime>,
+        token: AccountId,
+        spender: AccountId,
+        amount: u128,
+        caller: drink::AccountId32,
+    ) -> Result<(), psp22::PSP22Error> {
+        let _ = session.set_actor(caller);
+
+        handle_contract_result(
+            session
+                .execute(PSP22::increase_allowance(&token.into(), spender, amount))
+                .unwrap(),
+        )
+    }
+
+    /// Increases allowance of given token to given spender by given amount.
+    pub fn transfer(
+        session: &mut Session<MinimalRuntime>,
+        token: AccountId,
+        to: AccountId,
+        amount: u128,
+        caller: drink::AccountId32,
+    ) -> Result<(), psp22::PSP22Error> {
+        let _ = session.set_actor(caller);
+
+        handle_contract_result(
+            session
+                .execute(PSP22::transfer(&token.into(), to, amount, [].to_vec()))
+                .unwrap(),
+        )
+    }
+
+    /// Returns balance of given token for given account.
+    /// Fails if anything other than success.
+    pub fn balance_of(
+        session: &mut Session<MinimalRuntime>,
+        token: AccountId,
+        account: AccountId,
+    ) -> u128 {
+        handle_contract_result(
+            session
+                .query(PSP22::balance_of(&token.into(), account))
+                .unwrap(),
+        )
+    }
+
+    pub fn allowance(
+        session: &mut Session<MinimalRuntime>,
+        token: AccountId,
+        owner: AccountId,
+        spender: AccountId,
+    ) -> u128 {
+        handle_contract_result(
+            session
+                .query(PSP22::allowance(&token.into(), owner, spender))
+                .unwrap(),
+        )
+    }
+
+    pub fn total_supply(session: &mut Session<MinimalRuntime>, token: AccountId) -> u128 {
+        handle_contract_result(session.query(PSP22::total_supply(&token.into())).unwrap())
+    }
+
+    pub fn token_decimals(session: &mut Session<MinimalRuntime>, token: AccountId) -> u8 {
+        handle_contract_result(session.query(PSP22::token_decimals(&token.into())).unwrap())
+    }
+}
+
+pub mod permit {
+    use super::*;
+    use pair_contract::Permit as _;
+    use scale::Encode;
+    use sp_core::{ecdsa, hashing::blake2_256, Pair as _};
+
+    /// Account of the ECDSA key, as derived by the LP tokens from the signature.
+    pub fn account(signer: &ecdsa::Pair) -> AccountId32 {
+        AccountId32::new(blake2_256(&signer.public().0))
+    }
+
+    /// Returns the next permit nonce of `owner`.
+    /// All LP tokens share the `Permit` trait, so the pair wrapper is used for any of them.
+    pub fn nonces(
+        session: &mut Session<MinimalRuntime>,
+        token: AccountId,
+        owner: AccountId,
+    ) -> u64 {
+        session
+            .query(pair_contract::Instance::from(token).nonces(owner))
+            .unwrap()
+            .result
+            .unwrap()
+    }
+
+    /// Signs the permit payload for `spender` to spend `value` of the `token`
+    /// deployed on the chain with the `genesis_hash`.
+    pub fn sign(
+        signer: &ecdsa::Pair,
+        genesis_hash: [u8; 32],
+        token: AccountId,
+        spender: AccountId,
+        value: u128,
+        nonce: u64,
+        deadline: u64,
+    ) -> [u8; 65] {
+        let owner: AccountId = AsRef::<[u8; 32]>::as_ref(&account(signer)).clone().into();
+        let payload = (
+            b"common-amm:permit".as_slice(),
+            genesis_hash,
+            token,
+            owner,
+            spender,
+            value,
+            nonce,
+            deadline,
+        )
+            .encode();
+        signer.sign(&payload).0
+    }
+}
+
+pub mod mock_flash_loan_receiver {
+    use super::*;
+
+    pub fn setup(
+        session: &mut Session<MinimalRuntime>,
+        repay: bool,
+    ) -> mock_flash_loan_receiver_contract::Instance {
+        let instance = mock_flash_loan_receiver_contract::Instance::new(repay);
+
+        session
+            .instantiate(instance)
+            .unwrap()
+            .result
+            .to_account_id()
+            .into()
+    }
+}
+
+pub mod stable_swap {
+    use super::*;
+    use stable_pool_contract::{StablePool as _, StablePoolError};
+
+    pub fn setup(
+        session: &mut Session<MinimalRuntime>,
+        tokens: Vec<AccountId>,
+        tokens_decimals: Vec<u8>,
+        init_amp_coef: u128,
+        caller: drink::AccountId32,
+        trade_fee: u32,
+        protocol_fee: u32,
+        fee_receiver: Option<AccountId>,
+    ) -> stable_pool_contract::Instance {
+        let _ = session.set_actor(caller.clone());
+        let instance = stable_pool_contract::Instance::new_stable(
+            tokens,
+            tokens_decimals,
+            init_amp_coef,
+            caller.to_account_id(),
+            trade_fee,
+            protocol_fee,
+            fee_receiver,
+            GENESIS_HASH.into(),
+        );
+
+        session
+            .instantiate(instance)
+            .unwrap()
+            .result
+            .to_account_id()
+            .into()
+    }
+
+    pub fn add_liquidity(
+        session: &mut Session<MinimalRuntime>,
+        stable_pool: AccountId,
+        caller: drink::AccountId32,
+        min_share_amount: u128,
+        amounts: Vec<u128>,
+        to: AccountId,
+    ) -> Result<(u128, u128), StablePoolError> {
+        let _ = session.set_actor(caller);
+        handle_contract_result(
+            session
+                .execute(
+                    stable_pool_contract::Instance::from(stable_pool).add_liquidity(
+                        min_share_amount,
+                        amounts,
+                        to,
+                    ),
+                )
+                .unwrap(),
+        )
+    }
+
+    pub fn remove_liquidity_by_amounts(
+        session: &mut Session<MinimalRuntime>,
+        stable_pool: AccountId,
+        caller: drink::AccountId32,
+        max_share_amount: u128,
+        amounts: Vec<u128>,
+        to: AccountId,
+    ) -> Result<(u128, u128), StablePoolError> {
+        let _ = session.set_actor(caller);
+        handle_contract_result(
+            session
+                .execute(
+                    stable_pool_contract::Instance::from(stable_pool).remove_liquidity_by_amounts(
+                        max_share_amount,
+                        amounts,
+                        to,
+                    ),
+                )
+                .unwrap(),
+        )
+    }
+
+    pub fn remove_liquidity_by_shares(
+        session: &mut Session<MinimalRuntime>,
+        stable_pool: AccountId,
+        caller: drink::AccountId32,
+        shares_amount: u128,
+        min_amounts: Vec<u128>,
+        to: AccountId,
+    ) -> Result<Vec<u128>, StablePoolError> {
+        let _ = session.set_actor(caller);
+        handle_contract_result(
+            session
+                .execute(
+                    stable_pool_contract::Instance::from(stable_pool).remove_liquidity_by_shares(
+                        shares_amount,
+                        min_amounts,
+                        to,
+                    ),
+                )
+                .unwrap(),
+        )
+    }
+
+    pub fn remove_liquidity_one_coin(
+        session: &mut Session<MinimalRuntime>,
+        stable_pool: AccountId,
+        caller: drink::AccountId32,
+        shares_amount: u128,
+        token: AccountId,
+        min_amount: u128,
+        to: AccountId,
+    ) -> Result<(u128, u128), StablePoolError> {
+        let _ = session.set_actor(caller);
+        handle_contract_result(
+            session
+                .execute(
+                    stable_pool_contract::Instance::from(stable_pool).remove_liquidity_one_coin(
+                        shares_amount,
+                        token,
+                        min_amount,
+                        to,
+                    ),
+                )
+                .unwrap(),
+        )
+    }
+
+    pub fn swap_exact_in(
+        session: &mut Session<MinimalRuntime>,
+        stable_pool: AccountId,
+        caller: drink::AccountId32,
+        token_in: AccountId,
+        token_out: AccountId,
+        token_in_amount: u128,
+        min_token_out_amount: u128,
+        to: AccountId,
+    ) -> Result<(u128, u128), StablePoolError> {
+        let _ = session.set_actor(caller);
+        handle_contract_result(
+            session
+                .execute(
+                    stable_pool_contract::Instance::from(stable_pool).swap_exact_in(
+                        token_in,
+                        token_out,
+                        token_in_amount,
+                        min_token_out_amount,
+                        to,
+                    ),
+                )
+                .unwrap(),
+        )
+    }
+
+    pub fn swap_exact_out(
+        session: &mut Session<MinimalRuntime>,
+        stable_pool: AccountId,
+        caller: drink::AccountId32,
+        token_in: AccountId,
+        token_out: AccountId,
+        token_out_amount: u128,
+        max_token_in_amount: u128,
+        to: AccountId,
+    ) -> Result<(u128, u128), StablePoolError> {
+        let _ = session.set_actor(caller);
+        handle_contract_result(
+            session
+                .execute(
+                    stable_pool_contract::Instance::from(stable_pool).swap_exact_out(
+                        token_in,
+                        token_out,
+                        token_out_amount,
+                        max_token_in_amount,
+                        to,
+                    ),
+                )
+                .unwrap(),
+        )
+    }
+
+    pub fn swap_received(
+        session: &mut Session<MinimalRuntime>,
+        stable_pool: AccountId,
+        caller: drink::AccountId32,
+        token_in: AccountId,
+        token_out: AccountId,
+        min_token_out_amount: u128,
+        to: AccountId,
+    ) -> Result<(u128, u128), StablePoolError> {
+        let _ = session.set_actor(caller);
+        handle_contract_result(
+            session
+                .execute(
+                    stable_pool_contract::Instance::from(stable_pool).swap_received(
+                        token_in,
+                        token_out,
+                        min_token_out_amount,
+                        to,
+                    ),
+                )
+                .unwrap(),
+        )
+    }
+
+    pub fn flash_loan(
+        session: &mut Session<MinimalRuntime>,
+        stable_pool: AccountId,
+        caller: drink::AccountId32,
+        tokens: Vec<AccountId>,
+        amounts: Vec<u128>,
+        receiver: AccountId,
+    ) -> Result<Vec<u128>, StablePoolError> {
+        let _ = session.set_actor(caller);
+        handle_contract_result(
+            session
+                .execute(
+                    stable_pool_contract::Instance::from(stable_pool).flash_loan(
+                        tokens,
+                        amounts,
+                        receiver,
+                        vec![],
+                    ),
+                )
+                .unwrap(),
+        )
+    }
+
+    pub fn reserves(session: &mut Session<MinimalRuntime>, stable_pool: AccountId) -> Vec<u128> {
+        handle_contract_result(
+            session
+                .query(stable_pool_contract::Instance::from(stable_pool).reserves())
+                .unwrap(),
+        )
+    }
+
+    pub fn amp_coef(
+        session: &mut Session<MinimalRuntime>,
+        stable_pool: AccountId,
+    ) -> Result<u128, StablePoolError> {
+        handle_contract_result(
+            session
+                .query(stable_pool_contract::Instance::from(stable_pool).amp_coef())
+                .unwrap(),
+        )
+    }
+
+    pub fn fees(session: &mut Session<MinimalRuntime>, stable_pool: AccountId) -> (u32, u32) {
+        handle_contract_result(
+            session
+                .query(stable_pool_contract::Instance::from(stable_pool).fees())
+                .unwrap(),
+        )
+    }
+
+    pub fn token_rates(session: &mut Session<MinimalRuntime>, stable_pool: AccountId) -> Vec<u128> {
+        handle_contract_result(
+            session
+                .query(stable_pool_contract::Instance::from(stable_pool).token_rates())
+                .unwrap(),
+        )
+    }
+
+    pub fn token_rates_providers(
+        session: &mut Session<MinimalRuntime>,
+        stable_pool: AccountId,
+    ) -> Vec<Option<AccountId>> {
+        handle_contract_result(
+            session
+                .query(stable_pool_contract::Instance::from(stable_pool).token_rates_providers())
+                .unwrap(),
+        )
+    }
+
+    pub fn set_token_rate_provider(
+        session: &mut Session<MinimalRuntime>,
+        stable_pool: AccountId,
+        token: AccountId,
+        rate_provider: Option<AccountId>,
+        caller: drink::AccountId32,
+    ) -> Result<(), StablePoolError> {
+        let _ = session.set_actor(caller);
+        handle_contract_result(
+            session
+                .execute(
+                    stable_pool_contract::Instance::from(stable_pool)
+                        .set_token_rate_provider(token, rate_provider),
+                )
+                .unwrap(),
+        )
+    }
+
+    pub fn apply_token_rate_provider(
+        session: &mut Session<MinimalRuntime>,
+        stable_pool: AccountId,
+        token: AccountId,
+        caller: drink::AccountId32,
+    ) -> Result<(), StablePoolError> {
+        let _ = session.set_actor(caller);
+        handle_contract_result(
+            session
+                .execute(
+                    stable_pool_contract::Instance::from(stable_pool)
+                        .apply_token_rate_provider(token),
+                )
+                .unwrap(),
+        )
+    }
+
+    pub fn tokens(session: &mut Session<MinimalRuntime>, stable_pool: AccountId) -> Vec<AccountId> {
+        handle_contract_result(
+            session
+                .query(stable_pool_contract::Instance::from(stable_pool).tokens())
+                .unwrap(),
+        )
+    }
+
+    pub fn virtual_price(
+        session: &mut Session<MinimalRuntime>,
+        stable_pool: AccountId,
+    ) -> Result<u128, StablePoolError> {
+        handle_contract_result(
+            session
+                .query(stable_pool_contract::Instance::from(stable_pool).virtual_price())
+                .unwrap(),
+        )
+    }
+
+    pub fn invariant_d(
+        session: &mut Session<MinimalRuntime>,
+        stable_pool: AccountId,
+    ) -> Result<u128, StablePoolError> {
+        handle_contract_result(
+            session
+                .query(stable_pool_contract::Instance::from(stable_pool).invariant_d())
+                .unwrap(),
+        )
+    }
+
+    pub fn get_twap_price(
+        session: &mut Session<MinimalRuntime>,
+        stable_pool: AccountId,
+        token_in: AccountId,
+        token_out: AccountId,
+        window: u64,
+    ) -> Result<u128, StablePoolError> {
+        handle_contract_result(
+            session
+                .query(
+                    stable_pool_contract::Instance::from(stable_pool)
+                        .get_twap_price(token_in, token_out, window),
+                )
+                .unwrap(),
+        )
+    }
+
+    pub fn get_twap_virtual_price(
+        session: &mut Session<MinimalRuntime>,
+        stable_pool: AccountId,
+        window: u64,
+    ) -> Result<u128, StablePoolError> {
+        handle_contract_result(
+            session
+                .query(
+                    stable_pool_contract::Instance::from(stable_pool)
+                        .get_twap_virtual_price(window),
+                )
+                .unwrap(),
+        )
+    }
+
+    pub fn get_amounts_for_liquidity_burn(
+        session: &mut Session<MinimalRuntime>,
+        stable_pool: AccountId,
+        liquidity: u128,
+    ) -> Result<Vec<u128>, StablePoolError> {
+        handle_contract_result(
+            session
+                .query(
+                    stable_pool_contract::Instance::from(stable_pool)
+                        .get_amounts_for_liquidity_burn(liquidity),
+                )
+                .unwrap(),
+        )
+    }
+
+    pub fn get_amount_for_one_coin_burn(
+        session: &mut Session<MinimalRuntime>,
+        stable_pool: AccountId,
+        liquidity: u128,
+        token: AccountId,
+    ) -> Result<(u128, u128), StablePoolError> {
+        handle_contract_result(
+            session
+                .query(
+                    stable_pool_contract::Instance::from(stable_pool)
+                        .get_amount_for_one_coin_burn(liquidity, token),
+                )
+                .unwrap(),
+        )
+    }
+
+    pub fn get_amounts_for_liquidity_mint(
+        session: &mut Session<MinimalRuntime>,
+        stable_pool: AccountId,
+        liquidity: u128,
+    ) -> Result<Vec<u128>, StablePoolError> {
+        handle_contract_result(
+            session
+                .query(
+                    stable_pool_contract::Instance::from(stable_pool)
+                        .get_amounts_for_liquidity_mint(liquidity),
+                )
+                .unwrap(),
+        )
+    }
+
+    pub fn get_burn_liquidity_for_amounts(
+        session: &mut Session<MinimalRuntime>,
+        stable_pool: AccountId,
+        amounts: Vec<u128>,
+    ) -> Result<(u128, u128), StablePoolError> {
+        handle_contract_result(
+            session
+                .query(
+                    stable_pool_contract::Instance::from(stable_pool)
+                        .get_burn_liquidity_for_amounts(amounts),
+                )
+                .unwrap(),
+        )
+    }
+
+    #[allow(dead_code)]
+    pub fn get_mint_liquidity_for_amounts(
+        session: &mut Session<MinimalRuntime>,
+        stable_pool: AccountId,
+        amounts: Vec<u128>,
+    ) -> Result<(u128, u128), StablePoolError> {
+        handle_contract_result(
+            session
+                .query(
+                    stable_pool_contract::Instance::from(stable_pool)
+                        .get_mint_liquidity_for_amounts(amounts),
+                )
+                .unwrap(),
+        )
+    }
+
+    pub fn get_swap_amount_out(
+        session: &mut Session<MinimalRuntime>,
+        stable_pool: AccountId,
+        token_in: AccountId,
+        token_out: AccountId,
+        token_in_amount: u128,
+    ) -> Result<(u128, u128), StablePoolError> {
+        handle_contract_result(
+            session
+                .query(
+                    stable_pool_contract::Instance::from(stable_pool).get_swap_amount_out(
+                        token_in,
+                        token_out,
+                        token_in_amount,
+                    ),
+                )
+                .unwrap(),
+        )
+    }
+
+    pub fn get_swap_amount_in(
+        session: &mut Session<MinimalRuntime>,
+        stable_pool: AccountId,
+        token_in: AccountId,
+        token_out: AccountId,
+        token_out_amount: u128,
+    ) -> Result<(u128, u128), StablePoolError> {
+        handle_contract_result(
+            session
+                .query(
+                    stable_pool_contract::Instance::from(stable_pool).get_swap_amount_in(
+                        token_in,
+                        token_out,
+                        token_out_amount,
+                    ),
+                )
+                .unwrap(),
+        )
+    }
+}
+
+pub mod concentrated_pool {
+    use super::*;
+    use concentrated_pool_contract::{ConcentratedPool as _, ConcentratedPoolError, Position};
+
+    /// Creates a pool with the initial sqrt price given as a Q64.96 number.
+    pub fn setup(
+        session: &mut Session<MinimalRuntime>,
+        token_0: AccountId,
+        token_1: AccountId,
+        fee: u32,
+        tick_spacing: i32,
+        sqrt_price: primitive_types::U256,
+        caller: drink::AccountId32,
+    ) -> concentrated_pool_contract::Instance {
+        let _ = session.set_actor(caller);
+        let instance = concentrated_pool_contract::Instance::new(
+            token_0,
+            token_1,
+            fee,
+            tick_spacing,
+            concentrated_pool_contract::WrappedU256(concentrated_pool_contract::U256(sqrt_price.0)),
+        );
+
+        session
+            .instantiate(instance)
+            .unwrap()
+            .result
+            .to_account_id()
+            .into()
+    }
+
+    pub fn mint_position(
+        session: &mut Session<MinimalRuntime>,
+        pool: AccountId,
+        caller: drink::AccountId32,
+        tick_lower: i32,
+        tick_upper: i32,
+        liquidity: u128,
+        amount_0_max: u128,
+        amount_1_max: u128,
+        to: AccountId,
+    ) -> Result<(u128, u128, u128), ConcentratedPoolError> {
+        let _ = session.set_actor(caller);
+        handle_contract_result(
+            session
+                .execute(
+                    concentrated_pool_contract::Instance::from(pool).mint_position(
+                        tick_lower,
+                        tick_upper,
+                        liquidity,
+                        amount_0_max,
+                        amount_1_max,
+                        to,
+                    ),
+                )
+                .unwrap(),
+        )
+    }
+
+    pub fn decrease_liquidity(
+        session: &mut Session<MinimalRuntime>,
+        pool: AccountId,
+        caller: drink::AccountId32,
+        position_id: u128,
+        liquidity: u128,
+        amount_0_min: u128,
+        amount_1_min: u128,
+    ) -> Result<(u128, u128), ConcentratedPoolError> {
+        let _ = session.set_actor(caller);
+        handle_contract_result(
+            session
+                .execute(
+                    concentrated_pool_contract::Instance::from(pool).decrease_liquidity(
+                        position_id,
+                        liquidity,
+                        amount_0_min,
+                        amount_1_min,
+                    ),
+                )
+                .unwrap(),
+        )
+    }
+
+    pub fn collect(
+        session: &mut Session<MinimalRuntime>,
+        pool: AccountId,
+        caller: drink::AccountId32,
+        position_id: u128,
+        to: AccountId,
+    ) -> Result<(u128, u128), ConcentratedPoolError> {
+        let _ = session.set_actor(caller);
+        handle_contract_result(
+            session
+                .execute(concentrated_pool_contract::Instance::from(pool).collect(position_id, to))
+                .unwrap(),
+        )
+    }
+
+    pub fn burn_position(
+        session: &mut Session<MinimalRuntime>,
+        pool: AccountId,
+        caller: drink::AccountId32,
+        position_id: u128,
+    ) -> Result<(), ConcentratedPoolError> {
+        let _ = session.set_actor(caller);
+        handle_contract_result(
+            session
+                .execute(
+                    concentrated_pool_contract::Instance::from(pool).burn_position(position_id),
+                )
+                .unwrap(),
+        )
+    }
+
+    pub fn transfer_position(
+        session: &mut Session<MinimalRuntime>,
+        pool: AccountId,
+        caller: drink::AccountId32,
+        position_id: u128,
+        to: AccountId,
+    ) -> Result<(), ConcentratedPoolError> {
+        let _ = session.set_actor(caller);
+        handle_contract_result(
+            session
+                .execute(
+                    concentrated_pool_contract::Instance::from(pool)
+                        .transfer_position(position_id, to),
+                )
+                .unwrap(),
+        )
+    }
+
+    pub fn swap_exact_in(
+        session: &mut Session<MinimalRuntime>,
+        pool: AccountId,
+        caller: drink::AccountId32,
+        token_in: AccountId,
+        token_out: AccountId,
+        token_in_amount: u128,
+        min_token_out_amount: u128,
+        to: AccountId,
+    ) -> Result<(u128, u128), ConcentratedPoolError> {
+        let _ = session.set_actor(caller);
+        handle_contract_result(
+            session
+                .execute(
+                    concentrated_pool_contract::Instance::from(pool).swap_exact_in(
+                        token_in,
+                        token_out,
+                        token_in_amount,
+                        min_token_out_amount,
+                        to,
+                    ),
+                )
+                .unwrap(),
+        )
+    }
+
+    pub fn swap_exact_out(
+        session: &mut Session<MinimalRuntime>,
+        pool: AccountId,
+        caller: drink::AccountId32,
+        token_in: AccountId,
+        token_out: AccountId,
+        token_out_amount: u128,
+        max_token_in_amount: u128,
+        to: AccountId,
+    ) -> Result<(u128, u128), ConcentratedPoolError> {
+        let _ = session.set_actor(caller);
+        handle_contract_result(
+            session
+                .execute(
+                    concentrated_pool_contract::Instance::from(pool).swap_exact_out(
+                        token_in,
+                        token_out,
+                        token_out_amount,
+                        max_token_in_amount,
+                        to,
+                    ),
+                )
+                .unwrap(),
+        )
+    }
+
+    pub fn get_swap_amount_out(
+        session: &mut Session<MinimalRuntime>,
+        pool: AccountId,
+        token_in: AccountId,
+        token_out: AccountId,
+        token_in_amount: u128,
+    ) -> Result<(u128, u128), ConcentratedPoolError> {
+        handle_contract_result(
+            session
+                .query(
+                    concentrated_pool_contract::Instance::from(pool).get_swap_amount_out(
+                        token_in,
+                        token_out,
+                        token_in_amount,
+                    ),
+                )
+                .unwrap(),
+        )
+    }
+
+    pub fn get_amounts_for_liquidity(
+        session: &mut Session<MinimalRuntime>,
+        pool: AccountId,
+        tick_lower: i32,
+        tick_upper: i32,
+        liquidity: u128,
+    ) -> Result<(u128, u128), ConcentratedPoolError> {
+        handle_contract_result(
+            session
+                .query(
+                    concentrated_pool_contract::Instance::from(pool)
+                        .get_amounts_for_liquidity(tick_lower, tick_upper, liquidity),
+                )
+                .unwrap(),
+        )
+    }
+
+    pub fn position(
+        session: &mut Session<MinimalRuntime>,
+        pool: AccountId,
+        position_id: u128,
+    ) -> Option<Position> {
+        handle_contract_result(
+            session
+                .query(concentrated_pool_contract::Instance::from(pool).position(position_id))
+                .unwrap(),
+        )
+    }
+
+    pub fn reserves(session: &mut Session<MinimalRuntime>, pool: AccountId) -> (u128, u128) {
+        handle_contract_result(
+            session
+                .query(concentrated_pool_contract::Instance::from(pool).reserves())
+                .unwrap(),
+        )
+    }
+
+    pub fn liquidity(session: &mut Session<MinimalRuntime>, pool: AccountId) -> u128 {
+        handle_contract_result(
+            session
+                .query(concentrated_pool_contract::Instance::from(pool).liquidity())
+                .unwrap(),
+        )
+    }
+
+    pub fn current_tick(session: &mut Session<MinimalRuntime>, pool: AccountId) -> i32 {
+        handle_contract_result(
+            session
+                .query(concentrated_pool_contract::Instance::from(pool).current_tick())
+                .unwrap(),
+        )
+    }
+}
+
+pub mod weighted_pool {
+    use super::*;
+    use weighted_pool_contract::{WeightedPool as _, WeightedPoolError};
+
+    pub fn setup(
+        session: &mut Session<MinimalRuntime>,
+        tokens: Vec<AccountId>,
+        tokens_decimals: Vec<u8>,
+        weights: Vec<u128>,
+        caller: drink::AccountId32,
+        trade_fee: u32,
+        protocol_fee: u32,
+        fee_receiver: Option<AccountId>,
+    ) -> weighted_pool_contract::Instance {
+        let _ = session.set_actor(caller.clone());
+        let instance = weighted_pool_contract::Instance::new(
+            tokens,
+            tokens_decimals,
+            weights,
+            caller.to_account_id(),
+            trade_fee,
+            protocol_fee,
+            fee_receiver,
+        );
+
+        session
+            .instantiate(instance)
+            .unwrap()
+            .result
+            .to_account_id()
+            .into()
+    }
+
+    pub fn add_liquidity(
+        session: &mut Session<MinimalRuntime>,
+        pool: AccountId,
+        caller: drink::AccountId32,
+        min_share_amount: u128,
+        amounts: Vec<u128>,
+        to: AccountId,
+    ) -> Result<u128, WeightedPoolError> {
+        let _ = session.set_actor(caller);
+        handle_contract_result(
+            session
+                .execute(weighted_pool_contract::Instance::from(pool).add_liquidity(
+                    min_share_amount,
+                    amounts,
+                    to,
+                ))
+                .unwrap(),
+        )
+    }
+
+    pub fn add_liquidity_one_coin(
+        session: &mut Session<MinimalRuntime>,
+        pool: AccountId,
+        caller: drink::AccountId32,
+        token: AccountId,
+        amount: u128,
+        min_share_amount: u128,
+        to: AccountId,
+    ) -> Result<(u128, u128), WeightedPoolError> {
+        let _ = session.set_actor(caller);
+        handle_contract_result(
+            session
+                .execute(
+                    weighted_pool_contract::Instance::from(pool).add_liquidity_one_coin(
+                        token,
+                        amount,
+                        min_share_amount,
+                        to,
+                    ),
+                )
+                .unwrap(),
+        )
+    }
+
+    pub fn remove_liquidity_by_shares(
+        session: &mut Session<MinimalRuntime>,
+        pool: AccountId,
+        caller: drink::AccountId32,
+        shares_amount: u128,
+        min_amounts: Vec<u128>,
+        to: AccountId,
+    ) -> Result<Vec<u128>, WeightedPoolError> {
+        let _ = session.set_actor(caller);
+        handle_contract_result(
+            session
+                .execute(
+                    weighted_pool_contract::Instance::from(pool).remove_liquidity_by_shares(
+                        shares_amount,
+                        min_amounts,
+                        to,
+                    ),
+                )
+                .unwrap(),
+        )
+    }
+
+    pub fn remove_liquidity_one_coin(
+        session: &mut Session<MinimalRuntime>,
+        pool: AccountId,
+        caller: drink::AccountId32,
+        shares_amount: u128,
+        token: AccountId,
+        min_amount: u128,
+        to: AccountId,
+    ) -> Result<(u128, u128), WeightedPoolError> {
+        let _ = session.set_actor(caller);
+        handle_contract_result(
+            session
+                .execute(
+                    weighted_pool_contract::Instance::from(pool).remove_liquidity_one_coin(
+                        shares_amount,
+                        token,
+                        min_amount,
+                        to,
+                    ),
+                )
+                .unwrap(),
+        )
+    }
+
+    pub fn swap_exact_in(
+        session: &mut Session<MinimalRuntime>,
+        pool: AccountId,
+        caller: drink::AccountId32,
+        token_in: AccountId,
+        token_out: AccountId,
+        token_in_amount: u128,
+        min_token_out_amount: u128,
+        to: AccountId,
+    ) -> Result<(u128, u128), WeightedPoolError> {
+        let _ = session.set_actor(caller);
+        handle_contract_result(
+            session
+                .execute(weighted_pool_contract::Instance::from(pool).swap_exact_in(
+                    token_in,
+                    token_out,
+                    token_in_amount,
+                    min_token_out_amount,
+                    to,
+                ))
+                .unwrap(),
+        )
+    }
+
+    pub fn swap_exact_out(
+        session: &mut Session<MinimalRuntime>,
+        pool: AccountId,
+        caller: drink::AccountId32,
+        token_in: AccountId,
+        token_out: AccountId,
+        token_out_amount: u128,
+        max_token_in_amount: u128,
+        to: AccountId,
+    ) -> Result<(u128, u128), WeightedPoolError> {
+        let _ = session.set_actor(caller);
+        handle_contract_result(
+            session
+                .execute(weighted_pool_contract::Instance::from(pool).swap_exact_out(
+                    token_in,
+                    token_out,
+                    token_out_amount,
+                    max_token_in_amount,
+                    to,
+                ))
+                .unwrap(),
+        )
+    }
 
+    pub fn get_swap_amount_out(
+        session: &mut Session<MinimalRuntime>,
+        pool: AccountId,
+        token_in: AccountId,
+        token_out: AccountId,
+        token_in_amount: u128,
+    ) -> Result<(u128, u128), WeightedPoolError> {
         handle_contract_result(
             session
-                .execute(PSP22::increase_allowance(&token.into(), spender, amount))
+                .query(
+                    weighted_pool_contract::Instance::from(pool).get_swap_amount_out(
+                        token_in,
+                        token_out,
+                        token_in_amount,
+                    ),
+                )
                 .unwrap(),
         )
     }
 
-    /// Increases allowance of given token to given spender by given amount.
-    pub fn transfer(
+    pub fn get_swap_amount_in(
         session: &mut Session<MinimalRuntime>,
-        token: AccountId,
-        to: AccountId,
-        amount: u128,
-        caller: drink::AccountId32,
-    ) -> Result<(), psp22::PSP22Error> {
-        let _ = session.set_actor(caller);
-
+        pool: AccountId,
+        token_in: AccountId,
+        token_out: AccountId,
+        token_out_amount: u128,
+    ) -> Result<(u128, u128), WeightedPoolError> {
         handle_contract_result(
             session
-                .execute(PSP22::transfer(&token.into(), to, amount, [].to_vec()))
+                .query(
+                    weighted_pool_contract::Instance::from(pool).get_swap_amount_in(
+                        token_in,
+                        token_out,
+                        token_out_amount,
+                    ),
+                )
                 .unwrap(),
         )
     }
 
-    /// Returns balance of given token for given account.
-    /// Fails if anything other than success.
-    pub fn balance_of(
+    pub fn get_amount_for_one_coin_burn(
         session: &mut Session<MinimalRuntime>,
+        pool: AccountId,
+        shares: u128,
         token: AccountId,
-        account: AccountId,
-    ) -> u128 {
+    ) -> Result<(u128, u128), WeightedPoolError> {
         handle_contract_result(
             session
-                .query(PSP22::balance_of(&token.into(), account))
+                .query(
+                    weighted_pool_contract::Instance::from(pool)
+                        .get_amount_for_one_coin_burn(shares, token),
+                )
                 .unwrap(),
         )
     }
 
-    pub fn total_supply(session: &mut Session<MinimalRuntime>, token: AccountId) -> u128 {
-        handle_contract_result(session.query(PSP22::total_supply(&token.into())).unwrap())
-    }
-
-    pub fn token_decimals(session: &mut Session<MinimalRuntime>, token: AccountId) -> u8 {
-        handle_contract_result(session.query(PSP22::token_decimals(&token.into())).unwrap())
+    pub fn reserves(session: &mut Session<MinimalRuntime>, pool: AccountId) -> Vec<u128> {
+        handle_contract_result(
+            session
+                .query(weighted_pool_contract::Instance::from(pool).reserves())
+                .unwrap(),
+        )
     }
 }
 
-pub mod stable_swap {
+pub mod crypto_pool {
     use super::*;
-    use stable_pool_contract::{StablePool as _, StablePoolError};
+    use crypto_pool_contract::{CryptoPool as _, CryptoPoolError};
+
+    /// Parameters of the crypto pool, see the `CryptoPoolContract` constructor.
+    pub struct CryptoPoolParams {
+        pub amp: u128,
+        pub gamma: u128,
+        pub mid_fee: u32,
+        pub out_fee: u32,
+        pub fee_gamma: u128,
+        pub allowed_extra_profit: u128,
+        pub adjustment_step: u128,
+        pub ma_half_time: u64,
+        pub initial_price: u128,
+    }
 
     pub fn setup(
         session: &mut Session<MinimalRuntime>,
         tokens: Vec<AccountId>,
         tokens_decimals: Vec<u8>,
-        init_amp_coef: u128,
+        params: CryptoPoolParams,
         caller: drink::AccountId32,
-        trade_fee: u32,
         protocol_fee: u32,
         fee_receiver: Option<AccountId>,
-    ) -> stable_pool_contract::Instance {
+    ) -> crypto_pool_contract::Instance {
         let _ = session.set_actor(caller.clone());
-        let instance = stable_pool_contract::Instance::new_stable(
+        let instance = crypto_pool_contract::Instance::new(
             tokens,
             tokens_decimals,
-            init_amp_coef,
+            params.amp,
+            params.gamma,
+            params.mid_fee,
+            params.out_fee,
+            params.fee_gamma,
+            params.allowed_extra_profit,
+            params.adjustment_step,
+            params.ma_half_time,
+            params.initial_price,
             caller.to_account_id(),
-            trade_fee,
             protocol_fee,
             fee_receiver,
         );
@@ -746,41 +2801,39 @@ pub mod stable_swap {
 
     pub fn add_liquidity(
         session: &mut Session<MinimalRuntime>,
-        stable_pool: AccountId,
+        pool: AccountId,
         caller: drink::AccountId32,
         min_share_amount: u128,
         amounts: Vec<u128>,
         to: AccountId,
-    ) -> Result<(u128, u128), StablePoolError> {
+    ) -> Result<u128, CryptoPoolError> {
         let _ = session.set_actor(caller);
         handle_contract_result(
             session
-                .execute(
-                    stable_pool_contract::Instance::from(stable_pool).add_liquidity(
-                        min_share_amount,
-                        amounts,
-                        to,
-                    ),
-                )
+                .execute(crypto_pool_contract::Instance::from(pool).add_liquidity(
+                    min_share_amount,
+                    amounts,
+                    to,
+                ))
                 .unwrap(),
         )
     }
 
-    pub fn remove_liquidity_by_amounts(
+    pub fn remove_liquidity_by_shares(
         session: &mut Session<MinimalRuntime>,
-        stable_pool: AccountId,
+        pool: AccountId,
         caller: drink::AccountId32,
-        max_share_amount: u128,
-        amounts: Vec<u128>,
+        shares_amount: u128,
+        min_amounts: Vec<u128>,
         to: AccountId,
-    ) -> Result<(u128, u128), StablePoolError> {
+    ) -> Result<Vec<u128>, CryptoPoolError> {
         let _ = session.set_actor(caller);
         handle_contract_result(
             session
                 .execute(
-                    stable_pool_contract::Instance::from(stable_pool).remove_liquidity_by_amounts(
-                        max_share_amount,
-                        amounts,
+                    crypto_pool_contract::Instance::from(pool).remove_liquidity_by_shares(
+                        shares_amount,
+                        min_amounts,
                         to,
                     ),
                 )
@@ -788,21 +2841,23 @@ pub mod stable_swap {
         )
     }
 
-    pub fn remove_liquidity_by_shares(
+    pub fn remove_liquidity_one_coin(
         session: &mut Session<MinimalRuntime>,
-        stable_pool: AccountId,
+        pool: AccountId,
         caller: drink::AccountId32,
         shares_amount: u128,
-        min_amounts: Vec<u128>,
+        token: AccountId,
+        min_amount: u128,
         to: AccountId,
-    ) -> Result<Vec<u128>, StablePoolError> {
+    ) -> Result<u128, CryptoPoolError> {
         let _ = session.set_actor(caller);
         handle_contract_result(
             session
                 .execute(
-                    stable_pool_contract::Instance::from(stable_pool).remove_liquidity_by_shares(
+                    crypto_pool_contract::Instance::from(pool).remove_liquidity_one_coin(
                         shares_amount,
-                        min_amounts,
+                        token,
+                        min_amount,
                         to,
                     ),
                 )
@@ -812,220 +2867,152 @@ pub mod stable_swap {
 
     pub fn swap_exact_in(
         session: &mut Session<MinimalRuntime>,
-        stable_pool: AccountId,
+        pool: AccountId,
         caller: drink::AccountId32,
         token_in: AccountId,
         token_out: AccountId,
         token_in_amount: u128,
         min_token_out_amount: u128,
         to: AccountId,
-    ) -> Result<(u128, u128), StablePoolError> {
+    ) -> Result<(u128, u128), CryptoPoolError> {
         let _ = session.set_actor(caller);
         handle_contract_result(
             session
-                .execute(
-                    stable_pool_contract::Instance::from(stable_pool).swap_exact_in(
-                        token_in,
-                        token_out,
-                        token_in_amount,
-                        min_token_out_amount,
-                        to,
-                    ),
-                )
+                .execute(crypto_pool_contract::Instance::from(pool).swap_exact_in(
+                    token_in,
+                    token_out,
+                    token_in_amount,
+                    min_token_out_amount,
+                    to,
+                ))
                 .unwrap(),
         )
     }
 
     pub fn swap_exact_out(
         session: &mut Session<MinimalRuntime>,
-        stable_pool: AccountId,
+        pool: AccountId,
         caller: drink::AccountId32,
         token_in: AccountId,
         token_out: AccountId,
         token_out_amount: u128,
         max_token_in_amount: u128,
         to: AccountId,
-    ) -> Result<(u128, u128), StablePoolError> {
+    ) -> Result<(u128, u128), CryptoPoolError> {
         let _ = session.set_actor(caller);
         handle_contract_result(
             session
-                .execute(
-                    stable_pool_contract::Instance::from(stable_pool).swap_exact_out(
-                        token_in,
-                        token_out,
-                        token_out_amount,
-                        max_token_in_amount,
-                        to,
-                    ),
-                )
+                .execute(crypto_pool_contract::Instance::from(pool).swap_exact_out(
+                    token_in,
+                    token_out,
+                    token_out_amount,
+                    max_token_in_amount,
+                    to,
+                ))
                 .unwrap(),
         )
     }
 
-    pub fn swap_received(
+    pub fn get_swap_amount_out(
         session: &mut Session<MinimalRuntime>,
-        stable_pool: AccountId,
-        caller: drink::AccountId32,
+        pool: AccountId,
         token_in: AccountId,
         token_out: AccountId,
-        min_token_out_amount: u128,
-        to: AccountId,
-    ) -> Result<(u128, u128), StablePoolError> {
-        let _ = session.set_actor(caller);
+        token_in_amount: u128,
+    ) -> Result<(u128, u128), CryptoPoolError> {
         handle_contract_result(
             session
-                .execute(
-                    stable_pool_contract::Instance::from(stable_pool).swap_received(
+                .query(
+                    crypto_pool_contract::Instance::from(pool).get_swap_amount_out(
                         token_in,
                         token_out,
-                        min_token_out_amount,
-                        to,
+                        token_in_amount,
                     ),
                 )
                 .unwrap(),
         )
     }
 
-    pub fn reserves(session: &mut Session<MinimalRuntime>, stable_pool: AccountId) -> Vec<u128> {
-        handle_contract_result(
-            session
-                .query(stable_pool_contract::Instance::from(stable_pool).reserves())
-                .unwrap(),
-        )
-    }
-
-    pub fn amp_coef(
+    pub fn get_swap_amount_in(
         session: &mut Session<MinimalRuntime>,
-        stable_pool: AccountId,
-    ) -> Result<u128, StablePoolError> {
-        handle_contract_result(
-            session
-                .query(stable_pool_contract::Instance::from(stable_pool).amp_coef())
-                .unwrap(),
-        )
-    }
-
-    pub fn fees(session: &mut Session<MinimalRuntime>, stable_pool: AccountId) -> (u32, u32) {
-        handle_contract_result(
-            session
-                .query(stable_pool_contract::Instance::from(stable_pool).fees())
-                .unwrap(),
-        )
-    }
-
-    pub fn token_rates(session: &mut Session<MinimalRuntime>, stable_pool: AccountId) -> Vec<u128> {
-        handle_contract_result(
-            session
-                .query(stable_pool_contract::Instance::from(stable_pool).token_rates())
-                .unwrap(),
-        )
-    }
-
-    pub fn tokens(session: &mut Session<MinimalRuntime>, stable_pool: AccountId) -> Vec<AccountId> {
+        pool: AccountId,
+        token_in: AccountId,
+        token_out: AccountId,
+        token_out_amount: u128,
+    ) -> Result<(u128, u128), CryptoPoolError> {
         handle_contract_result(
             session
-                .query(stable_pool_contract::Instance::from(stable_pool).tokens())
+                .query(
+                    crypto_pool_contract::Instance::from(pool).get_swap_amount_in(
+                        token_in,
+                        token_out,
+                        token_out_amount,
+                    ),
+                )
                 .unwrap(),
         )
     }
 
-    pub fn get_amounts_for_liquidity_burn(
+    pub fn get_amount_for_one_coin_burn(
         session: &mut Session<MinimalRuntime>,
-        stable_pool: AccountId,
-        liquidity: u128,
-    ) -> Result<Vec<u128>, StablePoolError> {
+        pool: AccountId,
+        shares: u128,
+        token: AccountId,
+    ) -> Result<u128, CryptoPoolError> {
         handle_contract_result(
             session
                 .query(
-                    stable_pool_contract::Instance::from(stable_pool)
-                        .get_amounts_for_liquidity_burn(liquidity),
+                    crypto_pool_contract::Instance::from(pool)
+                        .get_amount_for_one_coin_burn(shares, token),
                 )
                 .unwrap(),
         )
     }
 
-    pub fn get_amounts_for_liquidity_mint(
-        session: &mut Session<MinimalRuntime>,
-        stable_pool: AccountId,
-        liquidity: u128,
-    ) -> Result<Vec<u128>, StablePoolError> {
+    pub fn reserves(session: &mut Session<MinimalRuntime>, pool: AccountId) -> Vec<u128> {
         handle_contract_result(
             session
-                .query(
-                    stable_pool_contract::Instance::from(stable_pool)
-                        .get_amounts_for_liquidity_mint(liquidity),
-                )
+                .query(crypto_pool_contract::Instance::from(pool).reserves())
                 .unwrap(),
         )
     }
 
-    pub fn get_burn_liquidity_for_amounts(
+    pub fn get_mint_liquidity_for_amounts(
         session: &mut Session<MinimalRuntime>,
-        stable_pool: AccountId,
+        pool: AccountId,
         amounts: Vec<u128>,
-    ) -> Result<(u128, u128), StablePoolError> {
+    ) -> Result<u128, CryptoPoolError> {
         handle_contract_result(
             session
                 .query(
-                    stable_pool_contract::Instance::from(stable_pool)
-                        .get_burn_liquidity_for_amounts(amounts),
+                    crypto_pool_contract::Instance::from(pool)
+                        .get_mint_liquidity_for_amounts(amounts),
                 )
                 .unwrap(),
         )
     }
 
-    #[allow(dead_code)]
-    pub fn get_mint_liquidity_for_amounts(
-        session: &mut Session<MinimalRuntime>,
-        stable_pool: AccountId,
-        amounts: Vec<u128>,
-    ) -> Result<(u128, u128), StablePoolError> {
+    pub fn price_scale(session: &mut Session<MinimalRuntime>, pool: AccountId) -> u128 {
         handle_contract_result(
             session
-                .query(
-                    stable_pool_contract::Instance::from(stable_pool)
-                        .get_mint_liquidity_for_amounts(amounts),
-                )
+                .query(crypto_pool_contract::Instance::from(pool).price_scale())
                 .unwrap(),
         )
     }
 
-    pub fn get_swap_amount_out(
-        session: &mut Session<MinimalRuntime>,
-        stable_pool: AccountId,
-        token_in: AccountId,
-        token_out: AccountId,
-        token_in_amount: u128,
-    ) -> Result<(u128, u128), StablePoolError> {
+    pub fn price_oracle(session: &mut Session<MinimalRuntime>, pool: AccountId) -> u128 {
         handle_contract_result(
             session
-                .query(
-                    stable_pool_contract::Instance::from(stable_pool).get_swap_amount_out(
-                        token_in,
-                        token_out,
-                        token_in_amount,
-                    ),
-                )
+                .query(crypto_pool_contract::Instance::from(pool).price_oracle())
                 .unwrap(),
         )
+        .expect("Should return price oracle")
     }
 
-    pub fn get_swap_amount_in(
-        session: &mut Session<MinimalRuntime>,
-        stable_pool: AccountId,
-        token_in: AccountId,
-        token_out: AccountId,
-        token_out_amount: u128,
-    ) -> Result<(u128, u128), StablePoolError> {
+    pub fn virtual_price(session: &mut Session<MinimalRuntime>, pool: AccountId) -> u128 {
         handle_contract_result(
             session
-                .query(
-                    stable_pool_contract::Instance::from(stable_pool).get_swap_amount_in(
-                        token_in,
-                        token_out,
-                        token_out_amount,
-                    ),
-                )
+                .query(crypto_pool_contract::Instance::from(pool).virtual_price())
                 .unwrap(),
         )
     }
@@ -0,0 +1,207 @@
+use crate::utils::*;
+use crate::weighted_pool_contract;
+
+use drink::{self, runtime::MinimalRuntime, session::Session};
+use ink_primitives::AccountId;
+use weighted_pool_contract::WeightedPoolError;
+
+const TRADE_FEE: u32 = 3_000_000;
+
+/// Sets up ICE and WOOD and a 80/20 pool of them. BOB approves the pool to spend both tokens.
+pub fn setup_weighted_pool(
+    session: &mut Session<MinimalRuntime>,
+) -> (AccountId, AccountId, AccountId) {
+    upload_all(session);
+    let ice = psp22_utils::setup(session, ICE.to_string(), BOB);
+    let wood = psp22_utils::setup(session, WOOD.to_string(), BOB);
+    let pool = weighted_pool::setup(
+        session,
+        vec![ice.into(), wood.into()],
+        vec![18, 18],
+        vec![8 * TOKEN / 10, 2 * TOKEN / 10],
+        BOB,
+        TRADE_FEE,
+        0,
+        None,
+    );
+    for token in [ice, wood] {
+        psp22_utils::increase_allowance(session, token.into(), pool.into(), u128::MAX, BOB)
+            .expect("Should increase allowance");
+    }
+    (pool.into(), ice.into(), wood.into())
+}
+
+#[drink::test]
+fn test_weighted_pool_swaps(mut session: Session) {
+    let (pool, ice, wood) = setup_weighted_pool(&mut session);
+
+    // reserves proportional to the weights make the spot price equal 1
+    weighted_pool::add_liquidity(
+        &mut session,
+        pool,
+        BOB,
+        1,
+        vec![800 * TOKEN, 200 * TOKEN],
+        bob(),
+    )
+    .expect("Should add liquidity");
+    assert_eq!(
+        weighted_pool::reserves(&mut session, pool),
+        vec![800 * TOKEN, 200 * TOKEN]
+    );
+
+    let amount_in = TOKEN;
+    let expected = weighted_pool::get_swap_amount_out(&mut session, pool, ice, wood, amount_in)
+        .expect("Should compute swap amount");
+    let wood_before = psp22_utils::balance_of(&mut session, wood, bob());
+    let (amount_out, fee) =
+        weighted_pool::swap_exact_in(&mut session, pool, BOB, ice, wood, amount_in, 0, bob())
+            .expect("Should swap");
+    assert_eq!((amount_out, fee), expected);
+    assert_eq!(fee, amount_in * TRADE_FEE as u128 / 1_000_000_000);
+    assert!(amount_out < amount_in - fee && amount_out > amount_in * 99 / 100);
+    assert_eq!(
+        psp22_utils::balance_of(&mut session, wood, bob()),
+        wood_before + amount_out
+    );
+
+    let amount_out = TOKEN;
+    let expected = weighted_pool::get_swap_amount_in(&mut session, pool, wood, ice, amount_out)
+        .expect("Should compute swap amount");
+    let ice_before = psp22_utils::balance_of(&mut session, ice, bob());
+    let (amount_in, _) = weighted_pool::swap_exact_out(
+        &mut session,
+        pool,
+        BOB,
+        wood,
+        ice,
+        amount_out,
+        u128::MAX,
+        bob(),
+    )
+    .expect("Should swap");
+    assert_eq!(amount_in, expected.0);
+    assert_eq!(
+        psp22_utils::balance_of(&mut session, ice, bob()),
+        ice_before + amount_out
+    );
+
+    // a single swap can't exceed 30% of the reserve
+    let res =
+        weighted_pool::swap_exact_in(&mut session, pool, BOB, ice, wood, 400 * TOKEN, 0, bob());
+    assert_eq!(res, Err(WeightedPoolError::MaxInRatioExceeded()));
+    let res = weighted_pool::swap_exact_out(
+        &mut session,
+        pool,
+        BOB,
+        ice,
+        wood,
+        100 * TOKEN,
+        u128::MAX,
+        bob(),
+    );
+    assert_eq!(res, Err(WeightedPoolError::MaxOutRatioExceeded()));
+}
+
+#[drink::test]
+fn test_weighted_pool_liquidity(mut session: Session) {
+    let (pool, _, wood) = setup_weighted_pool(&mut session);
+
+    let res = weighted_pool::add_liquidity(&mut session, pool, BOB, 1, vec![800 * TOKEN, 0], bob());
+    assert_eq!(res, Err(WeightedPoolError::ZeroAmounts()));
+    let shares = weighted_pool::add_liquidity(
+        &mut session,
+        pool,
+        BOB,
+        1,
+        vec![800 * TOKEN, 200 * TOKEN],
+        bob(),
+    )
+    .expect("Should add liquidity");
+    assert_eq!(psp22_utils::balance_of(&mut session, pool, bob()), shares);
+
+    // fee is charged only on the part of the deposit exceeding the token's weight
+    let (one_coin_shares, fee) =
+        weighted_pool::add_liquidity_one_coin(&mut session, pool, BOB, wood, 10 * TOKEN, 1, bob())
+            .expect("Should add liquidity");
+    assert_eq!(fee, 8 * TOKEN * TRADE_FEE as u128 / 1_000_000_000);
+    assert!(one_coin_shares > 0);
+
+    let expected =
+        weighted_pool::get_amount_for_one_coin_burn(&mut session, pool, one_coin_shares, wood)
+            .expect("Should compute withdraw amount");
+    let wood_before = psp22_utils::balance_of(&mut session, wood, bob());
+    let (amount, _) = weighted_pool::remove_liquidity_one_coin(
+        &mut session,
+        pool,
+        BOB,
+        one_coin_shares,
+        wood,
+        0,
+        bob(),
+    )
+    .expect("Should remove liquidity");
+    assert_eq!(amount, expected.0);
+    assert!(amount < 10 * TOKEN);
+    assert_eq!(
+        psp22_utils::balance_of(&mut session, wood, bob()),
+        wood_before + amount
+    );
+
+    let reserves = weighted_pool::reserves(&mut session, pool);
+    let amounts = weighted_pool::remove_liquidity_by_shares(
+        &mut session,
+        pool,
+        BOB,
+        shares / 2,
+        vec![1, 1],
+        bob(),
+    )
+    .expect("Should remove liquidity");
+    assert_eq!(
+        weighted_pool::reserves(&mut session, pool),
+        vec![reserves[0] - amounts[0], reserves[1] - amounts[1]]
+    );
+    assert_eq!(
+        psp22_utils::balance_of(&mut session, pool, bob()),
+        shares - shares / 2
+    );
+}
+
+#[drink::test]
+fn test_weighted_pool_imbalanced_liquidity(mut session: Session) {
+    let (pool, ice, wood) = setup_weighted_pool(&mut session);
+
+    let shares = weighted_pool::add_liquidity(
+        &mut session,
+        pool,
+        BOB,
+        1,
+        vec![800 * TOKEN, 200 * TOKEN],
+        bob(),
+    )
+    .expect("Should add liquidity");
+
+    // only the amounts proportional to the reserves are taken, the surplus of WOOD is not
+    let ice_before = psp22_utils::balance_of(&mut session, ice, bob());
+    let wood_before = psp22_utils::balance_of(&mut session, wood, bob());
+    let new_shares = weighted_pool::add_liquidity(
+        &mut session,
+        pool,
+        BOB,
+        1,
+        vec![80 * TOKEN, 100 * TOKEN],
+        bob(),
+    )
+    .expect("Should add liquidity");
+    assert_eq!(new_shares, shares / 10);
+    let ice_taken = ice_before - psp22_utils::balance_of(&mut session, ice, bob());
+    let wood_taken = wood_before - psp22_utils::balance_of(&mut session, wood, bob());
+    // up to the rounding of the shares
+    assert!(ice_taken <= 80 * TOKEN && 80 * TOKEN - ice_taken < TOKEN / 1_000_000);
+    assert!(wood_taken <= 20 * TOKEN && 20 * TOKEN - wood_taken < TOKEN / 1_000_000);
+    assert_eq!(
+        weighted_pool::reserves(&mut session, pool),
+        vec![800 * TOKEN + ice_taken, 200 * TOKEN + wood_taken]
+    );
+}
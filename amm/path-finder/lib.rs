@@ -0,0 +1,136 @@
+//! Off-chain counterpart of the `RouterV2::find_best_path` query.
+//!
+//! Searches a snapshot of the pools known to `RouterV2` (pairs registered in the pair `Factory`
+//! and stable pools created via the StablePool factory or registered in the router) for
+//! the `Vec<Step>` path with the highest output amount. Shares the search with the on-chain
+//! query, but does not perform any contract calls, so it can be run against any number
+//! of pools and hops.
+//!
+//! Like the on-chain query, it covers only pairs and stable pools. Concentrated, weighted
+//! and crypto pools, which `RouterV2` can swap through as well, have no snapshots here,
+//! so paths through them are not found.
+mod pool;
+
+pub use pool::{PairSnapshot, PoolSnapshot, StablePoolSnapshot};
+
+pub use traits::{find_best_path, PathFinderPool};
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ink::primitives::AccountId;
+    use traits::Step;
+
+    const ONE: u128 = 10u128.pow(18);
+    const ONE_USD: u128 = 10u128.pow(6);
+    const RATE_ONE: u128 = 10u128.pow(12);
+
+    fn account(id: u8) -> AccountId {
+        AccountId::from([id; 32])
+    }
+
+    fn pair(id: u8, token_0: u8, token_1: u8, reserve_0: u128, reserve_1: u128) -> PoolSnapshot {
+        PoolSnapshot::Pair(PairSnapshot {
+            id: account(id),
+            token_0: account(token_0),
+            token_1: account(token_1),
+            reserve_0,
+            reserve_1,
+            fee: 3,
+        })
+    }
+
+    fn step(token_in: u8, pool_id: u8) -> Step {
+        Step {
+            token_in: account(token_in),
+            pool_id: account(pool_id),
+        }
+    }
+
+    #[test]
+    fn finds_direct_path() {
+        let pools = vec![pair(100, 1, 2, 1_000 * ONE, 1_000 * ONE)];
+        let (path, amount_out) =
+            find_best_path(&pools, account(1), account(2), ONE, 1).expect("path should exist");
+        assert_eq!(path, vec![step(1, 100)]);
+        assert_eq!(
+            Some(amount_out),
+            pools[0].get_amount_out(account(1), account(2), ONE)
+        );
+    }
+
+    #[test]
+    fn prefers_deeper_multi_hop_path() {
+        let pools = vec![
+            // shallow direct pair
+            pair(100, 1, 2, 10 * ONE, 10 * ONE),
+            // deep pairs through token 3
+            pair(101, 1, 3, 10_000 * ONE, 10_000 * ONE),
+            pair(102, 3, 2, 10_000 * ONE, 10_000 * ONE),
+        ];
+        let (path, amount_out) =
+            find_best_path(&pools, account(1), account(2), 5 * ONE, 2).expect("path should exist");
+        assert_eq!(path, vec![step(1, 101), step(3, 102)]);
+        let direct_amount_out = pools[0]
+            .get_amount_out(account(1), account(2), 5 * ONE)
+            .unwrap();
+        assert!(amount_out > direct_amount_out);
+    }
+
+    #[test]
+    fn respects_max_hops() {
+        let pools = vec![
+            pair(100, 1, 2, 10 * ONE, 10 * ONE),
+            pair(101, 1, 3, 10_000 * ONE, 10_000 * ONE),
+            pair(102, 3, 2, 10_000 * ONE, 10_000 * ONE),
+        ];
+        let (path, _) =
+            find_best_path(&pools, account(1), account(2), 5 * ONE, 1).expect("path should exist");
+        assert_eq!(path, vec![step(1, 100)]);
+    }
+
+    #[test]
+    fn routes_through_stable_pool() {
+        let stable_pool = StablePoolSnapshot::new(
+            account(200),
+            vec![account(1), account(2)],
+            vec![6, 18],
+            vec![RATE_ONE, RATE_ONE],
+            vec![100_000 * ONE_USD, 100_000 * ONE],
+            1000,
+            (2_500_000, 200_000_000),
+        )
+        .unwrap();
+        let pools = vec![
+            pair(100, 1, 2, 1_000 * ONE_USD, 1_000 * ONE),
+            PoolSnapshot::StablePool(stable_pool),
+        ];
+        let (path, amount_out) = find_best_path(&pools, account(1), account(2), 100 * ONE_USD, 2)
+            .expect("path should exist");
+        assert_eq!(path, vec![step(1, 200)]);
+        // close to 1:1 exchange
+        assert!(amount_out > 99 * ONE && amount_out < 100 * ONE);
+    }
+
+    #[test]
+    fn skips_empty_pools() {
+        let pools = vec![
+            pair(100, 1, 2, 0, 0),
+            pair(101, 1, 3, 1_000 * ONE, 1_000 * ONE),
+            pair(102, 3, 2, 1_000 * ONE, 1_000 * ONE),
+        ];
+        let (path, _) =
+            find_best_path(&pools, account(1), account(2), ONE, 3).expect("path should exist");
+        assert_eq!(path, vec![step(1, 101), step(3, 102)]);
+    }
+
+    #[test]
+    fn returns_none_for_unreachable_token() {
+        let pools = vec![
+            pair(100, 1, 2, 1_000 * ONE, 1_000 * ONE),
+            pair(101, 3, 4, 1_000 * ONE, 1_000 * ONE),
+        ];
+        assert_eq!(find_best_path(&pools, account(1), account(4), ONE, 4), None);
+        assert_eq!(find_best_path(&pools, account(1), account(1), ONE, 4), None);
+    }
+}
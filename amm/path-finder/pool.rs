@@ -0,0 +1,166 @@
+use amm_helpers::{
+    constants::stable_pool::TOKEN_TARGET_DECIMALS,
+    math::casted_mul,
+    stable_swap_math::{fees::Fees, rated_swap_to},
+};
+use ink::primitives::AccountId;
+use traits::PathFinderPool;
+
+const PAIR_TRADING_FEE_DENOM: u128 = 1000;
+
+/// Snapshot of a `Pair` contract state.
+#[derive(Debug, Clone)]
+pub struct PairSnapshot {
+    pub id: AccountId,
+    pub token_0: AccountId,
+    pub token_1: AccountId,
+    /// Reserves as returned by `Pair::get_reserves`.
+    pub reserve_0: u128,
+    pub reserve_1: u128,
+    /// Fee as returned by `Pair::get_fee`.
+    pub fee: u8,
+}
+
+impl PairSnapshot {
+    /// Returns how much of `token_out` is received for `amount_in` of `token_in`.
+    /// Mirrors the quote of the `RouterV2` contract.
+    pub fn get_amount_out(
+        &self,
+        token_in: AccountId,
+        token_out: AccountId,
+        amount_in: u128,
+    ) -> Option<u128> {
+        let (reserve_in, reserve_out) = if (token_in, token_out) == (self.token_0, self.token_1) {
+            (self.reserve_0, self.reserve_1)
+        } else if (token_in, token_out) == (self.token_1, self.token_0) {
+            (self.reserve_1, self.reserve_0)
+        } else {
+            return None;
+        };
+        if amount_in == 0 || reserve_in == 0 || reserve_out == 0 {
+            return None;
+        }
+        // Adjusts for fees paid in the `token_in`.
+        let amount_in_with_fee = casted_mul(amount_in, PAIR_TRADING_FEE_DENOM - self.fee as u128);
+        let numerator = amount_in_with_fee.checked_mul(reserve_out.into())?;
+        let denominator =
+            casted_mul(reserve_in, PAIR_TRADING_FEE_DENOM).checked_add(amount_in_with_fee)?;
+        numerator.checked_div(denominator)?.try_into().ok()
+    }
+}
+
+/// Snapshot of a `StablePool` contract state.
+#[derive(Debug)]
+pub struct StablePoolSnapshot {
+    pub id: AccountId,
+    /// Tokens as returned by `StablePool::tokens`.
+    pub tokens: Vec<AccountId>,
+    /// Reserves as returned by `StablePool::reserves`.
+    pub reserves: Vec<u128>,
+    /// Token rates multiplied by the token precisions,
+    /// i.e. `rate * 10^(TOKEN_TARGET_DECIMALS - decimals)`.
+    pub scaled_rates: Vec<u128>,
+    /// Amplification coefficient as returned by `StablePool::amp_coef`.
+    pub amp_coef: u128,
+    pub fees: Fees,
+}
+
+impl StablePoolSnapshot {
+    /// Creates a snapshot from token rates as returned by `StablePool::token_rates`,
+    /// token decimals and fees as returned by `StablePool::fees`.
+    ///
+    /// Returns `None` if any of the decimals exceeds `TOKEN_TARGET_DECIMALS`.
+    pub fn new(
+        id: AccountId,
+        tokens: Vec<AccountId>,
+        tokens_decimals: Vec<u8>,
+        rates: Vec<u128>,
+        reserves: Vec<u128>,
+        amp_coef: u128,
+        (trade_fee, protocol_fee): (u32, u32),
+    ) -> Option<Self> {
+        let scaled_rates = rates
+            .iter()
+            .zip(tokens_decimals.iter())
+            .map(|(&rate, &decimals)| {
+                let precision = 10u128.pow(TOKEN_TARGET_DECIMALS.checked_sub(decimals)? as u32);
+                rate.checked_mul(precision)
+            })
+            .collect::<Option<Vec<u128>>>()?;
+        Some(Self {
+            id,
+            tokens,
+            reserves,
+            scaled_rates,
+            amp_coef,
+            fees: Fees::new(trade_fee, protocol_fee)?,
+        })
+    }
+
+    /// Returns how much of `token_out` is received for `amount_in` of `token_in`.
+    /// Mirrors `StablePool::get_swap_amount_out`.
+    pub fn get_amount_out(
+        &self,
+        token_in: AccountId,
+        token_out: AccountId,
+        amount_in: u128,
+    ) -> Option<u128> {
+        let token_in_id = self.tokens.iter().position(|&token| token == token_in)?;
+        let token_out_id = self.tokens.iter().position(|&token| token == token_out)?;
+        if token_in_id == token_out_id {
+            return None;
+        }
+        rated_swap_to(
+            &self.scaled_rates,
+            token_in_id,
+            amount_in,
+            token_out_id,
+            &self.reserves,
+            &self.fees,
+            self.amp_coef,
+        )
+        .map(|(amount_out, _)| amount_out)
+        .ok()
+    }
+}
+
+/// Snapshot of a pool known to the `RouterV2` contract.
+#[derive(Debug)]
+pub enum PoolSnapshot {
+    Pair(PairSnapshot),
+    StablePool(StablePoolSnapshot),
+}
+
+impl PoolSnapshot {
+    pub fn get_amount_out(
+        &self,
+        token_in: AccountId,
+        token_out: AccountId,
+        amount_in: u128,
+    ) -> Option<u128> {
+        match self {
+            PoolSnapshot::Pair(pool) => pool.get_amount_out(token_in, token_out, amount_in),
+            PoolSnapshot::StablePool(pool) => pool.get_amount_out(token_in, token_out, amount_in),
+        }
+    }
+}
+
+impl PathFinderPool for PoolSnapshot {
+    fn id(&self) -> AccountId {
+        match self {
+            PoolSnapshot::Pair(pool) => pool.id,
+            PoolSnapshot::StablePool(pool) => pool.id,
+        }
+    }
+
+    fn tokens(&self) -> Vec<AccountId> {
+        match self {
+            PoolSnapshot::Pair(pool) => vec![pool.token_0, pool.token_1],
+            PoolSnapshot::StablePool(pool) => pool.tokens.clone(),
+        }
+    }
+
+    fn quote(&self, token_in: AccountId, token_out: AccountId, amount_in: u128) -> Option<u128> {
+        self.get_amount_out(token_in, token_out, amount_in)
+    }
+}
@@ -10,9 +10,18 @@ declare -a CONTRACTS=(
     "factory_contract" 
     "pair_contract" 
     "stable_pool_contract" 
+    "stable_pool_factory_contract" 
+    "concentrated_pool_contract" 
+    "weighted_pool_contract" 
+    "crypto_pool_contract" 
+    "twap_oracle_contract" 
+    "twap_order_contract" 
+    "limit_order_contract" 
     "mock_rate_provider_contract" 
+    "mock_flash_loan_receiver_contract" 
     "router_contract"
     "router_v2_contract"
+    "farm_contract"
 )
 
 function wrap_contracts() {
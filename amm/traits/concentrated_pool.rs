@@ -0,0 +1,281 @@
+use amm_helpers::types::WrappedU256;
+use ink::primitives::AccountId;
+use ink::LangError;
+use psp22::PSP22Error;
+
+use crate::MathError;
+
+/// Liquidity provided by a single account in a price range of a `ConcentratedPool`.
+///
+/// Positions are NFT-like records identified by a pool-unique id, owned by a single account
+/// and transferable with `ConcentratedPool::transfer_position`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, scale::Encode, scale::Decode)]
+#[cfg_attr(
+    feature = "std",
+    derive(scale_info::TypeInfo, ink::storage::traits::StorageLayout)
+)]
+pub struct Position {
+    /// Account allowed to manage the position.
+    pub owner: AccountId,
+    /// Lower tick of the price range.
+    pub tick_lower: i32,
+    /// Upper tick of the price range.
+    pub tick_upper: i32,
+    /// Liquidity provided in the price range.
+    pub liquidity: u128,
+    /// Fee growth of token 0 inside the price range as of the last update of the position.
+    pub fee_growth_inside_0_last: WrappedU256,
+    /// Fee growth of token 1 inside the price range as of the last update of the position.
+    pub fee_growth_inside_1_last: WrappedU256,
+    /// Amount of token 0 (withdrawn liquidity and fees) which can be collected by the owner.
+    pub tokens_owed_0: u128,
+    /// Amount of token 1 (withdrawn liquidity and fees) which can be collected by the owner.
+    pub tokens_owed_1: u128,
+}
+
+/// Concentrated-liquidity pool of two tokens, based on the UniswapV3 model.
+///
+/// Liquidity providers choose the price range (between two initialized ticks) in which
+/// their liquidity is active. The price of token 0 in terms of token 1 at tick `i`
+/// is `1.0001^i`; sqrt prices are represented as Q64.96 numbers.
+#[ink::trait_definition]
+pub trait ConcentratedPool {
+    /// Returns the pair of tokens in the pool, (token 0, token 1).
+    #[ink(message)]
+    fn tokens(&self) -> (AccountId, AccountId);
+
+    /// Returns the trade fee, in hundredths of a basis point (1e6 precision).
+    #[ink(message)]
+    fn fee(&self) -> u32;
+
+    /// Returns the distance between two initializable ticks.
+    #[ink(message)]
+    fn tick_spacing(&self) -> i32;
+
+    /// Returns the current sqrt price of token 0 in terms of token 1, as a Q64.96 number.
+    #[ink(message)]
+    fn sqrt_price(&self) -> WrappedU256;
+
+    /// Returns the current tick, i.e. the greatest tick with the sqrt price not
+    /// greater than `sqrt_price`.
+    #[ink(message)]
+    fn current_tick(&self) -> i32;
+
+    /// Returns the liquidity active at the current price.
+    #[ink(message)]
+    fn liquidity(&self) -> u128;
+
+    /// Returns the tracked balances of token 0 and token 1,
+    /// including fees and withdrawn liquidity not yet collected by the positions' owners.
+    #[ink(message)]
+    fn reserves(&self) -> (u128, u128);
+
+    /// Returns the position with given id, `None` if it does not exist.
+    #[ink(message)]
+    fn position(&self, position_id: u128) -> Option<Position>;
+
+    /// Returns amounts of token 0 and token 1 required to provide `liquidity`
+    /// in the price range [`tick_lower`, `tick_upper`) at the current price.
+    #[ink(message)]
+    fn get_amounts_for_liquidity(
+        &self,
+        tick_lower: i32,
+        tick_upper: i32,
+        liquidity: u128,
+    ) -> Result<(u128, u128), ConcentratedPoolError>;
+
+    /// Creates a new position with `liquidity` in the price range [`tick_lower`, `tick_upper`)
+    /// owned by `to`.
+    ///
+    /// Both ticks have to be multiples of the `tick_spacing`.
+    /// Required token amounts are transferred from the caller, who has to approve them first.
+    /// Fails if they exceed `amount_0_max` or `amount_1_max`.
+    ///
+    /// Returns a tuple of (position id, amount of token 0, amount of token 1).
+    #[ink(message)]
+    fn mint_position(
+        &mut self,
+        tick_lower: i32,
+        tick_upper: i32,
+        liquidity: u128,
+        amount_0_max: u128,
+        amount_1_max: u128,
+        to: AccountId,
+    ) -> Result<(u128, u128, u128), ConcentratedPoolError>;
+
+    /// Adds `liquidity` to the position with given id.
+    ///
+    /// Required token amounts are transferred from the caller, who has to approve them first.
+    /// Fails if they exceed `amount_0_max` or `amount_1_max`. Can be called by anyone.
+    ///
+    /// Returns a tuple of (amount of token 0, amount of token 1).
+    #[ink(message)]
+    fn increase_liquidity(
+        &mut self,
+        position_id: u128,
+        liquidity: u128,
+        amount_0_max: u128,
+        amount_1_max: u128,
+    ) -> Result<(u128, u128), ConcentratedPoolError>;
+
+    /// Removes `liquidity` from the position with given id.
+    ///
+    /// Withdrawn token amounts are credited to the position and can be transferred with `collect`.
+    /// Fails if they are less than `amount_0_min` or `amount_1_min`.
+    /// Can be called only by the owner of the position.
+    ///
+    /// Returns a tuple of (amount of token 0, amount of token 1).
+    #[ink(message)]
+    fn decrease_liquidity(
+        &mut self,
+        position_id: u128,
+        liquidity: u128,
+        amount_0_min: u128,
+        amount_1_min: u128,
+    ) -> Result<(u128, u128), ConcentratedPoolError>;
+
+    /// Transfers all tokens owed to the position with given id (withdrawn liquidity and
+    /// accrued fees) to `to`.
+    ///
+    /// Can be called only by the owner of the position.
+    ///
+    /// Returns a tuple of (amount of token 0, amount of token 1).
+    #[ink(message)]
+    fn collect(
+        &mut self,
+        position_id: u128,
+        to: AccountId,
+    ) -> Result<(u128, u128), ConcentratedPoolError>;
+
+    /// Removes the position with given id.
+    ///
+    /// The position must have no liquidity and no tokens owed.
+    /// Can be called only by the owner of the position.
+    #[ink(message)]
+    fn burn_position(&mut self, position_id: u128) -> Result<(), ConcentratedPoolError>;
+
+    /// Transfers the ownership of the position with given id to `to`.
+    ///
+    /// Can be called only by the owner of the position.
+    #[ink(message)]
+    fn transfer_position(
+        &mut self,
+        position_id: u128,
+        to: AccountId,
+    ) -> Result<(), ConcentratedPoolError>;
+
+    /// Swaps `token_in_amount` of `token_in` (transferred from the caller)
+    /// to `token_out` and transfers it to `to`.
+    ///
+    /// Fails if the output amount is less than `min_token_out_amount`.
+    ///
+    /// Returns a tuple of (amount out, fee).
+    /// NOTE: fee is applied on `token_in`
+    #[ink(message)]
+    fn swap_exact_in(
+        &mut self,
+        token_in: AccountId,
+        token_out: AccountId,
+        token_in_amount: u128,
+        min_token_out_amount: u128,
+        to: AccountId,
+    ) -> Result<(u128, u128), ConcentratedPoolError>;
+
+    /// Swaps `token_in` (transferred from the caller) to `token_out_amount` of `token_out`
+    /// and transfers it to `to`.
+    ///
+    /// Fails if the input amount is greater than `max_token_in_amount`.
+    ///
+    /// Returns a tuple of (amount in, fee).
+    /// NOTE: fee is applied on `token_in`
+    #[ink(message)]
+    fn swap_exact_out(
+        &mut self,
+        token_in: AccountId,
+        token_out: AccountId,
+        token_out_amount: u128,
+        max_token_in_amount: u128,
+        to: AccountId,
+    ) -> Result<(u128, u128), ConcentratedPoolError>;
+
+    /// Swaps the excess of `token_in` (the difference between the pool's balance and
+    /// its reserve) to `token_out` and transfers it to `to`.
+    ///
+    /// Fails if the output amount is less than `min_token_out_amount`.
+    ///
+    /// Returns a tuple of (amount out, fee).
+    /// NOTE: fee is applied on `token_in`
+    #[ink(message)]
+    fn swap_received(
+        &mut self,
+        token_in: AccountId,
+        token_out: AccountId,
+        min_token_out_amount: u128,
+        to: AccountId,
+    ) -> Result<(u128, u128), ConcentratedPoolError>;
+
+    /// Calculate swap amount of `token_out` given `token_in_amount`.
+    ///
+    /// Returns a tuple of (amount out, fee)
+    /// NOTE: fee is applied on `token_in`
+    #[ink(message)]
+    fn get_swap_amount_out(
+        &self,
+        token_in: AccountId,
+        token_out: AccountId,
+        token_in_amount: u128,
+    ) -> Result<(u128, u128), ConcentratedPoolError>;
+
+    /// Calculate required swap amount of `token_in` to get `token_out_amount`.
+    ///
+    /// Returns a tuple of (amount in, fee)
+    /// NOTE: fee is applied on `token_in`
+    #[ink(message)]
+    fn get_swap_amount_in(
+        &self,
+        token_in: AccountId,
+        token_out: AccountId,
+        token_out_amount: u128,
+    ) -> Result<(u128, u128), ConcentratedPoolError>;
+}
+
+#[derive(Debug, PartialEq, Eq, scale::Encode, scale::Decode)]
+#[cfg_attr(feature = "std", derive(scale_info::TypeInfo))]
+pub enum ConcentratedPoolError {
+    PSP22Error(PSP22Error),
+    LangError(LangError),
+    MathError(MathError),
+    InvalidTokenId(AccountId),
+    IdenticalTokenId,
+    InvalidFee,
+    InvalidTickSpacing,
+    InvalidSqrtPrice,
+    InvalidTickRange,
+    ZeroLiquidity,
+    TickLiquidityOverflow,
+    InsufficientLiquidity,
+    InsufficientInputAmount,
+    InsufficientOutputAmount,
+    TooLargeInputAmount,
+    PositionNotFound,
+    CallerIsNotPositionOwner,
+    PositionNotEmpty,
+}
+
+impl From<PSP22Error> for ConcentratedPoolError {
+    fn from(error: PSP22Error) -> Self {
+        ConcentratedPoolError::PSP22Error(error)
+    }
+}
+
+impl From<LangError> for ConcentratedPoolError {
+    fn from(error: LangError) -> Self {
+        ConcentratedPoolError::LangError(error)
+    }
+}
+
+impl From<MathError> for ConcentratedPoolError {
+    fn from(error: MathError) -> Self {
+        ConcentratedPoolError::MathError(error)
+    }
+}
@@ -0,0 +1,271 @@
+use ink::prelude::vec::Vec;
+use ink::primitives::AccountId;
+use ink::LangError;
+use psp22::PSP22Error;
+
+use crate::{MathError, Ownable2StepError};
+
+/// Crypto-swap pool of 2 volatile tokens, based on the Curve V2 model.
+///
+/// The liquidity is concentrated around the price scale, which follows an internal
+/// EMA price oracle. The pool repegs, i.e. moves the price scale towards the oracle price,
+/// when it has earned enough in fees to pay for the loss caused by the repegging.
+///
+/// Prices are the prices of the second token in terms of the first one, with 1e18 precision.
+#[ink::trait_definition]
+pub trait CryptoPool {
+    /// Returns list of tokens in the pool.
+    #[ink(message)]
+    fn tokens(&self) -> Vec<AccountId>;
+
+    /// Returns list of tokens reserves.
+    #[ink(message)]
+    fn reserves(&self) -> Vec<u128>;
+
+    /// Returns the amplification coefficient (multiplied by N^N * A_MULTIPLIER)
+    /// and gamma (1e18 precision).
+    #[ink(message)]
+    fn amp_gamma(&self) -> (u128, u128);
+
+    /// Returns current fees: mid fee, out fee (1e9 precision), fee gamma (1e18 precision)
+    /// and protocol fee (1e9 precision).
+    #[ink(message)]
+    fn fees(&self) -> (u32, u32, u128, u32);
+
+    /// Protocol fees receiver (if any)
+    #[ink(message)]
+    fn fee_receiver(&self) -> Option<AccountId>;
+
+    /// Returns the price scale, around which the liquidity is concentrated.
+    #[ink(message)]
+    fn price_scale(&self) -> u128;
+
+    /// Returns the EMA oracle price as of now.
+    #[ink(message)]
+    fn price_oracle(&self) -> Result<u128, CryptoPoolError>;
+
+    /// Returns the spot price after the last operation.
+    #[ink(message)]
+    fn last_price(&self) -> u128;
+
+    /// Returns the virtual price of the LP token (1e18 precision).
+    #[ink(message)]
+    fn virtual_price(&self) -> u128;
+
+    /// Calculate swap amount of `token_out`
+    /// given `token_in amount`.
+    ///
+    /// Returns a tuple of (amount out, fee)
+    /// NOTE: fee is applied on `token_out`
+    #[ink(message)]
+    fn get_swap_amount_out(
+        &self,
+        token_in: AccountId,
+        token_out: AccountId,
+        token_in_amount: u128,
+    ) -> Result<(u128, u128), CryptoPoolError>;
+
+    /// Calculate required swap amount of `token_in`
+    /// to get `token_out_amount`.
+    ///
+    /// Returns a tuple of (amount in, fee)
+    /// NOTE: fee is applied on `token_out`
+    #[ink(message)]
+    fn get_swap_amount_in(
+        &self,
+        token_in: AccountId,
+        token_out: AccountId,
+        token_out_amount: u128,
+    ) -> Result<(u128, u128), CryptoPoolError>;
+
+    /// Calculate how many lp tokens will be minted
+    /// for depositing `amounts` of the tokens.
+    #[ink(message)]
+    fn get_mint_liquidity_for_amounts(&self, amounts: Vec<u128>) -> Result<u128, CryptoPoolError>;
+
+    /// Calculate withdraw amounts for
+    /// burning `liquidity` amount of lp tokens.
+    ///
+    /// Returns withdraw amounts
+    #[ink(message)]
+    fn get_amounts_for_liquidity_burn(&self, liquidity: u128)
+        -> Result<Vec<u128>, CryptoPoolError>;
+
+    /// Calculate how much of `token` will be withdrawn
+    /// for burning `shares` amount of lp tokens.
+    #[ink(message)]
+    fn get_amount_for_one_coin_burn(
+        &self,
+        shares: u128,
+        token: AccountId,
+    ) -> Result<u128, CryptoPoolError>;
+
+    /// Deposit `amounts` of tokens to receive lpt tokens to `to` account.
+    /// Caller must allow enough spending allowance of underlying tokens
+    /// for this contract.
+    ///
+    /// The first deposit requires both of the tokens in amounts balanced at the initial price.
+    /// Next deposits can be imbalanced, the imbalanced part is charged with the half of the trade fee.
+    ///
+    /// Returns an error if the minted LP tokens amount is less
+    /// than `min_share_amount`.
+    /// Returns minted lpt amount
+    #[ink(message)]
+    fn add_liquidity(
+        &mut self,
+        min_share_amount: u128,
+        amounts: Vec<u128>,
+        to: AccountId,
+    ) -> Result<u128, CryptoPoolError>;
+
+    /// Burns lp tokens and withdraws underlying tokens in balanced amounts to `to` account.
+    /// Fails if any of the amounts received is less than in `min_amounts`.
+    /// Returns withdrawal amounts
+    #[ink(message)]
+    fn remove_liquidity_by_shares(
+        &mut self,
+        shares: u128,
+        min_amounts: Vec<u128>,
+        to: AccountId,
+    ) -> Result<Vec<u128>, CryptoPoolError>;
+
+    /// Burns `shares` of lp tokens and withdraws underlying tokens
+    /// in a single `token` to `to` account.
+    /// Fails if the amount received is less than `min_amount`.
+    /// NOTE: Fee is applied on the invariant decrease.
+    /// Returns withdrawn amount
+    #[ink(message)]
+    fn remove_liquidity_one_coin(
+        &mut self,
+        shares: u128,
+        token: AccountId,
+        min_amount: u128,
+        to: AccountId,
+    ) -> Result<u128, CryptoPoolError>;
+
+    /// Swaps token_in to token_out.
+    /// Swapped tokens are transferred to the `to` account.
+    /// caller account must allow enough spending allowance of `token_in`
+    /// for this contract.
+    /// Returns an error if swapped `token_out` amount is less than
+    /// `min_token_out_amount`.
+    /// NOTE: Fee is applied on `token_out`.
+    /// Returns a tuple of (token out amount, fee amount)
+    #[ink(message)]
+    fn swap_exact_in(
+        &mut self,
+        token_in: AccountId,
+        token_out: AccountId,
+        token_in_amount: u128,
+        min_token_out_amount: u128,
+        to: AccountId,
+    ) -> Result<(u128, u128), CryptoPoolError>;
+
+    /// Swaps token_in to token_out.
+    /// Swapped tokens are transferred to the `to` account.
+    /// Caller account must allow enough spending allowance of `token_in`
+    /// for this contract.
+    /// Returns an error if it is required to spend more than
+    /// `max_token_in_amount` to get `token_out_amount`.
+    /// NOTE: Fee is applied on `token_out`.
+    /// Returns a tuple of (token in amount, fee amount)
+    #[ink(message)]
+    fn swap_exact_out(
+        &mut self,
+        token_in: AccountId,
+        token_out: AccountId,
+        token_out_amount: u128,
+        max_token_in_amount: u128,
+        to: AccountId,
+    ) -> Result<(u128, u128), CryptoPoolError>;
+
+    /// Swaps excess reserve balance of `token_in` to `token_out`.
+    /// Swapped tokens are transferred to the `to` account.
+    /// Returns a tuple of (token out amount, fee amount)
+    #[ink(message)]
+    fn swap_received(
+        &mut self,
+        token_in: AccountId,
+        token_out: AccountId,
+        min_token_out_amount: u128,
+        to: AccountId,
+    ) -> Result<(u128, u128), CryptoPoolError>;
+
+    // --- OWNER RESTRICTED FUNCTIONS --- //
+
+    #[ink(message)]
+    fn set_fee_receiver(&mut self, fee_receiver: Option<AccountId>) -> Result<(), CryptoPoolError>;
+
+    /// Set fees
+    /// - mid_fee and out_fee given as integers with 1e9 precision. The maximum is 10% (100000000)
+    /// - fee_gamma given as an integer with 1e18 precision. The maximum is 1e18
+    /// - protocol_fee given as an integer with 1e9 precision. The maximum is 50% (500000000)
+    #[ink(message)]
+    fn set_fees(
+        &mut self,
+        mid_fee: u32,
+        out_fee: u32,
+        fee_gamma: u128,
+        protocol_fee: u32,
+    ) -> Result<(), CryptoPoolError>;
+
+    /// Set repegging parameters
+    /// - allowed_extra_profit given with 1e18 precision. The maximum is 1% (1e16)
+    /// - adjustment_step given with 1e18 precision. The maximum is 1e18
+    /// - ma_half_time given in milisec. From 1 sec to 7 days
+    #[ink(message)]
+    fn set_rebalancing_params(
+        &mut self,
+        allowed_extra_profit: u128,
+        adjustment_step: u128,
+        ma_half_time: u64,
+    ) -> Result<(), CryptoPoolError>;
+}
+
+#[derive(Debug, PartialEq, Eq, scale::Encode, scale::Decode)]
+#[cfg_attr(feature = "std", derive(scale_info::TypeInfo))]
+pub enum CryptoPoolError {
+    Ownable2StepError(Ownable2StepError),
+    MathError(MathError),
+    PSP22Error(PSP22Error),
+    LangError(LangError),
+    InvalidTokenId(AccountId),
+    IdenticalTokenId,
+    IncorrectAmountsCount,
+    ZeroAmounts,
+    InsufficientLiquidityMinted,
+    InsufficientOutputAmount,
+    InsufficientLiquidity,
+    InsufficientInputAmount,
+    IncorrectTokenCount,
+    TooLargeTokenDecimal,
+    InvalidAmpGamma,
+    InvalidFee,
+    InvalidPrice,
+    InvalidRebalancingParams,
+    VirtualPriceDecreased,
+}
+
+impl From<PSP22Error> for CryptoPoolError {
+    fn from(error: PSP22Error) -> Self {
+        CryptoPoolError::PSP22Error(error)
+    }
+}
+
+impl From<LangError> for CryptoPoolError {
+    fn from(error: LangError) -> Self {
+        CryptoPoolError::LangError(error)
+    }
+}
+
+impl From<MathError> for CryptoPoolError {
+    fn from(error: MathError) -> Self {
+        CryptoPoolError::MathError(error)
+    }
+}
+
+impl From<Ownable2StepError> for CryptoPoolError {
+    fn from(error: Ownable2StepError) -> Self {
+        CryptoPoolError::Ownable2StepError(error)
+    }
+}
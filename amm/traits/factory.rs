@@ -1,5 +1,8 @@
 use crate::PairError;
-use ink::primitives::{AccountId, Hash};
+use ink::{
+    prelude::vec::Vec,
+    primitives::{AccountId, Hash},
+};
 
 /// Factory trait for tracking all pairs within the UniswapV2 DEX.
 /// Creates new, unique instances of `Pair` smart contract per token pairs.
@@ -18,17 +21,33 @@ pub trait Factory {
     #[ink(message)]
     fn pair_contract_code_hash(&self) -> Hash;
 
-    /// Creates an instance of the `Pair` contract for the `(token_0, token_1)` pair.
+    /// Creates an instance of the `Pair` contract for the `(token_0, token_1)` pair
+    /// with the `fee` trading fee (in millis).
     /// Returns the address of the contract instance if successful.
-    /// Fails if the `Pair` instance of the token pair already exists
-    /// or the token pair is illegal.
+    /// Fails if the `Pair` instance of the token pair with the same fee already exists,
+    /// the token pair is illegal or the fee tier is not enabled.
     #[ink(message)]
     fn create_pair(
         &mut self,
         token_0: AccountId,
         token_1: AccountId,
+        fee: u8,
     ) -> Result<AccountId, FactoryError>;
 
+    /// Returns the enabled fee tiers (in millis) that pairs can be created with.
+    #[ink(message)]
+    fn fee_tiers(&self) -> Vec<u8>;
+
+    /// Enables the `fee` tier (in millis) for the new pairs.
+    /// Can only be called by the `fee_to_setter`.
+    #[ink(message)]
+    fn enable_fee_tier(&mut self, fee: u8) -> Result<(), FactoryError>;
+
+    /// Disables the `fee` tier for the new pairs. Existing pairs are not affected.
+    /// Can only be called by the `fee_to_setter`.
+    #[ink(message)]
+    fn disable_fee_tier(&mut self, fee: u8) -> Result<(), FactoryError>;
+
     /// Sets the address for receiving protocol's share of trading fees.
     #[ink(message)]
     fn set_fee_to(&mut self, fee_to: AccountId) -> Result<(), FactoryError>;
@@ -41,13 +60,62 @@ pub trait Factory {
     #[ink(message)]
     fn fee_to(&self) -> Option<AccountId>;
 
+    /// Sets the address receiving protocol's share of trading fees of the `pair`,
+    /// overriding `fee_to`. Setting it to `None` removes the override.
+    #[ink(message)]
+    fn set_pair_fee_to(
+        &mut self,
+        pair: AccountId,
+        fee_to: Option<AccountId>,
+    ) -> Result<(), FactoryError>;
+
+    /// Returns recipient address of the trading fees of the `pair`.
+    /// That is the `pair`'s override, if set, or `fee_to` otherwise.
+    #[ink(message)]
+    fn pair_fee_to(&self, pair: AccountId) -> Option<AccountId>;
+
+    /// Returns `N` such that the protocol receives `1/N` of the LP fees growth.
+    #[ink(message)]
+    fn protocol_fee_denom(&self) -> u8;
+
+    /// Sets `N` such that the protocol receives `1/N` of the LP fees growth.
+    /// Can only be called by the `fee_to_setter`.
+    #[ink(message)]
+    fn set_protocol_fee_denom(&mut self, denom: u8) -> Result<(), FactoryError>;
+
     /// Returns account allowed to call `set_fee_to_setter`.
     #[ink(message)]
     fn fee_to_setter(&self) -> AccountId;
 
-    /// Returns address of `Pair` contract instance (if any) for `(token_0, token_1)` pair.
+    /// Returns address of `Pair` contract instance (if any) for `(token_0, token_1)` pair
+    /// created with the `fee` trading fee. The pair's current fee may differ after
+    /// `set_pair_fee`, use `is_pair` to verify that an address is a pair of this factory.
+    #[ink(message)]
+    fn get_pair(&self, token_0: AccountId, token_1: AccountId, fee: u8) -> Option<AccountId>;
+
+    /// Returns `true` if `pair` has been created via this factory.
+    #[ink(message)]
+    fn is_pair(&self, pair: AccountId) -> bool;
+
+    /// Schedules the change of the `pair`'s trading fee to `fee` (in millis)
+    /// at `effective_time` (in milisec). See `Pair::set_fee`.
+    /// Can only be called by the `fee_to_setter`.
+    #[ink(message)]
+    fn set_pair_fee(
+        &mut self,
+        pair: AccountId,
+        fee: u8,
+        effective_time: u64,
+    ) -> Result<(), FactoryError>;
+
+    /// Returns the fee (in basis points) charged by the pairs on flash loans.
+    #[ink(message)]
+    fn flash_loan_fee(&self) -> u16;
+
+    /// Sets the fee (in basis points) charged by the pairs on flash loans.
+    /// Can only be called by the `fee_to_setter`.
     #[ink(message)]
-    fn get_pair(&self, token_0: AccountId, token_1: AccountId) -> Option<AccountId>;
+    fn set_flash_loan_fee(&mut self, fee: u16) -> Result<(), FactoryError>;
 }
 
 /// Errors that can be returned from calling `Factory`'s methods.
@@ -59,6 +127,10 @@ pub enum FactoryError {
     IdenticalAddresses,
     PairExists,
     PairInstantiationFailed,
+    InvalidFlashLoanFee,
+    InvalidFee,
+    FeeTierNotEnabled,
+    InvalidProtocolFee,
 }
 
 impl From<PairError> for FactoryError {
@@ -0,0 +1,34 @@
+use ink::prelude::vec::Vec;
+use ink::primitives::AccountId;
+
+/// Value that `FlashBorrower::on_flash_loan` has to return for the loan to succeed.
+///
+/// Equal to `keccak256("ERC3156FlashBorrower.onFlashLoan")`, as in ERC-3156.
+pub const FLASH_BORROWER_CALLBACK_SUCCESS: [u8; 32] = [
+    0x43, 0x91, 0x48, 0xf0, 0xbb, 0xc6, 0x82, 0xca, 0x07, 0x9e, 0x46, 0xd6, 0xe2, 0xc2, 0xf0, 0xc1,
+    0xe3, 0xb8, 0x20, 0xf1, 0xa2, 0x91, 0xb0, 0x69, 0xd8, 0x88, 0x2a, 0xbf, 0x8c, 0xf1, 0x8d, 0xd9,
+];
+
+/// An interface to implement by the receiver of `Pair::flash_loan` and `StablePool::flash_loan`.
+///
+/// The pool transfers the borrowed `amounts` of `tokens` to the receiver and calls
+/// `on_flash_loan`. Before returning `FLASH_BORROWER_CALLBACK_SUCCESS`, the receiver must
+/// transfer back to the pool (the caller) the borrowed amounts plus `fees` of each token,
+/// otherwise the loan fails. A `Pair` always lends a single token.
+///
+/// Note to the implementors:
+/// Implementation should ensure that the caller is actually a `Pair` or a `StablePool`
+/// contract instance (e.g. with `Factory::is_pair` or `StablePoolFactory::is_pool`)
+/// and that the `initiator` is trusted.
+#[ink::trait_definition]
+pub trait FlashBorrower {
+    #[ink(message)]
+    fn on_flash_loan(
+        &mut self,
+        initiator: AccountId,
+        tokens: Vec<AccountId>,
+        amounts: Vec<u128>,
+        fees: Vec<u128>,
+        data: Vec<u8>,
+    ) -> [u8; 32];
+}
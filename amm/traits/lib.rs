@@ -1,22 +1,43 @@
 #![cfg_attr(not(feature = "std"), no_std, no_main)]
 
+mod concentrated_pool;
+mod crypto_pool;
 mod factory;
+mod flash_borrower;
+mod limit_order;
 mod ownable2step;
 mod pair;
+mod path_finder;
+mod permit;
 mod rate_provider;
 mod router;
 mod router_v2;
 mod stable_pool;
+mod stable_pool_factory;
 mod swap_callee;
+mod twap_oracle;
+mod twap_order;
+mod weighted_pool;
 
 pub type Balance = <ink::env::DefaultEnvironment as ink::env::Environment>::Balance;
 
 pub use amm_helpers::math::MathError;
+pub use concentrated_pool::{ConcentratedPool, ConcentratedPoolError, Position};
+pub use crypto_pool::{CryptoPool, CryptoPoolError};
 pub use factory::{Factory, FactoryError};
+pub use farm_trait::{Farm, FarmError};
+pub use flash_borrower::{FlashBorrower, FLASH_BORROWER_CALLBACK_SUCCESS};
+pub use limit_order::{LimitOrderBook, LimitOrderError, Order};
 pub use ownable2step::{Ownable2Step, Ownable2StepData, Ownable2StepError, Ownable2StepResult};
 pub use pair::{Pair, PairError};
+pub use path_finder::{find_best_path, PathFinderPool};
+pub use permit::{permit_payload, Permit, PermitData, PermitError, PermitSignature, PERMIT_DOMAIN};
 pub use rate_provider::RateProvider;
 pub use router::{Router, RouterError};
-pub use router_v2::{RouterV2, RouterV2Error, Step};
+pub use router_v2::{CallAmount, RouterCall, RouterV2, RouterV2Error, Step, WeightedPath};
 pub use stable_pool::{StablePool, StablePoolError};
+pub use stable_pool_factory::{StablePoolFactory, StablePoolFactoryError};
 pub use swap_callee::SwapCallee;
+pub use twap_oracle::{Observation, TwapOracle, TwapOracleError};
+pub use twap_order::{TwapOrder, TwapOrderBook, TwapOrderError};
+pub use weighted_pool::{WeightedPool, WeightedPoolError};
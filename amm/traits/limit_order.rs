@@ -0,0 +1,105 @@
+use crate::{Balance, RouterV2Error, Step};
+use ink::{prelude::vec::Vec, primitives::AccountId};
+use psp22::PSP22Error;
+
+/// Resting order to exchange `amount_in` of `path[0].token_in` for `token_out`.
+#[derive(Debug, Clone, PartialEq, Eq, scale::Encode, scale::Decode)]
+#[cfg_attr(
+    feature = "std",
+    derive(scale_info::TypeInfo, ink::storage::traits::StorageLayout)
+)]
+pub struct Order {
+    /// Account which placed the order and receives its output.
+    pub owner: AccountId,
+    /// `RouterV2` path the order is executed along.
+    pub path: Vec<Step>,
+    pub token_out: AccountId,
+    /// Amount of `path[0].token_in` escrowed in the order.
+    pub amount_in: u128,
+    /// Minimal amount of `token_out` received for the whole `amount_in`.
+    /// Together with `amount_in` it sets the target price of the order.
+    pub amount_out_min: u128,
+    /// Amount of native tokens paid to the keeper who fills the order.
+    pub tip: Balance,
+    /// Timestamp (in milliseconds) after which the order cannot be filled.
+    pub expiry: u64,
+}
+
+/// Book of limit orders filled by permissionless keepers through `RouterV2`.
+#[ink::trait_definition]
+pub trait LimitOrderBook {
+    /// Returns address of the `RouterV2` the orders are executed with.
+    #[ink(message)]
+    fn router(&self) -> AccountId;
+
+    /// Returns the order with `order_id`, if it is still open.
+    #[ink(message)]
+    fn get_order(&self, order_id: u64) -> Option<Order>;
+
+    /// Places an order to exchange `amount_in` of `path[0].token_in` for at least
+    /// `amount_out_min` of `token_out` along the `path`, before the `expiry` timestamp.
+    ///
+    /// Transfers `amount_in` from the caller to the contract, so the caller has to approve
+    /// the contract beforehand. The transferred native value is the tip of the order.
+    /// Fails with `InvalidTokenOut` if `token_out` cannot be received from the last pool
+    /// of the `path`.
+    ///
+    /// Returns id of the new order.
+    #[ink(message, payable)]
+    fn place_order(
+        &mut self,
+        path: Vec<Step>,
+        token_out: AccountId,
+        amount_in: u128,
+        amount_out_min: u128,
+        expiry: u64,
+    ) -> Result<u64, LimitOrderError>;
+
+    /// Cancels the order and returns its escrowed tokens and tip to the owner.
+    ///
+    /// Can be called by the owner at any time or by anyone once the order expires.
+    #[ink(message)]
+    fn cancel_order(&mut self, order_id: u64) -> Result<(), LimitOrderError>;
+
+    /// Returns amount of `token_out` the order would receive if filled now.
+    ///
+    /// The order is executable when the returned amount is not less than its `amount_out_min`.
+    #[ink(message)]
+    fn quote_order(&mut self, order_id: u64) -> Result<u128, LimitOrderError>;
+
+    /// Fills the order by swapping its tokens through `RouterV2` and transferring
+    /// the output to the order owner. Pays the tip of the order to the caller.
+    ///
+    /// Can be called by anyone. Fails if the order has expired or if the output
+    /// is less than `amount_out_min`.
+    ///
+    /// Returns the amount of `token_out` received by the owner.
+    #[ink(message)]
+    fn fill_order(&mut self, order_id: u64) -> Result<u128, LimitOrderError>;
+}
+
+#[derive(Debug, PartialEq, Eq, scale::Encode, scale::Decode)]
+#[cfg_attr(feature = "std", derive(scale_info::TypeInfo))]
+pub enum LimitOrderError {
+    PSP22Error(PSP22Error),
+    RouterV2Error(RouterV2Error),
+    CallerIsNotOwner,
+    EmptyPath,
+    Expired,
+    InvalidTokenOut,
+    OrderNotFound,
+    TransferError,
+    ZeroAmount,
+}
+
+impl From<PSP22Error> for LimitOrderError {
+    fn from(error: PSP22Error) -> Self {
+        LimitOrderError::PSP22Error(error)
+    }
+}
+
+impl From<RouterV2Error> for LimitOrderError {
+    fn from(error: RouterV2Error) -> Self {
+        LimitOrderError::RouterV2Error(error)
+    }
+}
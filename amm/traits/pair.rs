@@ -86,6 +86,54 @@ pub trait Pair {
     /// Returns protocol fee (in millis).
     #[ink(message)]
     fn get_fee(&self) -> u8;
+
+    /// Returns the scheduled fee (in millis) and the time (in milisec) it takes effect at,
+    /// if there is a pending fee change.
+    #[ink(message)]
+    fn get_future_fee(&self) -> Option<(u8, u64)>;
+
+    /// Schedules the change of the fee to `fee` (in millis) at `effective_time` (in milisec).
+    /// Replaces a pending fee change, if any.
+    ///
+    /// Can only be called by the `Factory`. `effective_time` has to be at least
+    /// `MIN_FEE_CHANGE_DELAY` in the future so that LPs get advance notice.
+    #[ink(message)]
+    fn set_fee(&mut self, fee: u8, effective_time: u64) -> Result<(), PairError>;
+
+    /// Returns the amount of liquidity tokens that would be minted to the protocol's
+    /// fee recipient at the next liquidity event, given the current reserves.
+    #[ink(message)]
+    fn pending_protocol_fee(&self) -> Result<u128, PairError>;
+
+    /// Mints the pending protocol fee (see `pending_protocol_fee`) to the protocol's
+    /// fee recipient without adding or removing liquidity. Can be called by anyone.
+    ///
+    /// Returns the amount of minted liquidity tokens.
+    #[ink(message)]
+    fn collect_protocol_fee(&mut self) -> Result<u128, PairError>;
+
+    /// Returns the max amount of `token` available for a flash loan.
+    /// Returns 0 if `token` is not one of the pair's tokens.
+    #[ink(message)]
+    fn max_flash_loan(&self, token: AccountId) -> u128;
+
+    /// Returns the fee charged for a flash loan of `amount` of `token`.
+    #[ink(message)]
+    fn flash_fee(&self, token: AccountId, amount: u128) -> Result<u128, PairError>;
+
+    /// Lends `amount` of `token` to `receiver` and calls its `FlashBorrower::on_flash_loan`
+    /// with `data`. The receiver has to transfer back `amount` plus the fee before the
+    /// callback returns. The fee is added to the reserves.
+    ///
+    /// Returns the charged fee.
+    #[ink(message)]
+    fn flash_loan(
+        &mut self,
+        token: AccountId,
+        amount: u128,
+        receiver: AccountId,
+        data: Vec<u8>,
+    ) -> Result<u128, PairError>;
 }
 
 #[derive(Debug, PartialEq, Eq, scale::Encode, scale::Decode)]
@@ -102,6 +150,12 @@ pub enum PairError {
     InsufficientInputAmount,
     InvalidTo,
     ReservesOverflow,
+    UnsupportedFlashLoanToken,
+    InvalidFlashLoanCallback,
+    InsufficientFlashLoanRepayment,
+    CallerIsNotFactory,
+    InvalidFee,
+    InvalidFeeChangeTime,
 }
 
 impl From<PSP22Error> for PairError {
@@ -0,0 +1,99 @@
+use crate::Step;
+use ink::{prelude::vec::Vec, primitives::AccountId};
+
+/// Pool which can be searched by `find_best_path`.
+///
+/// Implemented by the pools of the `RouterV2` contract, which quote swaps with
+/// cross-contract calls, and by the snapshots of the off-chain path finder.
+pub trait PathFinderPool {
+    fn id(&self) -> AccountId;
+
+    /// Returns tokens which can be exchanged in the pool.
+    fn tokens(&self) -> Vec<AccountId>;
+
+    /// Returns how much of `token_out` is received for `amount_in` of `token_in`
+    /// or `None` if the swap cannot be quoted (e.g. because of insufficient liquidity).
+    fn quote(&self, token_in: AccountId, token_out: AccountId, amount_in: u128) -> Option<u128>;
+}
+
+/// Searches `pools` for the path exchanging `amount_in` of `token_in` to `token_out`
+/// with the highest output amount.
+///
+/// Considered paths consist of at most `max_hops` steps and never visit
+/// the same pool or the same token twice. Steps which cannot be quoted
+/// are skipped.
+///
+/// Returns the best path together with its quoted output amount
+/// or `None` if `token_out` is not reachable.
+pub fn find_best_path<P: PathFinderPool>(
+    pools: &[P],
+    token_in: AccountId,
+    token_out: AccountId,
+    amount_in: u128,
+    max_hops: u8,
+) -> Option<(Vec<Step>, u128)> {
+    if token_in == token_out || amount_in == 0 {
+        return None;
+    }
+    let mut path = Vec::with_capacity(max_hops as usize);
+    let mut best = None;
+    search(
+        pools, token_in, token_out, amount_in, max_hops, &mut path, &mut best,
+    );
+    best
+}
+
+/// Depth-first search extending `path` with a step from `token`.
+fn search<P: PathFinderPool>(
+    pools: &[P],
+    token: AccountId,
+    token_out: AccountId,
+    amount: u128,
+    hops_left: u8,
+    path: &mut Vec<Step>,
+    best: &mut Option<(Vec<Step>, u128)>,
+) {
+    for pool in pools {
+        let pool_id = pool.id();
+        if path.iter().any(|step| step.pool_id == pool_id) {
+            continue;
+        }
+        let tokens = pool.tokens();
+        if !tokens.contains(&token) {
+            continue;
+        }
+        for &next_token in tokens.iter().filter(|&&t| t != token) {
+            // do not go back to a token already visited in this path
+            if path.iter().any(|step| step.token_in == next_token) {
+                continue;
+            }
+            let amount_out = match pool.quote(token, next_token, amount) {
+                Some(amount_out) if amount_out > 0 => amount_out,
+                _ => continue,
+            };
+            path.push(Step {
+                token_in: token,
+                pool_id,
+            });
+            if next_token == token_out {
+                if best
+                    .as_ref()
+                    .map_or(true, |(_, best_amount_out)| amount_out > *best_amount_out)
+                {
+                    *best = Some((path.clone(), amount_out));
+                }
+            } else if hops_left > 1 {
+                search(
+                    pools,
+                    next_token,
+                    token_out,
+                    amount_out,
+                    hops_left - 1,
+                    path,
+                    best,
+                );
+            }
+            path.pop();
+        }
+    }
+}
@@ -0,0 +1,178 @@
+use ink::{
+    env::{
+        block_timestamp, ecdsa_recover,
+        hash::{Blake2x256, HashOutput},
+        hash_bytes, DefaultEnvironment as Env,
+    },
+    prelude::vec::Vec,
+    primitives::{AccountId, Hash},
+    storage::Mapping,
+};
+use psp22::PSP22Error;
+use scale::{Decode, Encode};
+
+/// Domain separator of the permit payload.
+pub const PERMIT_DOMAIN: &[u8] = b"common-amm:permit";
+
+/// Signature of the permit payload.
+#[derive(Debug, Clone, PartialEq, Eq, Encode, Decode)]
+#[cfg_attr(feature = "std", derive(scale_info::TypeInfo))]
+pub enum PermitSignature {
+    /// Recoverable ECDSA (secp256k1) signature: `r`, `s` and the recovery id.
+    Ecdsa([u8; 65]),
+    /// Sr25519 signature. Not supported, always rejected with `UnsupportedSignature`.
+    Sr25519([u8; 64]),
+}
+
+/// Implement this trait to let token owners approve spending with a signature
+/// instead of an on-chain `approve` transaction.
+///
+/// The owner signs the SCALE-encoded payload returned by `permit_payload`, which
+/// includes the chain's genesis hash, the token's address and the owner's current nonce,
+/// so a signature can be used only once and only with the token and chain it was made for.
+/// The genesis hash is not available to ink! 4.3 contracts, so it is passed to the
+/// token's constructor.
+///
+/// Only ECDSA accounts can use permits. Signatures are made over the Blake2x256 hash
+/// of the payload and `owner` must be the Blake2x256 hash of the compressed public key
+/// that made the signature. ink! 4.3 cannot verify sr25519 signatures, hence owners
+/// with sr25519 (or ed25519) keys have to use `approve` and
+/// `PermitSignature::Sr25519` always fails with `PermitError::UnsupportedSignature`.
+#[ink::trait_definition]
+pub trait Permit {
+    /// Returns the nonce that has to be signed in the next permit of `owner`.
+    #[ink(message)]
+    fn nonces(&self, owner: AccountId) -> u64;
+
+    /// Sets the allowance of `spender` over the tokens of `owner` to `value`,
+    /// given a valid `signature` of `owner`.
+    ///
+    /// Increments the nonce of `owner` and emits the `Approval` event.
+    /// Fails if the `deadline` has passed.
+    #[ink(message)]
+    fn permit(
+        &mut self,
+        owner: AccountId,
+        spender: AccountId,
+        value: u128,
+        deadline: u64,
+        signature: PermitSignature,
+    ) -> Result<(), PermitError>;
+}
+
+#[derive(Debug, PartialEq, Eq, Encode, Decode)]
+#[cfg_attr(feature = "std", derive(scale_info::TypeInfo))]
+pub enum PermitError {
+    PSP22Error(PSP22Error),
+    /// The deadline of the permit has passed.
+    Expired,
+    /// The signature is malformed or it was not made by the owner over the expected payload.
+    InvalidSignature,
+    /// The signature scheme is not supported.
+    UnsupportedSignature,
+}
+
+impl From<PSP22Error> for PermitError {
+    fn from(error: PSP22Error) -> Self {
+        PermitError::PSP22Error(error)
+    }
+}
+
+/// Returns the payload that `owner` signs to permit `spender` to spend `value`
+/// of the `token` deployed on the chain with the `genesis_hash`.
+pub fn permit_payload(
+    genesis_hash: Hash,
+    token: AccountId,
+    owner: AccountId,
+    spender: AccountId,
+    value: u128,
+    nonce: u64,
+    deadline: u64,
+) -> Vec<u8> {
+    (
+        PERMIT_DOMAIN,
+        genesis_hash,
+        token,
+        owner,
+        spender,
+        value,
+        nonce,
+        deadline,
+    )
+        .encode()
+}
+
+#[derive(Debug)]
+#[ink::storage_item]
+pub struct PermitData {
+    genesis_hash: Hash,
+    nonces: Mapping<AccountId, u64>,
+}
+
+impl PermitData {
+    pub fn new(genesis_hash: Hash) -> Self {
+        Self {
+            genesis_hash,
+            nonces: Default::default(),
+        }
+    }
+
+    pub fn nonce(&self, owner: AccountId) -> u64 {
+        self.nonces.get(owner).unwrap_or(0)
+    }
+
+    /// Verifies the permit signed by `owner` for the `token` and consumes its nonce.
+    pub fn use_permit(
+        &mut self,
+        token: AccountId,
+        owner: AccountId,
+        spender: AccountId,
+        value: u128,
+        deadline: u64,
+        signature: &PermitSignature,
+    ) -> Result<(), PermitError> {
+        if block_timestamp::<Env>() > deadline {
+            return Err(PermitError::Expired);
+        }
+        let nonce = self.nonce(owner);
+        verify_signature(
+            signature,
+            &permit_payload(
+                self.genesis_hash,
+                token,
+                owner,
+                spender,
+                value,
+                nonce,
+                deadline,
+            ),
+            owner,
+        )?;
+        self.nonces.insert(owner, &(nonce + 1));
+        Ok(())
+    }
+}
+
+/// Checks that `signature` of the `payload` was made by `owner`.
+fn verify_signature(
+    signature: &PermitSignature,
+    payload: &[u8],
+    owner: AccountId,
+) -> Result<(), PermitError> {
+    match signature {
+        PermitSignature::Ecdsa(signature) => {
+            let mut message_hash = <Blake2x256 as HashOutput>::Type::default();
+            hash_bytes::<Blake2x256>(payload, &mut message_hash);
+            let mut public_key = [0u8; 33];
+            ecdsa_recover(signature, &message_hash, &mut public_key)
+                .map_err(|_| PermitError::InvalidSignature)?;
+            let mut signer = <Blake2x256 as HashOutput>::Type::default();
+            hash_bytes::<Blake2x256>(&public_key, &mut signer);
+            if AccountId::from(signer) != owner {
+                return Err(PermitError::InvalidSignature);
+            }
+            Ok(())
+        }
+        PermitSignature::Sr25519(_) => Err(PermitError::UnsupportedSignature),
+    }
+}
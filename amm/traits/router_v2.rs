@@ -1,4 +1,7 @@
-use crate::{Balance, FactoryError, MathError, PairError, StablePoolError};
+use crate::{
+    Balance, ConcentratedPoolError, CryptoPoolError, FactoryError, FarmError, MathError,
+    Ownable2StepError, PairError, PermitError, PermitSignature, StablePoolError, WeightedPoolError,
+};
 use ink::{prelude::vec::Vec, primitives::AccountId, LangError};
 use psp22::PSP22Error;
 
@@ -9,13 +12,101 @@ use psp22::PSP22Error;
 /// should never contain two steps which use the same pool, as it may cause the dry input / output
 /// amount calculation not matching the actual swap result. It is not ensured inside the `swap_*`
 /// methods, making the result of using a pool-repeating `path` undefined.
-#[derive(scale::Decode, scale::Encode)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, scale::Decode, scale::Encode)]
 #[cfg_attr(feature = "std", derive(scale_info::TypeInfo))]
 pub struct Step {
     pub token_in: AccountId,
     pub pool_id: AccountId,
 }
 
+/// A `path` together with its share of the input amount in a split-route swap.
+///
+/// The input amount is distributed between the paths proportionally to their weights.
+#[derive(Debug, Clone, PartialEq, Eq, scale::Decode, scale::Encode)]
+#[cfg_attr(feature = "std", derive(scale_info::TypeInfo))]
+pub struct WeightedPath {
+    pub path: Vec<Step>,
+    pub weight: u32,
+}
+
+/// Amount of a token used as an input of a `RouterCall`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, scale::Decode, scale::Encode)]
+#[cfg_attr(feature = "std", derive(scale_info::TypeInfo))]
+pub enum CallAmount {
+    /// Exact amount, taken from the tokens held by the router.
+    Exact(u128),
+    /// Whole balance of the token held by the router, e.g. the output of the previous call.
+    RouterBalance,
+}
+
+/// A single operation of `RouterV2::multicall`.
+///
+/// The input tokens of the calls are taken from the router's own balance, so the batch
+/// should start with `Pull` of the caller's tokens. Outputs of a call can be passed
+/// to the next one by setting its `to` to the router's address and using
+/// `CallAmount::RouterBalance` as the input of the next call.
+/// The batch should end with `Sweep` of the tokens left in the router, as
+/// they can be used by anyone otherwise.
+#[derive(Debug, Clone, PartialEq, Eq, scale::Decode, scale::Encode)]
+#[cfg_attr(feature = "std", derive(scale_info::TypeInfo))]
+pub enum RouterCall {
+    /// Transfers `amount` of `token` from the caller to the router.
+    Pull { token: AccountId, amount: u128 },
+    /// Transfers the whole balance of `token` held by the router to `to` account.
+    Sweep { token: AccountId, to: AccountId },
+    /// See `RouterV2::swap_exact_tokens_for_tokens`.
+    SwapExactTokensForTokens {
+        amount_in: CallAmount,
+        amount_out_min: u128,
+        path: Vec<Step>,
+        token_out: AccountId,
+        to: AccountId,
+    },
+    /// See `RouterV2::add_pair_liquidity`.
+    AddPairLiquidity {
+        pair: Option<AccountId>,
+        token_0: AccountId,
+        token_1: AccountId,
+        amount_0_desired: CallAmount,
+        amount_1_desired: CallAmount,
+        amount_0_min: u128,
+        amount_1_min: u128,
+        to: AccountId,
+    },
+    /// See `RouterV2::remove_pair_liquidity`.
+    RemovePairLiquidity {
+        pair: AccountId,
+        token_0: AccountId,
+        token_1: AccountId,
+        liquidity: CallAmount,
+        amount_0_min: u128,
+        amount_1_min: u128,
+        to: AccountId,
+    },
+    /// See `RouterV2::add_stable_pool_liquidity`.
+    AddStablePoolLiquidity {
+        pool: AccountId,
+        min_share_amount: u128,
+        amounts: Vec<CallAmount>,
+        to: AccountId,
+    },
+    /// See `RouterV2::remove_stable_pool_liquidity_by_share`.
+    RemoveStablePoolLiquidityByShare {
+        pool: AccountId,
+        share_amount: CallAmount,
+        min_amounts: Vec<u128>,
+        to: AccountId,
+    },
+    /// See `RouterV2::remove_stable_pool_liquidity_one_coin`.
+    RemoveStablePoolLiquidityOneCoin {
+        pool: AccountId,
+        share_amount: CallAmount,
+        token: AccountId,
+        min_amount: u128,
+        to: AccountId,
+    },
+}
+
 #[ink::trait_definition]
 pub trait RouterV2 {
     /// Returns address of the pair `Factory` contract for this `RouterV2` instance.
@@ -30,8 +121,8 @@ pub trait RouterV2 {
 
     /// Adds liquidity to the `pair`.
     ///
-    /// If `pair` is `None` then it creates a new pair for
-    /// `(token_0, token_1)` via the Factory contract.
+    /// If `pair` is `None` then it creates a new pair with the default fee tier
+    /// for `(token_0, token_1)` via the Factory contract.
     /// Throws an error if the Pair already exists in the Factory.
     ///
     /// Will add at least `*_min` amount of tokens and up to `*_desired`
@@ -74,8 +165,8 @@ pub trait RouterV2 {
 
     /// Adds liquidity to the `pair`.
     ///
-    /// If `pair` is `None` then it creates a new pair for
-    /// `(token, wrapped_native)` via the Factory contract.
+    /// If `pair` is `None` then it creates a new pair with the default fee tier
+    /// for `(token, wrapped_native)` via the Factory contract.
     /// Throws an error if the Pair already exists in the Factory.
     ///
     /// Will add at least `*_min` amount of tokens and up to `*_desired`
@@ -112,6 +203,73 @@ pub trait RouterV2 {
         deadline: u64,
     ) -> Result<(u128, Balance), RouterV2Error>;
 
+    // ----------- ZAP METHODS ----------- //
+
+    /// Adds liquidity to the `pair` from `amount_in` of a single `token_in`.
+    ///
+    /// Swaps the part of `amount_in` through the `pair` so that the rest of it
+    /// and the swap output are in the ratio of the pair's reserves, then deposits both.
+    /// The swapped part is computed with the pair's fee taken into account.
+    ///
+    /// Only the used amount of `token_in` is transferred from the caller. The leftover
+    /// of the other token (rounding dust) is refunded to the caller.
+    ///
+    /// Fails if less than `min_liquidity` of LP tokens is minted for `to` account.
+    ///
+    /// Returns a tuple of (used amount of `token_in`, minted liquidity).
+    #[ink(message)]
+    fn zap_in_pair(
+        &mut self,
+        pair: AccountId,
+        token_in: AccountId,
+        amount_in: u128,
+        min_liquidity: u128,
+        to: AccountId,
+        deadline: u64,
+    ) -> Result<(u128, u128), RouterV2Error>;
+
+    /// Removes `liquidity` amount of LP tokens from the `pair` and swaps
+    /// the withdrawn other token to `token_out` through the same `pair`.
+    ///
+    /// Fails if the total amount of `token_out` is lower than `min_amount_out`.
+    ///
+    /// Returns the amount of `token_out` transferred to `to` account.
+    #[ink(message)]
+    fn zap_out_pair(
+        &mut self,
+        pair: AccountId,
+        liquidity: u128,
+        token_out: AccountId,
+        min_amount_out: u128,
+        to: AccountId,
+        deadline: u64,
+    ) -> Result<u128, RouterV2Error>;
+
+    /// Adds liquidity to the stable `pool` from an arbitrary input token.
+    ///
+    /// Exchanges `amount_in` of `path[0].token_in` along the `path` to `token`,
+    /// one of the pool's coins, and deposits the whole output to the `pool`
+    /// as a single-sided deposit.
+    ///
+    /// If a non-zero native amount is transferred, `path[0].token_in` has to be
+    /// the wrapped native token. `amount_in` of the transferred native token is wrapped
+    /// and used instead of transferring the wrapped version, the surplus is refunded.
+    ///
+    /// Fails if less than `min_share_amount` of LP tokens is minted for `to` account.
+    ///
+    /// Returns a tuple of (minted shares, fee).
+    #[ink(message, payable)]
+    fn zap_in_stable_pool(
+        &mut self,
+        pool: AccountId,
+        amount_in: u128,
+        path: Vec<Step>,
+        token: AccountId,
+        min_share_amount: u128,
+        to: AccountId,
+        deadline: u64,
+    ) -> Result<(u128, u128), RouterV2Error>;
+
     // ----------- STABLE POOL LIQUIDITY METHODS ----------- //
 
     /// Adds liquidity to the stable pool.
@@ -157,6 +315,164 @@ pub trait RouterV2 {
         deadline: u64,
     ) -> Result<Vec<u128>, RouterV2Error>;
 
+    /// Withdraws liquidity from the stable pool in a single `token`.
+    ///
+    /// If the `token` is the wrapped native token, it attempts to unwrap it
+    /// and withdraw the native token to the `to` account.
+    ///
+    /// Returns a tuple of (withdrawn amount, fee amount), the fee is in `token` units.
+    #[ink(message)]
+    fn remove_stable_pool_liquidity_one_coin(
+        &mut self,
+        pool: AccountId,
+        share_amount: u128,
+        token: AccountId,
+        min_amount: u128,
+        to: AccountId,
+        deadline: u64,
+    ) -> Result<(u128, u128), RouterV2Error>;
+
+    // ----------- FARM METHODS ----------- //
+
+    /// Adds liquidity to the `pair` and deposits the minted LP tokens
+    /// to the `farm` on behalf of the caller.
+    ///
+    /// Fails if the `farm` is not created for the `pair`.
+    ///
+    /// Returns a tuple of (amount of `token_0`, amount of `token_1`, staked liquidity).
+    #[ink(message)]
+    fn add_pair_liquidity_and_stake(
+        &mut self,
+        pair: AccountId,
+        token_0: AccountId,
+        token_1: AccountId,
+        amount_0_desired: u128,
+        amount_1_desired: u128,
+        amount_0_min: u128,
+        amount_1_min: u128,
+        farm: AccountId,
+        deadline: u64,
+    ) -> Result<(u128, u128, u128), RouterV2Error>;
+
+    /// Adds liquidity to the stable `pool` and deposits the minted LP tokens
+    /// to the `farm` on behalf of the caller.
+    ///
+    /// If a non-zero native amount is transferred, it attempts to wrap the transferred
+    /// native token and use it instead of transferring the wrapped version.
+    ///
+    /// Fails if the `farm` is not created for the `pool`.
+    ///
+    /// Returns a tuple of (staked shares, fee).
+    #[ink(message, payable)]
+    fn add_stable_pool_liquidity_and_stake(
+        &mut self,
+        pool: AccountId,
+        min_share_amount: u128,
+        amounts: Vec<u128>,
+        farm: AccountId,
+        deadline: u64,
+    ) -> Result<(u128, u128), RouterV2Error>;
+
+    // ----------- MULTICALL METHODS ----------- //
+
+    /// Executes the `calls` one by one in a single transaction.
+    ///
+    /// Fails, reverting all of the calls, if any of them fails.
+    /// The `deadline` applies to the whole batch.
+    ///
+    /// Returns the amounts returned by each of the calls, in the order of the
+    /// corresponding message's result. `Pull` and `Sweep` return the transferred amount.
+    #[ink(message)]
+    fn multicall(
+        &mut self,
+        calls: Vec<RouterCall>,
+        deadline: u64,
+    ) -> Result<Vec<Vec<u128>>, RouterV2Error>;
+
+    // ----------- PERMIT METHODS ----------- //
+    //
+    // The methods below are the liquidity removals that don't require a prior
+    // `approve` of LP tokens. The caller's `signature` permits the router to spend
+    // the LP tokens (see `Permit::permit`) and is valid until the `deadline`.
+    // The permit is not used if the router's allowance is already sufficient,
+    // so a call doesn't fail if its permit has been front-run.
+    // Only ECDSA accounts can sign permits, sr25519 signatures are rejected
+    // with `PermitError::UnsupportedSignature`.
+
+    /// Same as `remove_pair_liquidity`, but approves `liquidity` with the caller's permit.
+    /// Only usable by ECDSA accounts.
+    #[ink(message)]
+    fn remove_pair_liquidity_with_permit(
+        &mut self,
+        pair: AccountId,
+        token_0: AccountId,
+        token_1: AccountId,
+        liquidity: u128,
+        amount_0_min: u128,
+        amount_1_min: u128,
+        to: AccountId,
+        deadline: u64,
+        signature: PermitSignature,
+    ) -> Result<(u128, u128), RouterV2Error>;
+
+    /// Same as `remove_pair_liquidity_native`, but approves `liquidity` with the caller's permit.
+    /// Only usable by ECDSA accounts.
+    #[ink(message)]
+    fn remove_pair_liquidity_native_with_permit(
+        &mut self,
+        pair: AccountId,
+        token: AccountId,
+        liquidity: u128,
+        amount_token_min: u128,
+        amount_native_min: Balance,
+        to: AccountId,
+        deadline: u64,
+        signature: PermitSignature,
+    ) -> Result<(u128, Balance), RouterV2Error>;
+
+    /// Same as `remove_stable_pool_liquidity`, but approves `max_share_amount`
+    /// with the caller's permit.
+    /// Only usable by ECDSA accounts.
+    #[ink(message)]
+    fn remove_stable_pool_liquidity_with_permit(
+        &mut self,
+        pool: AccountId,
+        max_share_amount: u128,
+        amounts: Vec<u128>,
+        to: AccountId,
+        deadline: u64,
+        signature: PermitSignature,
+    ) -> Result<(u128, u128), RouterV2Error>;
+
+    /// Same as `remove_stable_pool_liquidity_by_share`, but approves `share_amount`
+    /// with the caller's permit.
+    /// Only usable by ECDSA accounts.
+    #[ink(message)]
+    fn remove_stable_pool_liquidity_by_share_with_permit(
+        &mut self,
+        pool: AccountId,
+        share_amount: u128,
+        min_amounts: Vec<u128>,
+        to: AccountId,
+        deadline: u64,
+        signature: PermitSignature,
+    ) -> Result<Vec<u128>, RouterV2Error>;
+
+    /// Same as `remove_stable_pool_liquidity_one_coin`, but approves `share_amount`
+    /// with the caller's permit.
+    /// Only usable by ECDSA accounts.
+    #[ink(message)]
+    fn remove_stable_pool_liquidity_one_coin_with_permit(
+        &mut self,
+        pool: AccountId,
+        share_amount: u128,
+        token: AccountId,
+        min_amount: u128,
+        to: AccountId,
+        deadline: u64,
+        signature: PermitSignature,
+    ) -> Result<(u128, u128), RouterV2Error>;
+
     // ----------- SWAP METHODS ----------- //
 
     /// Exchanges tokens along the `path` to `token_out`.
@@ -176,6 +492,30 @@ pub trait RouterV2 {
         deadline: u64,
     ) -> Result<Vec<u128>, RouterV2Error>;
 
+    /// Exchanges `amount_in` of tokens to `token_out` along several `paths`.
+    ///
+    /// `amount_in` is split between the `paths` proportionally to their weights,
+    /// the rounding leftover goes to the last path. All paths have to start
+    /// with the same token.
+    ///
+    /// The paths are quoted and executed one after another, so each path
+    /// sees the pool states updated by the previous ones.
+    ///
+    /// Fails if the total output amount is less than `amount_out_min`.
+    /// Transfers tokens to account under `to` address.
+    ///
+    /// Returns list of swap outcomes along each of the paths.
+    #[ink(message)]
+    fn swap_exact_tokens_for_tokens_split(
+        &mut self,
+        amount_in: u128,
+        amount_out_min: u128,
+        paths: Vec<WeightedPath>,
+        token_out: AccountId,
+        to: AccountId,
+        deadline: u64,
+    ) -> Result<Vec<Vec<u128>>, RouterV2Error>;
+
     /// Exchanges tokens along `path` to `token_out`
     /// so that at the end caller receives `amount_out`
     /// worth of tokens and pays no more than `amount_in_max`
@@ -274,6 +614,110 @@ pub trait RouterV2 {
         path: Vec<Step>,
         token_out: AccountId,
     ) -> Result<Vec<u128>, RouterV2Error>;
+
+    /// Returns tokens which can be exchanged in the pool `pool_id`.
+    ///
+    /// Fails if `pool_id` is not a pool supported by this `RouterV2` instance.
+    #[ink(message)]
+    fn pool_tokens(&self, pool_id: AccountId) -> Result<Vec<AccountId>, RouterV2Error>;
+
+    // ----------- QUOTE METHODS ----------- //
+
+    /// Returns amount of `B` tokens that have to be supplied
+    /// with the `amount_0` of `A` tokens to maintain
+    /// the constant ratio of `reserve_0 / reserve_1`.
+    #[ink(message)]
+    fn quote(
+        &self,
+        amount_0: u128,
+        reserve_0: u128,
+        reserve_1: u128,
+    ) -> Result<u128, RouterV2Error>;
+
+    /// Returns amount of `B` tokens received for `amount_in` of `A` tokens
+    /// from a `Pair` with `reserve_0` of `A`, `reserve_1` of `B` and `fee` (in millis).
+    #[ink(message)]
+    fn get_amount_out(
+        &self,
+        amount_in: u128,
+        reserve_0: u128,
+        reserve_1: u128,
+        fee: u8,
+    ) -> Result<u128, RouterV2Error>;
+
+    /// Returns amount of `A` tokens user has to supply to get exactly `amount_out`
+    /// of `B` tokens from a `Pair` with `reserve_0` of `A`, `reserve_1` of `B`
+    /// and `fee` (in millis).
+    #[ink(message)]
+    fn get_amount_in(
+        &self,
+        amount_out: u128,
+        reserve_0: u128,
+        reserve_1: u128,
+        fee: u8,
+    ) -> Result<u128, RouterV2Error>;
+
+    /// Returns a tuple of (amount out, fee) for swapping `amount_in` of the token
+    /// at `token_in_id` to the token at `token_out_id` in a stable pool with
+    /// given `reserves`, amplification coefficient `amp_coef` and `trade_fee`
+    /// (with 1e9 precision).
+    ///
+    /// `scaled_rates` are the token rates (see `StablePool::token_rates`)
+    /// multiplied by `10^(18 - token decimals)`.
+    /// Fails if any of the `reserves` or `scaled_rates` is zero.
+    ///
+    /// NOTE: fee is applied on the token out.
+    #[ink(message)]
+    fn get_stable_amount_out(
+        &self,
+        amount_in: u128,
+        token_in_id: u8,
+        token_out_id: u8,
+        reserves: Vec<u128>,
+        scaled_rates: Vec<u128>,
+        amp_coef: u128,
+        trade_fee: u32,
+    ) -> Result<(u128, u128), RouterV2Error>;
+
+    /// Returns a tuple of (amount in, fee) for receiving exactly `amount_out`
+    /// of the token at `token_out_id` for the token at `token_in_id` from
+    /// a stable pool. See `get_stable_amount_out` for the parameters.
+    ///
+    /// NOTE: fee is applied on the token out.
+    #[ink(message)]
+    fn get_stable_amount_in(
+        &self,
+        amount_out: u128,
+        token_in_id: u8,
+        token_out_id: u8,
+        reserves: Vec<u128>,
+        scaled_rates: Vec<u128>,
+        amp_coef: u128,
+        trade_fee: u32,
+    ) -> Result<(u128, u128), RouterV2Error>;
+
+    /// Searches for the `path` exchanging `amount_in` of `token_in` to `token_out`
+    /// with the highest output amount.
+    ///
+    /// Searches all pairs of the pair `Factory`, all pools of the StablePool factory (if set)
+    /// and the stable pools registered in this `RouterV2` instance. Other kinds of pools
+    /// are not searched. Paths consist of at most `max_hops` (at most 4) steps and never
+    /// use the same pool twice.
+    ///
+    /// Returns the best path together with its quoted output amount.
+    /// Fails with `TooManyPools` if there are more than 32 pools to search.
+    ///
+    /// NOTE: Every pool is fetched and every considered step is quoted with cross-contract
+    /// calls, so the cost grows quickly with the number of pools and `max_hops`.
+    /// Use the off-chain `amm-path-finder` crate once there are more pools.
+    #[ink(message)]
+    fn find_best_path(
+        &self,
+        token_in: AccountId,
+        token_out: AccountId,
+        amount_in: u128,
+        max_hops: u8,
+    ) -> Result<(Vec<Step>, u128), RouterV2Error>;
 }
 
 #[derive(Debug, PartialEq, Eq, scale::Encode, scale::Decode)]
@@ -285,10 +729,17 @@ pub enum RouterV2Error {
     LangError(LangError),
     MathError(MathError),
     StablePoolError(StablePoolError),
+    ConcentratedPoolError(ConcentratedPoolError),
+    WeightedPoolError(WeightedPoolError),
+    CryptoPoolError(CryptoPoolError),
+    PermitError(PermitError),
+    FarmError(FarmError),
+    Ownable2StepError(Ownable2StepError),
 
     EmptyPath,
     Expired,
     InvalidPoolAddress,
+    InvalidFarm,
     InvalidToken,
     InvalidRecipient,
     TransferError,
@@ -299,6 +750,11 @@ pub enum RouterV2Error {
     InsufficientAmount0,
     InsufficientAmount1,
     InsufficientLiquidity,
+
+    InvalidMaxHops,
+    PathNotFound,
+    TooManyPools,
+    InvalidRate,
 }
 
 macro_rules! impl_froms {
@@ -319,5 +775,11 @@ impl_froms!(
     PairError,
     LangError,
     MathError,
-    StablePoolError
+    StablePoolError,
+    ConcentratedPoolError,
+    WeightedPoolError,
+    CryptoPoolError,
+    PermitError,
+    FarmError,
+    Ownable2StepError
 );
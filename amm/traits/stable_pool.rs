@@ -43,6 +43,11 @@ pub trait StablePool {
     #[ink(message)]
     fn token_rates_providers(&self) -> Vec<Option<AccountId>>;
 
+    /// Returns the scheduled rate provider of `token` and the time (in milisec)
+    /// from which it can be applied, if there is a pending change.
+    #[ink(message)]
+    fn future_token_rate_provider(&self, token: AccountId) -> Option<(Option<AccountId>, u64)>;
+
     /// Calculate swap amount of `token_out`
     /// given `token_in amount`.
     ///
@@ -121,6 +126,60 @@ pub trait StablePool {
         liquidity: u128,
     ) -> Result<Vec<u128>, StablePoolError>;
 
+    /// Calculate how much of `token` will be withdrawn
+    /// for burning `shares` amount of lp tokens.
+    ///
+    /// Updates cached token rates if there was a new block since the previous update.
+    ///
+    /// Returns a tuple of (withdraw amount, fee amount)
+    /// where the fee is in `token` units, unlike in `remove_liquidity_by_amounts`
+    /// where it is in lp token units.
+    #[ink(message)]
+    fn get_amount_for_one_coin_burn(
+        &mut self,
+        shares: u128,
+        token: AccountId,
+    ) -> Result<(u128, u128), StablePoolError>;
+
+    /// Returns the value of one LP token in terms of the invariant (D / total supply),
+    /// with 1e18 precision.
+    ///
+    /// Updates cached token rates if there was a new block since the previous update.
+    ///
+    /// Fails if there is no liquidity in the pool.
+    #[ink(message)]
+    fn virtual_price(&mut self) -> Result<u128, StablePoolError>;
+
+    /// Returns the stable swap invariant (D) of the rated reserves, with 18 decimal places.
+    ///
+    /// Updates cached token rates if there was a new block since the previous update.
+    #[ink(message)]
+    fn invariant_d(&mut self) -> Result<u128, StablePoolError>;
+
+    /// Returns the price of `token_in` in terms of `token_out`
+    /// averaged over at least the last `window` milisec, with 1e18 precision.
+    /// The price is expressed in token amounts, i.e. `amount` of `token_in` is worth
+    /// approximately `amount * price / 1e18` of `token_out`.
+    ///
+    /// The average is computed since the most recent observation at least `window` milisec old,
+    /// so it may cover a longer period (at most by the observation period of 5 minutes
+    /// if the pool is traded frequently enough).
+    /// Fails if there is no such observation.
+    #[ink(message)]
+    fn get_twap_price(
+        &self,
+        token_in: AccountId,
+        token_out: AccountId,
+        window: u64,
+    ) -> Result<u128, StablePoolError>;
+
+    /// Returns the virtual price (value of the lp token in terms of the invariant)
+    /// averaged over at least the last `window` milisec, with 1e18 precision.
+    ///
+    /// See `get_twap_price` for the details of the averaging.
+    #[ink(message)]
+    fn get_twap_virtual_price(&self, window: u64) -> Result<u128, StablePoolError>;
+
     /// Deposit `amounts` of tokens to receive lpt tokens to `to` account.
     /// Caller must allow enough spending allowance of underlying tokens
     /// for this contract.
@@ -157,6 +216,22 @@ pub trait StablePool {
         to: AccountId,
     ) -> Result<Vec<u128>, StablePoolError>;
 
+    /// Burns `shares` of lp tokens and withdraws underlying tokens
+    /// in a single `token` to `to` account.
+    /// Fails if the amount received is less than `min_amount`.
+    /// NOTE: Fee is applied in the same way as in `remove_liquidity_by_amounts`.
+    /// Returns a tuple of (withdrawn amount, fee amount)
+    /// where the fee is in `token` units, unlike in `remove_liquidity_by_amounts`
+    /// where it is in lp token units.
+    #[ink(message)]
+    fn remove_liquidity_one_coin(
+        &mut self,
+        shares: u128,
+        token: AccountId,
+        min_amount: u128,
+        to: AccountId,
+    ) -> Result<(u128, u128), StablePoolError>;
+
     /// Swaps token_in to token_out.
     /// Swapped tokens are transferred to the `to` account.
     /// caller account must allow enough spending allowance of `token_in`
@@ -205,6 +280,22 @@ pub trait StablePool {
         to: AccountId,
     ) -> Result<(u128, u128), StablePoolError>;
 
+    /// Lends `amounts` of `tokens` to the `receiver` for the duration of the call.
+    ///
+    /// Transfers the tokens to the `receiver` and calls its `FlashBorrower::on_flash_loan`
+    /// with `data`. The `receiver` must transfer back the borrowed amounts plus fees
+    /// before the callback returns. The fee is charged as the trade fee, part of it goes
+    /// to the protocol as in swaps.
+    /// Returns the fees charged for each token.
+    #[ink(message)]
+    fn flash_loan(
+        &mut self,
+        tokens: Vec<AccountId>,
+        amounts: Vec<u128>,
+        receiver: AccountId,
+        data: Vec<u8>,
+    ) -> Result<Vec<u128>, StablePoolError>;
+
     // --- OWNER RESTRICTED FUNCTIONS --- //
 
     #[ink(message)]
@@ -228,6 +319,30 @@ pub trait StablePool {
     /// If ramping is not in progress, it does not influence the A.
     #[ink(message)]
     fn stop_ramp_amp_coef(&mut self) -> Result<(), StablePoolError>;
+
+    /// Schedules the change of the rate provider of `token` to `rate_provider`.
+    /// If `rate_provider` is None, the rate of `token` becomes constant.
+    /// Replaces a pending change of the `token`'s rate provider, if any.
+    ///
+    /// The change can be applied after `RATE_PROVIDER_CHANGE_DELAY`,
+    /// see `apply_token_rate_provider`.
+    #[ink(message)]
+    fn set_token_rate_provider(
+        &mut self,
+        token: AccountId,
+        rate_provider: Option<AccountId>,
+    ) -> Result<(), StablePoolError>;
+
+    /// Applies the scheduled change of the rate provider of `token`
+    /// and resets its cached rate.
+    ///
+    /// Updates the oracle before and after the change, so that the cumulative
+    /// prices of the periods of both providers are not mixed.
+    ///
+    /// Fails if the rate of the new provider differs from the cached rate
+    /// by more than `MAX_RATE_CHANGE`.
+    #[ink(message)]
+    fn apply_token_rate_provider(&mut self, token: AccountId) -> Result<(), StablePoolError>;
 }
 
 #[derive(Debug, PartialEq, Eq, scale::Encode, scale::Decode)]
@@ -253,6 +368,13 @@ pub enum StablePoolError {
     AmpCoefTooHigh,
     AmpCoefRampDurationTooShort,
     AmpCoefChangeTooLarge,
+    InvalidWindow,
+    InsufficientObservations,
+    InsufficientFlashLoanRepayment,
+    InvalidFlashLoanCallback,
+    NoRateProviderChange,
+    RateProviderChangeTooEarly,
+    RateChangeTooLarge,
 }
 
 impl From<PSP22Error> for StablePoolError {
@@ -0,0 +1,85 @@
+use ink::{
+    prelude::vec::Vec,
+    primitives::{AccountId, Hash},
+};
+
+use crate::{Ownable2StepError, StablePoolError};
+
+#[ink::trait_definition]
+pub trait StablePoolFactory {
+    /// Returns address of the pool under `pid` index.
+    #[ink(message)]
+    fn all_pools(&self, pid: u64) -> Option<AccountId>;
+
+    /// Returns number of pools created via this factory.
+    #[ink(message)]
+    fn all_pools_length(&self) -> u64;
+
+    /// Returns code hash of the `StablePool` contract used to instantiate pools.
+    #[ink(message)]
+    fn pool_contract_code_hash(&self) -> Hash;
+
+    /// Returns address of the pool for the `tokens` set, if there is one.
+    ///
+    /// `tokens` have to be sorted in ascending order.
+    #[ink(message)]
+    fn get_pool(&self, tokens: Vec<AccountId>) -> Option<AccountId>;
+
+    /// Returns `true` if `pool` has been created via this factory.
+    #[ink(message)]
+    fn is_pool(&self, pool: AccountId) -> bool;
+
+    /// Creates a new stable pool for the `tokens` set.
+    ///
+    /// `tokens` have to be sorted in ascending order and must not contain duplicates.
+    /// Only one pool per `tokens` set can be created.
+    /// See `StablePoolContract::new_rated` for the description of the remaining arguments.
+    ///
+    /// Can only be called by the factory owner.
+    ///
+    /// Returns address of the created pool.
+    #[ink(message)]
+    fn create_pool(
+        &mut self,
+        tokens: Vec<AccountId>,
+        tokens_decimals: Vec<u8>,
+        external_rates: Vec<Option<AccountId>>,
+        init_amp_coef: u128,
+        owner: AccountId,
+        trade_fee: u32,
+        protocol_fee: u32,
+        fee_receiver: Option<AccountId>,
+    ) -> Result<AccountId, StablePoolFactoryError>;
+
+    /// Sets code hash of the `StablePool` contract used to instantiate new pools.
+    /// Does not affect pools created so far.
+    ///
+    /// Can only be called by the factory owner.
+    #[ink(message)]
+    fn set_pool_contract_code_hash(
+        &mut self,
+        code_hash: Hash,
+    ) -> Result<(), StablePoolFactoryError>;
+}
+
+#[derive(Debug, PartialEq, Eq, scale::Encode, scale::Decode)]
+#[cfg_attr(feature = "std", derive(scale_info::TypeInfo))]
+pub enum StablePoolFactoryError {
+    Ownable2StepError(Ownable2StepError),
+    StablePoolError(StablePoolError),
+    UnsortedTokens,
+    PoolExists,
+    PoolInstantiationFailed,
+}
+
+impl From<Ownable2StepError> for StablePoolFactoryError {
+    fn from(error: Ownable2StepError) -> Self {
+        StablePoolFactoryError::Ownable2StepError(error)
+    }
+}
+
+impl From<StablePoolError> for StablePoolFactoryError {
+    fn from(error: StablePoolError) -> Self {
+        StablePoolFactoryError::StablePoolError(error)
+    }
+}
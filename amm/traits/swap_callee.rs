@@ -7,18 +7,14 @@ use ink::primitives::AccountId;
 ///
 /// Note to the implementors:
 /// Implementation should ensure that the caller is actually a `Pair` contract instance.
-/// For example by calling the caller using `Pair` contract interface and checking that
-/// it's an instance of expected token pair in the Factory.
+/// For example by checking that the caller has been created by the Factory.
 ///
 /// Example:
 ///
 /// ```rust
 /// fn swap_call(&mut self, sender: AccountId, amount0: u128, amount1: u128, data: Vec<u8>) {
-///   let pair: contract_ref!(Pair) = self.env().caller().into();
-///   let token_0 = pair.get_token_0();
-///   let token_1 = pair.get_token_1();
 ///   let factory: contract_ref!(Factory) = self.factory.into(); // Needs to know the factory address.
-///   assert!(factory.get_pair(token_0, token_1) == self.env().caller());
+///   assert!(factory.is_pair(self.env().caller()));
 ///
 ///   // rest of the code
 /// }`
@@ -0,0 +1,77 @@
+use amm_helpers::types::WrappedU256;
+use ink::primitives::AccountId;
+
+use crate::MathError;
+
+/// Cumulative prices of a `Pair` recorded at a given time.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, scale::Encode, scale::Decode)]
+#[cfg_attr(
+    feature = "std",
+    derive(scale_info::TypeInfo, ink::storage::traits::StorageLayout)
+)]
+pub struct Observation {
+    /// Timestamp (in seconds, modulo 2^32) at which the observation was recorded.
+    pub timestamp: u32,
+    /// Value of the `Pair::price_0_cumulative_last` at `timestamp`.
+    pub price_0_cumulative: WrappedU256,
+    /// Value of the `Pair::price_1_cumulative_last` at `timestamp`.
+    pub price_1_cumulative: WrappedU256,
+}
+
+#[ink::trait_definition]
+pub trait TwapOracle {
+    /// Returns minimal time (in seconds) between two consecutive observations of a pair.
+    #[ink(message)]
+    fn period(&self) -> u32;
+
+    /// Returns max number of observations stored for each pair.
+    /// When exceeded, the oldest observation is overwritten.
+    #[ink(message)]
+    fn capacity(&self) -> u32;
+
+    /// Returns the most recent observation of the `pair`.
+    #[ink(message)]
+    fn last_observation(&self, pair: AccountId) -> Option<Observation>;
+
+    /// Records an observation of the current cumulative prices of the `pair`.
+    ///
+    /// Does nothing if less than `period` seconds elapsed since the last observation
+    /// of the `pair`. Should be called at least once per `period` to keep `consult` working.
+    ///
+    /// Can be called by anyone.
+    #[ink(message)]
+    fn update(&mut self, pair: AccountId) -> Result<(), TwapOracleError>;
+
+    /// Returns how much of the other token of the `pair` is worth `amount_in` of `token_in`
+    /// using the price averaged over the last `window` seconds.
+    ///
+    /// The average is computed since the most recent observation older than `window` seconds.
+    /// Fails if there is no such observation or if it is more than `period` seconds older
+    /// than `window` (which means that `update` was not called frequently enough).
+    #[ink(message)]
+    fn consult(
+        &self,
+        pair: AccountId,
+        token_in: AccountId,
+        amount_in: u128,
+        window: u32,
+    ) -> Result<u128, TwapOracleError>;
+}
+
+#[derive(Debug, PartialEq, Eq, scale::Encode, scale::Decode)]
+#[cfg_attr(feature = "std", derive(scale_info::TypeInfo))]
+pub enum TwapOracleError {
+    MathError(MathError),
+    InvalidConfig,
+    InvalidToken,
+    InvalidWindow,
+    InsufficientObservations,
+    StaleObservations,
+    ReservesOverflow,
+}
+
+impl From<MathError> for TwapOracleError {
+    fn from(error: MathError) -> Self {
+        TwapOracleError::MathError(error)
+    }
+}
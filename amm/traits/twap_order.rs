@@ -0,0 +1,118 @@
+use crate::{MathError, RouterV2Error, Step, TwapOracleError};
+use ink::{prelude::vec::Vec, primitives::AccountId};
+use psp22::PSP22Error;
+
+/// Order selling `amount_in` of `path[0].token_in` for `token_out` in equal slices
+/// executed at least `interval` milliseconds apart.
+#[derive(Debug, Clone, PartialEq, Eq, scale::Encode, scale::Decode)]
+#[cfg_attr(
+    feature = "std",
+    derive(scale_info::TypeInfo, ink::storage::traits::StorageLayout)
+)]
+pub struct TwapOrder {
+    /// Account which created the order and receives its output.
+    pub owner: AccountId,
+    /// `RouterV2` path the slices are swapped along. Has to consist of pairs only.
+    pub path: Vec<Step>,
+    pub token_out: AccountId,
+    /// Amount of `path[0].token_in` not swapped yet.
+    pub amount_remaining: u128,
+    /// Number of slices not executed yet.
+    pub slices_remaining: u32,
+    /// Minimal time (in milliseconds) between two consecutive slices.
+    pub interval: u64,
+    /// Max deviation (in basis points) of each slice's output below the TWAP quote.
+    pub max_deviation: u16,
+    /// Timestamp (in milliseconds) from which the next slice can be executed.
+    pub next_execution: u64,
+}
+
+/// Splits large swaps into slices executed over time through `RouterV2`,
+/// so that each of them moves the pairs only a little.
+#[ink::trait_definition]
+pub trait TwapOrderBook {
+    /// Returns address of the `RouterV2` the slices are swapped with.
+    #[ink(message)]
+    fn router(&self) -> AccountId;
+
+    /// Returns address of the `TwapOracle` the slices are checked against.
+    #[ink(message)]
+    fn oracle(&self) -> AccountId;
+
+    /// Returns length (in milliseconds) of the window of the TWAP used to check the slices.
+    #[ink(message)]
+    fn window(&self) -> u64;
+
+    /// Returns the order with `order_id`, if it is still open.
+    #[ink(message)]
+    fn get_order(&self, order_id: u64) -> Option<TwapOrder>;
+
+    /// Creates an order selling `amount_in` of `path[0].token_in` for `token_out`
+    /// in `slices` equal slices, executed at least `interval` milliseconds apart.
+    ///
+    /// The output of each slice cannot be lower than its quote from the TWAP of the pairs
+    /// along the `path` by more than `max_deviation` basis points.
+    /// Fails with `PoolIsNotPair` if any step of the `path` is not a Pair.
+    ///
+    /// Transfers `amount_in` from the caller to the contract, so the caller has to approve
+    /// the contract beforehand.
+    ///
+    /// Returns id of the new order.
+    #[ink(message)]
+    fn create_order(
+        &mut self,
+        path: Vec<Step>,
+        token_out: AccountId,
+        amount_in: u128,
+        slices: u32,
+        interval: u64,
+        max_deviation: u16,
+    ) -> Result<u64, TwapOrderError>;
+
+    /// Swaps the next slice of the order through `RouterV2` and transfers the output
+    /// to the order owner. The last slice swaps all the remaining tokens.
+    ///
+    /// Can be called by anyone, once `interval` passed since the previous slice.
+    /// Records new observations of the pairs along the path in the oracle.
+    ///
+    /// Returns the amount of `token_out` received by the owner.
+    #[ink(message)]
+    fn execute_slice(&mut self, order_id: u64) -> Result<u128, TwapOrderError>;
+
+    /// Cancels the order and returns its remaining tokens to the owner.
+    ///
+    /// Can be called only by the owner.
+    #[ink(message)]
+    fn cancel_order(&mut self, order_id: u64) -> Result<(), TwapOrderError>;
+}
+
+#[derive(Debug, PartialEq, Eq, scale::Encode, scale::Decode)]
+#[cfg_attr(feature = "std", derive(scale_info::TypeInfo))]
+pub enum TwapOrderError {
+    PSP22Error(PSP22Error),
+    RouterV2Error(RouterV2Error),
+    TwapOracleError(TwapOracleError),
+    MathError(MathError),
+    CallerIsNotOwner,
+    EmptyPath,
+    InvalidDeviation,
+    InvalidSlices,
+    InvalidWindow,
+    OrderNotFound,
+    PoolIsNotPair,
+    TooEarly,
+}
+
+macro_rules! impl_froms {
+    ( $( $error:ident ),* ) => {
+        $(
+            impl From<$error> for TwapOrderError {
+                fn from(error: $error) -> Self {
+                    TwapOrderError::$error(error)
+                }
+            }
+        )*
+    };
+}
+
+impl_froms!(PSP22Error, RouterV2Error, TwapOracleError, MathError);
@@ -0,0 +1,273 @@
+use ink::prelude::vec::Vec;
+use ink::primitives::AccountId;
+use ink::LangError;
+use psp22::PSP22Error;
+
+use crate::{MathError, Ownable2StepError};
+
+/// Weighted-product pool of 2 to 8 tokens, based on the Balancer V2 model.
+///
+/// The pool keeps `PROD{b_i^w_i}` constant in swaps, where `b_i` are the reserves and `w_i`
+/// the fixed normalized weights of the tokens (summing up to 1e18). The spot price of token `i`
+/// in terms of token `j` is `(b_j / w_j) / (b_i / w_i)`.
+#[ink::trait_definition]
+pub trait WeightedPool {
+    /// Returns list of tokens in the pool.
+    #[ink(message)]
+    fn tokens(&self) -> Vec<AccountId>;
+
+    /// Returns list of normalized tokens weights with 1e18 precision.
+    #[ink(message)]
+    fn weights(&self) -> Vec<u128>;
+
+    /// Returns list of tokens reserves.
+    #[ink(message)]
+    fn reserves(&self) -> Vec<u128>;
+
+    /// Returns current trade and protocol fees in 1e9 precision.
+    #[ink(message)]
+    fn fees(&self) -> (u32, u32);
+
+    /// Protocol fees receiver (if any)
+    #[ink(message)]
+    fn fee_receiver(&self) -> Option<AccountId>;
+
+    /// Calculate swap amount of `token_out`
+    /// given `token_in amount`.
+    ///
+    /// Returns a tuple of (amount out, fee)
+    /// NOTE: fee is applied on `token_in`
+    #[ink(message)]
+    fn get_swap_amount_out(
+        &self,
+        token_in: AccountId,
+        token_out: AccountId,
+        token_in_amount: u128,
+    ) -> Result<(u128, u128), WeightedPoolError>;
+
+    /// Calculate required swap amount of `token_in`
+    /// to get `token_out_amount`.
+    ///
+    /// Returns a tuple of (amount in, fee)
+    /// NOTE: fee is applied on `token_in`
+    #[ink(message)]
+    fn get_swap_amount_in(
+        &self,
+        token_in: AccountId,
+        token_out: AccountId,
+        token_out_amount: u128,
+    ) -> Result<(u128, u128), WeightedPoolError>;
+
+    /// Calculate deposit amounts required
+    /// to mint `liquidity` amount of lp tokens.
+    ///
+    /// Returns required deposit amounts
+    #[ink(message)]
+    fn get_amounts_for_liquidity_mint(
+        &self,
+        liquidity: u128,
+    ) -> Result<Vec<u128>, WeightedPoolError>;
+
+    /// Calculate withdraw amounts for
+    /// burning `liquidity` amount of lp tokens.
+    ///
+    /// Returns withdraw amounts
+    #[ink(message)]
+    fn get_amounts_for_liquidity_burn(
+        &self,
+        liquidity: u128,
+    ) -> Result<Vec<u128>, WeightedPoolError>;
+
+    /// Calculate how many lp tokens will be minted
+    /// for depositing `amount` of a single `token`.
+    ///
+    /// Returns a tuple of (lpt amount, fee)
+    #[ink(message)]
+    fn get_mint_liquidity_for_one_coin(
+        &self,
+        token: AccountId,
+        amount: u128,
+    ) -> Result<(u128, u128), WeightedPoolError>;
+
+    /// Calculate how much of `token` will be withdrawn
+    /// for burning `shares` amount of lp tokens.
+    ///
+    /// Returns a tuple of (withdraw amount, fee amount)
+    #[ink(message)]
+    fn get_amount_for_one_coin_burn(
+        &self,
+        shares: u128,
+        token: AccountId,
+    ) -> Result<(u128, u128), WeightedPoolError>;
+
+    /// Deposit `amounts` of all the tokens to receive lpt tokens to `to` account.
+    /// Caller must allow enough spending allowance of underlying tokens
+    /// for this contract.
+    ///
+    /// The first deposit sets the initial prices and mints the invariant
+    /// of the deposited amounts. Next deposits mint lp tokens proportionally to the least
+    /// (relative to its reserve) of the `amounts` and transfer only the amounts of
+    /// the tokens proportional to the reserves, the surplus of the others is not taken.
+    /// Use `get_amounts_for_liquidity_mint` to get the exact amounts.
+    ///
+    /// Returns an error if the minted LP tokens amount is less
+    /// than `min_share_amount`.
+    /// Returns minted lpt amount
+    #[ink(message)]
+    fn add_liquidity(
+        &mut self,
+        min_share_amount: u128,
+        amounts: Vec<u128>,
+        to: AccountId,
+    ) -> Result<u128, WeightedPoolError>;
+
+    /// Deposit `amount` of a single `token` to receive lpt tokens to `to` account.
+    /// Caller must allow enough spending allowance of `token` for this contract.
+    /// NOTE: Fee is applied on the part of `amount` exceeding the token's weight share.
+    ///
+    /// Returns an error if the minted LP tokens amount is less
+    /// than `min_share_amount`.
+    /// Returns a tuple of (minted lpt amount, fee)
+    #[ink(message)]
+    fn add_liquidity_one_coin(
+        &mut self,
+        token: AccountId,
+        amount: u128,
+        min_share_amount: u128,
+        to: AccountId,
+    ) -> Result<(u128, u128), WeightedPoolError>;
+
+    /// Burns lp tokens and withdraws underlying tokens in balanced amounts to `to` account.
+    /// Fails if any of the amounts received is less than in `min_amounts`.
+    /// Returns withdrawal amounts
+    #[ink(message)]
+    fn remove_liquidity_by_shares(
+        &mut self,
+        shares: u128,
+        min_amounts: Vec<u128>,
+        to: AccountId,
+    ) -> Result<Vec<u128>, WeightedPoolError>;
+
+    /// Burns `shares` of lp tokens and withdraws underlying tokens
+    /// in a single `token` to `to` account.
+    /// Fails if the amount received is less than `min_amount`.
+    /// NOTE: Fee is applied on the part of the amount exceeding the token's weight share.
+    /// Returns a tuple of (withdrawn amount, fee amount)
+    #[ink(message)]
+    fn remove_liquidity_one_coin(
+        &mut self,
+        shares: u128,
+        token: AccountId,
+        min_amount: u128,
+        to: AccountId,
+    ) -> Result<(u128, u128), WeightedPoolError>;
+
+    /// Swaps token_in to token_out.
+    /// Swapped tokens are transferred to the `to` account.
+    /// caller account must allow enough spending allowance of `token_in`
+    /// for this contract.
+    /// Returns an error if swapped `token_out` amount is less than
+    /// `min_token_out_amount`.
+    /// NOTE: Fee is applied on `token_in`.
+    /// Returns a tuple of (token out amount, fee amount)
+    #[ink(message)]
+    fn swap_exact_in(
+        &mut self,
+        token_in: AccountId,
+        token_out: AccountId,
+        token_in_amount: u128,
+        min_token_out_amount: u128,
+        to: AccountId,
+    ) -> Result<(u128, u128), WeightedPoolError>;
+
+    /// Swaps token_in to token_out.
+    /// Swapped tokens are transferred to the `to` account.
+    /// Caller account must allow enough spending allowance of `token_in`
+    /// for this contract.
+    /// Returns an error if it is required to spend more than
+    /// `max_token_in_amount` to get `token_out_amount`.
+    /// NOTE: Fee is applied on `token_in`.
+    /// Returns a tuple of (token in amount, fee amount)
+    #[ink(message)]
+    fn swap_exact_out(
+        &mut self,
+        token_in: AccountId,
+        token_out: AccountId,
+        token_out_amount: u128,
+        max_token_in_amount: u128,
+        to: AccountId,
+    ) -> Result<(u128, u128), WeightedPoolError>;
+
+    /// Swaps excess reserve balance of `token_in` to `token_out`.
+    /// Swapped tokens are transferred to the `to` account.
+    /// Returns a tuple of (token out amount, fee amount)
+    #[ink(message)]
+    fn swap_received(
+        &mut self,
+        token_in: AccountId,
+        token_out: AccountId,
+        min_token_out_amount: u128,
+        to: AccountId,
+    ) -> Result<(u128, u128), WeightedPoolError>;
+
+    // --- OWNER RESTRICTED FUNCTIONS --- //
+
+    #[ink(message)]
+    fn set_fee_receiver(
+        &mut self,
+        fee_receiver: Option<AccountId>,
+    ) -> Result<(), WeightedPoolError>;
+
+    /// Set fees
+    /// - trade_fee given as an integer with 1e9 precision. The the maximum is 1% (10000000)
+    /// - protocol_fee given as an integer with 1e9 precision. The maximum is 50% (500000000)
+    #[ink(message)]
+    fn set_fees(&mut self, trade_fee: u32, protocol_fee: u32) -> Result<(), WeightedPoolError>;
+}
+
+#[derive(Debug, PartialEq, Eq, scale::Encode, scale::Decode)]
+#[cfg_attr(feature = "std", derive(scale_info::TypeInfo))]
+pub enum WeightedPoolError {
+    Ownable2StepError(Ownable2StepError),
+    MathError(MathError),
+    PSP22Error(PSP22Error),
+    LangError(LangError),
+    InvalidTokenId(AccountId),
+    IdenticalTokenId,
+    IncorrectAmountsCount,
+    ZeroAmounts,
+    InsufficientLiquidityMinted,
+    InsufficientOutputAmount,
+    InsufficientLiquidity,
+    InsufficientInputAmount,
+    IncorrectTokenCount,
+    TooLargeTokenDecimal,
+    InvalidWeights,
+    InvalidFee,
+    MaxInRatioExceeded,
+    MaxOutRatioExceeded,
+}
+
+impl From<PSP22Error> for WeightedPoolError {
+    fn from(error: PSP22Error) -> Self {
+        WeightedPoolError::PSP22Error(error)
+    }
+}
+
+impl From<LangError> for WeightedPoolError {
+    fn from(error: LangError) -> Self {
+        WeightedPoolError::LangError(error)
+    }
+}
+
+impl From<MathError> for WeightedPoolError {
+    fn from(error: MathError) -> Self {
+        WeightedPoolError::MathError(error)
+    }
+}
+
+impl From<Ownable2StepError> for WeightedPoolError {
+    fn from(error: Ownable2StepError) -> Self {
+        WeightedPoolError::Ownable2StepError(error)
+    }
+}
@@ -266,14 +266,20 @@ mod farm {
             Ok(reward_rates)
         }
 
-        fn deposit(&mut self, account: AccountId, amount: u128) -> Result<(), FarmError> {
+        /// Deposits `amount` of `payer`'s shares under `account`.
+        fn deposit(
+            &mut self,
+            payer: AccountId,
+            account: AccountId,
+            amount: u128,
+        ) -> Result<(), FarmError> {
             if amount == 0 {
                 return Err(FarmError::InsufficientShares);
             }
             self.update()?;
             self.update_account(account);
             let mut pool: contract_ref!(PSP22) = self.pool_id.into();
-            pool.transfer_from(account, self.env().account_id(), amount, vec![])?;
+            pool.transfer_from(payer, self.env().account_id(), amount, vec![])?;
             let shares = self.shares.get(account).unwrap_or(0);
             self.shares.insert(account, &(shares + amount));
             self.total_shares += amount;
@@ -461,7 +467,14 @@ mod farm {
         #[ink(message)]
         fn deposit(&mut self, amount: u128) -> Result<(), FarmError> {
             let account = self.env().caller();
-            self.deposit(account, amount)?;
+            self.deposit(account, account, amount)?;
+            FarmContract::emit_event(self.env(), Event::Deposited(Deposited { account, amount }));
+            Ok(())
+        }
+
+        #[ink(message)]
+        fn deposit_for(&mut self, account: AccountId, amount: u128) -> Result<(), FarmError> {
+            self.deposit(self.env().caller(), account, amount)?;
             FarmContract::emit_event(self.env(), Event::Deposited(Deposited { account, amount }));
             Ok(())
         }
@@ -471,7 +484,7 @@ mod farm {
             let account = self.env().caller();
             let pool: contract_ref!(PSP22) = self.pool_id.into();
             let amount = pool.balance_of(account);
-            self.deposit(account, amount)?;
+            self.deposit(account, account, amount)?;
             FarmContract::emit_event(self.env(), Event::Deposited(Deposited { account, amount }));
             Ok(())
         }
@@ -68,6 +68,18 @@ pub fn deposit_to_farm(
     handle_ink_error(session.execute(farm.deposit(amount)).unwrap())
 }
 
+pub fn deposit_for(
+    session: &mut Session<MinimalRuntime>,
+    farm: &Farm,
+    account: AccountId,
+    amount: u128,
+    caller: AccountId32,
+) -> Result<(), FarmError> {
+    let _ = session.set_actor(caller);
+
+    handle_ink_error(session.execute(farm.deposit_for(account, amount)).unwrap())
+}
+
 pub fn withdraw_from_farm(
     session: &mut Session<MinimalRuntime>,
     farm: &Farm,
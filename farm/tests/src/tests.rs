@@ -745,6 +745,42 @@ fn owner_stop_farm_after_end(mut session: Session<MinimalRuntime>) {
     );
 }
 
+#[drink::test]
+fn deposit_for_credits_account(mut session: Session<MinimalRuntime>) {
+    let now = get_timestamp(&mut session);
+    let farm_start = now + 10;
+    seed_account(&mut session, FARMER);
+
+    let (farm, ice, _) = setup_farm(&mut session, farm_start, farm_start + 100, u128::MAX);
+
+    // FARMER pays for the deposit of BOB.
+    let deposit_amount = 1_000_000;
+    let farmer_ice_before = psp22::balance_of(&mut session, ice.into(), alice());
+    psp22::increase_allowance(
+        &mut session,
+        ice.into(),
+        farm.into(),
+        deposit_amount,
+        FARMER,
+    );
+    farm::deposit_for(&mut session, &farm, bob(), deposit_amount, FARMER).unwrap();
+    assert_eq!(
+        psp22::balance_of(&mut session, ice.into(), alice()),
+        farmer_ice_before - deposit_amount
+    );
+
+    assert_eq!(
+        farm::withdraw_from_farm(&mut session, &farm, deposit_amount, FARMER),
+        Err(FarmError::InsufficientShares())
+    );
+    let bob_ice_before = psp22::balance_of(&mut session, ice.into(), bob());
+    farm::withdraw_from_farm(&mut session, &farm, deposit_amount, BOB).unwrap();
+    assert_eq!(
+        psp22::balance_of(&mut session, ice.into(), bob()),
+        bob_ice_before + deposit_amount
+    );
+}
+
 #[drink::test]
 fn owner_withdraw_pool_token(mut session: Session<MinimalRuntime>) {
     let now = get_timestamp(&mut session);
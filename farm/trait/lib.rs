@@ -79,6 +79,10 @@ pub trait Farm {
     #[ink(message)]
     fn deposit(&mut self, amount: u128) -> Result<(), FarmError>;
 
+    /// Deposits `amount` of caller's LP tokens (shares) under `account` in the farm.
+    #[ink(message)]
+    fn deposit_for(&mut self, account: AccountId, amount: u128) -> Result<(), FarmError>;
+
     /// Deposits all transferred LP tokens under caller's account.
     #[ink(message)]
     fn deposit_all(&mut self) -> Result<(), FarmError>;
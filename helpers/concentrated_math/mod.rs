@@ -0,0 +1,436 @@
+pub mod tick_math;
+
+use crate::{constants::concentrated_pool::FEE_DENOM, math::MathError};
+use primitive_types::{U256, U512};
+
+/// Number of fractional bits of the Q64.96 sqrt prices.
+pub const RESOLUTION: usize = 96;
+
+/// Returns `2^96`, i.e. `1` as a Q64.96 number.
+pub fn q96() -> U256 {
+    U256::one() << RESOLUTION
+}
+
+/// Returns `2^128`, the precision of the fee growth accumulators.
+pub fn q128() -> U256 {
+    U256::one() << 128
+}
+
+/// Computes `a * b / denominator` rounding down, without intermediate overflow.
+pub fn mul_div(a: U256, b: U256, denominator: U256) -> Result<U256, MathError> {
+    if denominator.is_zero() {
+        return Err(MathError::DivByZero(60));
+    }
+    U256::try_from(a.full_mul(b) / U512::from(denominator)).map_err(|_| MathError::CastOverflow(60))
+}
+
+/// Computes `a * b / denominator` rounding up, without intermediate overflow.
+pub fn mul_div_rounding_up(a: U256, b: U256, denominator: U256) -> Result<U256, MathError> {
+    if denominator.is_zero() {
+        return Err(MathError::DivByZero(61));
+    }
+    let (quotient, remainder) = a.full_mul(b).div_mod(U512::from(denominator));
+    let result = U256::try_from(quotient).map_err(|_| MathError::CastOverflow(61))?;
+    if remainder.is_zero() {
+        Ok(result)
+    } else {
+        result
+            .checked_add(U256::one())
+            .ok_or(MathError::AddOverflow(61))
+    }
+}
+
+/// Computes `a / b` rounding up.
+fn div_rounding_up(a: U256, b: U256) -> Result<U256, MathError> {
+    let (quotient, remainder) = a.div_mod(b);
+    if remainder.is_zero() {
+        Ok(quotient)
+    } else {
+        quotient
+            .checked_add(U256::one())
+            .ok_or(MathError::AddOverflow(62))
+    }
+}
+
+/// Adds a signed liquidity delta to the liquidity `x`.
+pub fn add_delta(x: u128, delta: i128) -> Result<u128, MathError> {
+    if delta < 0 {
+        x.checked_sub(delta.unsigned_abs())
+            .ok_or(MathError::SubUnderflow(60))
+    } else {
+        x.checked_add(delta as u128)
+            .ok_or(MathError::AddOverflow(60))
+    }
+}
+
+/// Computes the amount of token 0 between two sqrt prices for given `liquidity`,
+/// i.e. `liquidity * (sqrt_b - sqrt_a) / (sqrt_a * sqrt_b)`.
+pub fn get_amount0_delta(
+    sqrt_price_a: U256,
+    sqrt_price_b: U256,
+    liquidity: u128,
+    round_up: bool,
+) -> Result<U256, MathError> {
+    let (sqrt_price_a, sqrt_price_b) = if sqrt_price_a > sqrt_price_b {
+        (sqrt_price_b, sqrt_price_a)
+    } else {
+        (sqrt_price_a, sqrt_price_b)
+    };
+    if sqrt_price_a.is_zero() {
+        return Err(MathError::DivByZero(62));
+    }
+    let numerator_1 = U256::from(liquidity) << RESOLUTION;
+    let numerator_2 = sqrt_price_b - sqrt_price_a;
+    if round_up {
+        div_rounding_up(
+            mul_div_rounding_up(numerator_1, numerator_2, sqrt_price_b)?,
+            sqrt_price_a,
+        )
+    } else {
+        Ok(mul_div(numerator_1, numerator_2, sqrt_price_b)? / sqrt_price_a)
+    }
+}
+
+/// Computes the amount of token 1 between two sqrt prices for given `liquidity`,
+/// i.e. `liquidity * (sqrt_b - sqrt_a)`.
+pub fn get_amount1_delta(
+    sqrt_price_a: U256,
+    sqrt_price_b: U256,
+    liquidity: u128,
+    round_up: bool,
+) -> Result<U256, MathError> {
+    let diff = sqrt_price_a.abs_diff(sqrt_price_b);
+    if round_up {
+        mul_div_rounding_up(liquidity.into(), diff, q96())
+    } else {
+        mul_div(liquidity.into(), diff, q96())
+    }
+}
+
+/// Computes the sqrt price after adding (or removing) `amount` of token 0.
+///
+/// The result is rounded up so that the price moves no further than the exact value.
+fn get_next_sqrt_price_from_amount0_rounding_up(
+    sqrt_price: U256,
+    liquidity: u128,
+    amount: U256,
+    add: bool,
+) -> Result<U256, MathError> {
+    if amount.is_zero() {
+        return Ok(sqrt_price);
+    }
+    // liquidity * sqrt_price / (liquidity +- amount * sqrt_price)
+    let numerator_1 = U512::from(liquidity) << RESOLUTION;
+    let product = amount.full_mul(sqrt_price);
+    let denominator = if add {
+        numerator_1
+            .checked_add(product)
+            .ok_or(MathError::AddOverflow(63))?
+    } else {
+        numerator_1
+            .checked_sub(product)
+            .filter(|denominator| !denominator.is_zero())
+            .ok_or(MathError::SubUnderflow(63))?
+    };
+    let numerator = numerator_1
+        .checked_mul(U512::from(sqrt_price))
+        .ok_or(MathError::MulOverflow(63))?;
+    let (quotient, remainder) = numerator.div_mod(denominator);
+    let quotient = if remainder.is_zero() {
+        quotient
+    } else {
+        quotient + 1
+    };
+    U256::try_from(quotient).map_err(|_| MathError::CastOverflow(63))
+}
+
+/// Computes the sqrt price after adding (or removing) `amount` of token 1.
+///
+/// The result is rounded down so that the price moves no further than the exact value.
+fn get_next_sqrt_price_from_amount1_rounding_down(
+    sqrt_price: U256,
+    liquidity: u128,
+    amount: U256,
+    add: bool,
+) -> Result<U256, MathError> {
+    if liquidity == 0 {
+        return Err(MathError::DivByZero(64));
+    }
+    let numerator = U512::from(amount) << RESOLUTION;
+    let denominator = U512::from(liquidity);
+    if add {
+        let quotient =
+            U256::try_from(numerator / denominator).map_err(|_| MathError::CastOverflow(64))?;
+        sqrt_price
+            .checked_add(quotient)
+            .ok_or(MathError::AddOverflow(64))
+    } else {
+        let (quotient, remainder) = numerator.div_mod(denominator);
+        let quotient = if remainder.is_zero() {
+            quotient
+        } else {
+            quotient + 1
+        };
+        let quotient = U256::try_from(quotient).map_err(|_| MathError::CastOverflow(65))?;
+        sqrt_price
+            .checked_sub(quotient)
+            .filter(|price| !price.is_zero())
+            .ok_or(MathError::SubUnderflow(64))
+    }
+}
+
+/// Computes the sqrt price after swapping `amount_in` of the input token.
+pub fn get_next_sqrt_price_from_input(
+    sqrt_price: U256,
+    liquidity: u128,
+    amount_in: U256,
+    zero_for_one: bool,
+) -> Result<U256, MathError> {
+    if sqrt_price.is_zero() || liquidity == 0 {
+        return Err(MathError::DivByZero(65));
+    }
+    if zero_for_one {
+        get_next_sqrt_price_from_amount0_rounding_up(sqrt_price, liquidity, amount_in, true)
+    } else {
+        get_next_sqrt_price_from_amount1_rounding_down(sqrt_price, liquidity, amount_in, true)
+    }
+}
+
+/// Computes the sqrt price after swapping for `amount_out` of the output token.
+pub fn get_next_sqrt_price_from_output(
+    sqrt_price: U256,
+    liquidity: u128,
+    amount_out: U256,
+    zero_for_one: bool,
+) -> Result<U256, MathError> {
+    if sqrt_price.is_zero() || liquidity == 0 {
+        return Err(MathError::DivByZero(66));
+    }
+    if zero_for_one {
+        get_next_sqrt_price_from_amount1_rounding_down(sqrt_price, liquidity, amount_out, false)
+    } else {
+        get_next_sqrt_price_from_amount0_rounding_up(sqrt_price, liquidity, amount_out, false)
+    }
+}
+
+/// Outcome of a swap within a single price range of constant liquidity.
+#[derive(Debug, PartialEq, Eq)]
+pub struct SwapStep {
+    /// Sqrt price after the step.
+    pub sqrt_price_next: U256,
+    /// Amount of the input token used, excluding the fee.
+    pub amount_in: U256,
+    /// Amount of the output token received.
+    pub amount_out: U256,
+    /// Fee taken from the input token.
+    pub fee_amount: U256,
+}
+
+/// Computes the result of swapping `amount_remaining` (exact input if `exact_in`,
+/// otherwise exact output) with constant `liquidity`, moving the price from
+/// `sqrt_price_current` towards `sqrt_price_target`.
+///
+/// `fee` is given with `FEE_DENOM` precision.
+pub fn compute_swap_step(
+    sqrt_price_current: U256,
+    sqrt_price_target: U256,
+    liquidity: u128,
+    amount_remaining: U256,
+    exact_in: bool,
+    fee: u32,
+) -> Result<SwapStep, MathError> {
+    let zero_for_one = sqrt_price_current >= sqrt_price_target;
+    let fee_denom = U256::from(FEE_DENOM);
+    let fee_complement = U256::from(
+        FEE_DENOM
+            .checked_sub(fee)
+            .ok_or(MathError::SubUnderflow(66))?,
+    );
+
+    // amount needed to reach the target price
+    let amount_to_target = match (exact_in, zero_for_one) {
+        (true, true) => get_amount0_delta(sqrt_price_target, sqrt_price_current, liquidity, true)?,
+        (true, false) => get_amount1_delta(sqrt_price_current, sqrt_price_target, liquidity, true)?,
+        (false, true) => {
+            get_amount1_delta(sqrt_price_target, sqrt_price_current, liquidity, false)?
+        }
+        (false, false) => {
+            get_amount0_delta(sqrt_price_current, sqrt_price_target, liquidity, false)?
+        }
+    };
+    let sqrt_price_next = if exact_in {
+        let amount_remaining_less_fee = mul_div(amount_remaining, fee_complement, fee_denom)?;
+        if amount_remaining_less_fee >= amount_to_target {
+            sqrt_price_target
+        } else {
+            get_next_sqrt_price_from_input(
+                sqrt_price_current,
+                liquidity,
+                amount_remaining_less_fee,
+                zero_for_one,
+            )?
+        }
+    } else if amount_remaining >= amount_to_target {
+        sqrt_price_target
+    } else {
+        get_next_sqrt_price_from_output(
+            sqrt_price_current,
+            liquidity,
+            amount_remaining,
+            zero_for_one,
+        )?
+    };
+    let reached_target = sqrt_price_next == sqrt_price_target;
+
+    let (amount_in, mut amount_out) = if zero_for_one {
+        (
+            if reached_target && exact_in {
+                amount_to_target
+            } else {
+                get_amount0_delta(sqrt_price_next, sqrt_price_current, liquidity, true)?
+            },
+            if reached_target && !exact_in {
+                amount_to_target
+            } else {
+                get_amount1_delta(sqrt_price_next, sqrt_price_current, liquidity, false)?
+            },
+        )
+    } else {
+        (
+            if reached_target && exact_in {
+                amount_to_target
+            } else {
+                get_amount1_delta(sqrt_price_current, sqrt_price_next, liquidity, true)?
+            },
+            if reached_target && !exact_in {
+                amount_to_target
+            } else {
+                get_amount0_delta(sqrt_price_current, sqrt_price_next, liquidity, false)?
+            },
+        )
+    };
+    // cap the output amount to not exceed the remaining output amount
+    if !exact_in && amount_out > amount_remaining {
+        amount_out = amount_remaining;
+    }
+
+    let fee_amount = if exact_in && !reached_target {
+        // the remainder of the input amount is taken as a fee
+        amount_remaining
+            .checked_sub(amount_in)
+            .ok_or(MathError::SubUnderflow(67))?
+    } else {
+        mul_div_rounding_up(amount_in, U256::from(fee), fee_complement)?
+    };
+
+    Ok(SwapStep {
+        sqrt_price_next,
+        amount_in,
+        amount_out,
+        fee_amount,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::tick_math::get_sqrt_ratio_at_tick;
+    use super::*;
+
+    const ONE: u128 = 10u128.pow(18);
+
+    #[test]
+    fn amount_deltas_round_in_favor_of_pool() {
+        let sqrt_a = get_sqrt_ratio_at_tick(-600).unwrap();
+        let sqrt_b = get_sqrt_ratio_at_tick(600).unwrap();
+        let liquidity = 1000 * ONE;
+        let amount0_up = get_amount0_delta(sqrt_a, sqrt_b, liquidity, true).unwrap();
+        let amount0_down = get_amount0_delta(sqrt_a, sqrt_b, liquidity, false).unwrap();
+        assert_eq!(amount0_up, amount0_down + 1);
+        let amount1_up = get_amount1_delta(sqrt_b, sqrt_a, liquidity, true).unwrap();
+        let amount1_down = get_amount1_delta(sqrt_b, sqrt_a, liquidity, false).unwrap();
+        assert_eq!(amount1_up, amount1_down + 1);
+        // symmetric range around price 1
+        assert_eq!(amount0_down, amount1_down);
+        assert_eq!(
+            get_amount0_delta(sqrt_a, sqrt_a, liquidity, true),
+            Ok(U256::zero())
+        );
+    }
+
+    #[test]
+    fn next_sqrt_price_from_input_and_output() {
+        let price = q96();
+        let liquidity = ONE;
+        // adding 0.1 of token 1 increases the sqrt price by 10%
+        assert_eq!(
+            get_next_sqrt_price_from_input(price, liquidity, U256::from(ONE / 10), false),
+            Ok(price * 11 / 10)
+        );
+        // adding 1 of token 0 halves the sqrt price
+        assert_eq!(
+            get_next_sqrt_price_from_input(price, liquidity, U256::from(ONE), true),
+            Ok(price / 2)
+        );
+        // removing 0.5 of token 0 doubles the sqrt price
+        assert_eq!(
+            get_next_sqrt_price_from_output(price, liquidity, U256::from(ONE / 2), false),
+            Ok(price * 2)
+        );
+        // cannot remove all of token 1
+        assert!(get_next_sqrt_price_from_output(price, liquidity, U256::from(ONE), true).is_err());
+        assert!(get_next_sqrt_price_from_input(price, 0, U256::from(ONE), true).is_err());
+    }
+
+    #[test]
+    fn swap_step_exact_in_reaching_target() {
+        let current = q96();
+        let target = get_sqrt_ratio_at_tick(-60).unwrap();
+        let step = compute_swap_step(current, target, ONE, U256::from(ONE), true, 3000).unwrap();
+        assert_eq!(step.sqrt_price_next, target);
+        assert_eq!(
+            step.amount_in,
+            get_amount0_delta(target, current, ONE, true).unwrap()
+        );
+        assert_eq!(
+            step.amount_out,
+            get_amount1_delta(target, current, ONE, false).unwrap()
+        );
+        assert_eq!(
+            step.fee_amount,
+            mul_div_rounding_up(step.amount_in, 3000.into(), 997000.into()).unwrap()
+        );
+        assert!(step.amount_in + step.fee_amount < U256::from(ONE));
+    }
+
+    #[test]
+    fn swap_step_exact_in_partial() {
+        let current = q96();
+        let target = get_sqrt_ratio_at_tick(6000).unwrap();
+        let amount = U256::from(ONE / 100);
+        let step = compute_swap_step(current, target, ONE, amount, true, 3000).unwrap();
+        assert!(step.sqrt_price_next < target && step.sqrt_price_next > current);
+        // whole input is consumed
+        assert_eq!(step.amount_in + step.fee_amount, amount);
+        assert!(step.amount_out < step.amount_in);
+    }
+
+    #[test]
+    fn swap_step_exact_out_partial() {
+        let current = q96();
+        let target = get_sqrt_ratio_at_tick(-6000).unwrap();
+        let amount = U256::from(ONE / 100);
+        let step = compute_swap_step(current, target, ONE, amount, false, 3000).unwrap();
+        assert!(step.sqrt_price_next > target && step.sqrt_price_next < current);
+        assert_eq!(step.amount_out, amount);
+        assert!(step.amount_in > step.amount_out);
+        assert!(step.fee_amount > U256::zero());
+    }
+
+    #[test]
+    fn add_liquidity_delta() {
+        assert_eq!(add_delta(10, -3), Ok(7));
+        assert_eq!(add_delta(10, 3), Ok(13));
+        assert_eq!(add_delta(1, -3), Err(MathError::SubUnderflow(60)));
+        assert_eq!(add_delta(u128::MAX, 1), Err(MathError::AddOverflow(60)));
+    }
+}
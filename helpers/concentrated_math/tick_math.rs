@@ -0,0 +1,118 @@
+use crate::{
+    constants::concentrated_pool::{MAX_SQRT_RATIO, MAX_TICK, MIN_SQRT_RATIO, MIN_TICK},
+    math::MathError,
+};
+use primitive_types::U256;
+
+/// Q128.128 values of `1.0001^(-2^i / 2)` for `i` in `1..20`.
+const TICK_FACTORS: [u128; 19] = [
+    0xfff97272373d413259a46990580e213a,
+    0xfff2e50f5f656932ef12357cf3c7fdcc,
+    0xffe5caca7e10e4e61c3624eaa0941cd0,
+    0xffcb9843d60f6159c9db58835c926644,
+    0xff973b41fa98c081472e6896dfb254c0,
+    0xff2ea16466c96a3843ec78b326b52861,
+    0xfe5dee046a99a2a811c461f1969c3053,
+    0xfcbe86c7900a88aedcffc83b479aa3a4,
+    0xf987a7253ac413176f2b074cf7815e54,
+    0xf3392b0822b70005940c7a398e4b70f3,
+    0xe7159475a2c29b7443b29c7fa6e889d9,
+    0xd097f3bdfd2022b8845ad8f792aa5825,
+    0xa9f746462d870fdf8a65dc1f90e061e5,
+    0x70d869a156d2a1b890bb3df62baf32f7,
+    0x31be135f97d08fd981231505542fcfa6,
+    0x9aa508b5b7a84e1c677de54f3e99bc9,
+    0x5d6af8dedb81196699c329225ee604,
+    0x2216e584f5fa1ea926041bedfe98,
+    0x48a170391f7dc42444e8fa2,
+];
+
+/// Q128.128 value of `1.0001^(-1/2)`.
+const TICK_FACTOR_ONE: u128 = 0xfffcb933bd6fad37aa2d162d1a594001;
+
+/// Computes `sqrt(1.0001^tick)` as a Q64.96 number.
+///
+/// Returns error if `tick` is outside of [`MIN_TICK`, `MAX_TICK`].
+pub fn get_sqrt_ratio_at_tick(tick: i32) -> Result<U256, MathError> {
+    if !(MIN_TICK..=MAX_TICK).contains(&tick) {
+        return Err(MathError::CastOverflow(70));
+    }
+    let abs_tick = tick.unsigned_abs();
+    let mut ratio = if abs_tick & 1 != 0 {
+        U256::from(TICK_FACTOR_ONE)
+    } else {
+        U256::one() << 128
+    };
+    for (i, &factor) in TICK_FACTORS.iter().enumerate() {
+        if abs_tick & (2 << i) != 0 {
+            // both factors are less than 2^128 so the product fits
+            ratio = (ratio * U256::from(factor)) >> 128;
+        }
+    }
+    if tick > 0 {
+        ratio = U256::MAX / ratio;
+    }
+    // Q128.128 -> Q64.96, rounding up
+    let rounding = if ratio.low_u32() == 0 { 0 } else { 1 };
+    Ok((ratio >> 32) + rounding)
+}
+
+/// Computes the greatest tick such that `get_sqrt_ratio_at_tick(tick) <= sqrt_price`.
+///
+/// Returns error if `sqrt_price` is outside of [`MIN_SQRT_RATIO`, `MAX_SQRT_RATIO`).
+pub fn get_tick_at_sqrt_ratio(sqrt_price: U256) -> Result<i32, MathError> {
+    if sqrt_price < MIN_SQRT_RATIO || sqrt_price >= MAX_SQRT_RATIO {
+        return Err(MathError::CastOverflow(71));
+    }
+    // binary search over the ticks, invariant: ratio(low) <= sqrt_price < ratio(high)
+    let (mut low, mut high) = (MIN_TICK, MAX_TICK);
+    while high - low > 1 {
+        let mid = low + (high - low) / 2;
+        if get_sqrt_ratio_at_tick(mid)? <= sqrt_price {
+            low = mid;
+        } else {
+            high = mid;
+        }
+    }
+    Ok(low)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sqrt_ratio_at_bounds() {
+        assert_eq!(get_sqrt_ratio_at_tick(MIN_TICK), Ok(MIN_SQRT_RATIO));
+        assert_eq!(get_sqrt_ratio_at_tick(MAX_TICK), Ok(MAX_SQRT_RATIO));
+        assert_eq!(get_sqrt_ratio_at_tick(0), Ok(U256::one() << 96));
+        assert!(get_sqrt_ratio_at_tick(MIN_TICK - 1).is_err());
+        assert!(get_sqrt_ratio_at_tick(MAX_TICK + 1).is_err());
+    }
+
+    #[test]
+    fn sqrt_ratio_is_increasing() {
+        let mut prev = get_sqrt_ratio_at_tick(-1000).unwrap();
+        for tick in -999..1000 {
+            let ratio = get_sqrt_ratio_at_tick(tick).unwrap();
+            assert!(ratio > prev, "Ratio should increase at tick {tick}");
+            prev = ratio;
+        }
+    }
+
+    #[test]
+    fn tick_at_sqrt_ratio() {
+        for tick in [MIN_TICK, -887000, -50000, -1, 0, 1, 60, 50000, MAX_TICK - 1] {
+            let ratio = get_sqrt_ratio_at_tick(tick).unwrap();
+            assert_eq!(get_tick_at_sqrt_ratio(ratio), Ok(tick));
+            if tick < MAX_TICK - 1 {
+                assert_eq!(get_tick_at_sqrt_ratio(ratio + 1), Ok(tick));
+            }
+            if tick > MIN_TICK {
+                assert_eq!(get_tick_at_sqrt_ratio(ratio - 1), Ok(tick - 1));
+            }
+        }
+        assert!(get_tick_at_sqrt_ratio(MIN_SQRT_RATIO - 1).is_err());
+        assert!(get_tick_at_sqrt_ratio(MAX_SQRT_RATIO).is_err());
+    }
+}
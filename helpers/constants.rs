@@ -63,4 +63,112 @@ pub mod stable_pool {
     pub const MAX_AMP: u128 = 1_000_000;
     /// Max amplification change (how many times it can increase/decrease compared to current value).
     pub const MAX_AMP_CHANGE: u128 = 10;
+
+    /// Time between scheduling a change of a token's rate provider and applying it, in milisec (24h).
+    pub const RATE_PROVIDER_CHANGE_DELAY: u64 = 86400000;
+    /// Max change of a token rate caused by changing its rate provider.
+    /// Given as an integer with 1e9 precision (1%).
+    pub const MAX_RATE_CHANGE: u32 = 10_000_000;
+
+    /// Precision of prices and virtual price. If the price is 1.2, it is represented as 1.2 * PRICE_PRECISION.
+    pub const PRICE_PRECISION: u128 = 10u128.pow(18);
+
+    /// Min time between two consecutive price oracle observations, in milisec (5 min).
+    pub const ORACLE_OBSERVATION_PERIOD: u64 = 300_000;
+    /// Max number of stored price oracle observations (24h of observations).
+    pub const ORACLE_MAX_OBSERVATIONS: u32 = 288;
+}
+
+pub mod pair {
+    /// Trading fee (in millis) of the pairs created with the default fee tier (0.3%).
+    pub const DEFAULT_FEE: u8 = 3;
+    /// Max trading fee (in millis) of a pair (1%).
+    pub const MAX_FEE: u8 = 10;
+    /// Min time between scheduling a pair's fee change and the change taking effect, in milisec (24h).
+    pub const MIN_FEE_CHANGE_DELAY: u64 = 86400000;
+
+    /// Protocol receives 1/DEFAULT_PROTOCOL_FEE_DENOM of the LP fees growth by default.
+    pub const DEFAULT_PROTOCOL_FEE_DENOM: u8 = 6;
+    /// Min protocol fee denominator, i.e. the protocol receives at most 50% of the LP fees growth.
+    pub const MIN_PROTOCOL_FEE_DENOM: u8 = 2;
+
+    /// Flash loan fees are given in basis points.
+    pub const FLASH_LOAN_FEE_DENOM: u128 = 10_000;
+    /// Flash loan fee set in the new factory (0.05%).
+    pub const DEFAULT_FLASH_LOAN_FEE: u16 = 5;
+    /// Max flash loan fee (1%).
+    pub const MAX_FLASH_LOAN_FEE: u16 = 100;
+}
+
+pub mod concentrated_pool {
+    use primitive_types::U256;
+
+    /// Min tick of a concentrated pool, sqrt(1.0001^MIN_TICK) ~ 2^-64.
+    pub const MIN_TICK: i32 = -887272;
+    /// Max tick of a concentrated pool, sqrt(1.0001^MAX_TICK) ~ 2^64.
+    pub const MAX_TICK: i32 = -MIN_TICK;
+    /// Sqrt price (Q64.96) at `MIN_TICK`.
+    pub const MIN_SQRT_RATIO: U256 = U256([4295128739, 0, 0, 0]);
+    /// Sqrt price (Q64.96) at `MAX_TICK`.
+    pub const MAX_SQRT_RATIO: U256 =
+        U256([6743328256752651558, 17280870778742802505, 4294805859, 0]);
+
+    /// Trade fees are given in hundredths of a basis point (1e6 precision).
+    pub const FEE_DENOM: u32 = 1_000_000;
+    /// Max trade fee of a concentrated pool (10%).
+    pub const MAX_FEE: u32 = 100_000;
+    /// Max distance between two initializable ticks.
+    pub const MAX_TICK_SPACING: i32 = 16384;
+}
+
+pub mod weighted_pool {
+    /// Weights are normalized, i.e. the weights of all the tokens in a pool sum up to
+    /// `WEIGHT_PRECISION`. Ratios of the reserves are given with the same precision.
+    pub const WEIGHT_PRECISION: u128 = 10u128.pow(18);
+    /// Min normalized weight of a token (1%).
+    pub const MIN_WEIGHT: u128 = WEIGHT_PRECISION / 100;
+
+    /// Max amount of a token which can be deposited (swapped in or joined with) in a single
+    /// operation, relative to its reserve (30%).
+    pub const MAX_IN_RATIO: u128 = 3 * WEIGHT_PRECISION / 10;
+    /// Max amount of a token which can be withdrawn (swapped out or exited with) in a single
+    /// operation, relative to its reserve (30%).
+    pub const MAX_OUT_RATIO: u128 = 3 * WEIGHT_PRECISION / 10;
+}
+
+pub mod crypto_pool {
+    /// Precision of prices, `gamma` and the other fixed-point parameters.
+    /// If the price is 1.2, it is represented as 1.2 * PRECISION.
+    pub const PRECISION: u128 = 10u128.pow(18);
+    /// Number of coins in a crypto pool.
+    pub const N_COINS: usize = 2;
+
+    /// `A` is given multiplied by N^N and by A_MULTIPLIER.
+    pub const A_MULTIPLIER: u128 = 10_000;
+    /// Min amplification coefficient (0.1 * N^N * A_MULTIPLIER).
+    pub const MIN_A: u128 = 4 * A_MULTIPLIER / 10;
+    /// Max amplification coefficient (100_000 * N^N * A_MULTIPLIER).
+    pub const MAX_A: u128 = 4 * A_MULTIPLIER * 100_000;
+    /// Min `gamma`, the distance from the constant product curve (1e-8).
+    pub const MIN_GAMMA: u128 = 10u128.pow(10);
+    /// Max `gamma`, the distance from the constant product curve (0.02).
+    pub const MAX_GAMMA: u128 = 2 * 10u128.pow(16);
+
+    /// Given as an integer with 1e9 precision (0.005%)
+    pub const MIN_FEE: u32 = 50_000;
+    /// Given as an integer with 1e9 precision (10%)
+    pub const MAX_FEE: u32 = 100_000_000;
+    /// Given as an integer with 1e9 precision (50%)
+    ///
+    /// It is the part of the pool's profit which goes to the protocol.
+    pub const MAX_PROTOCOL_FEE: u32 = 500_000_000;
+
+    /// Max profit above the half of the pool's profit which is not spent on repegging (1%).
+    pub const MAX_ALLOWED_EXTRA_PROFIT: u128 = PRECISION / 100;
+    /// Max relative step of the price scale adjustment (100%).
+    pub const MAX_ADJUSTMENT_STEP: u128 = PRECISION;
+    /// Min half-life of the price oracle EMA, in milisec (1 sec).
+    pub const MIN_MA_HALF_TIME: u64 = 1_000;
+    /// Max half-life of the price oracle EMA, in milisec (7 days).
+    pub const MAX_MA_HALF_TIME: u64 = 7 * 86_400_000;
 }
@@ -0,0 +1,108 @@
+use crate::{
+    constants::crypto_pool::{MAX_FEE, MIN_FEE, PRECISION},
+    math::MathError,
+};
+use primitive_types::U256;
+
+/// Dynamic trade fee of a crypto pool.
+///
+/// The fee is `mid_fee` when the pool is balanced and grows up to `out_fee`
+/// as the pool gets imbalanced. `fee_gamma` controls how fast it grows.
+#[ink::storage_item]
+#[derive(Debug, Default)]
+pub struct DynamicFees {
+    pub mid_fee: u32,
+    pub out_fee: u32,
+    pub fee_gamma: u128,
+}
+
+impl DynamicFees {
+    /// Create new fee instance.
+    /// - `mid_fee` and `out_fee` given as integers with 1e9 precision restricted to
+    ///    [[`MIN_FEE`](const@MIN_FEE), [`MAX_FEE`](const@MAX_FEE)], `mid_fee` not greater than `out_fee`
+    /// - `fee_gamma` given with 1e18 precision restricted to (0, 1]
+    pub fn new(mid_fee: u32, out_fee: u32, fee_gamma: u128) -> Option<Self> {
+        if mid_fee < MIN_FEE
+            || mid_fee > out_fee
+            || out_fee > MAX_FEE
+            || fee_gamma == 0
+            || fee_gamma > PRECISION
+        {
+            None
+        } else {
+            Some(Self {
+                mid_fee,
+                out_fee,
+                fee_gamma,
+            })
+        }
+    }
+
+    /// Returns the trade fee (1e9 precision) for the pool with balances `xp`.
+    pub fn fee(&self, xp: [u128; 2]) -> Result<u128, MathError> {
+        let precision = U256::from(PRECISION);
+        let sum = U256::from(xp[0]) + U256::from(xp[1]);
+        if sum.is_zero() {
+            return Err(MathError::DivByZero(170));
+        }
+        // K = 4 * x_0 * x_1 / (x_0 + x_1)^2, equals 1 for balanced pool
+        let k = precision * 4 * U256::from(xp[0]) / sum * U256::from(xp[1]) / sum;
+        let fee_gamma = U256::from(self.fee_gamma);
+        // f = fee_gamma / (fee_gamma + 1 - K)
+        let f = fee_gamma * precision
+            / (fee_gamma + precision)
+                .checked_sub(k)
+                .ok_or(MathError::SubUnderflow(170))?;
+        let fee =
+            (U256::from(self.mid_fee) * f + U256::from(self.out_fee) * (precision - f)) / precision;
+        Ok(fee.low_u128())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::constants::crypto_pool::{MAX_FEE, MIN_FEE, PRECISION};
+
+    use super::DynamicFees;
+
+    #[test]
+    fn test_fees_bounds() {
+        _ = DynamicFees::new(MIN_FEE, MAX_FEE, PRECISION).expect("Should instantiate fee");
+        assert!(
+            DynamicFees::new(MIN_FEE - 1, MAX_FEE, PRECISION).is_none(),
+            "Should fail to instantiate fee"
+        );
+        assert!(
+            DynamicFees::new(MIN_FEE, MAX_FEE + 1, PRECISION).is_none(),
+            "Should fail to instantiate fee"
+        );
+        assert!(
+            DynamicFees::new(MAX_FEE, MIN_FEE, PRECISION).is_none(),
+            "Should fail to instantiate fee"
+        );
+        assert!(
+            DynamicFees::new(MIN_FEE, MAX_FEE, 0).is_none(),
+            "Should fail to instantiate fee"
+        );
+    }
+
+    #[test]
+    fn test_dynamic_fee() {
+        let fees = DynamicFees::new(2_600_000, 45_000_000, 230_000_000_000_000)
+            .expect("Should instantiate fee");
+        let balanced = fees
+            .fee([1_000_000, 1_000_000])
+            .expect("Should compute fee");
+        assert_eq!(
+            balanced, 2_600_000,
+            "Fee of balanced pool should be mid fee"
+        );
+        let imbalanced = fees.fee([1_000_000, 500_000]).expect("Should compute fee");
+        assert!(
+            balanced < imbalanced && imbalanced <= 45_000_000,
+            "Fee should grow up to out fee with imbalance"
+        );
+        let max_imbalanced = fees.fee([1_000_000, 1]).expect("Should compute fee");
+        assert!(imbalanced < max_imbalanced);
+    }
+}
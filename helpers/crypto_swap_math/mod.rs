@@ -0,0 +1,638 @@
+pub mod fees;
+
+use crate::{
+    constants::{
+        crypto_pool::{A_MULTIPLIER, N_COINS, PRECISION},
+        stable_pool::FEE_DENOM,
+    },
+    math::{casted_mul, MathError},
+};
+use primitive_types::U256;
+
+use fees::DynamicFees;
+
+/// Max number of iterations performed in Newton–Raphson method
+const MAX_ITERATIONS: u8 = 255;
+
+/// Fee charged on every deposit (1e-5), with 1e9 precision.
+/// Prevents profiting from rounding in deposits and withdrawals.
+const NOISE_FEE: u128 = 10_000;
+
+/// Precision of `halfpow` series.
+const EXP_PRECISION: u128 = 10u128.pow(10);
+
+fn to_u128(x: U256, code: u8) -> Result<u128, MathError> {
+    x.try_into().map_err(|_| MathError::CastOverflow(code))
+}
+
+/// Computes `x * fee / FEE_DENOM` rounding up.
+fn fee_amount(x: u128, fee: u128) -> Result<u128, MathError> {
+    let (quotient, remainder) = casted_mul(x, fee).div_mod(FEE_DENOM.into());
+    let quotient = if remainder.is_zero() {
+        quotient
+    } else {
+        quotient + 1
+    };
+    to_u128(quotient, 160)
+}
+
+/// Computes `D * g1k0^2 / (gamma^2 * A * N^N)` with `PRECISION`,
+/// the common term of Newton's steps of `compute_d` and `compute_y`.
+fn compute_mul1(amp: U256, gamma: U256, d: U256, g1k0: U256) -> Result<U256, MathError> {
+    U256::from(PRECISION)
+        .checked_mul(d)
+        .and_then(|x| x.checked_div(gamma))
+        .and_then(|x| x.checked_mul(g1k0))
+        .and_then(|x| x.checked_div(gamma))
+        .and_then(|x| x.checked_mul(g1k0))
+        .and_then(|x| x.checked_mul(A_MULTIPLIER.into()))
+        .and_then(|x| x.checked_div(amp))
+        .ok_or(MathError::MulOverflow(161))
+}
+
+/// Checks that `x` is within [0.01 * D, 100 * D], where the invariant is reliable.
+fn check_frac(x: U256, d: U256, code: u8) -> Result<(), MathError> {
+    let frac = x * U256::from(PRECISION) / d;
+    if frac < U256::from(10u128.pow(16)) || frac > U256::from(10u128.pow(20)) {
+        return Err(MathError::Precision(code));
+    }
+    Ok(())
+}
+
+/// Computes the geometric mean of `x_0` and `x_1`.
+pub fn geometric_mean(x_0: u128, x_1: u128) -> u128 {
+    // sqrt of u128 * u128 fits in u128
+    casted_mul(x_0, x_1).integer_sqrt().low_u128()
+}
+
+/// Computes the crypto-swap invariant (D) of the balances `xp` (scaled with the price scale)
+/// given the amplification coefficient `amp` (multiplied by N^N * A_MULTIPLIER) and `gamma`.
+///
+/// The invariant equation (N = 2) is
+/// `K * D * SUM{x_i} + PROD{x_i} = K * D^2 + (D / N)^N`, where
+/// `K = A * K0 * gamma^2 / (gamma + 1 - K0)^2` and `K0 = N^N * PROD{x_i} / D^N`.
+pub fn compute_d(amp: u128, gamma: u128, xp: [u128; 2]) -> Result<u128, MathError> {
+    let precision = U256::from(PRECISION);
+    let n = U256::from(N_COINS);
+    let (x_0, x_1) = (U256::from(xp[0].max(xp[1])), U256::from(xp[0].min(xp[1])));
+    if x_1.is_zero() {
+        return Err(MathError::DivByZero(162));
+    }
+    let (amp, gamma) = (U256::from(amp), U256::from(gamma));
+    let sum = x_0 + x_1;
+    // initial guess is the constant product invariant
+    let mut d = n * (x_0 * x_1).integer_sqrt();
+    for _ in 0..MAX_ITERATIONS {
+        let d_prev = d;
+        // K0 = N^N * x_0 * x_1 / D^2
+        let k0 = precision * n * n * x_0 / d * x_1 / d;
+        if k0.is_zero() {
+            return Err(MathError::DivByZero(162));
+        }
+        // |gamma + 1 - K0| + 1
+        let g1k0 = (gamma + precision).abs_diff(k0) + 1;
+        let mul1 = compute_mul1(amp, gamma, d, g1k0)?;
+        // 2 * N * K0 / (gamma + 1 - K0)
+        let mul2 = precision * 2 * n * k0 / g1k0;
+        let neg_fprime = (sum + sum * mul2 / precision + mul1 * n / k0)
+            .checked_sub(mul2 * d / precision)
+            .ok_or(MathError::SubUnderflow(162))?;
+        if neg_fprime.is_zero() {
+            return Err(MathError::DivByZero(163));
+        }
+        // D -= f / fprime
+        let d_plus = d * (neg_fprime + sum) / neg_fprime;
+        let mut d_minus = d * d / neg_fprime;
+        if precision > k0 {
+            d_minus += d * (mul1 / neg_fprime) / precision * (precision - k0) / k0;
+        } else {
+            d_minus -= d * (mul1 / neg_fprime) / precision * (k0 - precision) / k0;
+        }
+        d = if d_plus > d_minus {
+            d_plus - d_minus
+        } else {
+            (d_minus - d_plus) / 2
+        };
+        if d.is_zero() {
+            return Err(MathError::DivByZero(163));
+        }
+        if d.abs_diff(d_prev) * U256::from(10u128.pow(14)) < d.max(10u128.pow(16).into()) {
+            check_frac(x_0, d, 162)?;
+            check_frac(x_1, d, 162)?;
+            return to_u128(d, 162);
+        }
+    }
+    Err(MathError::Precision(163))
+}
+
+/// Computes the balance of the `token_id` which satisfies the invariant `d`
+/// given the balance of the other token in `xp`.
+pub fn compute_y(
+    amp: u128,
+    gamma: u128,
+    xp: [u128; 2],
+    d: u128,
+    token_id: usize,
+) -> Result<u128, MathError> {
+    let precision = U256::from(PRECISION);
+    let n = U256::from(N_COINS);
+    let x_j = U256::from(xp[1 - token_id]);
+    let d = U256::from(d);
+    if x_j.is_zero() || d.is_zero() {
+        return Err(MathError::DivByZero(164));
+    }
+    let (amp, gamma) = (U256::from(amp), U256::from(gamma));
+    // initial guess is the constant product solution
+    let mut y = d * d / (x_j * n * n);
+    let k0_i = precision * n * x_j / d;
+    if k0_i < U256::from(10u128.pow(16)) * n || k0_i > U256::from(10u128.pow(20)) * n {
+        return Err(MathError::Precision(164));
+    }
+    let convergence_limit = (x_j / 10u128.pow(14))
+        .max(d / 10u128.pow(14))
+        .max(100.into());
+    for _ in 0..MAX_ITERATIONS {
+        let y_prev = y;
+        if y.is_zero() {
+            return Err(MathError::DivByZero(165));
+        }
+        // K0 = N^N * x_j * y / D^2
+        let k0 = k0_i * y * n / d;
+        if k0.is_zero() {
+            return Err(MathError::DivByZero(165));
+        }
+        let sum = x_j + y;
+        // |gamma + 1 - K0| + 1
+        let g1k0 = (gamma + precision).abs_diff(k0) + 1;
+        let mul1 = compute_mul1(amp, gamma, d, g1k0)?;
+        // 1 + 2 * K0 / (gamma + 1 - K0)
+        let mul2 = precision + precision * 2 * k0 / g1k0;
+        let mut yfprime = precision * y + sum * mul2 + mul1;
+        let dyfprime = d * mul2;
+        if yfprime < dyfprime {
+            y = y_prev / 2;
+            continue;
+        }
+        yfprime -= dyfprime;
+        let fprime = yfprime / y;
+        if fprime.is_zero() {
+            return Err(MathError::DivByZero(165));
+        }
+        // y -= f / fprime
+        let mut y_minus = mul1 / fprime;
+        let y_plus = (yfprime + precision * d) / fprime + y_minus * precision / k0;
+        y_minus += precision * sum / fprime;
+        y = if y_plus < y_minus {
+            y_prev / 2
+        } else {
+            y_plus - y_minus
+        };
+        if y.abs_diff(y_prev) < convergence_limit.max(y / 10u128.pow(14)) {
+            check_frac(y, d, 164)?;
+            return to_u128(y, 164);
+        }
+    }
+    Err(MathError::Precision(165))
+}
+
+/// Computes `0.5^power` for `power` given with `PRECISION`.
+pub fn halfpow(power: u128) -> Result<u128, MathError> {
+    let int_power = power / PRECISION;
+    let frac_power = power % PRECISION;
+    if int_power > 59 {
+        return Ok(0);
+    }
+    let result = PRECISION >> int_power;
+    if frac_power == 0 {
+        return Ok(result);
+    }
+    // binomial series of (1 - 0.5)^frac_power
+    let x = PRECISION / 2;
+    let mut term = PRECISION;
+    let mut sum = PRECISION;
+    let mut negative = false;
+    for i in 1..MAX_ITERATIONS as u128 {
+        let k = i * PRECISION;
+        let mut c = k - PRECISION;
+        if frac_power > c {
+            c = frac_power - c;
+            negative = !negative;
+        } else {
+            c -= frac_power;
+        }
+        term = term * (c * x / PRECISION) / k;
+        if negative {
+            sum -= term;
+        } else {
+            sum += term;
+        }
+        if term < EXP_PRECISION {
+            return to_u128(casted_mul(result, sum) / PRECISION, 166);
+        }
+    }
+    Err(MathError::Precision(166))
+}
+
+/// Computes the "extended constant product" invariant (xcp) of the balanced pool
+/// with invariant `d` at `price_scale`, i.e. the geometric mean of `D / N` of each token
+/// expressed in its own units.
+pub fn compute_xcp(d: u128, price_scale: u128) -> Result<u128, MathError> {
+    if price_scale == 0 {
+        return Err(MathError::DivByZero(167));
+    }
+    let x_0 = d / N_COINS as u128;
+    let x_1 = to_u128(
+        casted_mul(d, PRECISION) / casted_mul(N_COINS as u128, price_scale),
+        167,
+    )?;
+    Ok(geometric_mean(x_0, x_1))
+}
+
+/// Computes the spot price of the second token in terms of the first one
+/// for the pool with balances `xp` and invariant `d` by simulating a small trade.
+pub fn compute_spot_price(
+    amp: u128,
+    gamma: u128,
+    xp: [u128; 2],
+    d: u128,
+    price_scale: u128,
+) -> Result<u128, MathError> {
+    let dx = xp[0] / 10u128.pow(6);
+    let y = compute_y(amp, gamma, [xp[0] + dx, xp[1]], d, 1)?;
+    let dy = xp[1]
+        .checked_sub(y)
+        .filter(|&dy| dy > 0)
+        .ok_or(MathError::SubUnderflow(167))?;
+    to_u128(casted_mul(price_scale, dx) / dy, 167)
+}
+
+/// Computes the amount of the other token received for `token_in_amount` of `token_in_id`,
+/// all in balances scaled with the price scale.
+///
+/// The dynamic trade fee (of the pool after the trade) is applied on the amount out.
+///
+/// Returns a tuple of (amount out, fee).
+pub fn swap_to(
+    amp: u128,
+    gamma: u128,
+    fees: &DynamicFees,
+    xp: [u128; 2],
+    token_in_id: usize,
+    token_in_amount: u128,
+) -> Result<(u128, u128), MathError> {
+    let token_out_id = 1 - token_in_id;
+    let d = compute_d(amp, gamma, xp)?;
+    let mut new_xp = xp;
+    new_xp[token_in_id] = xp[token_in_id]
+        .checked_add(token_in_amount)
+        .ok_or(MathError::AddOverflow(168))?;
+    let y = compute_y(amp, gamma, new_xp, d, token_out_id)?;
+    // subtract 1 to round in favor of the pool
+    let dy = xp[token_out_id]
+        .checked_sub(y)
+        .and_then(|dy| dy.checked_sub(1))
+        .ok_or(MathError::SubUnderflow(168))?;
+    new_xp[token_out_id] = y;
+    let fee = fee_amount(dy, fees.fee(new_xp)?)?;
+    Ok((dy - fee, fee))
+}
+
+/// Computes the amount of the other token required to receive `token_out_amount`
+/// of `token_out_id`, all in balances scaled with the price scale.
+///
+/// The dynamic trade fee is applied on the amount out, so the pool pays out
+/// `token_out_amount + fee`. The fee rate is taken from the pool after the trade
+/// without the fee, so it may be marginally lower than the one of `swap_to`.
+///
+/// Returns a tuple of (amount in, fee).
+pub fn swap_from(
+    amp: u128,
+    gamma: u128,
+    fees: &DynamicFees,
+    xp: [u128; 2],
+    token_out_id: usize,
+    token_out_amount: u128,
+) -> Result<(u128, u128), MathError> {
+    let token_in_id = 1 - token_out_id;
+    let d = compute_d(amp, gamma, xp)?;
+    let mut new_xp = xp;
+    new_xp[token_out_id] = xp[token_out_id]
+        .checked_sub(token_out_amount)
+        .ok_or(MathError::SubUnderflow(169))?;
+    new_xp[token_in_id] = compute_y(amp, gamma, new_xp, d, token_in_id)?;
+    let fee_rate = fees.fee(new_xp)?;
+    let fee = casted_mul(token_out_amount, fee_rate)
+        .checked_add((FEE_DENOM as u128 - fee_rate - 1).into())
+        .ok_or(MathError::AddOverflow(169))?
+        / (FEE_DENOM as u128 - fee_rate);
+    let fee = to_u128(fee, 169)?;
+    new_xp[token_out_id] = new_xp[token_out_id]
+        .checked_sub(fee)
+        .ok_or(MathError::SubUnderflow(169))?;
+    let x = compute_y(amp, gamma, new_xp, d, token_in_id)?;
+    // add 1 to round in favor of the pool
+    let dx = x
+        .checked_sub(xp[token_in_id])
+        .and_then(|dx| dx.checked_add(1))
+        .ok_or(MathError::SubUnderflow(169))?;
+    Ok((dx, fee))
+}
+
+/// Computes the amount of LP tokens minted for a deposit changing the balances
+/// (scaled with the price scale) from `old_xp` with invariant `old_d` to `new_xp`.
+///
+/// The imbalanced part of the deposit is charged with the half of the trade fee,
+/// as it is equivalent to a swap.
+///
+/// Returns a tuple of (lp amount, new invariant).
+pub fn compute_lp_amount_for_deposit(
+    amp: u128,
+    gamma: u128,
+    fees: &DynamicFees,
+    old_xp: [u128; 2],
+    new_xp: [u128; 2],
+    old_d: u128,
+    total_supply: u128,
+) -> Result<(u128, u128), MathError> {
+    if old_d == 0 {
+        return Err(MathError::DivByZero(172));
+    }
+    let new_d = compute_d(amp, gamma, new_xp)?;
+    let lp_amount = to_u128(casted_mul(total_supply, new_d) / old_d, 168)?
+        .checked_sub(total_supply)
+        .ok_or(MathError::SubUnderflow(172))?;
+    let amounts = [
+        new_xp[0].saturating_sub(old_xp[0]),
+        new_xp[1].saturating_sub(old_xp[1]),
+    ];
+    let sum = amounts[0]
+        .checked_add(amounts[1])
+        .ok_or(MathError::AddOverflow(172))?;
+    if sum == 0 {
+        return Err(MathError::DivByZero(172));
+    }
+    let avg = sum / N_COINS as u128;
+    let diff = amounts[0].abs_diff(avg) + amounts[1].abs_diff(avg);
+    // fee * N / (4 * (N - 1)) on the imbalanced part
+    let fee_rate = to_u128(casted_mul(fees.fee(new_xp)? / 2, diff) / sum, 168)? + NOISE_FEE;
+    let fee = fee_amount(lp_amount, fee_rate)?;
+    Ok((lp_amount.saturating_sub(fee), new_d))
+}
+
+/// Computes the amount of `token_id` (scaled with the price scale) withdrawn for burning
+/// `lp_amount` of LP tokens from the pool with balances `xp` and invariant `d`.
+///
+/// The trade fee is charged on the invariant decrease.
+///
+/// Returns a tuple of (withdraw amount, new invariant).
+#[allow(clippy::too_many_arguments)]
+pub fn compute_amount_for_one_coin_withdraw(
+    amp: u128,
+    gamma: u128,
+    fees: &DynamicFees,
+    xp: [u128; 2],
+    d: u128,
+    lp_amount: u128,
+    total_supply: u128,
+    token_id: usize,
+) -> Result<(u128, u128), MathError> {
+    if lp_amount > total_supply {
+        return Err(MathError::SubUnderflow(173));
+    }
+    let d_decrease = to_u128(
+        casted_mul(lp_amount, d)
+            .checked_div(total_supply.into())
+            .ok_or(MathError::DivByZero(173))?,
+        169,
+    )?;
+    // charge the fee on D, i.e. decrease it less than by the burned share
+    let fee = to_u128(
+        casted_mul(fees.fee(xp)?, d_decrease) / (2 * FEE_DENOM as u128),
+        169,
+    )? + 1;
+    let new_d = d
+        .checked_sub(d_decrease.saturating_sub(fee))
+        .ok_or(MathError::SubUnderflow(173))?;
+    let y = compute_y(amp, gamma, xp, new_d, token_id)?;
+    let amount = xp[token_id]
+        .checked_sub(y)
+        .ok_or(MathError::SubUnderflow(173))?;
+    Ok((amount, new_d))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A = 10 (multiplied by N^N * A_MULTIPLIER)
+    const AMP: u128 = 400_000;
+    const GAMMA: u128 = 145_000_000_000_000;
+    const ONE: u128 = PRECISION;
+
+    fn fees() -> DynamicFees {
+        DynamicFees::new(2_600_000, 45_000_000, 230_000_000_000_000)
+            .expect("Should instantiate fee")
+    }
+
+    fn no_fees() -> DynamicFees {
+        DynamicFees {
+            mid_fee: 0,
+            out_fee: 0,
+            fee_gamma: ONE,
+        }
+    }
+
+    #[test]
+    fn d_computation_balanced_pool() {
+        let xp = [1_000_000 * ONE, 1_000_000 * ONE];
+        let d = compute_d(AMP, GAMMA, xp).expect("Should compute D");
+        assert_eq!(d, 2_000_000 * ONE, "Invariant should be equal to the sum");
+    }
+
+    #[test]
+    fn d_computation_imbalanced_pool() {
+        for xp in [
+            [1_000_000 * ONE, 500_000 * ONE],
+            [1_000_000 * ONE, 200_000 * ONE],
+            [300 * ONE, 70_000 * ONE],
+        ] {
+            let d = compute_d(AMP, GAMMA, xp).expect("Should compute D");
+            assert!(
+                d < xp[0] + xp[1],
+                "Invariant should be less than const sum invariant"
+            );
+            assert!(
+                d > 2 * geometric_mean(xp[0], xp[1]),
+                "Invariant should be greater than const prod invariant"
+            );
+        }
+    }
+
+    #[test]
+    fn d_computation_out_of_range() {
+        let xp = [1_000_000 * ONE, ONE];
+        assert!(compute_d(AMP, GAMMA, xp).is_err());
+        assert_eq!(
+            compute_d(AMP, GAMMA, [ONE, 0]),
+            Err(MathError::DivByZero(162))
+        );
+    }
+
+    #[test]
+    fn y_computation_satisfies_d() {
+        for xp in [
+            [1_000_000 * ONE, 1_000_000 * ONE],
+            [1_000_000 * ONE, 500_000 * ONE],
+            [300 * ONE, 70_000 * ONE],
+        ] {
+            let d = compute_d(AMP, GAMMA, xp).expect("Should compute D");
+            for token_id in 0..2 {
+                let y = compute_y(AMP, GAMMA, xp, d, token_id).expect("Should compute y");
+                assert!(
+                    y.abs_diff(xp[token_id]) <= xp[token_id] / 10u128.pow(15),
+                    "Should recover the balance"
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn halfpow_computation() {
+        assert_eq!(halfpow(0), Ok(ONE));
+        assert_eq!(halfpow(ONE), Ok(ONE / 2));
+        assert_eq!(halfpow(3 * ONE), Ok(ONE / 8));
+        assert_eq!(halfpow(60 * ONE), Ok(0));
+        // 0.5^0.5 = 0.70710678118...
+        let sqrt_half = halfpow(ONE / 2).expect("Should compute halfpow");
+        assert!(sqrt_half.abs_diff(707_106_781_186_547_524) < EXP_PRECISION);
+        // 0.5^1.5 = 0.35355339059...
+        let x = halfpow(3 * ONE / 2).expect("Should compute halfpow");
+        assert!(x.abs_diff(353_553_390_593_273_762) < EXP_PRECISION);
+    }
+
+    #[test]
+    fn spot_price_balanced_pool() {
+        let price_scale = 2_500 * ONE;
+        let xp = [2_500_000 * ONE, 2_500_000 * ONE];
+        let d = compute_d(AMP, GAMMA, xp).expect("Should compute D");
+        let price =
+            compute_spot_price(AMP, GAMMA, xp, d, price_scale).expect("Should compute price");
+        assert!(
+            price.abs_diff(price_scale) < price_scale / 10u128.pow(5),
+            "Spot price of balanced pool should be the price scale"
+        );
+    }
+
+    #[test]
+    fn xcp_computation() {
+        let price_scale = 4 * ONE;
+        // D / 2 = 1000 of token 0 and (D / 2) / 4 = 250 of token 1
+        let xcp = compute_xcp(2_000 * ONE, price_scale).expect("Should compute xcp");
+        assert_eq!(xcp, 500 * ONE);
+    }
+
+    #[test]
+    fn swap_to_computation_no_fees() {
+        let xp = [1_000_000 * ONE, 1_000_000 * ONE];
+        let amount_in = 1_000 * ONE;
+        let (amount_out, fee) =
+            swap_to(AMP, GAMMA, &no_fees(), xp, 0, amount_in).expect("Should compute swap");
+        assert_eq!(fee, 0);
+        assert!(
+            amount_out < amount_in && amount_out > amount_in * 999 / 1_000,
+            "Small trade of balanced pool should have small price impact"
+        );
+    }
+
+    #[test]
+    fn swap_to_computation_with_fees() {
+        let xp = [1_000_000 * ONE, 1_000_000 * ONE];
+        let amount_in = ONE;
+        let (amount_out, fee) =
+            swap_to(AMP, GAMMA, &fees(), xp, 1, amount_in).expect("Should compute swap");
+        assert_eq!(
+            fee,
+            fee_amount(amount_out + fee, 2_600_000).unwrap(),
+            "Fee of a small trade should be the mid fee"
+        );
+
+        let amount_in = 100_000 * ONE;
+        let (amount_out, fee) =
+            swap_to(AMP, GAMMA, &fees(), xp, 1, amount_in).expect("Should compute swap");
+        assert!(
+            fee > fee_amount(amount_out + fee, 2_600_000).unwrap(),
+            "Fee of a large trade should be higher than the mid fee"
+        );
+    }
+
+    #[test]
+    fn swap_to_from_computation() {
+        let xp = [1_000_000 * ONE, 500_000 * ONE];
+        for token_in_id in 0..2 {
+            let amount_in = 10_000 * ONE;
+            let (amount_out, _) = swap_to(AMP, GAMMA, &no_fees(), xp, token_in_id, amount_in)
+                .expect("Should compute swap");
+            let (amount_in_back, _) =
+                swap_from(AMP, GAMMA, &no_fees(), xp, 1 - token_in_id, amount_out)
+                    .expect("Should compute swap");
+            assert!(
+                amount_in_back.abs_diff(amount_in) < amount_in / 10u128.pow(12),
+                "Swap from should invert swap to"
+            );
+
+            let (amount_out, _) = swap_to(AMP, GAMMA, &fees(), xp, token_in_id, amount_in)
+                .expect("Should compute swap");
+            let (amount_in_back, _) =
+                swap_from(AMP, GAMMA, &fees(), xp, 1 - token_in_id, amount_out)
+                    .expect("Should compute swap");
+            assert!(
+                amount_in_back.abs_diff(amount_in) < amount_in / 10u128.pow(5),
+                "Swap from should invert swap to up to the fee rate difference"
+            );
+        }
+    }
+
+    #[test]
+    fn deposit_proportional_amounts() {
+        let old_xp = [1_000_000 * ONE, 1_000_000 * ONE];
+        let total_supply = 1_000_000 * ONE;
+        let old_d = compute_d(AMP, GAMMA, old_xp).expect("Should compute D");
+        let new_xp = [1_100_000 * ONE, 1_100_000 * ONE];
+        let (lp_amount, new_d) =
+            compute_lp_amount_for_deposit(AMP, GAMMA, &fees(), old_xp, new_xp, old_d, total_supply)
+                .expect("Should compute lp amount");
+        assert_eq!(new_d, 2_200_000 * ONE);
+        assert_eq!(
+            lp_amount,
+            total_supply / 10 - fee_amount(total_supply / 10, NOISE_FEE).unwrap(),
+            "Balanced deposit should be charged with the noise fee only"
+        );
+    }
+
+    #[test]
+    fn deposit_and_withdraw_one_coin() {
+        let old_xp = [1_000_000 * ONE, 1_000_000 * ONE];
+        let total_supply = 1_000_000 * ONE;
+        let old_d = compute_d(AMP, GAMMA, old_xp).expect("Should compute D");
+        let amount = 10_000 * ONE;
+        let new_xp = [old_xp[0] + amount, old_xp[1]];
+        let (lp_amount, new_d) =
+            compute_lp_amount_for_deposit(AMP, GAMMA, &fees(), old_xp, new_xp, old_d, total_supply)
+                .expect("Should compute lp amount");
+        let (withdrawn, _) = compute_amount_for_one_coin_withdraw(
+            AMP,
+            GAMMA,
+            &fees(),
+            new_xp,
+            new_d,
+            lp_amount,
+            total_supply + lp_amount,
+            0,
+        )
+        .expect("Should compute withdraw amount");
+        assert!(
+            withdrawn < amount && withdrawn > amount * 99 / 100,
+            "Should get back the deposit less the fees"
+        );
+    }
+}
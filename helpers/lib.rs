@@ -1,7 +1,10 @@
 #![cfg_attr(not(feature = "std"), no_std, no_main)]
 
+pub mod concentrated_math;
 pub mod constants;
+pub mod crypto_swap_math;
 mod ensure;
 pub mod math;
 pub mod stable_swap_math;
 pub mod types;
+pub mod weighted_math;
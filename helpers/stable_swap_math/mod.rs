@@ -1,7 +1,7 @@
 pub mod fees;
 
 use crate::{
-    constants::stable_pool::RATE_PRECISION,
+    constants::stable_pool::{PRICE_PRECISION, RATE_PRECISION},
     math::{casted_mul, MathError},
 };
 use ink::prelude::vec::Vec;
@@ -157,6 +157,54 @@ fn compute_y(
                 .ok_or(MathError::DivByZero(4))?;
         }
     }
+    compute_y_given_c(c, reserves_sum, d, ann, n)
+}
+
+/// Returns reserve of `token_y_id` for which the invariant equals `d`
+/// given reserves of the other tokens.
+///
+/// NOTE: `reserves[token_y_id]` is ignored. It does not check if `token_y_id` is out of bounds.
+fn compute_y_given_d(
+    reserves: &[u128],
+    token_y_id: usize,
+    d: U256,
+    amp_coef: u128,
+) -> Result<u128, MathError> {
+    let n = reserves.len() as u32;
+    let ann: U256 = casted_mul(
+        amp_coef,
+        n.checked_pow(n).ok_or(MathError::MulOverflow(50))?.into(),
+    );
+    let mut c = d;
+    let mut reserves_sum = U256::zero();
+    // reserves_sum = ... + x_(i') + ...
+    // c1 = ... * d / x_(i') * ... * d
+    // where  i' in (0,n) AND i' != token_y_id
+    for (idx, &reserve) in reserves.iter().enumerate() {
+        if idx != token_y_id {
+            reserves_sum = reserves_sum
+                .checked_add(reserve.into())
+                .ok_or(MathError::AddOverflow(50))?;
+            c = c
+                .checked_mul(d)
+                .ok_or(MathError::MulOverflow(51))?
+                .checked_div(reserve.into())
+                .ok_or(MathError::DivByZero(50))?;
+        }
+    }
+    compute_y_given_c(c, reserves_sum, d, ann, n)
+}
+
+/// Solves the invariant equation for the reserve of the remaining token
+/// with the Newton-Raphson method, given `c_1` = d^n / Prod{x_i'}
+/// and `reserves_sum` = SUM{x_i'} of the other tokens.
+fn compute_y_given_c(
+    mut c: U256,
+    reserves_sum: U256,
+    d: U256,
+    ann: U256,
+    n: u32,
+) -> Result<u128, MathError> {
     // c = c_1 * d / (A * n^2n)
     c = c
         .checked_mul(d)
@@ -559,6 +607,178 @@ pub fn rated_compute_lp_amount_for_withdraw(
     )
 }
 
+/// Computes amount of `token_id` received for burning `lpt_amount`
+/// when withdrawing liquidity in a single token.
+///
+/// The fee is charged in the same way as for the withdrawal by amounts
+/// (see [`compute_lp_amount_for_withdraw`]), on the difference between
+/// the new reserves and the reserves after a balanced withdrawal.
+/// Returns a tuple of (amount, fee) where fee is in `token_id` token.
+fn compute_amount_for_one_coin_withdraw(
+    lpt_amount: u128,
+    token_id: usize,
+    reserves: &[u128],
+    pool_token_supply: u128,
+    fees: Option<&Fees>,
+    amp_coef: u128,
+) -> Result<(u128, u128), MathError> {
+    let n_coins = reserves.len() as u32;
+    // Initial invariant, D0
+    let d_0 = compute_d(&reserves.to_vec(), amp_coef)?;
+    // Invariant after the withdrawal, D1 = D0 - lpt_amount * D0 / supply
+    let d_1 = d_0
+        .checked_sub(
+            d_0.checked_mul(lpt_amount.into())
+                .ok_or(MathError::MulOverflow(52))?
+                .checked_div(pool_token_supply.into())
+                .ok_or(MathError::DivByZero(51))?,
+        )
+        .ok_or(MathError::SubUnderflow(50))?;
+    let new_reserve = compute_y_given_d(reserves, token_id, d_1, amp_coef)?;
+    // amount without fee
+    let dy_0 = reserves[token_id]
+        .checked_sub(new_reserve)
+        .ok_or(MathError::SubUnderflow(51))?;
+
+    let dy = if let Some(fees) = fees {
+        // Recalculate the amount accounting for fees
+        let mut reduced_reserves = reserves.to_vec();
+        for i in 0..reserves.len() {
+            let ideal_reserve: u128 = d_1
+                .checked_mul(reserves[i].into())
+                .ok_or(MathError::MulOverflow(53))?
+                .checked_div(d_0)
+                .ok_or(MathError::DivByZero(52))?
+                .try_into()
+                .map_err(|_| MathError::CastOverflow(50))?;
+            let reserve_after = if i == token_id {
+                new_reserve
+            } else {
+                reserves[i]
+            };
+            let difference = ideal_reserve.abs_diff(reserve_after);
+            let fee = fees.normalized_trade_fee(n_coins, difference)?;
+            reduced_reserves[i] = reduced_reserves[i]
+                .checked_sub(fee)
+                .ok_or(MathError::SubUnderflow(52))?;
+        }
+        reduced_reserves[token_id]
+            .checked_sub(compute_y_given_d(
+                &reduced_reserves,
+                token_id,
+                d_1,
+                amp_coef,
+            )?)
+            .ok_or(MathError::SubUnderflow(53))?
+    } else {
+        dy_0
+    };
+    // sub 1 in case there are any rounding errors
+    let dy = dy.checked_sub(1).ok_or(MathError::SubUnderflow(54))?;
+    Ok((dy, dy_0.checked_sub(dy).ok_or(MathError::SubUnderflow(55))?))
+}
+
+pub fn rated_compute_amount_for_one_coin_withdraw(
+    rates: &[u128],
+    lpt_amount: u128,
+    token_id: usize,
+    reserves: &[u128],
+    pool_token_supply: u128,
+    fees: Option<&Fees>,
+    amp_coef: u128,
+) -> Result<(u128, u128), MathError> {
+    let r_reserves = amounts_to_rated(reserves, rates)?;
+    let (r_amount, r_fee) = compute_amount_for_one_coin_withdraw(
+        lpt_amount,
+        token_id,
+        &r_reserves,
+        pool_token_supply,
+        fees,
+        amp_coef,
+    )?;
+    let amount = amount_from_rated(r_amount, rates[token_id])?;
+    let fee = amount_from_rated(r_fee, rates[token_id])?;
+    Ok((amount, fee))
+}
+
+/// Computes stable swap invariant (D) of the rated `reserves`.
+pub fn rated_compute_d(
+    rates: &[u128],
+    reserves: &[u128],
+    amp_coef: u128,
+) -> Result<U256, MathError> {
+    compute_d(&amounts_to_rated(reserves, rates)?, amp_coef)
+}
+
+/// Computes marginal prices (excluding fees) of each token in terms of the `base_token_id` token
+/// given `d` - the invariant of the rated `reserves` as returned by [`rated_compute_d`].
+///
+/// Prices are expressed in token amounts (not rated) with `PRICE_PRECISION`,
+/// i.e. exchanging `amount` of token `i` yields approximately
+/// `amount * prices[i] / PRICE_PRECISION` of the base token.
+///
+/// NOTE: reserves must be non-zero.
+pub fn rated_spot_prices(
+    rates: &[u128],
+    reserves: &[u128],
+    d: U256,
+    amp_coef: u128,
+    base_token_id: usize,
+) -> Result<Vec<u128>, MathError> {
+    let r_reserves = amounts_to_rated(reserves, rates)?;
+    let n = r_reserves.len() as u32;
+    // A * n^n
+    let ann: U256 = casted_mul(
+        amp_coef,
+        n.checked_pow(n).ok_or(MathError::MulOverflow(40))?.into(),
+    );
+    // d_p = d^(n+1) / (n^n * Prod{x_i})
+    let mut d_p = d;
+    for &reserve in r_reserves.iter() {
+        d_p = d_p
+            .checked_mul(d)
+            .ok_or(MathError::MulOverflow(41))?
+            .checked_div(casted_mul(reserve, n.into()))
+            .ok_or(MathError::DivByZero(40))?;
+    }
+    // Price of token i in terms of token j equals
+    // (A * n^n + d_p / x_i) / (A * n^n + d_p / x_j) = x_j * (A * n^n * x_i + d_p) / (x_i * (A * n^n * x_j + d_p))
+    // where x are rated reserves.
+    let base_reserve = r_reserves[base_token_id];
+    let base_denominator = ann
+        .checked_mul(base_reserve.into())
+        .ok_or(MathError::MulOverflow(42))?
+        .checked_add(d_p)
+        .ok_or(MathError::AddOverflow(40))?;
+    r_reserves
+        .iter()
+        .zip(rates.iter())
+        .map(|(&reserve, &rate)| {
+            let rated_price = ann
+                .checked_mul(reserve.into())
+                .ok_or(MathError::MulOverflow(43))?
+                .checked_add(d_p)
+                .ok_or(MathError::AddOverflow(41))?
+                .checked_mul(PRICE_PRECISION.into())
+                .ok_or(MathError::MulOverflow(44))?
+                .checked_div(base_denominator)
+                .ok_or(MathError::DivByZero(41))?
+                .checked_mul(base_reserve.into())
+                .ok_or(MathError::MulOverflow(45))?
+                .checked_div(reserve.into())
+                .ok_or(MathError::DivByZero(42))?;
+            // rescale from rated amounts to token amounts
+            rated_price
+                .checked_mul(rate.into())
+                .ok_or(MathError::MulOverflow(46))?
+                .checked_div(rates[base_token_id].into())
+                .ok_or(MathError::DivByZero(43))?
+                .try_into()
+                .map_err(|_| MathError::CastOverflow(40))
+        })
+        .collect()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -796,4 +1016,107 @@ mod tests {
         assert_eq!(fee_part, 0, "Fee should be 0");
         assert_eq!(share, share_by_deposit, "Deposit amounts differ.");
     }
+
+    #[test]
+    fn spot_prices_balanced_pool() {
+        let amp_coef: u128 = 1000;
+        let reserves: Vec<u128> = vec![100000000000, 100000000000, 100000000000];
+        let rates: [u128; 3] = [RATE_PRECISION, RATE_PRECISION, RATE_PRECISION];
+        let d = rated_compute_d(&rates, &reserves, amp_coef).expect("Should compute D");
+        let prices =
+            rated_spot_prices(&rates, &reserves, d, amp_coef, 1).expect("Should compute prices");
+        assert_eq!(prices, vec![PRICE_PRECISION; 3], "Prices should be 1:1");
+    }
+
+    #[test]
+    fn spot_prices_rated_pool() {
+        let amp_coef: u128 = 1000;
+        // token 1 is worth twice as much as token 0 and has 6 decimal places less
+        let reserves: Vec<u128> = vec![200000000000000000, 100000000000];
+        let rates: [u128; 2] = [RATE_PRECISION, 2 * RATE_PRECISION * 10u128.pow(6)];
+        let d = rated_compute_d(&rates, &reserves, amp_coef).expect("Should compute D");
+        let prices =
+            rated_spot_prices(&rates, &reserves, d, amp_coef, 0).expect("Should compute prices");
+        assert_eq!(prices[0], PRICE_PRECISION);
+        assert_eq!(prices[1], 2 * PRICE_PRECISION * 10u128.pow(6));
+    }
+
+    #[test]
+    fn spot_prices_match_small_swaps() {
+        let amp_coef: u128 = 100;
+        let fees = Fees::zero();
+        let reserves: Vec<u128> = vec![100000000000000, 300000000000000];
+        let rates: [u128; 2] = [RATE_PRECISION, RATE_PRECISION];
+        let d = rated_compute_d(&rates, &reserves, amp_coef).expect("Should compute D");
+        let prices =
+            rated_spot_prices(&rates, &reserves, d, amp_coef, 1).expect("Should compute prices");
+        // token 0 is scarcer, so it should be more expensive
+        assert!(prices[0] > PRICE_PRECISION);
+        let token_in = 1000000000;
+        let (amount_out, _) = rated_swap_to(&rates, 0, token_in, 1, &reserves, &fees, amp_coef)
+            .expect("Should return swap result");
+        let expected_amount_out = token_in * prices[0] / PRICE_PRECISION;
+        // small swap has price impact below 0.01%
+        assert!(
+            amount_out <= expected_amount_out
+                && expected_amount_out - amount_out <= expected_amount_out / 10_000,
+            "Swap result {amount_out} should be close to the spot price result {expected_amount_out}"
+        );
+    }
+
+    #[test]
+    fn one_coin_withdraw_matches_withdraw_by_amounts() {
+        let amp_coef: u128 = 1000;
+        let fees = Fees::new(2_500_000, 0).expect("Should instantiate fees");
+        let reserves: Vec<u128> = vec![50000 * 10u128.pow(18), 150000 * 10u128.pow(18)];
+        let rates: [u128; 2] = [RATE_PRECISION, RATE_PRECISION];
+        let (supply, _) =
+            rated_compute_lp_amount_for_deposit(&rates, &reserves, &[0, 0], 0, None, amp_coef)
+                .expect("Should compute lp amount");
+        let lpt_amount = 1000 * 10u128.pow(18);
+        for token_id in 0..2 {
+            // without fees
+            let (amount, rounding) = rated_compute_amount_for_one_coin_withdraw(
+                &rates, lpt_amount, token_id, &reserves, supply, None, amp_coef,
+            )
+            .expect("Should compute amount");
+            assert!(rounding <= 1, "Only rounding should be charged");
+            let mut amounts = vec![0; 2];
+            amounts[token_id] = amount;
+            let (burned, _) = rated_compute_lp_amount_for_withdraw(
+                &rates, &amounts, &reserves, supply, None, amp_coef,
+            )
+            .expect("Should compute lp amount");
+            assert!(lpt_amount - burned <= 1, "Incorrect amount {amount}");
+
+            // with fees
+            let (amount_with_fee, fee) = rated_compute_amount_for_one_coin_withdraw(
+                &rates,
+                lpt_amount,
+                token_id,
+                &reserves,
+                supply,
+                Some(&fees),
+                amp_coef,
+            )
+            .expect("Should compute amount");
+            assert!(fee > 1, "Fee should be charged");
+            assert_eq!(amount_with_fee + fee, amount + rounding, "Incorrect fee");
+            amounts[token_id] = amount_with_fee;
+            let (burned, _) = rated_compute_lp_amount_for_withdraw(
+                &rates,
+                &amounts,
+                &reserves,
+                supply,
+                Some(&fees),
+                amp_coef,
+            )
+            .expect("Should compute lp amount");
+            // fee is approximately the same as when withdrawing by amounts
+            assert!(
+                burned <= lpt_amount && lpt_amount - burned <= lpt_amount / 10_000,
+                "Burned {burned} for amount {amount_with_fee}"
+            );
+        }
+    }
 }
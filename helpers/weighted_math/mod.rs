@@ -0,0 +1,478 @@
+use crate::{
+    constants::weighted_pool::WEIGHT_PRECISION,
+    math::{casted_mul, MathError},
+    stable_swap_math::fees::Fees,
+};
+use ink::prelude::vec::Vec;
+use primitive_types::U256;
+
+/// Ratios, weights and powers are fixed-point numbers with 18 decimal places.
+const ONE: u128 = WEIGHT_PRECISION;
+/// Logarithms and exponents are computed with 36 decimal places.
+const ONE_36: u128 = 10u128.pow(36);
+/// ln(2) with 36 decimal places.
+const LN_2_36: i128 = 693_147_180_559_945_309_417_232_121_458_176_568;
+/// Max exponent of `exp_36`, so that the result (e^130 ~ 2^188 with 18 decimal places) fits in U256.
+const MAX_EXPONENT_36: i128 = 130 * ONE_36 as i128;
+/// Min exponent of `exp_36`, below it the result (with 18 decimal places) rounds down to zero.
+const MIN_EXPONENT_36: i128 = -42 * ONE_36 as i128;
+/// Bound of the relative error of `pow` (1e-14), with 18 decimal places.
+///
+/// The actual error is below 1e-17 plus one unit in the last place,
+/// the margin is taken from Balancer V2.
+const MAX_POW_RELATIVE_ERROR: u128 = 10_000;
+
+/// Computes `a * b / c` rounding down or up.
+fn mul_div(a: u128, b: u128, c: u128, round_up: bool) -> Result<u128, MathError> {
+    if c == 0 {
+        return Err(MathError::DivByZero(130));
+    }
+    let (quotient, remainder) = casted_mul(a, b).div_mod(c.into());
+    let quotient = if round_up && !remainder.is_zero() {
+        quotient + 1
+    } else {
+        quotient
+    };
+    quotient
+        .try_into()
+        .map_err(|_| MathError::CastOverflow(130))
+}
+
+fn mul_down(a: u128, b: u128) -> Result<u128, MathError> {
+    mul_div(a, b, ONE, false)
+}
+
+fn mul_up(a: u128, b: u128) -> Result<u128, MathError> {
+    mul_div(a, b, ONE, true)
+}
+
+fn div_down(a: u128, b: u128) -> Result<u128, MathError> {
+    mul_div(a, ONE, b, false)
+}
+
+fn div_up(a: u128, b: u128) -> Result<u128, MathError> {
+    mul_div(a, ONE, b, true)
+}
+
+/// Returns `1 - x`, or zero if `x` is greater than one.
+fn complement(x: u128) -> u128 {
+    ONE.saturating_sub(x)
+}
+
+/// Computes the natural logarithm of `x` given with 18 decimal places.
+///
+/// Returns the result with 36 decimal places.
+fn ln_36(x: u128) -> Result<i128, MathError> {
+    if x == 0 {
+        return Err(MathError::DivByZero(131));
+    }
+    let one_36 = U256::from(ONE_36);
+    // Normalize `x` (with 36 decimal places) to `m` in [1, 2), so that `x = m * 2^k`.
+    let mut m = casted_mul(x, ONE);
+    let mut k = m.bits() as i32 - one_36.bits() as i32;
+    if k > 0 {
+        m >>= k as usize;
+    } else {
+        m <<= k.unsigned_abs() as usize;
+    }
+    if m < one_36 {
+        m <<= 1;
+        k -= 1;
+    }
+    // ln(m) = 2 * atanh(z) = 2 * (z + z^3 / 3 + z^5 / 5 + ...), where z = (m - 1) / (m + 1) < 1/3.
+    let z = (m - one_36) * one_36 / (m + one_36);
+    let z_squared = z * z / one_36;
+    let mut term = z;
+    let mut sum = z;
+    let mut n = 1u32;
+    while !term.is_zero() {
+        term = term * z_squared / one_36;
+        n += 2;
+        sum += term / n;
+    }
+    // ln(m) < ln(2), so it fits in i128.
+    Ok(k as i128 * LN_2_36 + (sum * U256::from(2)).as_u128() as i128)
+}
+
+/// Computes `e^x` for `x` given with 36 decimal places.
+///
+/// Returns the result with 18 decimal places.
+fn exp_36(x: i128) -> Result<U256, MathError> {
+    if x > MAX_EXPONENT_36 {
+        return Err(MathError::MulOverflow(132));
+    }
+    if x < MIN_EXPONENT_36 {
+        return Ok(U256::zero());
+    }
+    let one_36 = U256::from(ONE_36);
+    // e^|x| = 2^k * e^r, where r is in [0, ln(2)).
+    let a = x.unsigned_abs();
+    let k = (a / LN_2_36 as u128) as usize;
+    let r = U256::from(a - k as u128 * LN_2_36 as u128);
+    // Taylor series of e^r.
+    let mut term = one_36;
+    let mut sum = one_36;
+    let mut n = 0u32;
+    while !term.is_zero() {
+        n += 1;
+        term = term * r / one_36 / n;
+        sum += term;
+    }
+    if x >= 0 {
+        Ok((sum / ONE) << k)
+    } else {
+        Ok(((one_36 * one_36 / sum) >> k) / ONE)
+    }
+}
+
+/// Computes `factor * ln`, where `factor` is given with 18 decimal places
+/// and `ln` with 36 decimal places.
+fn mul_ln(factor: u128, ln: i128) -> Result<i128, MathError> {
+    let product: u128 = (casted_mul(factor, ln.unsigned_abs()) / ONE)
+        .try_into()
+        .map_err(|_| MathError::CastOverflow(133))?;
+    let product = i128::try_from(product).map_err(|_| MathError::CastOverflow(133))?;
+    Ok(if ln < 0 { -product } else { product })
+}
+
+/// Computes `base^exponent`, both given and returned with 18 decimal places.
+fn pow(base: u128, exponent: u128) -> Result<u128, MathError> {
+    if exponent == 0 {
+        return Ok(ONE);
+    }
+    if base == 0 {
+        return Ok(0);
+    }
+    exp_36(mul_ln(exponent, ln_36(base)?)?)?
+        .try_into()
+        .map_err(|_| MathError::CastOverflow(134))
+}
+
+/// Computes `base^exponent` rounded up by the max error of `pow`.
+pub fn pow_up(base: u128, exponent: u128) -> Result<u128, MathError> {
+    let raw = pow(base, exponent)?;
+    let max_error = mul_up(raw, MAX_POW_RELATIVE_ERROR)?;
+    raw.checked_add(max_error)
+        .ok_or(MathError::AddOverflow(134))
+}
+
+/// Computes `base^exponent` rounded down by the max error of `pow`.
+pub fn pow_down(base: u128, exponent: u128) -> Result<u128, MathError> {
+    let raw = pow(base, exponent)?;
+    let max_error = mul_up(raw, MAX_POW_RELATIVE_ERROR)?;
+    Ok(raw.saturating_sub(max_error))
+}
+
+/// Computes the invariant of the pool, `PROD{b_i^w_i}`, where `b_i` are the `reserves`
+/// scaled to 18 decimal places and `w_i` are the normalized `weights`.
+pub fn compute_invariant(reserves: &[u128], weights: &[u128]) -> Result<u128, MathError> {
+    let mut exponent = 0i128;
+    for (&reserve, &weight) in reserves.iter().zip(weights.iter()) {
+        exponent = exponent
+            .checked_add(mul_ln(weight, ln_36(reserve)?)?)
+            .ok_or(MathError::AddOverflow(135))?;
+    }
+    exp_36(exponent)?
+        .try_into()
+        .map_err(|_| MathError::CastOverflow(135))
+}
+
+/// Computes the amount of `token_out` received for `token_in_amount` of `token_in`.
+///
+/// The trade fee is applied on `token_in_amount`.
+///
+/// Returns a tuple of (amount out, fee).
+pub fn swap_to(
+    reserve_in: u128,
+    weight_in: u128,
+    reserve_out: u128,
+    weight_out: u128,
+    token_in_amount: u128,
+    fees: &Fees,
+) -> Result<(u128, u128), MathError> {
+    let fee = fees.trade_fee_from_gross(token_in_amount)?;
+    let amount_in = token_in_amount
+        .checked_sub(fee)
+        .ok_or(MathError::SubUnderflow(136))?;
+    // b_out * (1 - (b_in / (b_in + a_in))^(w_in / w_out))
+    let base = div_up(
+        reserve_in,
+        reserve_in
+            .checked_add(amount_in)
+            .ok_or(MathError::AddOverflow(136))?,
+    )?;
+    let power = pow_up(base, div_down(weight_in, weight_out)?)?;
+    let amount_out = mul_down(reserve_out, complement(power))?;
+    Ok((amount_out, fee))
+}
+
+/// Computes the amount of `token_in` required to receive `token_out_amount` of `token_out`.
+///
+/// The trade fee is applied on the returned amount in.
+///
+/// Returns a tuple of (amount in, fee).
+pub fn swap_from(
+    reserve_in: u128,
+    weight_in: u128,
+    reserve_out: u128,
+    weight_out: u128,
+    token_out_amount: u128,
+    fees: &Fees,
+) -> Result<(u128, u128), MathError> {
+    // b_in * ((b_out / (b_out - a_out))^(w_out / w_in) - 1)
+    let base = div_up(
+        reserve_out,
+        reserve_out
+            .checked_sub(token_out_amount)
+            .ok_or(MathError::SubUnderflow(137))?,
+    )?;
+    let power = pow_up(base, div_up(weight_out, weight_in)?)?;
+    let amount_in = mul_up(reserve_in, power.saturating_sub(ONE))?;
+    let fee = fees.trade_fee_from_net(amount_in)?;
+    Ok((
+        amount_in
+            .checked_add(fee)
+            .ok_or(MathError::AddOverflow(137))?,
+        fee,
+    ))
+}
+
+/// Computes the trade fee of a single token deposit or withdrawal of `amount`.
+///
+/// Only the part of the `amount` exceeding the token's `weight` share is charged,
+/// as the rest corresponds to a proportional deposit or withdrawal.
+fn one_coin_fee(amount: u128, weight: u128, fees: Option<&Fees>) -> Result<u128, MathError> {
+    match fees {
+        Some(fees) => fees.trade_fee_from_gross(mul_up(amount, complement(weight))?),
+        None => Ok(0),
+    }
+}
+
+/// Computes the amount of LP tokens minted for depositing `amount` of a single token
+/// with given `reserve` and `weight`.
+///
+/// Returns a tuple of (lp amount, fee).
+pub fn compute_lp_amount_for_one_coin_deposit(
+    reserve: u128,
+    weight: u128,
+    amount: u128,
+    total_supply: u128,
+    fees: Option<&Fees>,
+) -> Result<(u128, u128), MathError> {
+    let fee = one_coin_fee(amount, weight, fees)?;
+    let amount = amount
+        .checked_sub(fee)
+        .ok_or(MathError::SubUnderflow(138))?;
+    // s * ((b + a) / b)^w - s
+    let base = div_down(
+        reserve
+            .checked_add(amount)
+            .ok_or(MathError::AddOverflow(138))?,
+        reserve,
+    )?;
+    let invariant_ratio = pow_down(base, weight)?;
+    let lp_amount = mul_down(total_supply, invariant_ratio.saturating_sub(ONE))?;
+    Ok((lp_amount, fee))
+}
+
+/// Computes the amount of a single token with given `reserve` and `weight` withdrawn
+/// for burning `lp_amount` of LP tokens.
+///
+/// Returns a tuple of (withdrawn amount, fee).
+pub fn compute_amount_for_one_coin_withdraw(
+    reserve: u128,
+    weight: u128,
+    lp_amount: u128,
+    total_supply: u128,
+    fees: Option<&Fees>,
+) -> Result<(u128, u128), MathError> {
+    // b * (1 - ((s - lp) / s)^(1 / w))
+    let invariant_ratio = div_up(
+        total_supply
+            .checked_sub(lp_amount)
+            .ok_or(MathError::SubUnderflow(139))?,
+        total_supply,
+    )?;
+    let balance_ratio = pow_up(invariant_ratio, div_down(ONE, weight)?)?;
+    let amount = mul_down(reserve, complement(balance_ratio))?;
+    let fee = one_coin_fee(amount, weight, fees)?;
+    Ok((
+        amount
+            .checked_sub(fee)
+            .ok_or(MathError::SubUnderflow(139))?,
+        fee,
+    ))
+}
+
+/// Computes the amount of LP tokens minted for depositing `amounts` of all the tokens.
+///
+/// The amount is given by the least (relative to its reserve) of the `amounts`,
+/// the surplus of the others is not accounted for.
+pub fn compute_lp_amount_for_deposit(
+    amounts: &[u128],
+    reserves: &[u128],
+    total_supply: u128,
+) -> Result<u128, MathError> {
+    let mut lp_amount = u128::MAX;
+    for (&amount, &reserve) in amounts.iter().zip(reserves.iter()) {
+        lp_amount = lp_amount.min(mul_div(amount, total_supply, reserve, false)?);
+    }
+    Ok(lp_amount)
+}
+
+/// Computes the amounts of all the tokens required to mint `lp_amount` of LP tokens,
+/// rounded up.
+pub fn compute_amounts_for_lp_mint(
+    lp_amount: u128,
+    reserves: &[u128],
+    total_supply: u128,
+) -> Result<Vec<u128>, MathError> {
+    reserves
+        .iter()
+        .map(|&reserve| mul_div(reserve, lp_amount, total_supply, true))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assert_approx_eq(actual: u128, expected: u128, max_diff: u128) {
+        assert!(
+            actual.abs_diff(expected) <= max_diff,
+            "{actual} differs from {expected} by more than {max_diff}"
+        );
+    }
+
+    #[test]
+    fn ln_exp() {
+        assert_eq!(ln_36(ONE), Ok(0));
+        assert_approx_eq(ln_36(2 * ONE).unwrap() as u128, LN_2_36 as u128, 1);
+        assert_eq!(exp_36(0), Ok(ONE.into()));
+        assert_eq!(exp_36(MIN_EXPONENT_36 - 1), Ok(U256::zero()));
+        assert_eq!(
+            exp_36(MAX_EXPONENT_36 + 1),
+            Err(MathError::MulOverflow(132))
+        );
+        for x in [
+            1,
+            1_000,
+            ONE / 3,
+            ONE,
+            7 * ONE,
+            1_000_000 * ONE,
+            u128::MAX / 2,
+        ] {
+            let round_trip = exp_36(ln_36(x).unwrap()).unwrap().as_u128();
+            assert_approx_eq(round_trip, x, x / 10u128.pow(16) + 1);
+        }
+    }
+
+    #[test]
+    fn pow_values() {
+        // sqrt(2)
+        assert_approx_eq(pow(2 * ONE, ONE / 2).unwrap(), 1_414_213_562_373_095_048, 1);
+        assert_approx_eq(pow(ONE / 2, 3 * ONE).unwrap(), ONE / 8, 1);
+        // 0.7^100
+        assert_approx_eq(pow(7 * ONE / 10, 100 * ONE).unwrap(), 323, 1);
+        assert_eq!(pow(0, ONE), Ok(0));
+        assert_eq!(pow(5 * ONE, 0), Ok(ONE));
+        assert!(pow_down(3 * ONE, ONE).unwrap() < 3 * ONE);
+        assert!(pow_up(3 * ONE, ONE).unwrap() > 3 * ONE);
+    }
+
+    #[test]
+    fn invariant_of_equal_weights() {
+        let weights = [ONE / 2, ONE / 2];
+        // sqrt(400 * 900) = 600
+        let invariant = compute_invariant(&[400 * ONE, 900 * ONE], &weights).unwrap();
+        assert_approx_eq(invariant, 600 * ONE, 600 * 100);
+    }
+
+    #[test]
+    fn swap_equal_weights_matches_constant_product() {
+        let fees = Fees::zero();
+        let (reserve_in, reserve_out) = (1_000 * ONE, 2_000 * ONE);
+        let amount_in = 100 * ONE;
+        let (amount_out, fee) =
+            swap_to(reserve_in, ONE / 2, reserve_out, ONE / 2, amount_in, &fees).unwrap();
+        assert_eq!(fee, 0);
+        let expected = mul_div(reserve_out, amount_in, reserve_in + amount_in, false).unwrap();
+        assert!(amount_out <= expected);
+        assert_approx_eq(amount_out, expected, expected / 10u128.pow(13));
+    }
+
+    #[test]
+    fn swap_round_trip() {
+        let fees = Fees::new(3_000_000, 0).unwrap();
+        let (reserve_in, reserve_out) = (5_000 * ONE, 1_000 * ONE);
+        let (weight_in, weight_out) = (8 * ONE / 10, 2 * ONE / 10);
+        let amount_out = 10 * ONE;
+        let (amount_in, _) = swap_from(
+            reserve_in,
+            weight_in,
+            reserve_out,
+            weight_out,
+            amount_out,
+            &fees,
+        )
+        .unwrap();
+        let (received, _) = swap_to(
+            reserve_in,
+            weight_in,
+            reserve_out,
+            weight_out,
+            amount_in,
+            &fees,
+        )
+        .unwrap();
+        assert!(received >= amount_out);
+        assert_approx_eq(received, amount_out, amount_out / 10u128.pow(10));
+    }
+
+    #[test]
+    fn one_coin_deposit_and_withdraw() {
+        let fees = Fees::new(3_000_000, 0).unwrap();
+        let (reserve, weight, total_supply) = (1_000 * ONE, 8 * ONE / 10, 500 * ONE);
+        let amount = 100 * ONE;
+        let (lp_amount, fee) =
+            compute_lp_amount_for_one_coin_deposit(reserve, weight, amount, total_supply, None)
+                .unwrap();
+        assert_eq!(fee, 0);
+        let (withdrawn, _) = compute_amount_for_one_coin_withdraw(
+            reserve + amount,
+            weight,
+            lp_amount,
+            total_supply + lp_amount,
+            None,
+        )
+        .unwrap();
+        assert!(withdrawn <= amount);
+        assert_approx_eq(withdrawn, amount, amount / 10u128.pow(12));
+
+        // fee is charged only on the part exceeding the weight share
+        let (lp_amount_with_fee, fee) = compute_lp_amount_for_one_coin_deposit(
+            reserve,
+            weight,
+            amount,
+            total_supply,
+            Some(&fees),
+        )
+        .unwrap();
+        assert_eq!(fee, amount / 5 * 3 / 1000);
+        assert!(lp_amount_with_fee < lp_amount);
+    }
+
+    #[test]
+    fn proportional_deposit() {
+        let reserves = [1_000 * ONE, 3_000];
+        let amounts = compute_amounts_for_lp_mint(ONE, &reserves, 100 * ONE).unwrap();
+        assert_eq!(amounts, [10 * ONE, 30]);
+        assert_eq!(
+            compute_lp_amount_for_deposit(&amounts, &reserves, 100 * ONE),
+            Ok(ONE)
+        );
+        let amounts = compute_amounts_for_lp_mint(1, &reserves, 100 * ONE).unwrap();
+        assert_eq!(amounts, [10, 1]);
+    }
+}
@@ -3,7 +3,7 @@ import { loadAddresses } from './utils';
 import Token from '../../types/contracts/psp22';
 import Router from '../../types/contracts/router_contract';
 import Factory from '../../types/contracts/factory_contract';
-import { ONE_THOUSAND_STABLECOIN } from './constants';
+import { DEFAULT_PAIR_FEE, ONE_THOUSAND_STABLECOIN } from './constants';
 import { addLiquidityNative } from './utils';
 import { parseUnits } from './shared';
 
@@ -71,15 +71,27 @@ async function main(): Promise<void> {
   /// Query pair addresses
   const {
     value: { ok: dogeWAzeroAddress },
-  } = await factory.query.getPair(doge.address, wnativeAddress);
+  } = await factory.query.getPair(
+    doge.address,
+    wnativeAddress,
+    DEFAULT_PAIR_FEE,
+  );
   console.log('dogeWAzeroAddress', dogeWAzeroAddress);
   const {
     value: { ok: usdcWAzeroAddress },
-  } = await factory.query.getPair(usdc.address, wnativeAddress);
+  } = await factory.query.getPair(
+    usdc.address,
+    wnativeAddress,
+    DEFAULT_PAIR_FEE,
+  );
   console.log('usdcWAzeroAddress', usdcWAzeroAddress);
   const {
     value: { ok: usdtWAzeroAddress },
-  } = await factory.query.getPair(usdt.address, wnativeAddress);
+  } = await factory.query.getPair(
+    usdt.address,
+    wnativeAddress,
+    DEFAULT_PAIR_FEE,
+  );
   console.log('usdtWAzeroAddress', usdtWAzeroAddress);
 
   await api.disconnect();
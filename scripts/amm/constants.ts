@@ -9,5 +9,7 @@ export const ONE_HUNDRED_STABLECOIN = parseUnits(100, 6).toString();
 export const ONE_THOUSAND_STABLECOIN = parseUnits(1_000, 6).toString();
 export const TOTAL_SUPPLY = parseUnits(1_000_000, 18).toString();
 export const STABLE_TOTAL_SUPPLY = parseUnits(1_000_000, 6).toString();
+// Trading fee (in millis) of the pairs created with the default fee tier.
+export const DEFAULT_PAIR_FEE = 3;
 export const DUMMY_ADDRESS =
   '0x0000000000000000000000000000000000000000000000000000000000000000';
@@ -30,6 +30,7 @@ async function main(): Promise<void> {
   const { address: factoryAddress } = await factoryFactory.new(
     deployer.address,
     pairHash,
+    api.genesisHash.toHex(),
     { gasLimit: factoryInitGas },
   );
   const factory = new Factory(factoryAddress, deployer, api);
@@ -16,5 +16,6 @@ export async function estimateInit(
   return estimateContractInit(api, deployer, 'factory_contract.contract', [
     deployer.address,
     DUMMY_ADDRESS,
+    DUMMY_ADDRESS,
   ]);
 }
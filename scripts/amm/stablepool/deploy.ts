@@ -61,6 +61,7 @@ async function main(): Promise<void> {
             tradeFee,
             protocolFee,
             protocolFeeReceiver,
+            api.genesisHash.toHex(),
             { gasLimit: gasInit },
           )
           .then((res) => res.address);
@@ -76,6 +77,7 @@ async function main(): Promise<void> {
             tradeFee,
             protocolFee,
             protocolFeeReceiver,
+            api.genesisHash.toHex(),
             { gasLimit: gasInit },
           )
           .then((res) => res.address);
@@ -109,6 +109,7 @@ export async function estimateStablePoolInit(
         params.tradeFee,
         params.protocolFee,
         params.protocolFeeReceiver,
+        api.genesisHash.toHex(),
       ];
       break;
     case PoolType.Rated:
@@ -122,6 +123,7 @@ export async function estimateStablePoolInit(
         params.tradeFee,
         params.protocolFee,
         params.protocolFeeReceiver,
+        api.genesisHash.toHex(),
       ];
       break;
   }